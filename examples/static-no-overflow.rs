@@ -1,10 +1,10 @@
 use minus::error::MinusError;
-use minus::{page_all, Pager};
+use minus::{page_all, OverflowBehavior, Pager};
 use std::fmt::Write;
 
 fn main() -> Result<(), MinusError> {
     let mut pager = Pager::new();
-    pager.set_run_no_overflow(true)?;
+    pager.set_overflow_behavior(OverflowBehavior::PrintIfFits)?;
     for i in 0..=10u32 {
         writeln!(pager, "{}", i)?;
     }
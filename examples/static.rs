@@ -4,7 +4,7 @@ fn main() -> Result<(), MinusError> {
     let output = minus::Pager::new();
 
     for i in 0..=100 {
-        output.push_str(&format!("{}\n", i))?;
+        output.push_str(format!("{}\n", i))?;
     }
 
     minus::page_all(output)?;
@@ -0,0 +1,187 @@
+//! Copies the text on the screen, or a reference to it, to the system clipboard
+
+use super::utils::text::strip_ansi;
+use crate::PagerState;
+#[cfg(test)]
+use parking_lot::Mutex;
+#[cfg(test)]
+use std::sync::Arc;
+
+/// Where copied text is put, see [`PagerState::clipboard`]
+pub trait ClipboardBackend {
+    /// Puts `text` on the clipboard, or returns why it could not be put there
+    fn set_text(&mut self, text: String) -> Result<(), String>;
+}
+
+/// The clipboard of the system
+#[cfg_attr(test, allow(dead_code))]
+pub struct SystemClipboard;
+
+impl ClipboardBackend for SystemClipboard {
+    fn set_text(&mut self, text: String) -> Result<(), String> {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Keeps the copied text instead of putting it on the clipboard of the machine the tests run on
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct FakeClipboard {
+    pub copied: Arc<Mutex<Vec<String>>>,
+    /// Fails like a system without a clipboard when set
+    pub unavailable: bool,
+}
+
+#[cfg(test)]
+impl ClipboardBackend for FakeClipboard {
+    fn set_text(&mut self, text: String) -> Result<(), String> {
+        if self.unavailable {
+            return Err("no clipboard".to_string());
+        }
+        self.copied.lock().push(text);
+        Ok(())
+    }
+}
+
+/// Returns the backend used unless another one is set, which is a fake in tests
+pub fn default_backend() -> Box<dyn ClipboardBackend + Send + Sync> {
+    #[cfg(test)]
+    return Box::new(FakeClipboard::default());
+    #[cfg(not(test))]
+    Box::new(SystemClipboard)
+}
+
+/// Returns the rows currently displayed on the screen, without any ANSI styling
+pub fn visible_text(ps: &PagerState) -> String {
+    let lower_mark = ps.upper_mark.saturating_add(ps.content_rows());
+    ps.get_flattened_lines_with_bounds(ps.upper_mark, lower_mark)
+        .iter()
+        .map(|line| strip_ansi(line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Copies the rows on the screen to the clipboard and returns the message to show to the user
+///
+/// Failures, like running without a display server, are reported in the message instead of
+/// being returned, as there is nothing else the pager can do about them.
+pub fn copy_visible(ps: &mut PagerState) -> String {
+    let text = visible_text(ps);
+    copy_lines(ps, text)
+}
+
+/// Copies the lines of `text` to the clipboard and returns the message to show to the user
+///
+/// Failures are reported in the message, like [`copy_visible`] does.
+pub fn copy_lines(ps: &mut PagerState, text: String) -> String {
+    let num_lines = text.split('\n').count();
+    match ps.clipboard.set_text(text) {
+        Ok(()) => format!("Copied {num_lines} lines to the clipboard"),
+        Err(e) => format!("Could not copy to the clipboard: {e}"),
+    }
+}
+
+/// Returns the reference to the line at the top of the screen, or the cursor's line in cursor
+/// mode, made from [`PagerState::line_ref_template`]
+pub fn line_ref(ps: &PagerState) -> String {
+    let line_idx = ps.line_at_row(ps.cursor_line.unwrap_or(ps.upper_mark));
+    let mut line_ref = String::new();
+    let mut chars = ps.line_ref_template.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            line_ref.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('L') => line_ref.push_str(&(line_idx + 1).to_string()),
+            Some('C') => line_ref.push_str(&(ps.left_mark + 1).to_string()),
+            Some('T') => {
+                let line = ps.lines.lines().nth(line_idx).unwrap_or_default();
+                line_ref.push_str(&strip_ansi(line));
+            }
+            // `%%` is a single `%`, unknown placeholders are kept as they are
+            other => {
+                line_ref.push('%');
+                if let Some(other) = other.filter(|c| *c != '%') {
+                    line_ref.push(other);
+                }
+            }
+        }
+    }
+    line_ref
+}
+
+/// Copies the [reference](line_ref) to the current line to the clipboard and returns the message
+/// to show to the user
+///
+/// Failures are reported in the message, like [`copy_visible`] does.
+pub fn copy_line_ref(ps: &mut PagerState) -> String {
+    let line_ref = line_ref(ps);
+    match ps.clipboard.set_text(line_ref.clone()) {
+        Ok(()) => format!("Copied '{line_ref}' to the clipboard"),
+        Err(e) => format!("Could not copy to the clipboard: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{copy_line_ref, copy_visible, line_ref, visible_text, FakeClipboard};
+    use crate::PagerState;
+
+    #[test]
+    fn copies_visible_rows() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 80;
+        ps.rows = 3;
+        ps.lines = "zero\n\x1b[31mone\x1b[0m\ntwo\nthree".to_string();
+        ps.format_lines();
+        ps.upper_mark = 1;
+
+        // Only the two rows above the prompt, without the colors
+        assert_eq!(visible_text(&ps), "one\ntwo");
+
+        let clipboard = FakeClipboard::default();
+        ps.clipboard = Box::new(clipboard.clone());
+        assert_eq!(copy_visible(&mut ps), "Copied 2 lines to the clipboard");
+        assert_eq!(*clipboard.copied.lock(), vec!["one\ntwo".to_string()]);
+
+        // Without a clipboard, the user is told why
+        ps.clipboard = Box::new(FakeClipboard {
+            unavailable: true,
+            ..FakeClipboard::default()
+        });
+        assert_eq!(
+            copy_visible(&mut ps),
+            "Could not copy to the clipboard: no clipboard"
+        );
+    }
+
+    #[test]
+    fn references_top_line() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        ps.rows = 3;
+        ps.lines = "zero\n\x1b[31mone is long\x1b[0m\ntwo".to_string();
+        ps.format_lines();
+        // The second row of the wrapped line is at the top
+        ps.upper_mark = 2;
+        ps.left_mark = 4;
+        assert_eq!(line_ref(&ps), "2:5");
+
+        ps.line_ref_template = "file.rs:%L %T (100%%) %X".to_string();
+        assert_eq!(line_ref(&ps), "file.rs:2 one is long (100%) %X");
+
+        let clipboard = FakeClipboard::default();
+        ps.clipboard = Box::new(clipboard.clone());
+        assert_eq!(
+            copy_line_ref(&mut ps),
+            "Copied 'file.rs:2 one is long (100%) %X' to the clipboard"
+        );
+        assert_eq!(
+            *clipboard.copied.lock(),
+            vec!["file.rs:2 one is long (100%) %X".to_string()]
+        );
+    }
+}
@@ -1,17 +1,25 @@
 //! Provides the [`handle_event`] function
 
-use std::io::Write;
 use std::sync::{atomic::AtomicBool, Arc};
+use std::{
+    convert::TryInto,
+    io::Write,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "search")]
 use parking_lot::{Condvar, Mutex};
 
 #[cfg(feature = "search")]
 use super::search;
-use super::utils::display;
-use super::utils::text::AppendStyle;
+use super::utils::{display, text};
 use super::{events::Event, utils::term};
-use crate::{error::MinusError, input::InputEvent, PagerState};
+#[cfg(feature = "static_output")]
+use crate::OverflowBehavior;
+use crate::{
+    error::MinusError, input::InputEvent, state::SCROLL_FRAME_INTERVAL, DynamicPrompt,
+    EdgeFeedback, ExitReason, PagerObservation, PagerState,
+};
 
 /// Respond based on the type of event
 ///
@@ -31,33 +39,296 @@ pub fn handle_event(
 ) -> Result<(), MinusError> {
     match ev {
         Event::SetData(text) => {
-            p.lines = text;
-            p.format_lines();
+            p.line_source = None;
+            p.set_text(text);
+            format_with_progress(&mut out, p)?;
+            p.trim_buffer();
+            p.at_bottom &= p.is_at_bottom();
+            p.apply_start_line();
+        }
+        Event::SetStartLine(line) => {
+            p.start_line = Some(line);
+            p.apply_start_line();
+        }
+        Event::SetDataKeepPosition(text) => {
+            // Keep the same line at the top of the screen, or the last line if there are
+            // less lines now
+            let top_line = p.line_at_row(p.upper_mark);
+            p.line_source = None;
+            p.set_text(text);
+            format_with_progress(&mut out, p)?;
+            p.trim_buffer();
+            let last_line = p.lines.lines().count().saturating_sub(1);
+            p.upper_mark = p.row_of_line(top_line.min(last_line));
+            p.at_bottom &= p.is_at_bottom();
+
+            // format_lines has already run the search again
+            #[cfg(feature = "search")]
+            p.mark_first_visible_match();
         }
+        Event::InsertLines(idx, lines) => p.insert_lines(idx, &lines),
+        Event::DeleteLines(range) => p.delete_lines(range),
+        Event::AddBuffer(text) => p.add_buffer(text),
+        Event::NextBuffer | Event::UserInput(InputEvent::NextBuffer) => p.cycle_buffer(true),
+        Event::PrevBuffer | Event::UserInput(InputEvent::PrevBuffer) => p.cycle_buffer(false),
         Event::UserInput(InputEvent::Exit) => {
-            p.exit();
-            is_exitted.store(true, std::sync::atomic::Ordering::SeqCst);
-            term::cleanup(&mut out, &p.exit_strategy, true)?;
+            quit(out, p, is_exitted, ExitReason::UserQuit, false)?;
+        }
+        Event::UserInput(InputEvent::ExitAndDump) => {
+            quit(out, p, is_exitted, ExitReason::UserQuit, true)?;
+        }
+        Event::InputFinished => {
+            p.input_finished = true;
+            quit_if_finished(out, p, is_exitted)?;
+        }
+        Event::SetExitOnFinish(val) => {
+            p.exit_on_finish = val;
+            quit_if_finished(out, p, is_exitted)?;
+        }
+        Event::UserInput(InputEvent::UpdateUpperMark(um)) => {
+            // Any other scroll stops a smooth scroll where it is, and ends a drag
+            p.scroll_animation = None;
+            p.drag_row = None;
+            p.read_source(um.saturating_add(p.content_rows()));
+            // Scrolling past either end leaves the view where it is. Only the user is told about
+            // it, not an application that scrolls with Pager::scroll_to
+            let upper_mark = p.upper_mark;
+            let max_upper_mark = p.max_upper_mark();
+            if um.max(p.frozen_rows()).min(max_upper_mark) == upper_mark
+                && (p.is_at_top() || upper_mark == max_upper_mark)
+            {
+                edge_feedback(out, p)?;
+            }
+            start_scroll(out, p, um, is_exitted)?;
+        }
+        Event::ScrollTo(um) => {
+            p.scroll_animation = None;
+            p.drag_row = None;
+            p.read_source(um.saturating_add(p.content_rows()));
+            start_scroll(out, p, um, is_exitted)?;
+        }
+        Event::ScrollFrame => {
+            if let Some(animation) = p.scroll_animation.as_mut() {
+                let now = Instant::now();
+                let (um, done) = animation.position(now);
+                animation.next_frame = now + SCROLL_FRAME_INTERVAL;
+                if done {
+                    p.scroll_animation = None;
+                }
+                scroll_to(out, p, um, is_exitted)?;
+            }
+        }
+        Event::UserInput(InputEvent::ScrollBy(delta)) => {
+            handle_event(
+                Event::UserInput(InputEvent::UpdateUpperMark(
+                    p.upper_mark.saturating_add_signed(delta),
+                )),
+                out,
+                p,
+                is_exitted,
+                #[cfg(feature = "search")]
+                user_input_active,
+            )?;
+        }
+        Event::ScrollBy(delta) => {
+            handle_event(
+                Event::ScrollTo(p.upper_mark.saturating_add_signed(delta)),
+                out,
+                p,
+                is_exitted,
+                #[cfg(feature = "search")]
+                user_input_active,
+            )?;
+        }
+        Event::UserInput(InputEvent::UpdateCursor(row)) => {
+            if p.cursor_line.is_some() {
+                p.read_source(row.saturating_add(1));
+                p.move_cursor(row);
+                p.run_reach_callbacks();
+                quit_if_finished(out, p, is_exitted)?;
+            }
+        }
+        Event::UserInput(InputEvent::UpdateLeftMark(lm)) => {
+            let lm = lm.min(p.max_left_mark());
+            if lm != p.left_mark {
+                p.left_mark = lm;
+                if !p.line_wrapping {
+                    p.format_lines();
+                }
+            }
+        }
+        Event::UserInput(InputEvent::GotoPercent(percent)) => {
+            // Use the formatted rows so that wrapped lines are accounted for
+            let um = p.num_lines().saturating_mul(percent.min(100)) / 100;
+            handle_event(
+                Event::UserInput(InputEvent::UpdateUpperMark(um)),
+                out,
+                p,
+                is_exitted,
+                #[cfg(feature = "search")]
+                user_input_active,
+            )?;
+        }
+        Event::UserInput(InputEvent::StartDrag(row)) => p.drag_row = Some(row),
+        Event::UserInput(InputEvent::EndDrag) => p.drag_row = None,
+        Event::UserInput(InputEvent::Drag(row)) => {
+            if let Some(last) = p.drag_row {
+                // Dragging down pulls the text down along with the mouse, revealing the lines
+                // above
+                let um = p.upper_mark.saturating_add(last).saturating_sub(row);
+                handle_event(
+                    Event::UserInput(InputEvent::UpdateUpperMark(um)),
+                    out,
+                    p,
+                    is_exitted,
+                    #[cfg(feature = "search")]
+                    user_input_active,
+                )?;
+                // The scroll ended the drag, the drag itself carries on
+                p.drag_row = Some(row);
+            }
+        }
+        Event::UserInput(ev @ (InputEvent::NextParagraph(n) | InputEvent::PrevParagraph(n))) => {
+            let forward = matches!(ev, InputEvent::NextParagraph(_));
+            let line = p.paragraph_boundary(forward, n);
+            handle_event(
+                Event::UserInput(InputEvent::UpdateUpperMark(p.row_of_line(line))),
+                out,
+                p,
+                is_exitted,
+                #[cfg(feature = "search")]
+                user_input_active,
+            )?;
+        }
+        #[cfg(feature = "search")]
+        Event::UserInput(InputEvent::NextHighlight(group)) => {
+            if let Some(row) = search::next_highlight(p, group) {
+                handle_event(
+                    Event::UserInput(InputEvent::UpdateUpperMark(row)),
+                    out,
+                    p,
+                    is_exitted,
+                    user_input_active,
+                )?;
+            }
+        }
+        #[cfg(feature = "search")]
+        Event::UserInput(ev @ (InputEvent::NextSection(n) | InputEvent::PrevSection(n))) => {
+            let forward = matches!(ev, InputEvent::NextSection(_));
+            if let Some(row) = search::next_section(p, forward, n) {
+                handle_event(
+                    Event::UserInput(InputEvent::UpdateUpperMark(row)),
+                    out,
+                    p,
+                    is_exitted,
+                    user_input_active,
+                )?;
+            }
         }
-        Event::UserInput(InputEvent::UpdateUpperMark(mut um)) => {
-            display::draw_for_change(out, p, &mut um)?;
-            p.upper_mark = um;
+        #[cfg(feature = "search")]
+        Event::SetSectionPattern(pattern) => p.section_pattern = pattern,
+        #[cfg(feature = "clipboard")]
+        Event::UserInput(InputEvent::CopyVisible) => {
+            p.message = Some(super::clipboard::copy_visible(p));
+            p.format_prompt();
+        }
+        #[cfg(feature = "clipboard")]
+        Event::UserInput(InputEvent::CopyLineRef) => {
+            p.message = Some(super::clipboard::copy_line_ref(p));
+            p.format_prompt();
         }
+        #[cfg(feature = "clipboard")]
+        Event::SetLineRefTemplate(template) => p.line_ref_template = template,
+        // Taken when the pager starts
+        #[cfg(feature = "follow_file")]
+        Event::FollowFile(path) => p.followed_file = Some(path),
         Event::UserInput(InputEvent::RestorePrompt) => {
             // Set the message to None and new messages to false as all messages have been shown
             p.message = None;
             p.format_prompt();
         }
         Event::UserInput(InputEvent::UpdateTermArea(c, r)) => {
-            p.rows = r;
-            p.cols = c;
             // Readjust the text wrapping for the new number of columns
-            p.format_lines();
+            p.resize(c, r);
+            p.observe(|| PagerObservation::Resized { cols: c, rows: r });
         }
         Event::UserInput(InputEvent::UpdateLineNumber(l)) => {
-            p.line_numbers = l;
+            // Users cannot change line numbers that were locked with AlwaysOn or AlwaysOff
+            if p.line_numbers.is_invertible() {
+                p.line_numbers = l;
+                p.format_lines();
+            }
+        }
+        Event::UserInput(InputEvent::UpdateLineWrapping(val)) | Event::SetLineWrapping(val) => {
+            // Keep the line at the top of the screen in place after reformatting
+            let top_line = p.line_at_row(p.upper_mark);
+            p.line_wrapping = val;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::SetWrapMode(mode) => {
+            // Lines may take up a different number of rows, keep the line at the top in place
+            let top_line = p.line_at_row(p.upper_mark);
+            p.wrap_mode = mode;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        // Only what is left at the end of some rows changes, not the number of rows
+        Event::SetWideCharWrap(val) => {
+            p.wide_char_wrap = val;
+            p.format_lines();
+        }
+        Event::SetAmbiguousWidth(val) => {
+            // Lines with such characters may need more or less rows
+            let top_line = p.line_at_row(p.upper_mark);
+            p.ambiguous_width = val;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::SetWrapIndicator(indicator) => {
+            // The indicator takes up columns, so lines may need more rows
+            let top_line = p.line_at_row(p.upper_mark);
+            p.wrap_indicator = indicator;
             p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
         }
+        Event::SetColor(val) => {
+            p.color_enabled = val;
+            p.format_lines();
+        }
+        Event::SetScrollbar(val) => {
+            // The scrollbar takes up a column, so lines may need more rows
+            let top_line = p.line_at_row(p.upper_mark);
+            p.scrollbar = val;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::SetMaxContentWidth(width) => {
+            let top_line = p.line_at_row(p.upper_mark);
+            p.max_content_width = width;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::UserInput(InputEvent::UpdateShowWhitespace(val)) | Event::SetShowWhitespace(val) => {
+            // Trailing spaces are dropped when wrapping, so showing them can change the number of
+            // rows. Keep the line at the top of the screen in place
+            let top_line = p.line_at_row(p.upper_mark);
+            p.show_whitespace = val;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::SetControlCharPlaceholder(val) => p.control_char_placeholder = val,
+        Event::SetCarriageReturnMode(mode) => {
+            let top_line = p.line_at_row(p.upper_mark);
+            p.carriage_return_mode = mode;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::SetReflow(val) => p.set_reflow(val),
+        Event::SetScrollPastEnd(val) => p.scroll_past_end = val,
+        Event::SetFrozenHeaderLines(lines) => p.frozen_header_lines = lines,
+        Event::SetPageOverlap(val) => p.page_overlap = val,
+        Event::SetEndOfBufferChar(c) => p.end_of_buffer_char = c,
         #[cfg(feature = "search")]
         Event::UserInput(InputEvent::Search(m)) => {
             p.search_mode = m;
@@ -66,91 +337,91 @@ pub fn handle_event(
             let mut active = lock.lock();
             *active = false;
             drop(active);
-            let string = search::fetch_input(&mut out, p.search_mode, p.rows)?;
+            let mut literal = p.search_literal;
+            let string = search::fetch_input(
+                &mut out,
+                p.search_mode,
+                p.search_keys,
+                p.prompt_row(),
+                &mut literal,
+            )?;
+            p.search_literal = literal;
             let mut active = lock.lock();
             *active = true;
             drop(active);
             cvar.notify_one();
 
             if !string.is_empty() {
-                let regex = regex::Regex::new(string.as_str());
-                if let Ok(r) = regex {
-                    p.search_term = Some(r);
-                    // Format the lines, this will automatically generate the PagerState.search_idx
-                    p.format_lines();
-                    // Reset search mark so it won't be out of bounds if we have
-                    // less matches in this search than last time
-                    p.search_mark = 0;
-                    // Move to next search match after the current upper_mark
-                    search::next_nth_match(p, 1);
-                    p.format_prompt();
-                    display::draw_full(&mut out, p)?;
-                } else {
-                    // Send invalid regex message at the prompt if invalid regex is given
-                    p.message = Some("Invalid regular expression. Press Enter".to_owned());
-                    p.format_prompt();
-                }
+                commit_search(&mut out, p, &string)?;
             }
         }
         #[cfg(feature = "search")]
-        Event::UserInput(InputEvent::NextMatch | InputEvent::MoveToNextMatch(1))
-            if p.search_term.is_some() =>
-        {
+        Event::UserInput(InputEvent::NextMatch) if p.search_term.is_some() => {
             // Go to the next match
             search::next_nth_match(p, 1);
         }
         #[cfg(feature = "search")]
-        Event::UserInput(InputEvent::PrevMatch | InputEvent::MoveToPrevMatch(1))
-            if p.search_term.is_some() =>
-        {
-            // If no matches, return immediately
-            if p.search_idx.is_empty() {
-                return Ok(());
-            }
-            // Decrement the s_mark and get the preceeding index
-            p.search_mark = p.search_mark.saturating_sub(1);
-            if let Some(y) = p.search_idx.iter().nth(p.search_mark) {
-                // If the index is less than or equal to the upper_mark, then set y to the new upper_mark
-                if *y < p.upper_mark {
-                    p.upper_mark = *y;
-                    p.format_prompt();
-                }
-            }
+        Event::UserInput(InputEvent::MoveToNextMatch(n)) if p.search_term.is_some() => {
+            search::next_nth_match(p, n);
         }
         #[cfg(feature = "search")]
-        Event::UserInput(InputEvent::MoveToNextMatch(n)) if p.search_term.is_some() => {
-            // Go to the next match
-            search::next_nth_match(p, n.saturating_sub(1));
+        Event::UserInput(InputEvent::PrevMatch) if p.search_term.is_some() => {
+            search::prev_nth_match(p, 1);
         }
         #[cfg(feature = "search")]
         Event::UserInput(InputEvent::MoveToPrevMatch(n)) if p.search_term.is_some() => {
-            // If no matches, return immediately
-            if p.search_idx.is_empty() {
-                return Ok(());
-            }
-            // Decrement the s_mark and get the preceeding index
-            p.search_mark = p.search_mark.saturating_sub(n);
-            if let Some(y) = p.search_idx.iter().nth(p.search_mark) {
-                // If the index is less than or equal to the upper_mark, then set y to the new upper_mark
-                if *y < p.upper_mark {
-                    p.upper_mark = *y;
-                    p.format_prompt();
-                }
-            }
+            search::prev_nth_match(p, n);
+        }
+        #[cfg(feature = "search")]
+        Event::UserInput(InputEvent::GotoMatch(n)) if p.search_term.is_some() => {
+            search::goto_nth_match(p, n);
+        }
+        #[cfg(feature = "search")]
+        Event::UserInput(InputEvent::ClearSearch) if p.search_term.is_some() => {
+            p.search_term = None;
+            p.search_mark = 0;
+            // Reformat to remove the highlights, this also clears PagerState::search_idx
+            p.format_lines();
         }
 
         Event::AppendData(text) => {
-            let append_style = p.append_str(text.as_str());
-
-            if let AppendStyle::FullRedraw = append_style {
-                p.format_lines();
-            }
-            if let AppendStyle::PartialUpdate((fmt_line, num_unterminated)) = append_style {
-                p.append_str_on_unterminated(fmt_line, num_unterminated);
+            p.append_to_last_buffer(&text);
+        }
+        // Bytes and styled lines are handled like the text they turn into
+        ev @ (Event::AppendDataBytes(_) | Event::SetDataBytes(_) | Event::AppendStyledLine(_)) => {
+            handle_event(
+                decode_text(p, ev),
+                out,
+                p,
+                is_exitted,
+                #[cfg(feature = "search")]
+                user_input_active,
+            )?;
+        }
+        // The events of a batch are applied in order, until one of them quits the pager
+        Event::Batch(events) => {
+            for ev in events {
+                handle_event(
+                    ev,
+                    out,
+                    p,
+                    is_exitted,
+                    #[cfg(feature = "search")]
+                    user_input_active,
+                )?;
+                if is_exitted.load(std::sync::atomic::Ordering::SeqCst) {
+                    break;
+                }
             }
         }
         Event::SetPrompt(prompt) => {
             p.prompt = prompt;
+            p.prompt_right.clear();
+            p.format_prompt();
+        }
+        Event::SetPromptSegments(left, right) => {
+            p.prompt = left;
+            p.prompt_right = right;
             p.format_prompt();
         }
         Event::SendMessage(message) => {
@@ -161,12 +432,528 @@ pub fn handle_event(
             p.line_numbers = ln;
             p.format_lines();
         }
+        Event::SetContinuationNumberStyle(style) => {
+            // Wrapped lines keep their number of rows
+            p.continuation_number_style = style;
+            p.format_lines();
+        }
+        Event::SetLineNumberStyle(style) => {
+            // Keep the line at the top of the screen in place after reformatting
+            let top_line = p.line_at_row(p.upper_mark);
+            p.line_number_style = style;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::PauseRendering => p.rendering_paused = true,
+        Event::ResumeRendering => {
+            p.rendering_paused = false;
+            // Draw everything that changed while rendering was paused in one go
+            display::draw_full(&mut out, p)?;
+        }
+        Event::SetRedrawInterval(interval) => {
+            p.redraw_interval = interval;
+            // Without an interval, nothing would draw a redraw that is still deferred
+            if interval.is_none() && p.redraw_pending {
+                p.redraw_pending = false;
+                if !p.rendering_paused {
+                    display::draw_full(&mut out, p)?;
+                }
+            }
+        }
+        Event::SetResizeDebounce(debounce) => p.resize_debounce = debounce,
+        Event::SetAppendCoalesce(window) => p.append_coalesce = window,
+        Event::SetSmoothScroll(duration) => {
+            p.smooth_scroll = duration;
+            // Finish a running smooth scroll right away
+            if duration.is_none() {
+                if let Some(animation) = p.scroll_animation.take() {
+                    scroll_to(out, p, animation.to, is_exitted)?;
+                }
+            }
+        }
+        Event::SetTickInterval(interval) => {
+            p.tick_interval = interval;
+            p.next_tick = interval.map(|i| Instant::now() + i);
+        }
+        Event::Tick => {
+            p.next_tick = p.tick_interval.map(|i| Instant::now() + i);
+            p.format_prompt();
+        }
+        Event::SetDeterministicMode(val) => {
+            p.deterministic_mode = val;
+            // A deferred redraw would otherwise only happen once its deadline has passed
+            p.redraw_pending = false;
+            if p.resize_deadline.is_some() {
+                p.format_lines();
+            }
+        }
+        Event::SetTerminalSize(cols, rows) => {
+            // Unlike a resize of the terminal, this is never deferred
+            let top_line = p.line_at_row(p.upper_mark);
+            p.cols = cols;
+            p.rows = rows;
+            p.format_lines();
+            p.upper_mark = p.row_of_line(top_line);
+        }
+        Event::SetExitKeys(keys) => p.exit_keys = keys,
+        Event::SetDumpExitKeys(keys) => p.dump_exit_keys = keys,
+        Event::SetCountPrefixEnabled(val) => {
+            p.count_prefix_enabled = val;
+            if !val {
+                p.prefix_num.clear();
+                p.format_prompt();
+            }
+        }
+        Event::SetCountDisplay(val) => {
+            p.count_display = val;
+            p.format_prompt();
+        }
+        Event::SetCountTimeout(val) => p.count_timeout = val,
+        Event::UserInput(InputEvent::ClearPrefix) => {
+            p.prefix_num.clear();
+            p.format_prompt();
+        }
+        // Switching the capture at runtime is done by the reactor, as the terminal may not be set
+        // up yet when this is called
+        Event::SetMouseCapture(enabled) => p.mouse_capture = enabled,
+        Event::SetAlternateScreen(enabled) => p.alternate_screen = enabled,
+        Event::SetPanicCleanup(enabled) => p.panic_cleanup = enabled,
+        Event::SetExternalInput(enabled) => p.external_input = enabled,
+        // Ignored by the reactor once the pager runs, the output can't change then
+        Event::SetOutput(output) => p.output = Some(output),
+        // Events fed by the application are handled like those read from the terminal
+        Event::FeedInput(ev) => {
+            if let Some(iev) = p.classify_input(ev) {
+                handle_event(
+                    Event::UserInput(iev),
+                    out,
+                    p,
+                    is_exitted,
+                    #[cfg(feature = "search")]
+                    user_input_active,
+                )?;
+            }
+        }
+        Event::SetHighlightRow(row) => p.highlight_row = row,
+        Event::SetHighlightColor(color) => p.highlight_color = color,
+        Event::SetCursorMode(val) => {
+            // The cursor starts at the top of the screen
+            p.cursor_line = val.then_some(p.upper_mark.min(p.max_upper_mark()));
+            p.selection_anchor = None;
+        }
+        Event::SetCursorColor(color) => p.cursor_color = color,
+        Event::SetSelectionColor(color) => p.selection_color = color,
+        Event::SetOnYank(cb) => p.on_yank = Some(cb),
+        Event::SetEnterAction(action) => p.enter_action = action,
+        Event::SetPromptPosition(position) => p.prompt_position = position,
+        Event::SetEdgeFeedback(val) => p.edge_feedback = val,
+        Event::SetOnSelect(cb) => p.on_select = Some(cb),
+        Event::SetEventObserver(cb) => {
+            p.observed_upper_mark = p.upper_mark;
+            p.event_observer = Some(cb);
+        }
+        Event::UserInput(InputEvent::Select(line)) => {
+            if let Some(func) = p.on_select.as_mut() {
+                func(line);
+            }
+        }
+        Event::UserInput(InputEvent::ToggleSelection) => {
+            p.selection_anchor = match p.selection_anchor {
+                Some(_) => None,
+                None => p.cursor_line,
+            };
+        }
+        Event::UserInput(InputEvent::ClearSelection) => p.selection_anchor = None,
+        Event::UserInput(InputEvent::YankSelection) => {
+            if let Some(text) = p.selected_text() {
+                p.selection_anchor = None;
+                if let Some(func) = p.on_yank.as_mut() {
+                    func(text.clone());
+                }
+                #[cfg(feature = "clipboard")]
+                {
+                    p.message = Some(super::clipboard::copy_lines(p, text));
+                }
+                #[cfg(not(feature = "clipboard"))]
+                {
+                    let num_lines = text.split('\n').count();
+                    p.message = Some(format!("Yanked {num_lines} lines"));
+                }
+                p.format_prompt();
+            }
+        }
+        Event::SetScrollOff(val) => p.scroll_off = val,
+        Event::SetDragScroll(val) => p.drag_scroll = val,
+        Event::SetMouseScrollAmount(val) => p.mouse_scroll_amount = val,
+        Event::SetHorizontalMouseScrollAmount(val) => p.horizontal_mouse_scroll_amount = val,
+        Event::SetScrollWrap(val) => p.scroll_wrap = val,
+        Event::SetPromptStyle(style) => p.prompt_style = style,
+        Event::ResetDisplayOptions => p.reset_display_options(),
+        Event::SetMaxBufferBytes(max_bytes) => {
+            p.max_buffer_bytes = max_bytes;
+            p.trim_buffer();
+        }
+        Event::SetTextDirection(dir) => {
+            p.text_direction = dir;
+            p.format_lines();
+        }
+        Event::SetLazyFormatting(val) => {
+            p.lazy_formatting = val;
+            p.format_lines();
+        }
+        Event::SetTruncationMarker(marker) => {
+            p.truncation_marker = marker;
+            p.format_lines();
+        }
         Event::SetExitStrategy(es) => p.exit_strategy = es,
+        Event::SetExitDumpRemaining(val) => p.exit_dump_remaining = val,
+        Event::SetExitDumpScope(val) => p.exit_dump_scope = val,
+        #[cfg(feature = "static_output")]
+        Event::SetRunNoOverflow(val) => {
+            p.overflow_behavior = if val {
+                OverflowBehavior::PageIfOverflow
+            } else {
+                OverflowBehavior::AlwaysPage
+            };
+        }
         #[cfg(feature = "static_output")]
-        Event::SetRunNoOverflow(val) => p.run_no_overflow = val,
+        Event::SetOverflowBehavior(val) => p.overflow_behavior = val,
+        #[cfg(feature = "search")]
+        Event::SetSearchWrap(val) => p.search_wrap = val,
+        #[cfg(feature = "search")]
+        Event::SetCenterOnMatch(val) => p.center_on_match = val,
+        // The highlights are left out when drawing, hence nothing has to be formatted again
+        #[cfg(feature = "search")]
+        Event::UserInput(InputEvent::UpdateHighlightVisible(val))
+        | Event::SetHighlightVisible(val) => p.highlight_visible = val,
+        #[cfg(feature = "search")]
+        Event::UserInput(InputEvent::UpdateSearchFocus(val)) | Event::SetSearchFocus(val) => {
+            p.search_focus = val;
+        }
+        #[cfg(feature = "search")]
+        Event::SetSearchKeys(forward, reverse) => p.search_keys = (forward, reverse),
+        #[cfg(feature = "search")]
+        Event::SetSearchMultiline(val) => {
+            p.search_multiline = val;
+            p.format_lines();
+        }
+        #[cfg(feature = "search")]
+        Event::SetMaxSearchMatches(max) => {
+            p.max_search_matches = max;
+            p.format_lines();
+            p.search_mark = p.search_mark.min(p.search_idx.len().saturating_sub(1));
+            p.format_prompt();
+        }
+        #[cfg(feature = "search")]
+        Event::SetSearchRange(range) => {
+            p.search_range = range.map(|(start, end)| (start.min(end), start.max(end)));
+            p.format_lines();
+            p.search_mark = p.search_mark.min(p.search_idx.len().saturating_sub(1));
+            p.format_prompt();
+        }
+        #[cfg(feature = "search")]
+        Event::SetCurrentMatchStyle(style) => p.current_match_style = style,
+        #[cfg(feature = "search")]
+        Event::SetOtherMatchStyle(style) => {
+            p.other_match_style = style;
+            p.match_escapes = search::style_escapes(&style);
+            p.format_lines();
+        }
+        #[cfg(feature = "search")]
+        Event::SetSearchLiteral(val) => p.search_literal = val,
+        #[cfg(feature = "search")]
+        Event::SetKeepUnmatchedSearch(val) => p.keep_unmatched_search = val,
+        #[cfg(feature = "search")]
+        Event::SetBeepOnNoMatch(val) => p.beep_on_no_match = val,
+        #[cfg(feature = "search")]
+        Event::SetHighlightGroups(groups) => {
+            p.highlight_groups = groups;
+            p.format_lines();
+        }
         Event::SetInputClassifier(clf) => p.input_classifier = clf,
-        Event::AddExitCallback(cb) => p.exit_callbacks.push(cb),
-        Event::UserInput(_) => {}
+        Event::AddExitCallback(mut cb) => p.exit_callbacks.push(Box::new(move |_| cb())),
+        Event::AddExitCallbackWithReason(cb) => p.exit_callbacks.push(cb),
+        Event::WithState(func) => func(p),
+        Event::SetAppendFeedback(cb) => p.append_feedback = Some(cb),
+        Event::SetOnReachBottom(cb) => p.on_reach_bottom = Some(cb),
+        Event::SetOnReachTop(cb) => p.on_reach_top = Some(cb),
+        Event::SetOnBeforeReflow(cb) => p.on_before_reflow = Some(cb),
+        Event::SetOnAfterReflow(cb) => p.on_after_reflow = Some(cb),
+        Event::SetOnGutterMark(cb) => p.on_gutter_mark = Some(cb),
+        Event::SetOnLinkActivate(cb) => p.on_link_activate = Some(cb),
+        Event::SetLineSource(source) => p.set_line_source(source),
+        Event::SetHighlighter(highlighter) => {
+            p.highlighter = Some(highlighter);
+            p.format_lines();
+        }
+        Event::SetLineStyler(func) => {
+            p.line_styler = Some(func);
+            p.format_lines();
+        }
+        Event::SetDynamicPrompt(func) => {
+            p.dynamic_prompt = Some(func);
+            p.format_prompt();
+        }
+        Event::SetStatusBar(text) => {
+            p.status_bar = text.map(|text| -> DynamicPrompt { Box::new(move |_| text.clone()) });
+        }
+        Event::SetDynamicStatusBar(func) => p.status_bar = Some(func),
+        Event::SetGutterMarkGlyph(glyph) => {
+            p.gutter_mark_glyph = glyph;
+            p.format_lines();
+        }
+        Event::UserInput(InputEvent::ToggleGutterMark(idx)) => {
+            let marked = !p.gutter_marks.remove(&idx);
+            if marked {
+                p.gutter_marks.insert(idx);
+            }
+            if let Some(func) = p.on_gutter_mark.as_mut() {
+                func(idx, marked);
+            }
+            if p.gutter_mark_glyph.is_some() {
+                p.format_lines();
+            }
+        }
+        Event::UserInput(InputEvent::ActivateLink(formatted_row, column)) => {
+            if let Some(uri) = p.hyperlink_at(formatted_row, column) {
+                if let Some(func) = p.on_link_activate.as_mut() {
+                    func(uri);
+                }
+            }
+        }
+        // The screen is drawn again after the event is handled
+        Event::Redraw | Event::UserInput(_) => {}
+    }
+    p.observe_scroll();
+    Ok(())
+}
+
+/// Quits the pager for the given reason and restores the terminal
+///
+/// The text is written to the main screen if `dump` is `true` or
+/// [`PagerState::exit_dump_remaining`] is set. It always goes to stdout, also when the pager is
+/// drawn on a custom output.
+fn quit(
+    mut out: &mut impl Write,
+    p: &mut PagerState,
+    is_exitted: &Arc<AtomicBool>,
+    reason: ExitReason,
+    dump: bool,
+) -> Result<(), MinusError> {
+    p.exit(reason);
+    is_exitted.store(true, std::sync::atomic::Ordering::SeqCst);
+    end_flash(out, p)?;
+    if dump || p.exit_dump_remaining {
+        // The remaining lines must be written after the terminal has switched back to the
+        // main screen but before the process is possibly killed by the exit strategy
+        term::cleanup(
+            &mut out,
+            &crate::ExitStrategy::PagerQuit,
+            true,
+            !p.custom_output,
+            p.alternate_screen,
+        )?;
+        p.ensure_formatted(0, p.num_lines());
+        if p.custom_output {
+            display::write_remaining_lines(&mut std::io::stdout(), p)?;
+        } else {
+            display::write_remaining_lines(&mut out, p)?;
+        }
+        term::cleanup(
+            &mut out,
+            &p.exit_strategy,
+            false,
+            !p.custom_output,
+            p.alternate_screen,
+        )?;
+    } else {
+        term::cleanup(
+            &mut out,
+            &p.exit_strategy,
+            true,
+            !p.custom_output,
+            p.alternate_screen,
+        )?;
+    }
+    Ok(())
+}
+
+/// Scroll to the given row, smoothly if [`PagerState::smooth_scroll`] is set
+fn start_scroll(
+    out: &mut impl Write,
+    p: &mut PagerState,
+    um: usize,
+    is_exitted: &Arc<AtomicBool>,
+) -> Result<(), MinusError> {
+    if let Some(animation) = p.start_scroll_animation(um) {
+        p.scroll_animation = Some(animation);
+        Ok(())
+    } else {
+        scroll_to(out, p, um, is_exitted)
+    }
+}
+
+/// Move the top of the screen to the given row and draw the rows that came into view
+fn scroll_to(
+    mut out: &mut impl Write,
+    p: &mut PagerState,
+    mut um: usize,
+    is_exitted: &Arc<AtomicBool>,
+) -> Result<(), MinusError> {
+    // The text is redrawn once it has been formatted for the new terminal size
+    let can_draw = !p.rendering_paused && p.resize_deadline.is_none();
+    let has_highlight = p.highlight_row.is_some() || p.cursor_line.is_some();
+    if (p.redraw_pending || has_highlight) && can_draw {
+        // The screen is out of date or a highlighted row has new content, so redrawing
+        // only the changed rows is not enough
+        p.upper_mark = um;
+        p.keep_cursor_in_view();
+        display::draw_full(&mut out, p)?;
+        um = p.upper_mark;
+    } else if can_draw {
+        display::draw_for_change(out, p, &mut um)?;
+    }
+    p.upper_mark = um;
+    p.keep_cursor_in_view();
+    p.run_reach_callbacks();
+    quit_if_finished(out, p, is_exitted)
+}
+
+/// Quits the pager if [`PagerState::exit_on_finish`] is set, the input has finished and the end
+/// of the text is on the screen
+fn quit_if_finished(
+    out: &mut impl Write,
+    p: &mut PagerState,
+    is_exitted: &Arc<AtomicBool>,
+) -> Result<(), MinusError> {
+    if p.exit_on_finish && p.input_finished && p.is_at_bottom() {
+        quit(out, p, is_exitted, ExitReason::EndOfInput, false)?;
+    }
+    Ok(())
+}
+
+/// Text larger than this many bytes shows its formatting progress in the prompt
+const LARGE_TEXT_BYTES: usize = 1024 * 1024;
+
+/// Formats the text, showing the progress in place of the prompt if the text is large enough to
+/// take a noticeable amount of time
+///
+/// Nothing is shown before the screen has been drawn for the first time, as the terminal may not
+/// have been set up yet.
+fn format_with_progress(out: &mut impl Write, p: &mut PagerState) -> Result<(), MinusError> {
+    if p.lines.len() < LARGE_TEXT_BYTES
+        || p.last_draw.is_none()
+        || p.rendering_paused
+        || p.formats_lazily()
+    {
+        p.format_lines();
+        return Ok(());
+    }
+    let rows: u16 = p
+        .prompt_row()
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    let style = p.displayed_prompt_style();
+    let mut result = Ok(());
+    p.format_lines_with_progress(|percent| {
+        if result.is_ok() {
+            result = display::write_progress(out, &format!("Formatting… {percent}%"), &style, rows);
+        }
+    });
+    result?;
+    // Put the prompt back, in case the next redraw is deferred
+    display::write_progress(out, &p.displayed_prompt, &style, rows)
+}
+
+/// Tells the user that the text can't be scrolled further, as set by
+/// [`PagerState::edge_feedback`]
+fn edge_feedback(out: &mut impl Write, p: &mut PagerState) -> Result<(), MinusError> {
+    /// How long the screen stays inverted for [`EdgeFeedback::Flash`]
+    const FLASH_DURATION: Duration = Duration::from_millis(50);
+
+    match p.edge_feedback {
+        EdgeFeedback::None => return Ok(()),
+        EdgeFeedback::Bell => write!(out, "\x07")?,
+        EdgeFeedback::Flash => {
+            // Turn on reverse video for the whole screen, it is turned off again by end_flash
+            if p.flash_deadline.is_none() {
+                write!(out, "\x1b[?5h")?;
+            }
+            p.flash_deadline = Some(Instant::now() + FLASH_DURATION);
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Turns bytes and styled lines into the events for the text they stand for
+///
+/// Styled lines become text with escape sequences. This is the only place where bytes given to
+/// the pager are decoded, so that [`PagerState::incomplete_bytes`] is always completed by the
+/// bytes that were given next. Other events are returned as they are.
+pub fn decode_text(p: &mut PagerState, ev: Event) -> Event {
+    match ev {
+        Event::AppendDataBytes(bytes) => Event::AppendData(p.decode_bytes(&bytes, true)),
+        Event::SetDataBytes(bytes) => Event::SetData(p.decode_bytes(&bytes, false)),
+        Event::AppendStyledLine(spans) => Event::AppendData(text::styled_line(&spans)),
+        ev => ev,
+    }
+}
+
+/// Turns the screen inverted by [`EdgeFeedback::Flash`] back to normal, if it is
+pub fn end_flash(out: &mut impl Write, p: &mut PagerState) -> Result<(), MinusError> {
+    if p.flash_deadline.take().is_some() {
+        write!(out, "\x1b[?5l")?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Runs the search for `query` that the user has entered
+///
+/// If nothing matches, a message says so and the terminal bell rings if
+/// [`PagerState::beep_on_no_match`] is set. Unless [`PagerState::keep_unmatched_search`] is set,
+/// the previous search is then kept instead.
+#[cfg(feature = "search")]
+fn commit_search(out: &mut impl Write, p: &mut PagerState, query: &str) -> Result<(), MinusError> {
+    let Ok(regex) = search::compile_query(query, p.search_literal) else {
+        // Send invalid regex message at the prompt if invalid regex is given
+        p.message = Some("Invalid regular expression. Press Enter".to_owned());
+        p.format_prompt();
+        return Ok(());
+    };
+    let previous = p.search_term.replace(regex);
+    let previous_mark = p.search_mark;
+    // Format the lines, this will automatically generate the PagerState.search_idx
+    p.format_lines();
+    p.observe(|| PagerObservation::Searched {
+        query: query.to_string(),
+        matches: p.search_idx.len(),
+    });
+    if p.search_idx.is_empty() {
+        if p.beep_on_no_match {
+            write!(out, "\x07")?;
+        }
+        if !p.keep_unmatched_search {
+            p.search_term = previous;
+            p.search_mark = previous_mark;
+            p.format_lines();
+        }
+        p.message = Some(format!("Pattern not found: {query}"));
+        p.format_prompt();
+        return Ok(());
+    }
+    // Reset search mark so it won't be out of bounds if we have
+    // less matches in this search than last time
+    p.search_mark = 0;
+    // Move to next search match after the current upper_mark
+    search::next_nth_match_after(p, p.upper_mark, 1);
+    if p.search_matches_capped() {
+        p.message = Some(format!("Showing the first {} matches", p.search_idx.len()));
+        p.format_prompt();
+    }
+    if !p.rendering_paused {
+        display::draw_full(out, p)?;
     }
     Ok(())
 }
@@ -174,7 +961,9 @@ pub fn handle_event(
 #[cfg(test)]
 mod tests {
     use super::super::events::Event;
-    use super::handle_event;
+    use super::{end_flash, handle_event};
+    #[cfg(feature = "static_output")]
+    use crate::OverflowBehavior;
     use crate::{ExitStrategy, PagerState};
     use std::sync::{atomic::AtomicBool, Arc};
     #[cfg(feature = "search")]
@@ -279,11 +1068,48 @@ mod tests {
     #[cfg(feature = "static_output")]
     fn set_run_no_overflow() {
         let mut ps = PagerState::new().unwrap();
-        let ev = Event::SetRunNoOverflow(false);
+        let mut out = Vec::new();
+
+        // The old flag maps to the matching behavior
+        for (ev, behavior) in [
+            (
+                Event::SetRunNoOverflow(true),
+                OverflowBehavior::PageIfOverflow,
+            ),
+            (Event::SetRunNoOverflow(false), OverflowBehavior::AlwaysPage),
+            (
+                Event::SetOverflowBehavior(OverflowBehavior::PrintIfFits),
+                OverflowBehavior::PrintIfFits,
+            ),
+        ] {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+            assert_eq!(ps.overflow_behavior, behavior);
+        }
+    }
+
+    #[test]
+    fn toggle_line_wrapping_keeps_top_line() {
+        let mut ps = PagerState::new().unwrap();
+        // Every line takes up two rows when wrapped
+        ps.lines = (0..20)
+            .map(|i| format!("{i}{}", "#".repeat(100)))
+            .collect::<Vec<String>>()
+            .join("\n");
+        ps.format_lines();
+        // Start at the second row of line 5
+        ps.upper_mark = 11;
         let mut out = Vec::new();
 
         handle_event(
-            ev,
+            Event::SetLineWrapping(false),
             &mut out,
             &mut ps,
             &Arc::new(AtomicBool::new(false)),
@@ -291,12 +1117,334 @@ mod tests {
             &UIA,
         )
         .unwrap();
-        assert!(!ps.run_no_overflow);
-    }
+        assert_eq!(ps.num_lines(), 20);
+        assert_eq!(ps.upper_mark, 5);
+        assert!(ps.formatted_lines[5].starts_with('5'));
+        assert!(ps.formatted_lines[5].ends_with('>'));
 
-    #[test]
-    fn set_exit_strategy() {
-        let mut ps = PagerState::new().unwrap();
+        handle_event(
+            Event::SetLineWrapping(true),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.num_lines(), 40);
+        assert_eq!(ps.upper_mark, 10);
+    }
+
+    #[test]
+    fn wrap_mode_keeps_top_line() {
+        use crate::WrapMode;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        // Each line takes three rows when broken at words, but only two at characters
+        ps.lines = (0..10)
+            .map(|i| format!("{i} abcdefghi jklmnopq"))
+            .collect::<Vec<String>>()
+            .join("\n");
+        ps.format_lines();
+        assert_eq!(ps.num_lines(), 30);
+        ps.upper_mark = 15;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::SetWrapMode(WrapMode::Character),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.num_lines(), 20);
+        assert_eq!(ps.upper_mark, 10);
+        assert_eq!(ps.formatted_lines[10], "5 abcdefgh");
+        assert_eq!(ps.formatted_lines[11], "i jklmnopq");
+
+        handle_event(
+            Event::ResetDisplayOptions,
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.wrap_mode, WrapMode::Word);
+        assert_eq!(ps.upper_mark, 15);
+    }
+
+    #[test]
+    fn wrap_indicator() {
+        use crate::{LineNumbers, WrapMode};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        ps.lines = "abcdefghijklmnopqrstuvwxyz".to_string();
+        ps.wrap_mode = WrapMode::Character;
+        ps.format_lines();
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::SetWrapIndicator(Some("> ".to_string())),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // The continuation rows have two columns less for the text
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["abcdefghij", "> klmnopqr", "> stuvwxyz"]
+        );
+
+        // With line numbers, the indicator follows the blank gutter
+        ps.cols = 20;
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.format_lines();
+        assert_eq!(
+            ps.formatted_lines,
+            vec![
+                "     1. abcdefghijkl",
+                "        > mnopqrstuv",
+                "        > wxyz"
+            ]
+        );
+        // Positions of lines are computed with the same rows
+        assert_eq!(ps.row_of_line(1), 3);
+
+        // Words are wrapped within the shorter rows as well
+        ps.line_numbers = LineNumbers::Disabled;
+        ps.wrap_mode = WrapMode::Word;
+        ps.cols = 12;
+        ps.lines = "the quick brown fox jumps".to_string();
+        ps.format_lines();
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["the quick", "> brown fox", "> jumps"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    #[allow(clippy::trivial_regex)]
+    fn clear_search() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "match\n".repeat(20);
+        ps.search_term = Some(regex::Regex::new("match").unwrap());
+        ps.format_lines();
+        ps.upper_mark = 5;
+        ps.search_mark = 5;
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::ClearSearch),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(ps.search_term.is_none());
+        assert!(ps.search_idx.is_empty());
+        assert_eq!(ps.search_mark, 0);
+        assert_eq!(ps.upper_mark, 5);
+        assert_eq!(ps.formatted_lines[0], "match");
+
+        // Clearing again without a search should do nothing
+        handle_event(
+            Event::UserInput(InputEvent::ClearSearch),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(ps.search_term.is_none());
+    }
+
+    #[test]
+    fn pause_rendering() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        let events = [
+            Event::PauseRendering,
+            Event::AppendData("Line\n".repeat(50)),
+            Event::UserInput(InputEvent::UpdateUpperMark(20)),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // The state changes but nothing is drawn
+        assert!(out.is_empty());
+        assert_eq!(ps.num_lines(), 50);
+        assert_eq!(ps.upper_mark, 20);
+
+        handle_event(
+            Event::ResumeRendering,
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(!ps.rendering_paused);
+        assert!(String::from_utf8(out).unwrap().contains("Line"));
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic_output")]
+    fn redraw_throttling() {
+        use crate::minus_core::utils::display::draw_full;
+        use std::time::Duration;
+
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        handle_event(
+            Event::SetRedrawInterval(Some(Duration::from_secs(60))),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // Nothing has been drawn yet, so the first redraw is not delayed
+        assert!(!ps.throttle_redraw());
+        assert!(ps.redraw_deadline().is_none());
+
+        draw_full(&mut out, &mut ps).unwrap();
+        assert!(ps.throttle_redraw());
+        assert!(ps.redraw_pending);
+        assert_eq!(
+            ps.redraw_deadline(),
+            Some(ps.last_draw.unwrap() + Duration::from_secs(60))
+        );
+
+        // The deferred redraw clears the pending state
+        draw_full(&mut out, &mut ps).unwrap();
+        assert!(!ps.redraw_pending);
+        assert!(ps.redraw_deadline().is_none());
+
+        // Removing the interval draws a redraw that is still deferred right away
+        assert!(ps.throttle_redraw());
+        out.clear();
+        handle_event(
+            Event::SetRedrawInterval(None),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(!out.is_empty());
+        assert!(!ps.redraw_pending);
+        assert!(!ps.throttle_redraw());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic_output")]
+    fn deterministic_mode() {
+        use crate::minus_core::utils::display::draw_full;
+        use std::time::Duration;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 20;
+        ps.rows = 5;
+        ps.lines = "first\nsecond\nthird".to_string();
+        ps.format_lines();
+        ps.redraw_interval = Some(Duration::from_secs(60));
+        let mut out = Vec::new();
+        draw_full(&mut out, &mut ps).unwrap();
+        assert!(ps.throttle_redraw());
+
+        handle_event(
+            Event::SetDeterministicMode(true),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // Redraws are no longer deferred, no matter how recent the last one was
+        assert!(!ps.redraw_pending);
+        assert!(!ps.throttle_redraw());
+        assert!(ps.redraw_deadline().is_none());
+    }
+
+    #[test]
+    fn max_buffer_bytes() {
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        let trims = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = trims.clone();
+        let mut events = vec![
+            Event::SetMaxBufferBytes(Some(50)),
+            Event::SetOnAfterReflow(Box::new(move || {
+                counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            })),
+        ];
+        // Each line is 20 bytes long
+        events.extend((0..5).map(|i| Event::AppendData(format!("{i}{}\n", "x".repeat(18)))));
+
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // Each trim brings the text a quarter below the limit, so the fourth line doesn't trim
+        // again and only the fifth one does
+        assert!(ps.lines.len() <= 50 - 50 / 4);
+        assert_eq!(ps.formatted_lines, vec![format!("4{}", "x".repeat(18))]);
+        assert_eq!(trims.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert!(ps.message.is_some());
+
+        // A single line is kept even if it is larger than the limit
+        let long_line = "y".repeat(60);
+        handle_event(
+            Event::SetData(long_line.clone()),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.lines, long_line);
+    }
+
+    #[test]
+    fn set_exit_strategy() {
+        let mut ps = PagerState::new().unwrap();
         let ev = Event::SetExitStrategy(ExitStrategy::PagerQuit);
         let mut out = Vec::new();
 
@@ -313,13 +1461,20 @@ mod tests {
     }
 
     #[test]
-    fn add_exit_callback() {
+    fn exit_dump_remaining() {
+        use crate::input::InputEvent;
+
         let mut ps = PagerState::new().unwrap();
-        let ev = Event::AddExitCallback(Box::new(|| println!("Hello World")));
+        ps.lines = (1..=15)
+            .map(|i| format!("L{i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ps.format_lines();
+        ps.exit_strategy = ExitStrategy::PagerQuit;
         let mut out = Vec::new();
 
         handle_event(
-            ev,
+            Event::SetExitDumpRemaining(true),
             &mut out,
             &mut ps,
             &Arc::new(AtomicBool::new(false)),
@@ -327,6 +1482,2691 @@ mod tests {
             &UIA,
         )
         .unwrap();
-        assert_eq!(ps.exit_callbacks.len(), 1);
+        handle_event(
+            Event::UserInput(InputEvent::Exit),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // The first 9 lines are on the screen while the last row is used by the prompt
+        let expected = "L10\nL11\nL12\nL13\nL14\nL15\n";
+        assert!(String::from_utf8(out).unwrap().ends_with(expected));
+    }
+
+    #[test]
+    fn exit_and_dump() {
+        use crate::{input::InputEvent, DumpScope};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = (1..=15)
+            .map(|i| format!("L{i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ps.format_lines();
+        ps.exit_strategy = ExitStrategy::PagerQuit;
+        ps.upper_mark = 3;
+        let mut out = Vec::new();
+
+        for ev in [
+            Event::SetExitDumpScope(DumpScope::Entire),
+            Event::UserInput(InputEvent::ExitAndDump),
+        ] {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // All lines are written, including those that were on the screen
+        let expected = format!("{}\n", ps.lines);
+        assert!(String::from_utf8(out).unwrap().ends_with(&expected));
+        assert_eq!(ps.exit_reason, Some(crate::ExitReason::UserQuit));
+    }
+
+    #[test]
+    fn gutter_marks() {
+        use crate::{input::InputEvent, LineNumbers};
+        use parking_lot::Mutex;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 80;
+        ps.line_numbers = LineNumbers::Enabled;
+        let mut out = Vec::new();
+        let toggled = Arc::new(Mutex::new(Vec::new()));
+        let toggled2 = toggled.clone();
+
+        let events = [
+            Event::SetData("A\nB\nC\n".to_string()),
+            Event::SetOnGutterMark(Box::new(move |idx, marked| {
+                toggled2.lock().push((idx, marked));
+            })),
+            Event::SetGutterMarkGlyph(Some('*')),
+            Event::UserInput(InputEvent::ToggleGutterMark(1)),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        assert!(ps.gutter_marks.contains(&1));
+        assert_eq!(ps.formatted_lines[0], "     1. A");
+        assert_eq!(ps.formatted_lines[1], "*    2. B");
+
+        handle_event(
+            Event::UserInput(InputEvent::ToggleGutterMark(1)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(ps.gutter_marks.is_empty());
+        assert_eq!(ps.formatted_lines[1], "     2. B");
+        assert_eq!(*toggled.lock(), vec![(1, true), (1, false)]);
+    }
+
+    #[test]
+    fn reach_callbacks() {
+        use crate::input::InputEvent;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        let reached_bottom = Arc::new(AtomicUsize::new(0));
+        let reached_top = Arc::new(AtomicUsize::new(0));
+        let (bottom, top) = (reached_bottom.clone(), reached_top.clone());
+
+        let events = [
+            Event::SetData("Line\n".repeat(20)),
+            Event::SetOnReachBottom(Box::new(move || {
+                bottom.fetch_add(1, Ordering::SeqCst);
+            })),
+            Event::SetOnReachTop(Box::new(move || {
+                top.fetch_add(1, Ordering::SeqCst);
+            })),
+            Event::UserInput(InputEvent::UpdateUpperMark(5)),
+            Event::UserInput(InputEvent::UpdateUpperMark(usize::MAX - 1)),
+            // Already at the bottom, hence nothing should happen
+            Event::UserInput(InputEvent::UpdateUpperMark(usize::MAX - 1)),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        assert_eq!(reached_bottom.load(Ordering::SeqCst), 1);
+        assert_eq!(reached_top.load(Ordering::SeqCst), 0);
+
+        // Appending more data should re-arm the bottom callback
+        let events = [
+            Event::AppendData("Line\n".repeat(20)),
+            Event::UserInput(InputEvent::UpdateUpperMark(usize::MAX - 1)),
+            Event::UserInput(InputEvent::UpdateUpperMark(0)),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        assert_eq!(reached_bottom.load(Ordering::SeqCst), 2);
+        assert_eq!(reached_top.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines, clippy::trivial_regex)]
+    fn reset_display_options() {
+        use crate::{input::InputEvent, LineNumbers, TextDirection};
+        use crossterm::style::{Color, ContentStyle, Stylize};
+
+        let mut ps = PagerState::new().unwrap();
+        #[cfg(feature = "search")]
+        {
+            ps.search_term = Some(regex::Regex::new("word").unwrap());
+        }
+        let mut out = Vec::new();
+        let text = (0..20)
+            .map(|i| format!("{i} {}", "word ".repeat(30)))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let events = [
+            Event::SetData(text.clone()),
+            Event::SetLineNumbers(LineNumbers::Enabled),
+            Event::SetLineWrapping(false),
+            Event::SetTruncationMarker('~'),
+            Event::SetTextDirection(TextDirection::Rtl),
+            Event::SetHighlightRow(Some(2)),
+            Event::SetHighlightColor(Color::Red),
+            Event::SetGutterMarkGlyph(Some('*')),
+            Event::SetShowWhitespace(true),
+            Event::SetLineNumberStyle(crate::LineNumberStyle {
+                separator: "| ".to_string(),
+                alignment: crate::LineNumberAlignment::Left,
+                min_width: 4,
+            }),
+            Event::SetScrollPastEnd(true),
+            Event::SetEndOfBufferChar(None),
+            Event::SetPageOverlap(4),
+            Event::SetPromptStyle(ContentStyle::new()),
+            Event::SetMouseScrollAmount(1),
+            Event::SetHorizontalMouseScrollAmount(10),
+            Event::SetScrollOff(3),
+            Event::SetColor(!crate::state::color_by_default()),
+            Event::SetPromptPosition(crate::PromptPosition::Top),
+            Event::SetScrollbar(true),
+            Event::SetMaxContentWidth(Some(40)),
+            Event::SetEdgeFeedback(crate::EdgeFeedback::Bell),
+            Event::SetCarriageReturnMode(crate::CrMode::Strip),
+            Event::SetScrollWrap(true),
+            #[cfg(feature = "search")]
+            Event::SetSearchWrap(false),
+            #[cfg(feature = "search")]
+            Event::SetCenterOnMatch(true),
+            #[cfg(feature = "search")]
+            Event::SetHighlightVisible(false),
+            #[cfg(feature = "search")]
+            Event::SetSearchFocus(true),
+            #[cfg(feature = "search")]
+            Event::SetMaxSearchMatches(Some(1)),
+            Event::SetFrozenHeaderLines(2),
+            Event::SetAmbiguousWidth(crate::AmbiguousWidth::Wide),
+            #[cfg(feature = "search")]
+            Event::SetCurrentMatchStyle(ContentStyle::new().underlined()),
+            #[cfg(feature = "search")]
+            Event::SetOtherMatchStyle(ContentStyle::new().bold()),
+            Event::SetSmoothScroll(Some(std::time::Duration::from_secs(1))),
+            Event::UserInput(InputEvent::UpdateUpperMark(5)),
+            Event::ResetDisplayOptions,
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+
+        assert_eq!(ps.line_numbers, LineNumbers::Disabled);
+        assert!(ps.line_wrapping);
+        assert_eq!(ps.truncation_marker, '>');
+        assert_eq!(ps.text_direction, TextDirection::Ltr);
+        assert_eq!(ps.highlight_row, None);
+        assert_eq!(ps.highlight_color, Color::DarkGrey);
+        assert_eq!(ps.gutter_mark_glyph, None);
+        assert_eq!(ps.line_number_style, crate::LineNumberStyle::default());
+        assert!(!ps.show_whitespace);
+        assert!(!ps.scroll_past_end);
+        assert_eq!(ps.end_of_buffer_char, Some('~'));
+        assert_eq!(ps.page_overlap, 1);
+        assert_eq!(ps.prompt_style, ContentStyle::new().reverse());
+        assert_eq!(ps.mouse_scroll_amount, 5);
+        assert_eq!(ps.horizontal_mouse_scroll_amount, 5);
+        assert_eq!(ps.scroll_off, 0);
+        assert_eq!(ps.color_enabled, crate::state::color_by_default());
+        assert_eq!(ps.prompt_position, crate::PromptPosition::Bottom);
+        assert!(!ps.scrollbar);
+        assert_eq!(ps.max_content_width, None);
+        assert_eq!(ps.edge_feedback, crate::EdgeFeedback::None);
+        assert_eq!(ps.smooth_scroll, None);
+        assert!(ps.scroll_animation.is_none());
+        assert_eq!(ps.carriage_return_mode, crate::CrMode::default());
+        assert_eq!(ps.frozen_header_lines, 0);
+        assert_eq!(ps.ambiguous_width, crate::AmbiguousWidth::default());
+        assert!(!ps.scroll_wrap);
+        #[cfg(feature = "search")]
+        {
+            assert!(ps.search_wrap);
+            assert!(!ps.center_on_match);
+            assert!(ps.highlight_visible);
+            assert!(!ps.search_focus);
+            assert_eq!(ps.max_search_matches, None);
+            assert_eq!(ps.search_idx.len(), ps.num_lines());
+            assert_eq!(
+                ps.current_match_style,
+                ContentStyle::new().black().on_yellow()
+            );
+            assert_eq!(ps.other_match_style, ContentStyle::new().reverse());
+            assert_eq!(
+                ps.match_escapes,
+                super::search::style_escapes(&ContentStyle::new().reverse())
+            );
+        }
+        // The text and the line at the top are kept
+        assert_eq!(ps.lines, text);
+        assert!(ps.formatted_lines[ps.upper_mark].starts_with("5 "));
+    }
+
+    #[test]
+    fn goto_percent() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        ps.rows = 5;
+        // 50 lines that each wrap into 2 rows
+        ps.lines = vec!["aaaaaaaa bbbbbbbb"; 50].join("\n");
+        ps.format_lines();
+        assert_eq!(ps.num_lines(), 100);
+
+        let mut out = Vec::new();
+        for (percent, upper_mark) in [(50, 50), (0, 0), (100, 96), (150, 96)] {
+            handle_event(
+                Event::UserInput(InputEvent::GotoPercent(percent)),
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+            assert_eq!(ps.upper_mark, upper_mark);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn next_highlight() {
+        use crate::input::InputEvent;
+        use regex::Regex;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 3;
+        ps.lines = "start\nERROR a\ninfo\nWARN b\nERROR c\ninfo\nWARN d\nend".to_string();
+        ps.highlight_groups = vec![
+            Regex::new("ERROR|FATAL").unwrap(),
+            Regex::new("WARN(ING)?").unwrap(),
+        ];
+        ps.format_lines();
+
+        let mut out = Vec::new();
+        let mut next = |ps: &mut PagerState, group| {
+            handle_event(
+                Event::UserInput(InputEvent::NextHighlight(group)),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                &UIA,
+            )
+            .unwrap();
+            ps.upper_mark
+        };
+
+        // Any group
+        assert_eq!(next(&mut ps, None), 1);
+        assert_eq!(next(&mut ps, None), 3);
+        assert_eq!(next(&mut ps, None), 4);
+        // Only the second group
+        ps.upper_mark = 0;
+        assert_eq!(next(&mut ps, Some(1)), 3);
+        assert_eq!(next(&mut ps, Some(1)), 6);
+        // No more matches after the last one
+        assert_eq!(next(&mut ps, Some(1)), 6);
+        // Only the first group
+        ps.upper_mark = 1;
+        assert_eq!(next(&mut ps, Some(0)), 4);
+        // Groups that don't exist are ignored
+        assert_eq!(next(&mut ps, Some(5)), 4);
+    }
+
+    #[test]
+    fn set_data_keep_position() {
+        let text = |n: usize, suffix: &str| {
+            (0..n)
+                .map(|i| format!("line {i}{suffix}"))
+                .collect::<Vec<String>>()
+                .join("\n")
+        };
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.cols = 80;
+        ps.lines = text(20, "");
+        ps.format_lines();
+        ps.upper_mark = 10;
+        let mut out = Vec::new();
+
+        #[cfg(feature = "search")]
+        {
+            ps.search_term = Some(regex::Regex::new("!+").unwrap());
+            ps.format_lines();
+        }
+
+        let mut set = |ps: &mut PagerState, text: String| {
+            handle_event(
+                Event::SetDataKeepPosition(text),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Changed text, same number of lines
+        set(&mut ps, text(20, "!"));
+        assert_eq!(ps.upper_mark, 10);
+        assert!(ps.formatted_lines[10].starts_with("line 10"));
+        #[cfg(feature = "search")]
+        {
+            // The search found the new matches, starting from the one at the top of the screen
+            assert_eq!(ps.search_idx, (0..20).collect());
+            assert_eq!(ps.search_mark, 10);
+        }
+
+        // The text shrank below the top line
+        set(&mut ps, text(8, ""));
+        assert_eq!(ps.upper_mark, 7);
+        #[cfg(feature = "search")]
+        assert!(ps.search_idx.is_empty());
+    }
+
+    #[test]
+    fn reflow_callbacks() {
+        use crate::input::InputEvent;
+        use parking_lot::Mutex;
+
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (before, after) = (calls.clone(), calls.clone());
+
+        let events = [
+            Event::SetOnBeforeReflow(Box::new(move || before.lock().push("before"))),
+            Event::SetOnAfterReflow(Box::new(move || {
+                // The text should already be formatted for the new width
+                after.lock().push("after");
+            })),
+            Event::UserInput(InputEvent::UpdateTermArea(40, 10)),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        assert_eq!(*calls.lock(), vec!["before", "after"]);
+    }
+
+    #[test]
+    fn add_exit_callback() {
+        let mut ps = PagerState::new().unwrap();
+        let ev = Event::AddExitCallback(Box::new(|| println!("Hello World")));
+        let mut out = Vec::new();
+
+        handle_event(
+            ev,
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.exit_callbacks.len(), 1);
+    }
+
+    #[test]
+    fn with_state() {
+        use parking_lot::Mutex;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 7;
+        ps.upper_mark = 3;
+        let mut out = Vec::new();
+        let seen = Arc::new(Mutex::new(None));
+        let seen2 = seen.clone();
+
+        handle_event(
+            Event::WithState(Box::new(move |ps| {
+                *seen2.lock() = Some((ps.upper_mark, ps.rows));
+            })),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(*seen.lock(), Some((3, 7)));
+        // Nothing is drawn
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn exit_callbacks_with_reason() {
+        use crate::{input::InputEvent, ExitReason};
+        use parking_lot::Mutex;
+
+        let mut ps = PagerState::new().unwrap();
+        // Quitting the process would end the test run
+        ps.exit_strategy = ExitStrategy::PagerQuit;
+        let mut out = Vec::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (plain, with_reason) = (calls.clone(), calls.clone());
+
+        let events = [
+            Event::AddExitCallback(Box::new(move || plain.lock().push(None))),
+            Event::AddExitCallbackWithReason(Box::new(move |reason| {
+                with_reason.lock().push(Some(reason));
+            })),
+            Event::UserInput(InputEvent::Exit),
+            // The callbacks only run once
+            Event::UserInput(InputEvent::Exit),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // Both kinds of callbacks run in the order they were added
+        assert_eq!(*calls.lock(), vec![None, Some(ExitReason::UserQuit)]);
+        assert_eq!(ps.exit_reason, Some(ExitReason::UserQuit));
+
+        // Exiting for another reason later doesn't run them again
+        ps.exit(ExitReason::Error);
+        assert_eq!(calls.lock().len(), 2);
+    }
+
+    #[test]
+    fn update_left_mark() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        ps.rows = 5;
+        ps.line_wrapping = false;
+        ps.lines = format!("short\n\x1b[1m{}\x1b[0m", "0123456789".repeat(3));
+        ps.format_lines();
+        let mut out = Vec::new();
+
+        let mut scroll = |ps: &mut PagerState, lm| {
+            handle_event(
+                Event::UserInput(crate::input::InputEvent::UpdateLeftMark(lm)),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        scroll(&mut ps, 5);
+        assert_eq!(ps.left_mark, 5);
+        assert_eq!(ps.formatted_lines[0], "");
+        assert_eq!(ps.formatted_lines[1], "\x1b[1m567890123\x1b[0m>");
+
+        // The longest line is 30 columns wide, so it ends at the right edge at 20. ANSI escapes
+        // don't count.
+        scroll(&mut ps, 100);
+        assert_eq!(ps.left_mark, 20);
+        assert_eq!(ps.formatted_lines[1], "\x1b[1m0123456789\x1b[0m");
+    }
+
+    #[test]
+    fn set_line_number_style() {
+        use crate::{LineNumberAlignment, LineNumberStyle, LineNumbers};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 20;
+        ps.rows = 5;
+        ps.line_numbers = LineNumbers::Enabled;
+        ps.lines = (1..=12)
+            .map(|i| format!("L{i} {}", "word ".repeat(i % 3)))
+            .collect::<Vec<String>>()
+            .join("\n");
+        ps.format_lines();
+        assert_eq!(ps.formatted_lines[0], "      1. L1 word");
+        ps.upper_mark = ps.row_of_line(7);
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::SetLineNumberStyle(LineNumberStyle {
+                separator: "| ".to_string(),
+                alignment: LineNumberAlignment::Left,
+                min_width: 4,
+            }),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // The numbers take up the minimum width, as the line count has fewer digits
+        assert_eq!(ps.formatted_lines[0], "     1   | L1 word");
+        assert_eq!(ps.line_number_width(), 11);
+        // Wrapped rows are indented by the width of the gutter
+        assert_eq!(ps.formatted_lines[1], "     2   | L2 word");
+        assert_eq!(ps.formatted_lines[2], " ".repeat(11) + "word");
+        // The same line is still at the top of the screen
+        assert_eq!(ps.line_at_row(ps.upper_mark), 7);
+    }
+
+    #[test]
+    fn set_data_shows_progress() {
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 10;
+        ps.cols = 80;
+        let large = ("word ".repeat(20) + "\n").repeat(11_000);
+        let mut out = Vec::new();
+
+        let set_data = |ps: &mut PagerState, out: &mut Vec<u8>, text: &str| {
+            handle_event(
+                Event::SetData(text.to_string()),
+                out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Nothing has been drawn yet
+        set_data(&mut ps, &mut out, &large);
+        assert!(out.is_empty());
+
+        ps.last_draw = Some(std::time::Instant::now());
+        set_data(&mut ps, &mut out, "small text");
+        assert!(out.is_empty());
+
+        set_data(&mut ps, &mut out, &large);
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.contains("Formatting… 0%"));
+        assert!(written.contains("Formatting… 99%"));
+        // The prompt is put back once formatting is done
+        assert!(written.ends_with(&format!(
+            "{}{}",
+            ps.displayed_prompt,
+            crossterm::style::Attribute::Reset
+        )));
+    }
+
+    #[test]
+    fn lazy_formatting() {
+        use crate::{input::InputEvent, minus_core::utils::display::draw_full, LineNumbers};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 10;
+        ps.cols = 20;
+        ps.line_wrapping = false;
+        ps.line_numbers = LineNumbers::Enabled;
+        let mut out = Vec::new();
+
+        let events = [
+            Event::SetLazyFormatting(true),
+            Event::SetData(
+                (1..=1000)
+                    .map(|i| format!("line {i}"))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+                    + "\n",
+            ),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // Each line gets a row, but none of them has been formatted
+        assert_eq!(ps.num_lines(), 1000);
+        assert!(ps.formatted_lines.iter().all(String::is_empty));
+
+        draw_full(&mut out, &mut ps).unwrap();
+        assert_eq!(ps.formatted_lines[0], "        1. line 1");
+        assert!(ps.formatted_lines[500].is_empty());
+
+        // Moving somewhere else formats the rows that come into view
+        handle_event(
+            Event::UserInput(InputEvent::UpdateUpperMark(500)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.formatted_lines[505], "      506. line 506");
+        assert!(ps.formatted_lines[900].is_empty());
+
+        // Appended lines are formatted right away
+        handle_event(
+            Event::AppendData("appended\n".to_string()),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.num_lines(), 1001);
+        assert!(ps.formatted_lines[1000].ends_with(". appended"));
+
+        // Wrapped lines are formatted up front
+        handle_event(
+            Event::SetLineWrapping(true),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(ps.formatted_lines.iter().all(|row| !row.is_empty()));
+    }
+
+    #[test]
+    fn scroll_by() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.upper_mark = 2;
+        let mut out = Vec::new();
+
+        for (delta, expected) in [(5, 7), (-3, 4), (-10, 0)] {
+            handle_event(
+                Event::UserInput(InputEvent::ScrollBy(delta)),
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+            assert_eq!(ps.upper_mark, expected);
+        }
+    }
+
+    #[test]
+    fn show_whitespace() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.cols = 10;
+        ps.lines = "trailing   \nwords that wrap\ntab\there".to_string();
+        ps.format_lines();
+        ps.upper_mark = 1;
+        assert_eq!(ps.formatted_lines[0], "trailing");
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::UserInput(InputEvent::UpdateShowWhitespace(true)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.formatted_lines[0], "trailing\x1b[2m··");
+        // The marker that doesn't fit is wrapped onto the next row
+        assert_eq!(ps.formatted_lines[1], "·\x1b[22m");
+        assert_eq!(ps.formatted_lines[4], "tab\x1b[2m→\x1b[22mhere");
+        // The line that was at the top of the screen still is, now that it starts at row 2
+        assert_eq!(ps.upper_mark, 2);
+    }
+
+    #[test]
+    fn resize_debounce() {
+        use crate::input::InputEvent;
+        use std::time::{Duration, Instant};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 20;
+        ps.rows = 5;
+        ps.lines = "a line that is long enough to wrap".to_string();
+        ps.format_lines();
+        let mut out = Vec::new();
+
+        let events = [
+            Event::SetResizeDebounce(Duration::from_secs(60)),
+            Event::UserInput(InputEvent::UpdateTermArea(40, 10)),
+            Event::UserInput(InputEvent::UpdateTermArea(80, 12)),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // The size is updated right away but the text is still wrapped for the old width
+        assert_eq!((ps.cols, ps.rows), (80, 12));
+        assert_eq!(ps.num_lines(), 2);
+        assert!(ps.resize_deadline.is_some());
+        assert!(!ps.finish_resize());
+
+        // Once the debounce has run out, the text is formatted for the final size
+        ps.resize_deadline = Some(Instant::now());
+        assert!(ps.finish_resize());
+        assert_eq!(ps.num_lines(), 1);
+        assert!(ps.resize_deadline.is_none());
+
+        // Resizes are never deferred in deterministic mode
+        ps.deterministic_mode = true;
+        handle_event(
+            Event::UserInput(InputEvent::UpdateTermArea(20, 5)),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.num_lines(), 2);
+        assert!(ps.resize_deadline.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic_output")]
+    fn append_coalesce() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::{Duration, Instant};
+
+        // Streams 500 tokens that end a line every 100 tokens, applying them like the run loop
+        // does, and returns the text and the number of times it was formatted for an append
+        let stream = |window| {
+            let mut ps = PagerState::new().unwrap();
+            let passes = Arc::new(AtomicUsize::new(0));
+            let counter = passes.clone();
+            ps.append_feedback = Some(Box::new(move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }));
+            ps.append_coalesce = window;
+            for i in 1..=500 {
+                let token = if i % 100 == 0 { "token\n" } else { "token " };
+                if let Some(text) = ps.coalesce_append(token.to_string()) {
+                    ps.append(&text);
+                }
+            }
+            (ps.lines, passes.load(Ordering::Relaxed))
+        };
+        let (lines, passes) = stream(None);
+        assert_eq!(passes, 500);
+        // Only the tokens that end a line apply the text, which stays the same
+        let (coalesced, coalesced_passes) = stream(Some(Duration::from_secs(60)));
+        assert_eq!(coalesced, lines);
+        assert_eq!(coalesced_passes, 5);
+
+        // Once the window has run out, the text is applied without waiting for the line to end
+        let mut ps = PagerState::new().unwrap();
+        ps.append_coalesce = Some(Duration::from_secs(60));
+        assert_eq!(ps.coalesce_append("partial".to_string()), None);
+        assert!(ps.append_deadline.is_some());
+        ps.append_deadline = Some(Instant::now());
+        assert_eq!(
+            ps.coalesce_append(String::new()).as_deref(),
+            Some("partial")
+        );
+        assert!(ps.append_deadline.is_none());
+
+        // Nothing is held back in deterministic mode
+        ps.deterministic_mode = true;
+        assert_eq!(
+            ps.coalesce_append("token".to_string()).as_deref(),
+            Some("token")
+        );
+    }
+
+    #[test]
+    fn hyperlinks() {
+        use crate::input::InputEvent;
+        use parking_lot::Mutex;
+
+        const LINK: &str = "\x1b]8;;https://example.com\x1b\\";
+        const END: &str = "\x1b]8;;\x1b\\";
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        let mut out = Vec::new();
+        let opened = Arc::new(Mutex::new(Vec::new()));
+        let opened2 = opened.clone();
+
+        let events = [
+            Event::SetData(format!("see {LINK}the docs{END}\n")),
+            Event::SetOnLinkActivate(Box::new(move |uri| opened2.lock().push(uri))),
+            // On the second row
+            Event::UserInput(InputEvent::ActivateLink(1, 2)),
+            // Next to the link
+            Event::UserInput(InputEvent::ActivateLink(0, 2)),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // The URI takes up no space and the link is started again on the wrapped row
+        assert_eq!(
+            ps.formatted_lines,
+            vec![format!("see {LINK}the{END}"), format!("{LINK}docs{END}")]
+        );
+        assert_eq!(*opened.lock(), vec!["https://example.com".to_string()]);
+
+        // Truncated lines keep the link as well
+        ps.line_wrapping = false;
+        ps.format_lines();
+        assert_eq!(
+            ps.formatted_lines,
+            vec![format!("see {LINK}the d\x1b[0m>{END}")]
+        );
+    }
+
+    #[test]
+    fn append_unterminated() {
+        use crate::LineNumbers;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 80;
+        ps.rows = 5;
+        ps.line_numbers = LineNumbers::Enabled;
+        let mut out = Vec::new();
+
+        let mut append = |ps: &mut PagerState, text: &str| {
+            handle_event(
+                Event::AppendData(text.to_string()),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        append(&mut ps, "foo");
+        assert_eq!(ps.formatted_lines, vec!["     1. foo"]);
+        append(&mut ps, "bar\n");
+        assert_eq!(ps.formatted_lines, vec!["     1. foobar"]);
+        append(&mut ps, "baz");
+        assert_eq!(ps.formatted_lines, vec!["     1. foobar", "     2. baz"]);
+        assert_eq!(ps.unterminated, 1);
+
+        // Text that was set without a trailing newline is continued as well
+        ps.lines = "a\nb".to_string();
+        ps.format_lines();
+        append(&mut ps, "c\nd\n");
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["     1. a", "     2. bc", "     3. d"]
+        );
+        assert_eq!(ps.unterminated, 0);
+    }
+
+    #[test]
+    fn append_keeps_position() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 8;
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.upper_mark = 3;
+        #[cfg(feature = "search")]
+        {
+            ps.search_term = Some(regex::Regex::new("ba[rz]").unwrap());
+        }
+        let mut out = Vec::new();
+
+        for text in ["foo", "bar\n", "baz", " and a long tail"] {
+            handle_event(
+                Event::AppendData(text.to_string()),
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+            assert_eq!(ps.upper_mark, 3);
+        }
+
+        // Appending gives the same result as formatting all of the text at once
+        let appended = ps.formatted_lines.clone();
+        #[cfg(feature = "search")]
+        let search_idx = ps.search_idx.clone();
+        ps.format_lines();
+        assert_eq!(appended, ps.formatted_lines);
+        #[cfg(not(feature = "search"))]
+        assert_eq!(&appended[20..], ["foobar", "baz and", "a long", "tail"]);
+        assert_eq!(ps.unterminated, 3);
+        #[cfg(feature = "search")]
+        {
+            assert_eq!(search_idx, ps.search_idx);
+            assert_eq!(search_idx.into_iter().collect::<Vec<_>>(), vec![20, 21]);
+        }
+    }
+
+    #[test]
+    fn count_prefix() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+
+        ps.prefix_num = "12".to_string();
+        handle_event(
+            Event::UserInput(InputEvent::ClearPrefix),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(ps.prefix_num.is_empty());
+
+        ps.prefix_num = "3".to_string();
+        handle_event(
+            Event::SetCountPrefixEnabled(false),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(!ps.count_prefix_enabled);
+        assert!(ps.prefix_num.is_empty());
+    }
+
+    #[test]
+    fn count_display_and_timeout() {
+        use std::time::Duration;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 40;
+        // The default prompt is the name of the test binary, which may contain the count
+        ps.prompt = "prompt".to_string();
+        ps.prefix_num = "25".to_string();
+        ps.format_prompt();
+        assert!(ps.displayed_prompt.contains(" 25 "));
+
+        handle_event(
+            Event::SetCountDisplay(false),
+            &mut Vec::new(),
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(!ps.displayed_prompt.contains("25"));
+
+        handle_event(
+            Event::SetCountTimeout(Duration::from_secs(1)),
+            &mut Vec::new(),
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(!ps.pending_count_expired(Duration::from_millis(500)));
+        assert!(ps.pending_count_expired(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn buffers() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        let mut out = Vec::new();
+        let mut handle = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        handle(Event::SetData("first\n".repeat(20)), &mut ps);
+        handle(Event::UserInput(InputEvent::UpdateUpperMark(7)), &mut ps);
+        // A single buffer is not shown in the prompt
+        assert!(!ps.displayed_prompt.contains("[1/"));
+
+        handle(Event::AddBuffer("second\n".repeat(3)), &mut ps);
+        handle(Event::AddBuffer("third\n".repeat(3)), &mut ps);
+        // Adding a buffer does not switch to it
+        assert_eq!(ps.formatted_lines[0], "first");
+        assert!(ps.displayed_prompt.contains(" [1/3] "));
+
+        handle(Event::NextBuffer, &mut ps);
+        assert_eq!(ps.active_buffer, 1);
+        assert_eq!(ps.formatted_lines, vec!["second"; 3]);
+        assert_eq!(ps.upper_mark, 0);
+        assert!(ps.displayed_prompt.contains(" [2/3] "));
+
+        // Going back before the first buffer wraps around to the last one
+        handle(Event::UserInput(InputEvent::PrevBuffer), &mut ps);
+        handle(Event::UserInput(InputEvent::PrevBuffer), &mut ps);
+        assert_eq!(ps.active_buffer, 2);
+        assert_eq!(ps.formatted_lines[0], "third");
+
+        // The first buffer is back where the user left it
+        handle(Event::NextBuffer, &mut ps);
+        assert_eq!(ps.formatted_lines.len(), 20);
+        assert_eq!(ps.upper_mark, 7);
+
+        // Appended text goes to the last buffer, not the displayed one
+        handle(Event::AppendData("more\n".to_string()), &mut ps);
+        assert_eq!(ps.formatted_lines.len(), 20);
+        handle(Event::PrevBuffer, &mut ps);
+        assert_eq!(ps.formatted_lines, vec!["third", "third", "third", "more"]);
+        handle(Event::AppendData("end\n".to_string()), &mut ps);
+        assert_eq!(ps.formatted_lines[4], "end");
+    }
+
+    #[test]
+    fn dynamic_prompt() {
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "line\n".repeat(30);
+        ps.format_lines();
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::SetDynamicPrompt(Box::new(|ps| format!("at {}", ps.upper_mark))),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(ps.displayed_prompt.starts_with("at 0 "));
+
+        // The prompt is computed again when the screen is drawn
+        ps.upper_mark = 5;
+        crate::minus_core::utils::display::draw_full(&mut out, &mut ps).unwrap();
+        assert!(ps.displayed_prompt.starts_with("at 5 "));
+
+        // Messages are still shown in place of the prompt
+        ps.message = Some("message".to_string());
+        ps.format_prompt();
+        assert!(ps.displayed_prompt.starts_with("message "));
+
+        // A prompt that is too wide is cut off by its width
+        ps.message = None;
+        ps.dynamic_prompt = Some(Box::new(|_| "日本語のテキスト".to_string()));
+        ps.cols = 7;
+        ps.format_prompt();
+        assert_eq!(ps.displayed_prompt, "日本語>");
+    }
+
+    #[test]
+    fn prompt_segments() {
+        let mut ps = PagerState::new().unwrap();
+        ps.color_enabled = false;
+        let mut out = Vec::new();
+        handle_event(
+            Event::SetPromptSegments("file.txt".to_string(), "50%".to_string()),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+
+        // The right segment ends at the edge of the screen, whatever its width
+        for (cols, prompt) in [
+            (20, "file.txt         50%"),
+            (12, "file.txt 50%"),
+            (11, "file.txt50%"),
+        ] {
+            ps.cols = cols;
+            ps.format_prompt();
+            assert_eq!(ps.displayed_prompt, prompt);
+        }
+
+        // A row that is too narrow cuts the left segment off first
+        ps.cols = 8;
+        ps.format_prompt();
+        assert_eq!(ps.displayed_prompt, "file>50%");
+        ps.cols = 3;
+        ps.format_prompt();
+        assert_eq!(ps.displayed_prompt, "50%");
+        ps.cols = 2;
+        ps.format_prompt();
+        assert_eq!(ps.displayed_prompt, "5>");
+
+        // Messages take the whole row
+        ps.cols = 20;
+        ps.message = Some("message".to_string());
+        ps.format_prompt();
+        assert_eq!(ps.displayed_prompt, format!("message{}", " ".repeat(13)));
+
+        // A plain prompt doesn't keep the right segment
+        ps.message = None;
+        handle_event(
+            Event::SetPrompt("plain".to_string()),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.displayed_prompt, format!("plain{}", " ".repeat(15)));
+    }
+
+    #[test]
+    fn data_bytes() {
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        let events = [
+            Event::SetDataBytes(b"bad \xff byte\n".to_vec()),
+            // An `é` split across two appends, followed by a lone continuation byte
+            Event::AppendDataBytes(b"caf\xc3".to_vec()),
+            Event::AppendDataBytes(b"\xa9\x80\n".to_vec()),
+            // Control characters are made visible, escape sequences are kept
+            Event::AppendDataBytes(b"\x1b[1mbell\x07\n".to_vec()),
+            Event::SetControlCharPlaceholder(Some('.')),
+            Event::AppendDataBytes(b"nul\0\n".to_vec()),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            ps.lines,
+            "bad \u{fffd} byte\ncaf\u{e9}\u{fffd}\n\x1b[1mbell^G\nnul.\n"
+        );
+        assert!(ps.incomplete_bytes.is_empty());
+    }
+
+    #[test]
+    fn line_styler() {
+        use crossterm::style::{ContentStyle, Stylize};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        ps.lines = "ok\nERROR: disk full\n".to_string();
+        let mut out = Vec::new();
+
+        handle_event(
+            Event::SetLineStyler(Box::new(|idx, line| {
+                assert_eq!(idx == 1, line.starts_with("ERROR"));
+                line.starts_with("ERROR").then(|| ContentStyle::new().red())
+            })),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // The style applies to all rows of the line
+        let red = |row: &str| ContentStyle::new().red().apply(row).to_string();
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["ok".to_string(), red("ERROR:"), red("disk full")]
+        );
+    }
+
+    #[test]
+    fn highlighter() {
+        use crate::highlight::Highlighter;
+        use crossterm::style::{ContentStyle, Stylize};
+
+        /// Shows numbers in red
+        struct Numbers;
+        impl Highlighter for Numbers {
+            fn highlight(&self, line: &str, _: usize) -> Vec<(ContentStyle, String)> {
+                line.split_inclusive(' ')
+                    .map(|word| {
+                        let style = if word.trim().parse::<u32>().is_ok() {
+                            ContentStyle::new().red()
+                        } else {
+                            ContentStyle::new()
+                        };
+                        (style, word.to_string())
+                    })
+                    .collect()
+            }
+        }
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 8;
+        ps.lines = "a 12 345 b".to_string();
+        handle_event(
+            Event::SetHighlighter(Box::new(Numbers)),
+            &mut Vec::new(),
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        let red = |text: &str| ContentStyle::new().red().apply(text).to_string();
+        assert_eq!(
+            ps.formatted_lines,
+            vec![format!("a {}{}", red("12 "), red("345")), "b".to_string()]
+        );
+    }
+
+    #[test]
+    fn locked_line_numbers() {
+        use crate::{input::InputEvent, LineNumbers};
+
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        let mut update = |ln, ps: &mut PagerState| {
+            handle_event(
+                Event::UserInput(InputEvent::UpdateLineNumber(ln)),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        update(LineNumbers::Enabled, &mut ps);
+        assert_eq!(ps.line_numbers, LineNumbers::Enabled);
+        update(LineNumbers::Disabled, &mut ps);
+        assert_eq!(ps.line_numbers, LineNumbers::Disabled);
+
+        // Users cannot change locked line numbers
+        ps.line_numbers = LineNumbers::AlwaysOff;
+        update(LineNumbers::Enabled, &mut ps);
+        assert_eq!(ps.line_numbers, LineNumbers::AlwaysOff);
+        ps.line_numbers = LineNumbers::AlwaysOn;
+        update(LineNumbers::Disabled, &mut ps);
+        assert_eq!(ps.line_numbers, LineNumbers::AlwaysOn);
+    }
+
+    #[test]
+    fn scroll_past_end() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(10);
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // By default the last page always fills the screen
+        send(Event::UserInput(InputEvent::UpdateUpperMark(8)), &mut ps);
+        assert_eq!(ps.upper_mark, 6);
+
+        send(Event::SetScrollPastEnd(true), &mut ps);
+        send(Event::UserInput(InputEvent::UpdateUpperMark(8)), &mut ps);
+        assert_eq!(ps.upper_mark, 8);
+        // The last line is as far up as it goes
+        send(Event::UserInput(InputEvent::UpdateUpperMark(20)), &mut ps);
+        assert_eq!(ps.upper_mark, 9);
+        assert!(ps.is_at_bottom());
+    }
+
+    #[test]
+    #[cfg(feature = "dynamic_output")]
+    fn tick() {
+        use std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            time::{Duration, Instant},
+        };
+
+        let mut ps = PagerState::new().unwrap();
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+        assert!(ps.tick_deadline().is_none());
+
+        let count = AtomicUsize::new(0);
+        send(
+            Event::SetDynamicPrompt(Box::new(move |_| {
+                format!("tick {}", count.fetch_add(1, Ordering::SeqCst))
+            })),
+            &mut ps,
+        );
+        send(
+            Event::SetTickInterval(Some(Duration::from_secs(60))),
+            &mut ps,
+        );
+        let deadline = ps.tick_deadline().unwrap();
+        assert!(deadline > Instant::now());
+
+        // Each tick computes the prompt again and schedules the next tick
+        send(Event::Tick, &mut ps);
+        assert!(ps.displayed_prompt.starts_with("tick 1"));
+        assert!(ps.tick_deadline().unwrap() >= deadline);
+
+        // No ticks happen in deterministic mode or without an interval
+        ps.deterministic_mode = true;
+        assert!(ps.tick_deadline().is_none());
+        ps.deterministic_mode = false;
+        send(Event::SetTickInterval(None), &mut ps);
+        assert!(ps.tick_deadline().is_none());
+    }
+
+    #[test]
+    fn append_feedback() {
+        use crate::AppendFeedback;
+
+        let mut ps = PagerState::new().unwrap();
+        let feedback = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = feedback.clone();
+        let mut out = Vec::new();
+        for ev in [
+            Event::SetAppendFeedback(Box::new(move |f| sink.lock().unwrap().push(f))),
+            Event::AppendData("line\n".repeat(8)),
+            Event::AppendData("line\n".to_string()),
+            // The tenth line makes the line numbers wider
+            Event::AppendData("line\n".to_string()),
+        ] {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            *feedback.lock().unwrap(),
+            vec![
+                AppendFeedback::PartialUpdate,
+                AppendFeedback::PartialUpdate,
+                AppendFeedback::FullRedraw
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    #[allow(clippy::trivial_regex)]
+    fn hidden_highlights_keep_search() {
+        use crate::input::InputEvent;
+        use crate::minus_core::utils::display::write_lines;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = format!("{}match\n{}", "text\n".repeat(10), "text\n".repeat(10));
+        ps.search_term = Some(regex::Regex::new("match").unwrap());
+        ps.format_lines();
+        let mut out = Vec::new();
+
+        for ev in [
+            Event::UserInput(InputEvent::UpdateHighlightVisible(false)),
+            Event::UserInput(InputEvent::MoveToNextMatch(1)),
+        ] {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        // Moving between matches still works
+        assert_eq!(ps.upper_mark, 10);
+        assert!(ps.search_term.is_some());
+
+        // The rows keep their highlight, it is only left out when drawing
+        let highlighted = format!("{}match{}", ps.match_escapes.0, ps.match_escapes.1);
+        assert_eq!(ps.formatted_lines[10], highlighted);
+        let mut out = Vec::new();
+        write_lines(&mut out, &mut ps).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("\rmatch\n"));
+
+        // Showing the highlights brings them back
+        handle_event(
+            Event::UserInput(InputEvent::UpdateHighlightVisible(true)),
+            &mut Vec::new(),
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert_eq!(ps.formatted_lines[10], highlighted);
+        let mut out = Vec::new();
+        write_lines(&mut out, &mut ps).unwrap();
+        assert!(!String::from_utf8(out).unwrap().starts_with("\rmatch\n"));
+        assert_eq!(ps.upper_mark, 10);
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    #[allow(clippy::trivial_regex)]
+    fn search_focus() {
+        use crate::input::InputEvent;
+        use crate::minus_core::utils::display::write_lines;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 5;
+        ps.lines = "one\nab match\ntwo".to_string();
+        ps.search_term = Some(regex::Regex::new("match").unwrap());
+        ps.format_lines();
+        let draw = |ps: &mut PagerState| {
+            let mut out = Vec::new();
+            write_lines(&mut out, ps).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert!(!draw(&mut ps).contains("\x1b[2m"));
+
+        handle_event(
+            Event::UserInput(InputEvent::UpdateSearchFocus(true)),
+            &mut Vec::new(),
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        let rows = draw(&mut ps);
+        let rows = rows.lines().collect::<Vec<_>>();
+        // Lines without a match are dimmed, while both rows of the wrapped match keep their style
+        assert_eq!(rows[0], "\r\x1b[2mone\x1b[22m");
+        assert_eq!(rows[1], format!("\r{}", ps.formatted_lines[1]));
+        assert!(rows[2].contains("match") && !rows[2].contains("\x1b[2m"));
+        assert_eq!(rows[3], "\r\x1b[2mtwo\x1b[22m");
+
+        // Nothing is dimmed without a search
+        ps.search_term = None;
+        ps.format_lines();
+        assert_eq!(draw(&mut ps), "\rone\n\rab\n\rmatch\n\rtwo\n");
+    }
+
+    #[test]
+    fn paragraph_motions() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 3;
+        // Paragraphs start at lines 0, 3 and 7
+        ps.lines = "a\nb\n\nc\nd\n\n\ne\nf\n".to_string();
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                Event::UserInput(ev),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        send(InputEvent::NextParagraph(1), &mut ps);
+        assert_eq!(ps.upper_mark, 2);
+        // Both blank lines after the second paragraph are a single boundary
+        send(InputEvent::NextParagraph(1), &mut ps);
+        assert_eq!(ps.upper_mark, 5);
+        send(InputEvent::PrevParagraph(1), &mut ps);
+        assert_eq!(ps.upper_mark, 2);
+        // Moving beyond the last paragraph stops at the end of the text
+        send(InputEvent::NextParagraph(5), &mut ps);
+        assert_eq!(ps.upper_mark, ps.num_lines() - ps.content_rows());
+        send(InputEvent::PrevParagraph(5), &mut ps);
+        assert_eq!(ps.upper_mark, 0);
+    }
+
+    #[test]
+    fn drag_scroll() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.upper_mark = 10;
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                Event::UserInput(ev),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        send(InputEvent::StartDrag(2), &mut ps);
+        assert_eq!(ps.upper_mark, 10);
+        // Dragging down shows the lines above
+        send(InputEvent::Drag(4), &mut ps);
+        assert_eq!(ps.upper_mark, 8);
+        send(InputEvent::Drag(1), &mut ps);
+        assert_eq!(ps.upper_mark, 11);
+
+        // Releasing the button ends the drag
+        send(InputEvent::EndDrag, &mut ps);
+        send(InputEvent::Drag(3), &mut ps);
+        assert_eq!(ps.upper_mark, 11);
+
+        // So does scrolling some other way, the next drag doesn't jump by the rows moved before
+        send(InputEvent::StartDrag(2), &mut ps);
+        send(InputEvent::UpdateUpperMark(14), &mut ps);
+        assert_eq!(ps.drag_row, None);
+        send(InputEvent::Drag(9), &mut ps);
+        assert_eq!(ps.upper_mark, 14);
+    }
+
+    #[test]
+    fn exit_on_finish() {
+        use crate::input::InputEvent;
+        use std::sync::atomic::Ordering;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.exit_strategy = ExitStrategy::PagerQuit;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.exit_on_finish = true;
+        let is_exitted = Arc::new(AtomicBool::new(false));
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &is_exitted,
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // The user hasn't reached the end yet
+        send(Event::InputFinished, &mut ps);
+        assert!(!is_exitted.load(Ordering::SeqCst));
+        send(Event::UserInput(InputEvent::UpdateUpperMark(20)), &mut ps);
+        assert!(is_exitted.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn byte_offsets() {
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 3;
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Multibyte characters count with all of their bytes
+        send(Event::SetData("ä\nline\nunfin".to_string()), &mut ps);
+        assert_eq!(ps.line_ends, vec![3, 8, 13]);
+        send(Event::AppendData("ished\nlast\n".to_string()), &mut ps);
+        assert_eq!(ps.line_ends, vec![3, 8, 19, 24]);
+        assert_eq!(ps.total_bytes(), 24);
+
+        assert_eq!(ps.byte_offset(), 0);
+        ps.upper_mark = 2;
+        assert_eq!(ps.byte_offset(), 8);
+    }
+
+    #[test]
+    fn reflow() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 40;
+        ps.rows = 10;
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        let text = "A paragraph that was\nwrapped by hand at\ntwenty columns.\n\nNext\n";
+        send(Event::SetData(text.to_string()), &mut ps);
+        send(Event::SetReflow(true), &mut ps);
+        assert_eq!(
+            ps.formatted_lines,
+            vec![
+                "A paragraph that was wrapped by hand at",
+                "twenty columns.",
+                "",
+                "Next"
+            ]
+        );
+
+        // The last paragraph continues in appended text
+        send(Event::AppendData("paragraph\n".to_string()), &mut ps);
+        assert_eq!(ps.formatted_lines[3], "Next paragraph");
+        // Or a new one starts after a blank line
+        send(Event::AppendData("\nLast\n".to_string()), &mut ps);
+        send(Event::AppendData("one".to_string()), &mut ps);
+        assert_eq!(
+            ps.lines,
+            "A paragraph that was wrapped by hand at twenty columns.\n\nNext paragraph\n\nLast one"
+        );
+
+        // The original lines come back, including the appended one
+        send(Event::SetReflow(false), &mut ps);
+        assert_eq!(ps.lines, format!("{text}paragraph\n\nLast\none"));
+        assert_eq!(ps.formatted_lines.len(), 9);
+
+        // Resetting the display options turns reflowing off as well
+        send(Event::SetReflow(true), &mut ps);
+        send(Event::ResetDisplayOptions, &mut ps);
+        assert!(!ps.reflow);
+        assert_eq!(
+            ps.formatted_lines,
+            vec![
+                "A paragraph that was",
+                "wrapped by hand at",
+                "twenty columns.",
+                "",
+                "Next",
+                "paragraph",
+                "",
+                "Last",
+                "one"
+            ]
+        );
+    }
+
+    #[test]
+    fn cursor_mode() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 6;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.upper_mark = 2;
+        ps.scroll_off = 1;
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        send(Event::SetCursorMode(true), &mut ps);
+        assert_eq!(ps.cursor_line, Some(2));
+        // Within the margin of the 5 rows on the screen, the text doesn't scroll
+        send(Event::UserInput(InputEvent::UpdateCursor(5)), &mut ps);
+        assert_eq!((ps.cursor_line, ps.upper_mark), (Some(5), 2));
+        send(Event::UserInput(InputEvent::UpdateCursor(8)), &mut ps);
+        assert_eq!((ps.cursor_line, ps.upper_mark), (Some(8), 5));
+        send(Event::UserInput(InputEvent::UpdateCursor(5)), &mut ps);
+        assert_eq!((ps.cursor_line, ps.upper_mark), (Some(5), 4));
+        // At the ends of the text, the margin can't be kept
+        send(Event::UserInput(InputEvent::UpdateCursor(0)), &mut ps);
+        assert_eq!((ps.cursor_line, ps.upper_mark), (Some(0), 0));
+        send(Event::UserInput(InputEvent::UpdateCursor(100)), &mut ps);
+        assert_eq!((ps.cursor_line, ps.upper_mark), (Some(19), 15));
+
+        // Scrolling the text away takes the cursor along
+        send(Event::UserInput(InputEvent::UpdateUpperMark(3)), &mut ps);
+        assert_eq!(ps.cursor_line, Some(7));
+
+        send(Event::SetCursorMode(false), &mut ps);
+        assert_eq!(ps.cursor_line, None);
+        // Without cursor mode, the event does nothing
+        send(Event::UserInput(InputEvent::UpdateCursor(10)), &mut ps);
+        assert_eq!((ps.cursor_line, ps.upper_mark), (None, 3));
+    }
+
+    #[test]
+    fn yank_selection() {
+        use crate::input::InputEvent;
+        use parking_lot::Mutex;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        ps.rows = 10;
+        let yanked = Arc::new(Mutex::new(Vec::new()));
+        let yanked2 = yanked.clone();
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // The second line wraps onto two rows
+        send(
+            Event::SetData("zero\n\x1b[1mone is long\x1b[0m\ntwo\nthree".to_string()),
+            &mut ps,
+        );
+        send(
+            Event::SetOnYank(Box::new(move |text| yanked2.lock().push(text))),
+            &mut ps,
+        );
+        send(Event::SetCursorMode(true), &mut ps);
+        send(Event::UserInput(InputEvent::UpdateCursor(3)), &mut ps);
+        send(Event::UserInput(InputEvent::ToggleSelection), &mut ps);
+        send(Event::UserInput(InputEvent::UpdateCursor(2)), &mut ps);
+        assert_eq!(ps.selected_rows(), Some((2, 3)));
+
+        // Whole lines are yanked without their styling, in the order of the text
+        send(Event::UserInput(InputEvent::YankSelection), &mut ps);
+        assert_eq!(*yanked.lock(), vec!["one is long\ntwo".to_string()]);
+        assert_eq!(ps.selection_anchor, None);
+
+        send(Event::UserInput(InputEvent::ToggleSelection), &mut ps);
+        send(Event::UserInput(InputEvent::ClearSelection), &mut ps);
+        send(Event::UserInput(InputEvent::YankSelection), &mut ps);
+        assert_eq!(yanked.lock().len(), 1);
+    }
+
+    #[test]
+    fn select_line() {
+        use crate::input::InputEvent;
+        use parking_lot::Mutex;
+
+        let mut ps = PagerState::new().unwrap();
+        let selected = Arc::new(Mutex::new(None));
+        let selected2 = selected.clone();
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        send(
+            Event::SetOnSelect(Box::new(move |line| *selected2.lock() = Some(line))),
+            &mut ps,
+        );
+        send(Event::UserInput(InputEvent::Select(4)), &mut ps);
+        assert_eq!(*selected.lock(), Some(4));
+    }
+
+    #[test]
+    fn continuation_number_style() {
+        use crate::{ContinuationNumberStyle, LineNumbers};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 15;
+        ps.rows = 5;
+        ps.line_numbers = LineNumbers::Enabled;
+        // The line wraps into three rows
+        ps.lines = "aaaa bbbb cccc".to_string();
+        ps.format_lines();
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["     1. aaaa", "        bbbb", "        cccc"]
+        );
+        send(
+            Event::SetContinuationNumberStyle(ContinuationNumberStyle::Repeat),
+            &mut ps,
+        );
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["     1. aaaa", "     1. bbbb", "     1. cccc"]
+        );
+        send(
+            Event::SetContinuationNumberStyle(ContinuationNumberStyle::Marker('+')),
+            &mut ps,
+        );
+        assert_eq!(
+            ps.formatted_lines,
+            vec!["     1. aaaa", "     +. bbbb", "     +. cccc"]
+        );
+        send(
+            Event::SetContinuationNumberStyle(ContinuationNumberStyle::Blank),
+            &mut ps,
+        );
+        assert_eq!(ps.formatted_lines[1], "        bbbb");
+    }
+
+    #[test]
+    fn styled_line() {
+        use crate::minus_core::utils::text::strip_ansi;
+        use crossterm::style::{ContentStyle, Stylize};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 8;
+        let mut out = Vec::new();
+        let events = [
+            Event::AppendData("plain\n".to_string()),
+            Event::AppendStyledLine(vec![
+                (ContentStyle::new().red(), "red".to_string()),
+                (ContentStyle::new(), " and ".to_string()),
+                (ContentStyle::new().bold(), "bold".to_string()),
+            ]),
+            // Escape sequences in the text of a span are not interpreted
+            Event::AppendStyledLine(vec![(ContentStyle::new(), "\x1b[1mb".to_string())]),
+        ];
+        for ev in events {
+            handle_event(
+                ev,
+                &mut out,
+                &mut ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        }
+        assert_eq!(ps.lines.lines().count(), 3);
+        assert!(ps.formatted_lines[1].contains("\x1b[38;5;9mred"));
+        // Wrapping goes by the text without the styles
+        let rows = ps.formatted_lines.iter().map(|row| strip_ansi(row));
+        assert_eq!(
+            rows.map(|row| row.trim_end().to_string())
+                .collect::<Vec<_>>(),
+            vec!["plain", "red and", "bold", "^[[1mb"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn search_without_matches() {
+        use super::commit_search;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "alpha\nbeta\nalpha\n".to_string();
+        ps.format_lines();
+        let mut out = Vec::new();
+
+        commit_search(&mut out, &mut ps, "alpha").unwrap();
+        assert_eq!(ps.search_idx.len(), 2);
+        assert_eq!(ps.message, None);
+
+        // The search is kept by default, and the prompt says it didn't match
+        commit_search(&mut out, &mut ps, "gamma").unwrap();
+        assert_eq!(ps.message.as_deref(), Some("Pattern not found: gamma"));
+        assert_eq!(ps.search_term.as_ref().unwrap().as_str(), "gamma");
+        assert!(ps.search_idx.is_empty());
+        assert!(!out.contains(&b'\x07'));
+
+        commit_search(&mut out, &mut ps, "alpha").unwrap();
+        ps.message = None;
+        ps.keep_unmatched_search = false;
+        ps.beep_on_no_match = true;
+        out.clear();
+        commit_search(&mut out, &mut ps, "gamma").unwrap();
+        assert_eq!(ps.message.as_deref(), Some("Pattern not found: gamma"));
+        assert_eq!(ps.search_term.as_ref().unwrap().as_str(), "alpha");
+        assert_eq!(ps.search_idx.len(), 2);
+        assert_eq!(out, b"\x07");
+    }
+
+    #[test]
+    fn start_line() {
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Applied as soon as the line is there
+        send(Event::SetStartLine(8), &mut ps);
+        send(Event::AppendData("line\n".repeat(5)), &mut ps);
+        assert_eq!(ps.upper_mark, 0);
+        assert_eq!(ps.start_line, Some(8));
+        send(Event::AppendData("line\n".repeat(10)), &mut ps);
+        assert_eq!(ps.upper_mark, 7);
+        assert_eq!(ps.start_line, None);
+
+        // Only once
+        send(Event::AppendData("line\n".repeat(10)), &mut ps);
+        assert_eq!(ps.upper_mark, 7);
+
+        // Goes by lines rather than rows, and stops at the last page
+        ps.cols = 10;
+        send(
+            Event::SetData("x".repeat(25) + "\n" + &"line\n".repeat(5)),
+            &mut ps,
+        );
+        send(Event::SetStartLine(2), &mut ps);
+        assert_eq!(ps.upper_mark, 3);
+        send(Event::SetStartLine(6), &mut ps);
+        assert_eq!(ps.upper_mark, ps.max_upper_mark());
+    }
+
+    #[test]
+    fn event_observer() {
+        use crate::{input::InputEvent, ExitReason, PagerObservation};
+        use parking_lot::Mutex;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.exit_strategy = ExitStrategy::PagerQuit;
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen2 = seen.clone();
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Nothing is observed before the observer is set
+        send(Event::UserInput(InputEvent::UpdateUpperMark(2)), &mut ps);
+        send(
+            Event::SetEventObserver(Box::new(move |obs| seen2.lock().push(obs))),
+            &mut ps,
+        );
+        // Scrolling by a count is observed once
+        send(Event::UserInput(InputEvent::ScrollBy(3)), &mut ps);
+        // Not scrolling isn't observed
+        send(Event::UserInput(InputEvent::UpdateUpperMark(5)), &mut ps);
+        send(Event::UserInput(InputEvent::UpdateTermArea(30, 8)), &mut ps);
+        #[cfg(feature = "search")]
+        super::commit_search(&mut Vec::new(), &mut ps, "nothing").unwrap();
+        send(Event::UserInput(InputEvent::Exit), &mut ps);
+
+        let mut expected = vec![
+            PagerObservation::Scrolled { row: 5 },
+            PagerObservation::Resized { cols: 30, rows: 8 },
+        ];
+        #[cfg(feature = "search")]
+        expected.push(PagerObservation::Searched {
+            query: "nothing".to_string(),
+            matches: 0,
+        });
+        expected.push(PagerObservation::Exited(ExitReason::UserQuit));
+        assert_eq!(*seen.lock(), expected);
+    }
+
+    #[test]
+    fn append_keeps_top_line() {
+        use crate::LineNumbers;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 40;
+        ps.rows = 10;
+        ps.line_numbers = LineNumbers::Enabled;
+        // Lines that just fit next to two digit line numbers, but not next to three digits
+        let width = ps.cols - ps.gutter_width(2);
+        ps.lines = format!("{}\n", "x".repeat(width)).repeat(99);
+        ps.format_lines();
+        ps.upper_mark = 50;
+        assert_eq!(ps.line_at_row(ps.upper_mark), 50);
+
+        handle_event(
+            Event::AppendData("line 100\n".to_string()),
+            &mut Vec::new(),
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // Each old line now takes up two rows, the same line is still at the top
+        assert_eq!(ps.num_lines(), 199);
+        assert_eq!(ps.upper_mark, 100);
+        assert_eq!(ps.line_at_row(ps.upper_mark), 50);
+    }
+
+    #[test]
+    fn batch() {
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.exit_strategy = ExitStrategy::PagerQuit;
+        let is_exitted = Arc::new(AtomicBool::new(false));
+        handle_event(
+            Event::Batch(vec![
+                Event::SetData("1\n2\n3\n".to_string()),
+                Event::AppendData("4\n5\n6\n".to_string()),
+                Event::SetStartLine(3),
+                Event::UserInput(crate::input::InputEvent::Exit),
+                Event::SetPrompt("never set".to_string()),
+            ]),
+            &mut Vec::new(),
+            &mut ps,
+            &is_exitted,
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        // Applied in order, and nothing after the exit
+        assert_eq!(ps.lines, "1\n2\n3\n4\n5\n6\n");
+        assert_eq!(ps.upper_mark, 2);
+        assert!(is_exitted.load(std::sync::atomic::Ordering::SeqCst));
+        assert_ne!(ps.prompt, "never set");
+    }
+
+    #[test]
+    fn feed_input() {
+        use crossterm::event::{Event as TermEvent, KeyCode, KeyEvent, KeyModifiers};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut send = |c, ps: &mut PagerState| {
+            let ev = TermEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            handle_event(
+                Event::FeedInput(ev),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Counts apply to the command after them
+        send('3', &mut ps);
+        assert_eq!(ps.prefix_num, "3");
+        send('j', &mut ps);
+        assert_eq!(ps.upper_mark, 3);
+        assert!(ps.prefix_num.is_empty());
+    }
+
+    #[test]
+    fn line_row_mapping() {
+        let mut ps = PagerState::new().unwrap();
+        ps.cols = 10;
+        let send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut Vec::new(),
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        send(
+            Event::SetData("short\n".to_string() + &"x".repeat(25) + "\n"),
+            &mut ps,
+        );
+        // The second line is wrapped into three rows
+        assert_eq!(ps.source_line_at_row(0), Some(0));
+        assert_eq!(ps.source_line_at_row(3), Some(1));
+        assert_eq!(ps.source_line_at_row(4), None);
+        assert_eq!(ps.first_row_of_line(1), Some(1));
+        assert_eq!(ps.first_row_of_line(2), None);
+
+        // Appending keeps the mapping up to date, also when a line is continued
+        send(Event::AppendData("end".to_string()), &mut ps);
+        assert_eq!(ps.first_row_of_line(2), Some(4));
+        send(Event::AppendData("x".repeat(10) + "\nlast\n"), &mut ps);
+        assert_eq!(ps.source_line_at_row(5), Some(2));
+        assert_eq!(ps.first_row_of_line(3), Some(6));
+        let expected: Vec<usize> = ps
+            .rows_per_line()
+            .scan(0, |row, rows| {
+                let first = *row;
+                *row += rows;
+                Some(first)
+            })
+            .collect();
+        assert_eq!(ps.line_rows, expected);
+    }
+
+    #[test]
+    fn edge_feedback() {
+        use crate::{input::InputEvent, EdgeFeedback};
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        let scroll = |um, ps: &mut PagerState| {
+            let mut out = Vec::new();
+            handle_event(
+                Event::UserInput(InputEvent::UpdateUpperMark(um)),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // Silent by default
+        assert!(!scroll(0, &mut ps).contains('\x07'));
+
+        ps.edge_feedback = EdgeFeedback::Bell;
+        assert!(scroll(0, &mut ps).contains('\x07'));
+        // Only when the view can't move
+        assert!(!scroll(3, &mut ps).contains('\x07'));
+        assert!(!scroll(2, &mut ps).contains('\x07'));
+        let max = ps.max_upper_mark();
+        assert!(!scroll(max, &mut ps).contains('\x07'));
+        assert!(scroll(max + 1, &mut ps).contains('\x07'));
+
+        // Not when the application scrolls
+        let mut out = Vec::new();
+        handle_event(
+            Event::ScrollTo(max + 1),
+            &mut out,
+            &mut ps,
+            &Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            &UIA,
+        )
+        .unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains('\x07'));
+
+        // The screen is turned back once the flash is over, without waiting for it here
+        ps.edge_feedback = EdgeFeedback::Flash;
+        let out = scroll(max + 1, &mut ps);
+        assert!(out.contains("\x1b[?5h") && !out.contains("\x1b[?5l"));
+        assert!(ps.flash_deadline.is_some());
+        let mut out = Vec::new();
+        end_flash(&mut out, &mut ps).unwrap();
+        assert_eq!(out, b"\x1b[?5l");
+        assert_eq!(ps.flash_deadline, None);
+    }
+
+    #[test]
+    fn smooth_scroll() {
+        use crate::input::InputEvent;
+        use std::time::Duration;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = "line\n".repeat(100);
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+        let duration = Duration::from_secs(60);
+        send(Event::SetSmoothScroll(Some(duration)), &mut ps);
+
+        // Scrolls by less than a page happen right away
+        send(Event::UserInput(InputEvent::UpdateUpperMark(3)), &mut ps);
+        assert_eq!(ps.upper_mark, 3);
+        assert!(ps.scroll_frame_deadline().is_none());
+
+        // Larger jumps move a bit further with every frame until they reach their end
+        send(Event::UserInput(InputEvent::UpdateUpperMark(1000)), &mut ps);
+        assert_eq!(ps.upper_mark, 3);
+        assert!(ps.scroll_frame_deadline().is_some());
+        ps.scroll_animation.as_mut().unwrap().start -= duration / 2;
+        send(Event::ScrollFrame, &mut ps);
+        let halfway = ps.upper_mark;
+        // The scroll slows down towards its end, so more than half of it is done
+        assert!((50..96).contains(&halfway));
+        ps.scroll_animation.as_mut().unwrap().start -= duration / 2;
+        send(Event::ScrollFrame, &mut ps);
+        assert_eq!(ps.upper_mark, 96);
+        assert!(ps.scroll_animation.is_none());
+
+        // Scrolling again stops the smooth scroll where it is
+        send(Event::UserInput(InputEvent::UpdateUpperMark(0)), &mut ps);
+        send(Event::UserInput(InputEvent::UpdateUpperMark(95)), &mut ps);
+        assert_eq!(ps.upper_mark, 95);
+        assert!(ps.scroll_animation.is_none());
+
+        // Turning smooth scrolling off finishes a running one
+        send(Event::UserInput(InputEvent::UpdateUpperMark(0)), &mut ps);
+        send(Event::SetSmoothScroll(None), &mut ps);
+        assert_eq!(ps.upper_mark, 0);
+        assert!(ps.scroll_animation.is_none());
+
+        // Nothing is animated in deterministic mode
+        send(Event::SetSmoothScroll(Some(duration)), &mut ps);
+        ps.deterministic_mode = true;
+        send(Event::UserInput(InputEvent::UpdateUpperMark(50)), &mut ps);
+        assert_eq!(ps.upper_mark, 50);
+    }
+
+    #[test]
+    fn set_output() {
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.exit_strategy = ExitStrategy::PagerQuit;
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        send(Event::SetOutput(Box::new(std::io::sink())), &mut ps);
+        assert!(ps.output.is_some());
+        // As done when the pager starts
+        ps.custom_output = ps.output.take().is_some();
+
+        // The screen is still restored on quitting, but the terminal of the process is left alone
+        send(Event::UserInput(InputEvent::Exit), &mut ps);
+        assert!(String::from_utf8(out).unwrap().contains("\x1b[?25h"));
+    }
+
+    #[test]
+    fn carriage_return_mode() {
+        use crate::CrMode;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "50%\r100%\nnext\r\n".to_string();
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut send = |mode, ps: &mut PagerState| {
+            handle_event(
+                Event::SetCarriageReturnMode(mode),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Carriage returns are passed on by default, except those of line endings
+        assert_eq!(ps.formatted_lines, vec!["50%\r100%", "next"]);
+        send(CrMode::Overwrite, &mut ps);
+        assert_eq!(ps.formatted_lines, vec!["100%", "next"]);
+        send(CrMode::Strip, &mut ps);
+        assert_eq!(ps.formatted_lines, vec!["50%100%", "next"]);
+        send(CrMode::Literal, &mut ps);
+        assert_eq!(ps.formatted_lines, vec!["50%\r100%", "next"]);
+
+        // The width of the overwritten line decides how it is wrapped
+        ps.cols = 4;
+        ps.lines = "1%\r100%\n".to_string();
+        send(CrMode::Overwrite, &mut ps);
+        assert_eq!(ps.formatted_lines, vec!["100%"]);
+        send(CrMode::Strip, &mut ps);
+        assert_eq!(ps.formatted_lines, vec!["1%10", "0%"]);
+    }
+
+    #[test]
+    fn set_terminal_size() {
+        use crate::input::InputEvent;
+        use std::time::Duration;
+
+        let mut ps = PagerState::with_size(1, 1);
+        ps.lines = "abcdefgh\nij".to_string();
+        ps.format_lines();
+        ps.resize_debounce = Duration::from_millis(100);
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // The text is wrapped to the given width right away, even with a resize debounce
+        send(Event::SetTerminalSize(4, 6), &mut ps);
+        assert_eq!((ps.cols, ps.rows), (4, 6));
+        assert_eq!(ps.formatted_lines, vec!["abcd", "efgh", "ij"]);
+
+        // A resize of the real terminal still takes over
+        ps.resize_debounce = Duration::ZERO;
+        send(Event::UserInput(InputEvent::UpdateTermArea(8, 3)), &mut ps);
+        assert_eq!((ps.cols, ps.rows), (8, 3));
+        assert_eq!(ps.formatted_lines, vec!["abcdefgh", "ij"]);
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn max_search_matches() {
+        use super::commit_search;
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = "line\n".repeat(20);
+        ps.format_lines();
+        ps.max_search_matches = Some(5);
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        commit_search(&mut Vec::new(), &mut ps, ".").unwrap();
+        assert_eq!(ps.search_idx, (0..5).collect());
+        assert_eq!(ps.message.as_deref(), Some("Showing the first 5 matches"));
+        // Rows past the limit are not highlighted
+        assert_ne!(ps.formatted_lines[4], "line");
+        assert_eq!(ps.formatted_lines[5], "line");
+
+        // Moving between matches stays within the collected ones
+        assert_eq!(ps.search_mark, 1);
+        send(Event::UserInput(InputEvent::MoveToNextMatch(3)), &mut ps);
+        assert_eq!(ps.search_mark, 4);
+        send(Event::UserInput(InputEvent::MoveToNextMatch(1)), &mut ps);
+        assert_eq!(ps.search_mark, 0);
+
+        // Appended text doesn't take the matches past the limit
+        ps.append_str("line\n");
+        assert_eq!(ps.search_idx.len(), 5);
+
+        send(Event::SetMaxSearchMatches(None), &mut ps);
+        assert_eq!(ps.search_idx.len(), 21);
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn goto_match() {
+        use super::commit_search;
+        use crate::input::InputEvent;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.rows = 5;
+        ps.lines = (0..30)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<String>>()
+            .join("\n");
+        ps.format_lines();
+        let mut out = Vec::new();
+        let mut send = |ev, ps: &mut PagerState| {
+            handle_event(
+                ev,
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Every line ending in 5 matches: 5, 15 and 25
+        commit_search(&mut Vec::new(), &mut ps, "5$").unwrap();
+        assert_eq!(ps.search_idx, [5, 15, 25].iter().copied().collect());
+
+        send(Event::UserInput(InputEvent::GotoMatch(2)), &mut ps);
+        assert_eq!(ps.search_mark, 1);
+        assert!((ps.upper_mark..ps.upper_mark + ps.rows).contains(&15));
+        assert_eq!(ps.message, None);
+
+        // Ordinals past the last match go to the last one
+        send(Event::UserInput(InputEvent::GotoMatch(9)), &mut ps);
+        assert_eq!(ps.search_mark, 2);
+        assert!((ps.upper_mark..ps.upper_mark + ps.rows).contains(&25));
+        assert_eq!(
+            ps.message.as_deref(),
+            Some("Only 3 matches, showing the last one")
+        );
+    }
+
+    #[test]
+    fn insert_lines() {
+        use std::collections::BTreeSet;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = (0..20)
+            .map(|i| format!("line {i}\n"))
+            .collect::<Vec<_>>()
+            .concat();
+        ps.format_lines();
+        ps.upper_mark = 5;
+        ps.gutter_marks.insert(3);
+        ps.gutter_marks.insert(8);
+        let mut out = Vec::new();
+        let mut insert = |idx, lines: &[&str], ps: &mut PagerState| {
+            handle_event(
+                Event::InsertLines(idx, lines.iter().map(ToString::to_string).collect()),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // At the start, the view and the marks move along with the text
+        insert(0, &["new 1", "new 2"], &mut ps);
+        assert_eq!(
+            ps.lines.lines().take(3).collect::<Vec<_>>(),
+            vec!["new 1", "new 2", "line 0"]
+        );
+        assert_eq!(ps.formatted_lines[ps.upper_mark], "line 5");
+        assert_eq!(ps.gutter_marks, BTreeSet::from([5, 10]));
+
+        // In the middle, only what comes after the new lines moves
+        insert(9, &["middle"], &mut ps);
+        assert_eq!(ps.lines.lines().nth(8), Some("line 6"));
+        assert_eq!(ps.lines.lines().nth(9), Some("middle"));
+        assert_eq!(ps.lines.lines().nth(10), Some("line 7"));
+        assert_eq!(ps.formatted_lines[ps.upper_mark], "line 5");
+        assert_eq!(ps.gutter_marks, BTreeSet::from([5, 11]));
+
+        // Past the end, the lines are appended, also after an unterminated last line
+        insert(100, &["end"], &mut ps);
+        assert_eq!(ps.lines.lines().last(), Some("end"));
+        ps.lines.push_str("partial");
+        ps.format_lines();
+        insert(100, &["after"], &mut ps);
+        assert!(ps.lines.ends_with("end\npartial\nafter\n"));
+        assert_eq!(ps.formatted_lines[ps.upper_mark], "line 5");
+    }
+
+    #[test]
+    fn delete_lines() {
+        use std::collections::BTreeSet;
+
+        let mut ps = PagerState::new().unwrap();
+        ps.lines = (0..20)
+            .map(|i| format!("line {i}\n"))
+            .collect::<Vec<_>>()
+            .concat();
+        ps.format_lines();
+        ps.upper_mark = 5;
+        ps.gutter_marks.insert(3);
+        ps.gutter_marks.insert(6);
+        ps.gutter_marks.insert(12);
+        let mut out = Vec::new();
+        let mut delete = |range, ps: &mut PagerState| {
+            handle_event(
+                Event::DeleteLines(range),
+                &mut out,
+                ps,
+                &Arc::new(AtomicBool::new(false)),
+                #[cfg(feature = "search")]
+                &UIA,
+            )
+            .unwrap();
+        };
+
+        // Above the view, the view and the marks move up along with the text
+        delete(1..3, &mut ps);
+        assert_eq!(
+            ps.lines.lines().take(2).collect::<Vec<_>>(),
+            vec!["line 0", "line 3"]
+        );
+        assert_eq!(ps.formatted_lines[ps.upper_mark], "line 5");
+        assert_eq!(ps.gutter_marks, BTreeSet::from([1, 4, 10]));
+
+        // Overlapping the top of the view, the next remaining line goes to the top and the
+        // marks on removed lines are dropped
+        delete(2..5, &mut ps);
+        assert_eq!(ps.upper_mark, 2);
+        assert_eq!(ps.formatted_lines[ps.upper_mark], "line 7");
+        assert_eq!(ps.gutter_marks, BTreeSet::from([1, 7]));
+
+        // Below the top of the view, it stays where it is
+        delete(4..6, &mut ps);
+        assert_eq!(ps.formatted_lines[ps.upper_mark], "line 7");
+        assert_eq!(ps.lines.lines().nth(4), Some("line 11"));
+
+        // Empty and out of range ranges do nothing, others are clamped
+        let lines = ps.lines.clone();
+        #[allow(clippy::reversed_empty_ranges)]
+        delete(5..3, &mut ps);
+        delete(4..4, &mut ps);
+        delete(100..200, &mut ps);
+        assert_eq!(ps.lines, lines);
+        delete(10..200, &mut ps);
+        assert_eq!(ps.lines.lines().count(), 10);
+        assert_eq!(ps.lines.lines().last(), Some("line 16"));
+
+        // Removing everything from the top of the view leaves it on the last line
+        delete(2..10, &mut ps);
+        assert_eq!(ps.lines, "line 0\nline 3\n");
+        assert_eq!(ps.upper_mark, 1);
     }
 }
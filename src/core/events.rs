@@ -1,58 +1,488 @@
 //! Provides the [`Event`] enum and all its related implementations
-use std::fmt::Debug;
+use crossterm::{
+    event::KeyEvent,
+    style::{Color, ContentStyle},
+};
+use std::{fmt::Debug, io::Write, ops::Range, time::Duration};
 
+#[cfg(feature = "static_output")]
+use crate::OverflowBehavior;
 use crate::{
+    highlight::Highlighter,
     input::{InputClassifier, InputEvent},
-    ExitStrategy, LineNumbers,
+    source::LineSource,
+    AmbiguousWidth, AppendFeedback, ContinuationNumberStyle, CrMode, DumpScope, DynamicPrompt,
+    EdgeFeedback, EnterAction, ExitReason, ExitStrategy, LineNumberStyle, LineNumbers, LineStyler,
+    PagerObservation, PagerState, PromptPosition, TextDirection, WideCharWrap, WrapMode,
 };
 
 /// Different events that can be encountered while the pager is running
 pub enum Event {
     AppendData(String),
     SetData(String),
+    AppendDataBytes(Vec<u8>),
+    SetDataBytes(Vec<u8>),
+    AppendStyledLine(Vec<(ContentStyle, String)>),
+    Batch(Vec<Self>),
+    SetDataKeepPosition(String),
+    InsertLines(usize, Vec<String>),
+    DeleteLines(Range<usize>),
+    SetStartLine(usize),
+    AddBuffer(String),
+    NextBuffer,
+    PrevBuffer,
     UserInput(InputEvent),
+    ScrollTo(usize),
+    ScrollBy(isize),
+    FeedInput(crossterm::event::Event),
     SetPrompt(String),
+    SetPromptSegments(String, String),
     SendMessage(String),
     SetLineNumbers(LineNumbers),
+    SetLineNumberStyle(LineNumberStyle),
+    SetContinuationNumberStyle(ContinuationNumberStyle),
+    SetLineWrapping(bool),
+    SetWrapMode(WrapMode),
+    SetWideCharWrap(WideCharWrap),
+    SetAmbiguousWidth(AmbiguousWidth),
+    SetWrapIndicator(Option<String>),
+    SetShowWhitespace(bool),
+    SetCarriageReturnMode(CrMode),
+    SetControlCharPlaceholder(Option<char>),
+    SetColor(bool),
+    SetScrollbar(bool),
+    SetMaxContentWidth(Option<usize>),
+    SetReflow(bool),
+    SetScrollPastEnd(bool),
+    SetFrozenHeaderLines(usize),
+    Redraw,
+    SetPageOverlap(usize),
+    SetTruncationMarker(char),
+    SetLazyFormatting(bool),
+    SetTextDirection(TextDirection),
+    SetMaxBufferBytes(Option<usize>),
+    SetExitKeys(Vec<KeyEvent>),
+    SetDumpExitKeys(Vec<KeyEvent>),
+    SetCountPrefixEnabled(bool),
+    SetCountDisplay(bool),
+    SetCountTimeout(Duration),
+    SetMouseCapture(bool),
+    SetDragScroll(bool),
+    SetMouseScrollAmount(usize),
+    SetHorizontalMouseScrollAmount(usize),
+    SetScrollWrap(bool),
+    InputFinished,
+    SetExitOnFinish(bool),
+    SetAlternateScreen(bool),
+    SetPanicCleanup(bool),
+    SetExternalInput(bool),
+    SetOutput(Box<dyn Write + Send>),
+    SetHighlightRow(Option<usize>),
+    SetHighlightColor(Color),
+    SetCursorMode(bool),
+    SetCursorColor(Color),
+    SetScrollOff(usize),
+    SetSelectionColor(Color),
+    SetOnYank(Box<dyn FnMut(String) + Send + Sync + 'static>),
+    SetEnterAction(EnterAction),
+    SetPromptPosition(PromptPosition),
+    SetEdgeFeedback(EdgeFeedback),
+    SetOnSelect(Box<dyn FnMut(usize) + Send + Sync + 'static>),
+    SetEventObserver(Box<dyn Fn(PagerObservation) + Send + Sync + 'static>),
+    SetPromptStyle(ContentStyle),
+    ResetDisplayOptions,
+    SetRedrawInterval(Option<Duration>),
+    SetResizeDebounce(Duration),
+    SetAppendCoalesce(Option<Duration>),
+    SetTickInterval(Option<Duration>),
+    Tick,
+    SetSmoothScroll(Option<Duration>),
+    ScrollFrame,
+    SetDeterministicMode(bool),
+    SetTerminalSize(usize, usize),
+    PauseRendering,
+    ResumeRendering,
     SetExitStrategy(ExitStrategy),
+    SetExitDumpRemaining(bool),
+    SetExitDumpScope(DumpScope),
     SetInputClassifier(Box<dyn InputClassifier + Send + Sync + 'static>),
     AddExitCallback(Box<dyn FnMut() + Send + Sync + 'static>),
+    AddExitCallbackWithReason(Box<dyn FnMut(ExitReason) + Send + Sync + 'static>),
+    WithState(Box<dyn FnOnce(&PagerState) + Send + 'static>),
+    SetAppendFeedback(Box<dyn Fn(AppendFeedback) + Send + Sync + 'static>),
+    SetOnReachBottom(Box<dyn FnMut() + Send + Sync + 'static>),
+    SetOnReachTop(Box<dyn FnMut() + Send + Sync + 'static>),
+    SetOnBeforeReflow(Box<dyn FnMut() + Send + Sync + 'static>),
+    SetOnAfterReflow(Box<dyn FnMut() + Send + Sync + 'static>),
+    SetOnGutterMark(Box<dyn FnMut(usize, bool) + Send + Sync + 'static>),
+    SetOnLinkActivate(Box<dyn FnMut(String) + Send + Sync + 'static>),
+    SetLineStyler(LineStyler),
+    SetHighlighter(Box<dyn Highlighter + Send + Sync + 'static>),
+    SetLineSource(Box<dyn LineSource + Send + Sync + 'static>),
+    SetDynamicPrompt(DynamicPrompt),
+    SetStatusBar(Option<String>),
+    SetDynamicStatusBar(DynamicPrompt),
+    SetGutterMarkGlyph(Option<char>),
+    SetEndOfBufferChar(Option<char>),
     #[cfg(feature = "static_output")]
     SetRunNoOverflow(bool),
+    #[cfg(feature = "static_output")]
+    SetOverflowBehavior(OverflowBehavior),
+    #[cfg(feature = "search")]
+    SetSearchWrap(bool),
+    #[cfg(feature = "search")]
+    SetCenterOnMatch(bool),
+    #[cfg(feature = "search")]
+    SetHighlightVisible(bool),
+    #[cfg(feature = "search")]
+    SetSearchFocus(bool),
+    #[cfg(feature = "search")]
+    SetSearchMultiline(bool),
+    #[cfg(feature = "search")]
+    SetMaxSearchMatches(Option<usize>),
+    #[cfg(feature = "search")]
+    SetSearchKeys(char, char),
+    #[cfg(feature = "search")]
+    SetSearchLiteral(bool),
+    #[cfg(feature = "search")]
+    SetKeepUnmatchedSearch(bool),
+    #[cfg(feature = "search")]
+    SetBeepOnNoMatch(bool),
+    #[cfg(feature = "search")]
+    SetSearchRange(Option<(usize, usize)>),
+    #[cfg(feature = "search")]
+    SetHighlightGroups(Vec<regex::Regex>),
+    #[cfg(feature = "search")]
+    SetSectionPattern(Option<regex::Regex>),
+    #[cfg(feature = "search")]
+    SetCurrentMatchStyle(ContentStyle),
+    #[cfg(feature = "search")]
+    SetOtherMatchStyle(ContentStyle),
+    #[cfg(feature = "clipboard")]
+    SetLineRefTemplate(String),
+    #[cfg(feature = "follow_file")]
+    FollowFile(std::path::PathBuf),
 }
 
 impl PartialEq for Event {
+    #[allow(clippy::too_many_lines)]
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::SetData(d1), Self::SetData(d2))
+            | (Self::SetDataKeepPosition(d1), Self::SetDataKeepPosition(d2))
+            | (Self::AddBuffer(d1), Self::AddBuffer(d2))
             | (Self::AppendData(d1), Self::AppendData(d2))
             | (Self::SetPrompt(d1), Self::SetPrompt(d2))
             | (Self::SendMessage(d1), Self::SendMessage(d2)) => d1 == d2,
             (Self::SetLineNumbers(d1), Self::SetLineNumbers(d2)) => d1 == d2,
+            (Self::SetLineNumberStyle(d1), Self::SetLineNumberStyle(d2)) => d1 == d2,
+            (Self::SetContinuationNumberStyle(d1), Self::SetContinuationNumberStyle(d2)) => {
+                d1 == d2
+            }
+            (Self::UserInput(d1), Self::UserInput(d2)) => d1 == d2,
+            (Self::ScrollBy(d1), Self::ScrollBy(d2)) => d1 == d2,
+            (Self::SetLineWrapping(d1), Self::SetLineWrapping(d2)) => d1 == d2,
+            (Self::SetWrapMode(d1), Self::SetWrapMode(d2)) => d1 == d2,
+            (Self::SetWideCharWrap(d1), Self::SetWideCharWrap(d2)) => d1 == d2,
+            (Self::SetAmbiguousWidth(d1), Self::SetAmbiguousWidth(d2)) => d1 == d2,
+            (Self::SetCarriageReturnMode(d1), Self::SetCarriageReturnMode(d2)) => d1 == d2,
+            (Self::SetControlCharPlaceholder(d1), Self::SetControlCharPlaceholder(d2)) => d1 == d2,
+            (Self::SetWrapIndicator(d1), Self::SetWrapIndicator(d2)) => d1 == d2,
+            (Self::SetShowWhitespace(d1), Self::SetShowWhitespace(d2))
+            | (Self::SetColor(d1), Self::SetColor(d2))
+            | (Self::SetScrollbar(d1), Self::SetScrollbar(d2))
+            | (Self::SetReflow(d1), Self::SetReflow(d2))
+            | (Self::SetScrollPastEnd(d1), Self::SetScrollPastEnd(d2)) => d1 == d2,
+            (Self::SetPageOverlap(d1), Self::SetPageOverlap(d2))
+            | (Self::SetFrozenHeaderLines(d1), Self::SetFrozenHeaderLines(d2))
+            | (Self::SetStartLine(d1), Self::SetStartLine(d2))
+            | (Self::ScrollTo(d1), Self::ScrollTo(d2))
+            | (Self::SetMouseScrollAmount(d1), Self::SetMouseScrollAmount(d2))
+            | (
+                Self::SetHorizontalMouseScrollAmount(d1),
+                Self::SetHorizontalMouseScrollAmount(d2),
+            ) => d1 == d2,
+            (Self::SetTruncationMarker(d1), Self::SetTruncationMarker(d2)) => d1 == d2,
+            (Self::SetLazyFormatting(d1), Self::SetLazyFormatting(d2)) => d1 == d2,
+            (Self::SetTextDirection(d1), Self::SetTextDirection(d2)) => d1 == d2,
+            (Self::SetEnterAction(d1), Self::SetEnterAction(d2)) => d1 == d2,
+            (Self::SetPromptPosition(d1), Self::SetPromptPosition(d2)) => d1 == d2,
+            (Self::SetEdgeFeedback(d1), Self::SetEdgeFeedback(d2)) => d1 == d2,
+            (Self::FeedInput(d1), Self::FeedInput(d2)) => d1 == d2,
+            (Self::SetMaxContentWidth(d1), Self::SetMaxContentWidth(d2)) => d1 == d2,
+            (Self::Batch(d1), Self::Batch(d2)) => d1 == d2,
+            (Self::SetMaxBufferBytes(d1), Self::SetMaxBufferBytes(d2)) => d1 == d2,
+            (Self::SetExitKeys(d1), Self::SetExitKeys(d2))
+            | (Self::SetDumpExitKeys(d1), Self::SetDumpExitKeys(d2)) => d1 == d2,
+            (Self::SetCountPrefixEnabled(d1), Self::SetCountPrefixEnabled(d2))
+            | (Self::SetCountDisplay(d1), Self::SetCountDisplay(d2)) => d1 == d2,
+            (Self::SetCountTimeout(d1), Self::SetCountTimeout(d2)) => d1 == d2,
+            (Self::SetMouseCapture(d1), Self::SetMouseCapture(d2))
+            | (Self::SetDragScroll(d1), Self::SetDragScroll(d2))
+            | (Self::SetScrollWrap(d1), Self::SetScrollWrap(d2))
+            | (Self::SetExitOnFinish(d1), Self::SetExitOnFinish(d2)) => d1 == d2,
+            (Self::SetAlternateScreen(d1), Self::SetAlternateScreen(d2))
+            | (Self::SetPanicCleanup(d1), Self::SetPanicCleanup(d2))
+            | (Self::SetExternalInput(d1), Self::SetExternalInput(d2)) => d1 == d2,
+            (Self::SetHighlightRow(d1), Self::SetHighlightRow(d2)) => d1 == d2,
+            (Self::SetHighlightColor(d1), Self::SetHighlightColor(d2))
+            | (Self::SetCursorColor(d1), Self::SetCursorColor(d2))
+            | (Self::SetSelectionColor(d1), Self::SetSelectionColor(d2)) => d1 == d2,
+            (Self::SetCursorMode(d1), Self::SetCursorMode(d2)) => d1 == d2,
+            (Self::SetScrollOff(d1), Self::SetScrollOff(d2)) => d1 == d2,
+            (Self::SetPromptStyle(d1), Self::SetPromptStyle(d2)) => d1 == d2,
+            (Self::SetRedrawInterval(d1), Self::SetRedrawInterval(d2))
+            | (Self::SetAppendCoalesce(d1), Self::SetAppendCoalesce(d2)) => d1 == d2,
+            (Self::SetResizeDebounce(d1), Self::SetResizeDebounce(d2)) => d1 == d2,
+            (Self::SetTickInterval(d1), Self::SetTickInterval(d2))
+            | (Self::SetSmoothScroll(d1), Self::SetSmoothScroll(d2)) => d1 == d2,
+            (Self::SetDeterministicMode(d1), Self::SetDeterministicMode(d2)) => d1 == d2,
+            (Self::SetTerminalSize(c1, r1), Self::SetTerminalSize(c2, r2)) => c1 == c2 && r1 == r2,
+            (Self::SetPromptSegments(l1, r1), Self::SetPromptSegments(l2, r2)) => {
+                l1 == l2 && r1 == r2
+            }
+            (Self::InsertLines(i1, l1), Self::InsertLines(i2, l2)) => i1 == i2 && l1 == l2,
+            (Self::DeleteLines(r1), Self::DeleteLines(r2)) => r1 == r2,
             (Self::SetExitStrategy(d1), Self::SetExitStrategy(d2)) => d1 == d2,
+            (Self::SetExitDumpRemaining(d1), Self::SetExitDumpRemaining(d2)) => d1 == d2,
+            (Self::SetExitDumpScope(d1), Self::SetExitDumpScope(d2)) => d1 == d2,
             #[cfg(feature = "static_output")]
             (Self::SetRunNoOverflow(d1), Self::SetRunNoOverflow(d2)) => d1 == d2,
-            (Self::SetInputClassifier(_), Self::SetInputClassifier(_))
-            | (Self::AddExitCallback(_), Self::AddExitCallback(_)) => true,
+            #[cfg(feature = "static_output")]
+            (Self::SetOverflowBehavior(d1), Self::SetOverflowBehavior(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetSearchWrap(d1), Self::SetSearchWrap(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetCenterOnMatch(d1), Self::SetCenterOnMatch(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetHighlightVisible(d1), Self::SetHighlightVisible(d2))
+            | (Self::SetSearchFocus(d1), Self::SetSearchFocus(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetSearchMultiline(d1), Self::SetSearchMultiline(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetMaxSearchMatches(d1), Self::SetMaxSearchMatches(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetSearchKeys(f1, r1), Self::SetSearchKeys(f2, r2)) => f1 == f2 && r1 == r2,
+            #[cfg(feature = "search")]
+            (Self::SetSearchLiteral(d1), Self::SetSearchLiteral(d2))
+            | (Self::SetKeepUnmatchedSearch(d1), Self::SetKeepUnmatchedSearch(d2))
+            | (Self::SetBeepOnNoMatch(d1), Self::SetBeepOnNoMatch(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetSearchRange(d1), Self::SetSearchRange(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetCurrentMatchStyle(d1), Self::SetCurrentMatchStyle(d2))
+            | (Self::SetOtherMatchStyle(d1), Self::SetOtherMatchStyle(d2)) => d1 == d2,
+            #[cfg(feature = "search")]
+            (Self::SetHighlightGroups(d1), Self::SetHighlightGroups(d2)) => d1
+                .iter()
+                .map(regex::Regex::as_str)
+                .eq(d2.iter().map(regex::Regex::as_str)),
+            #[cfg(feature = "search")]
+            (Self::SetSectionPattern(d1), Self::SetSectionPattern(d2)) => {
+                d1.as_ref().map(regex::Regex::as_str) == d2.as_ref().map(regex::Regex::as_str)
+            }
+            #[cfg(feature = "clipboard")]
+            (Self::SetLineRefTemplate(d1), Self::SetLineRefTemplate(d2)) => d1 == d2,
+            #[cfg(feature = "follow_file")]
+            (Self::FollowFile(d1), Self::FollowFile(d2)) => d1 == d2,
+            (Self::AppendDataBytes(d1), Self::AppendDataBytes(d2))
+            | (Self::SetDataBytes(d1), Self::SetDataBytes(d2)) => d1 == d2,
+            (Self::AppendStyledLine(d1), Self::AppendStyledLine(d2)) => d1 == d2,
+            (Self::ResetDisplayOptions, Self::ResetDisplayOptions)
+            | (Self::NextBuffer, Self::NextBuffer)
+            | (Self::PrevBuffer, Self::PrevBuffer)
+            | (Self::Tick, Self::Tick)
+            | (Self::ScrollFrame, Self::ScrollFrame)
+            | (Self::PauseRendering, Self::PauseRendering)
+            | (Self::ResumeRendering, Self::ResumeRendering)
+            | (Self::SetInputClassifier(_), Self::SetInputClassifier(_))
+            | (Self::AddExitCallback(_), Self::AddExitCallback(_))
+            | (Self::AddExitCallbackWithReason(_), Self::AddExitCallbackWithReason(_))
+            | (Self::WithState(_), Self::WithState(_))
+            | (Self::SetOutput(_), Self::SetOutput(_))
+            | (Self::SetAppendFeedback(_), Self::SetAppendFeedback(_))
+            | (Self::Redraw, Self::Redraw)
+            | (Self::InputFinished, Self::InputFinished)
+            | (Self::SetOnReachBottom(_), Self::SetOnReachBottom(_))
+            | (Self::SetOnReachTop(_), Self::SetOnReachTop(_))
+            | (Self::SetOnBeforeReflow(_), Self::SetOnBeforeReflow(_))
+            | (Self::SetOnAfterReflow(_), Self::SetOnAfterReflow(_))
+            | (Self::SetOnGutterMark(_), Self::SetOnGutterMark(_))
+            | (Self::SetOnYank(_), Self::SetOnYank(_))
+            | (Self::SetOnSelect(_), Self::SetOnSelect(_))
+            | (Self::SetEventObserver(_), Self::SetEventObserver(_))
+            | (Self::SetOnLinkActivate(_), Self::SetOnLinkActivate(_))
+            | (Self::SetLineStyler(_), Self::SetLineStyler(_))
+            | (Self::SetHighlighter(_), Self::SetHighlighter(_))
+            | (Self::SetLineSource(_), Self::SetLineSource(_))
+            | (Self::SetDynamicPrompt(_), Self::SetDynamicPrompt(_))
+            | (Self::SetDynamicStatusBar(_), Self::SetDynamicStatusBar(_)) => true,
+            (Self::SetStatusBar(d1), Self::SetStatusBar(d2)) => d1 == d2,
+            (Self::SetGutterMarkGlyph(d1), Self::SetGutterMarkGlyph(d2))
+            | (Self::SetEndOfBufferChar(d1), Self::SetEndOfBufferChar(d2)) => d1 == d2,
             _ => false,
         }
     }
 }
 
 impl Debug for Event {
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::SetData(text) => write!(f, "SetData({text:?})"),
+            Self::AppendDataBytes(bytes) => write!(f, "AppendDataBytes({bytes:?})"),
+            Self::SetDataBytes(bytes) => write!(f, "SetDataBytes({bytes:?})"),
+            Self::AppendStyledLine(spans) => write!(f, "AppendStyledLine({spans:?})"),
+            Self::Batch(events) => write!(f, "Batch({events:?})"),
+            Self::AddBuffer(text) => write!(f, "AddBuffer({text:?})"),
+            Self::NextBuffer => write!(f, "NextBuffer"),
+            Self::PrevBuffer => write!(f, "PrevBuffer"),
+            Self::SetDataKeepPosition(text) => write!(f, "SetDataKeepPosition({text:?})"),
+            Self::InsertLines(idx, lines) => write!(f, "InsertLines({idx:?}, {lines:?})"),
+            Self::DeleteLines(range) => write!(f, "DeleteLines({range:?})"),
+            Self::SetStartLine(line) => write!(f, "SetStartLine({line:?})"),
             Self::AppendData(text) => write!(f, "AppendData({text:?})"),
             Self::SetPrompt(text) => write!(f, "SetPrompt({text:?})"),
+            Self::SetPromptSegments(left, right) => {
+                write!(f, "SetPromptSegments({left:?}, {right:?})")
+            }
             Self::SendMessage(text) => write!(f, "SendMessage({text:?})"),
             Self::SetLineNumbers(ln) => write!(f, "SetLineNumbers({ln:?})"),
+            Self::SetLineNumberStyle(style) => write!(f, "SetLineNumberStyle({style:?})"),
+            Self::SetContinuationNumberStyle(style) => {
+                write!(f, "SetContinuationNumberStyle({style:?})")
+            }
+            Self::SetLineWrapping(val) => write!(f, "SetLineWrapping({val:?})"),
+            Self::SetWrapMode(val) => write!(f, "SetWrapMode({val:?})"),
+            Self::SetWideCharWrap(val) => write!(f, "SetWideCharWrap({val:?})"),
+            Self::SetAmbiguousWidth(val) => write!(f, "SetAmbiguousWidth({val:?})"),
+            Self::SetWrapIndicator(val) => write!(f, "SetWrapIndicator({val:?})"),
+            Self::SetShowWhitespace(val) => write!(f, "SetShowWhitespace({val:?})"),
+            Self::SetCarriageReturnMode(val) => write!(f, "SetCarriageReturnMode({val:?})"),
+            Self::SetControlCharPlaceholder(val) => {
+                write!(f, "SetControlCharPlaceholder({val:?})")
+            }
+            Self::SetColor(val) => write!(f, "SetColor({val:?})"),
+            Self::SetScrollbar(val) => write!(f, "SetScrollbar({val:?})"),
+            Self::SetMaxContentWidth(val) => write!(f, "SetMaxContentWidth({val:?})"),
+            Self::SetReflow(val) => write!(f, "SetReflow({val:?})"),
+            Self::SetScrollPastEnd(val) => write!(f, "SetScrollPastEnd({val:?})"),
+            Self::SetFrozenHeaderLines(val) => write!(f, "SetFrozenHeaderLines({val:?})"),
+            Self::Redraw => write!(f, "Redraw"),
+            Self::SetPageOverlap(val) => write!(f, "SetPageOverlap({val:?})"),
+            Self::SetTruncationMarker(c) => write!(f, "SetTruncationMarker({c:?})"),
+            Self::SetLazyFormatting(val) => write!(f, "SetLazyFormatting({val:?})"),
+            Self::SetTextDirection(dir) => write!(f, "SetTextDirection({dir:?})"),
+            Self::SetMaxBufferBytes(val) => write!(f, "SetMaxBufferBytes({val:?})"),
+            Self::SetExitKeys(val) => write!(f, "SetExitKeys({val:?})"),
+            Self::SetDumpExitKeys(val) => write!(f, "SetDumpExitKeys({val:?})"),
+            Self::SetCountPrefixEnabled(val) => write!(f, "SetCountPrefixEnabled({val:?})"),
+            Self::SetCountDisplay(val) => write!(f, "SetCountDisplay({val:?})"),
+            Self::SetCountTimeout(val) => write!(f, "SetCountTimeout({val:?})"),
+            Self::SetMouseCapture(val) => write!(f, "SetMouseCapture({val:?})"),
+            Self::SetDragScroll(val) => write!(f, "SetDragScroll({val:?})"),
+            Self::SetMouseScrollAmount(val) => write!(f, "SetMouseScrollAmount({val:?})"),
+            Self::SetHorizontalMouseScrollAmount(val) => {
+                write!(f, "SetHorizontalMouseScrollAmount({val:?})")
+            }
+            Self::SetScrollWrap(val) => write!(f, "SetScrollWrap({val:?})"),
+            Self::InputFinished => write!(f, "InputFinished"),
+            Self::SetExitOnFinish(val) => write!(f, "SetExitOnFinish({val:?})"),
+            Self::SetAlternateScreen(val) => write!(f, "SetAlternateScreen({val:?})"),
+            Self::SetPanicCleanup(val) => write!(f, "SetPanicCleanup({val:?})"),
+            Self::SetExternalInput(val) => write!(f, "SetExternalInput({val:?})"),
+            Self::FeedInput(ev) => write!(f, "FeedInput({ev:?})"),
+            Self::SetHighlightRow(val) => write!(f, "SetHighlightRow({val:?})"),
+            Self::SetHighlightColor(val) => write!(f, "SetHighlightColor({val:?})"),
+            Self::SetCursorMode(val) => write!(f, "SetCursorMode({val:?})"),
+            Self::SetCursorColor(val) => write!(f, "SetCursorColor({val:?})"),
+            Self::SetScrollOff(val) => write!(f, "SetScrollOff({val:?})"),
+            Self::SetSelectionColor(val) => write!(f, "SetSelectionColor({val:?})"),
+            Self::SetOnYank(_) => write!(f, "SetOnYank"),
+            Self::SetEnterAction(val) => write!(f, "SetEnterAction({val:?})"),
+            Self::SetPromptPosition(val) => write!(f, "SetPromptPosition({val:?})"),
+            Self::SetEdgeFeedback(val) => write!(f, "SetEdgeFeedback({val:?})"),
+            Self::SetOnSelect(_) => write!(f, "SetOnSelect"),
+            Self::SetEventObserver(_) => write!(f, "SetEventObserver"),
+            Self::SetPromptStyle(val) => write!(f, "SetPromptStyle({val:?})"),
+            Self::ResetDisplayOptions => write!(f, "ResetDisplayOptions"),
+            Self::SetRedrawInterval(val) => write!(f, "SetRedrawInterval({val:?})"),
+            Self::SetResizeDebounce(val) => write!(f, "SetResizeDebounce({val:?})"),
+            Self::SetAppendCoalesce(val) => write!(f, "SetAppendCoalesce({val:?})"),
+            Self::SetTickInterval(val) => write!(f, "SetTickInterval({val:?})"),
+            Self::Tick => write!(f, "Tick"),
+            Self::SetSmoothScroll(val) => write!(f, "SetSmoothScroll({val:?})"),
+            Self::ScrollFrame => write!(f, "ScrollFrame"),
+            Self::SetDeterministicMode(val) => write!(f, "SetDeterministicMode({val:?})"),
+            Self::SetTerminalSize(cols, rows) => write!(f, "SetTerminalSize({cols:?}, {rows:?})"),
+            Self::PauseRendering => write!(f, "PauseRendering"),
+            Self::ResumeRendering => write!(f, "ResumeRendering"),
             Self::SetExitStrategy(es) => write!(f, "SetExitStrategy({es:?})"),
+            Self::SetExitDumpRemaining(val) => write!(f, "SetExitDumpRemaining({val:?})"),
+            Self::SetExitDumpScope(val) => write!(f, "SetExitDumpScope({val:?})"),
             Self::SetInputClassifier(_) => write!(f, "SetInputClassifier"),
             Self::AddExitCallback(_) => write!(f, "AddExitCallback"),
+            Self::AddExitCallbackWithReason(_) => write!(f, "AddExitCallbackWithReason"),
+            Self::WithState(_) => write!(f, "WithState"),
+            Self::SetOutput(_) => write!(f, "SetOutput"),
+            Self::SetAppendFeedback(_) => write!(f, "SetAppendFeedback"),
+            Self::SetOnReachBottom(_) => write!(f, "SetOnReachBottom"),
+            Self::SetOnReachTop(_) => write!(f, "SetOnReachTop"),
+            Self::SetOnBeforeReflow(_) => write!(f, "SetOnBeforeReflow"),
+            Self::SetOnAfterReflow(_) => write!(f, "SetOnAfterReflow"),
+            Self::SetOnGutterMark(_) => write!(f, "SetOnGutterMark"),
+            Self::SetOnLinkActivate(_) => write!(f, "SetOnLinkActivate"),
+            Self::SetLineStyler(_) => write!(f, "SetLineStyler"),
+            Self::SetHighlighter(_) => write!(f, "SetHighlighter"),
+            Self::SetLineSource(_) => write!(f, "SetLineSource"),
+            Self::SetDynamicPrompt(_) => write!(f, "SetDynamicPrompt"),
+            Self::SetStatusBar(text) => write!(f, "SetStatusBar({text:?})"),
+            Self::SetDynamicStatusBar(_) => write!(f, "SetDynamicStatusBar"),
+            Self::SetGutterMarkGlyph(val) => write!(f, "SetGutterMarkGlyph({val:?})"),
+            Self::SetEndOfBufferChar(val) => write!(f, "SetEndOfBufferChar({val:?})"),
             #[cfg(feature = "static_output")]
             Self::SetRunNoOverflow(val) => write!(f, "SetRunNoOverflow({val:?})"),
+            #[cfg(feature = "static_output")]
+            Self::SetOverflowBehavior(val) => write!(f, "SetOverflowBehavior({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetSearchWrap(val) => write!(f, "SetSearchWrap({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetCenterOnMatch(val) => write!(f, "SetCenterOnMatch({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetHighlightVisible(val) => write!(f, "SetHighlightVisible({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetSearchFocus(val) => write!(f, "SetSearchFocus({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetSearchMultiline(val) => write!(f, "SetSearchMultiline({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetMaxSearchMatches(val) => write!(f, "SetMaxSearchMatches({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetSearchKeys(forward, reverse) => {
+                write!(f, "SetSearchKeys({forward:?}, {reverse:?})")
+            }
+            #[cfg(feature = "search")]
+            Self::SetSearchLiteral(val) => write!(f, "SetSearchLiteral({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetKeepUnmatchedSearch(val) => write!(f, "SetKeepUnmatchedSearch({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetBeepOnNoMatch(val) => write!(f, "SetBeepOnNoMatch({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetSearchRange(val) => write!(f, "SetSearchRange({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetHighlightGroups(val) => write!(f, "SetHighlightGroups({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetSectionPattern(val) => write!(f, "SetSectionPattern({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetCurrentMatchStyle(val) => write!(f, "SetCurrentMatchStyle({val:?})"),
+            #[cfg(feature = "search")]
+            Self::SetOtherMatchStyle(val) => write!(f, "SetOtherMatchStyle({val:?})"),
+            #[cfg(feature = "clipboard")]
+            Self::SetLineRefTemplate(val) => write!(f, "SetLineRefTemplate({val:?})"),
+            #[cfg(feature = "follow_file")]
+            Self::FollowFile(path) => write!(f, "FollowFile({path:?})"),
             Self::UserInput(input) => write!(f, "UserInput({input:?})"),
+            Self::ScrollTo(line) => write!(f, "ScrollTo({line:?})"),
+            Self::ScrollBy(delta) => write!(f, "ScrollBy({delta:?})"),
         }
     }
 }
@@ -60,19 +490,100 @@ impl Debug for Event {
 impl Event {
     #[allow(dead_code)]
     pub(crate) const fn is_exit_event(&self) -> bool {
-        matches!(self, Self::UserInput(InputEvent::Exit))
+        matches!(
+            self,
+            Self::UserInput(InputEvent::Exit | InputEvent::ExitAndDump)
+        )
     }
 
     #[allow(dead_code)]
     pub(crate) const fn is_movement(&self) -> bool {
-        matches!(self, Self::UserInput(InputEvent::UpdateUpperMark(_)))
+        matches!(
+            self,
+            Self::UserInput(
+                InputEvent::UpdateUpperMark(_)
+                    | InputEvent::GotoPercent(_)
+                    | InputEvent::NextParagraph(_)
+                    | InputEvent::StartDrag(_)
+                    | InputEvent::Drag(_)
+                    | InputEvent::PrevParagraph(_)
+                    | InputEvent::ScrollBy(_)
+            ) | Self::ScrollTo(_)
+                | Self::ScrollBy(_)
+                | Self::ScrollFrame
+        )
     }
 
     #[cfg(feature = "dynamic_output")]
     pub(crate) const fn required_immidiate_screen_update(&self) -> bool {
+        #[cfg(feature = "search")]
+        if matches!(
+            self,
+            Self::SetSearchMultiline(_)
+                | Self::SetMaxSearchMatches(_)
+                | Self::SetSearchRange(_)
+                | Self::SetHighlightVisible(_)
+                | Self::SetSearchFocus(_)
+                | Self::SetHighlightGroups(_)
+                | Self::SetCurrentMatchStyle(_)
+                | Self::SetOtherMatchStyle(_)
+        ) {
+            return true;
+        }
         matches!(
             self,
-            Self::SetData(_) | Self::SetPrompt(_) | Self::SendMessage(_) | Self::UserInput(_)
+            Self::SetData(_)
+                | Self::SetDataKeepPosition(_)
+                | Self::InsertLines(..)
+                | Self::DeleteLines(_)
+                | Self::SetStartLine(_)
+                | Self::AddBuffer(_)
+                | Self::NextBuffer
+                | Self::PrevBuffer
+                | Self::SetPrompt(_)
+                | Self::SetPromptSegments(..)
+                | Self::SetDynamicPrompt(_)
+                | Self::SetStatusBar(_)
+                | Self::SetDynamicStatusBar(_)
+                | Self::SetLineStyler(_)
+                | Self::SetHighlighter(_)
+                | Self::SetLineSource(_)
+                | Self::SendMessage(_)
+                | Self::SetLineWrapping(_)
+                | Self::SetWrapMode(_)
+                | Self::SetWideCharWrap(_)
+                | Self::SetAmbiguousWidth(_)
+                | Self::SetTerminalSize(..)
+                | Self::SetWrapIndicator(_)
+                | Self::SetShowWhitespace(_)
+                | Self::SetCarriageReturnMode(_)
+                | Self::SetColor(_)
+                | Self::SetScrollbar(_)
+                | Self::SetMaxContentWidth(_)
+                | Self::SetPromptPosition(_)
+                | Self::SetReflow(_)
+                | Self::SetScrollPastEnd(_)
+                | Self::SetFrozenHeaderLines(_)
+                | Self::SetTruncationMarker(_)
+                | Self::SetLazyFormatting(_)
+                | Self::SetTextDirection(_)
+                | Self::SetLineNumberStyle(_)
+                | Self::SetContinuationNumberStyle(_)
+                | Self::SetMaxBufferBytes(_)
+                | Self::SetGutterMarkGlyph(_)
+                | Self::SetEndOfBufferChar(_)
+                | Self::SetHighlightRow(_)
+                | Self::SetHighlightColor(_)
+                | Self::SetCursorMode(_)
+                | Self::SetCursorColor(_)
+                | Self::SetSelectionColor(_)
+                | Self::SetPromptStyle(_)
+                | Self::SetCountDisplay(_)
+                | Self::ResetDisplayOptions
+                | Self::Redraw
+                | Self::UserInput(_)
+                | Self::ScrollTo(_)
+                | Self::ScrollBy(_)
         )
     }
 }
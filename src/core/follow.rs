@@ -0,0 +1,179 @@
+//! Keeps the pager in sync with a file on disk, like `tail -f`
+//!
+//! The file is polled for changes instead of relying on filesystem notifications, so that no
+//! platform specific dependencies are needed.
+
+use super::{events::Event, utils::text::decode_utf8_lossy};
+use crossbeam_channel::Sender;
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, Read, Seek, SeekFrom},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+/// How often the file is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// How often the follower checks whether the pager has quit while waiting for the next poll
+const EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Tracks how much of a file has already been sent to the pager
+pub struct FileFollower {
+    path: PathBuf,
+    /// Number of bytes of the file that have been read
+    pos: u64,
+    /// Identity of the file that was read last, used for detecting replaced files
+    id: Option<(u64, u64)>,
+    /// Bytes of an incomplete multibyte character at the end of the last read
+    buf: Vec<u8>,
+}
+
+impl FileFollower {
+    pub const fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            pos: 0,
+            id: None,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Checks the file for changes and returns the events that bring the pager up to date
+    ///
+    /// Bytes appended to the file are sent with [`Event::AppendData`]. If the file got shorter
+    /// or was replaced by another file, as happens when logs are rotated, the entire file is sent
+    /// again with [`Event::SetData`] and the view is moved back to the top.
+    ///
+    /// # Errors
+    /// Returns an error if the file could not be read
+    pub fn poll(&mut self) -> io::Result<Vec<Event>> {
+        let meta = fs::metadata(&self.path)?;
+        let id = file_id(&meta);
+        let replaced = meta.len() < self.pos || (self.id.is_some() && id != self.id);
+        self.id = id;
+
+        if replaced {
+            self.pos = 0;
+            self.buf.clear();
+        } else if meta.len() == self.pos {
+            return Ok(Vec::new());
+        }
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.pos))?;
+        let read = file.read_to_end(&mut self.buf)?;
+        self.pos += read as u64;
+        let text = decode_utf8_lossy(&mut self.buf);
+
+        Ok(if replaced {
+            vec![Event::SetData(text), Event::ScrollTo(0)]
+        } else if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![Event::AppendData(text)]
+        })
+    }
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)]
+fn file_id(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+const fn file_id(_meta: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Polls the file at `path` and sends its changes over `tx` until `is_exitted` is set
+///
+/// Errors while reading the file are ignored, as the file may briefly be missing while it is
+/// being rotated.
+pub fn follow_file(path: PathBuf, tx: &Sender<Event>, is_exitted: &AtomicBool) {
+    let mut follower = FileFollower::new(path);
+
+    while !is_exitted.load(Ordering::SeqCst) {
+        for ev in follower.poll().unwrap_or_default() {
+            if tx.send(ev).is_err() {
+                return;
+            }
+        }
+        // Wait in small steps, so that quitting the pager is not held up
+        let mut waited = Duration::ZERO;
+        while waited < POLL_INTERVAL && !is_exitted.load(Ordering::SeqCst) {
+            thread::sleep(EXIT_CHECK_INTERVAL);
+            waited += EXIT_CHECK_INTERVAL;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{follow_file, FileFollower};
+    use crate::minus_core::events::Event;
+    use std::{
+        fs,
+        io::Write,
+        sync::atomic::{AtomicBool, Ordering},
+        sync::Arc,
+    };
+
+    #[test]
+    fn follower_stops_on_exit() {
+        let path = std::env::temp_dir().join(format!("minus-follow-exit-{}", std::process::id()));
+        fs::write(&path, "text\n").unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let is_exitted = Arc::new(AtomicBool::new(false));
+        let is_exitted2 = is_exitted.clone();
+        let path2 = path.clone();
+        let follower = std::thread::spawn(move || follow_file(path2, &tx, &is_exitted2));
+
+        assert_eq!(rx.recv().unwrap(), Event::AppendData("text\n".to_string()));
+        is_exitted.store(true, Ordering::SeqCst);
+        follower.join().unwrap();
+        // The sender was dropped with the thread
+        assert!(rx.recv().is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn follow_appends_and_truncation() {
+        let path = std::env::temp_dir().join(format!("minus-follow-{}", std::process::id()));
+        fs::write(&path, "first\n").unwrap();
+        let mut follower = FileFollower::new(path.clone());
+
+        assert_eq!(
+            follower.poll().unwrap(),
+            vec![Event::AppendData("first\n".to_string())]
+        );
+        // Nothing changed
+        assert!(follower.poll().unwrap().is_empty());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        // Write a multibyte character in two parts
+        file.write_all(b"second \xc3").unwrap();
+        assert_eq!(
+            follower.poll().unwrap(),
+            vec![Event::AppendData("second ".to_string())]
+        );
+        file.write_all(b"\xa9\n").unwrap();
+        assert_eq!(
+            follower.poll().unwrap(),
+            vec![Event::AppendData("é\n".to_string())]
+        );
+
+        // The file was truncated
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(
+            follower.poll().unwrap(),
+            vec![Event::SetData("new\n".to_string()), Event::ScrollTo(0),]
+        );
+
+        fs::remove_file(&path).unwrap();
+        assert!(follower.poll().is_err());
+    }
+}
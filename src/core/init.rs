@@ -2,23 +2,26 @@
 //!
 //! This module provides two main functions:-
 //! * The [`init_core`] function which is responsible for setting the initial state of the
-//! Pager, do enviroment checks and initializing various core functions on either async
-//! tasks or native threads depending on the feature set
+//!   Pager, do enviroment checks and initializing various core functions on either async
+//!   tasks or native threads depending on the feature set
 //!
 //! * The [`start_reactor`] function displays the displays the output and also polls
-//! the [`Receiver`] held inside the [`Pager`] for events. Whenever a event is
-//! detected, it reacts to it accordingly.
+//!   the [`Receiver`] held inside the [`Pager`] for events. Whenever a event is
+//!   detected, it reacts to it accordingly.
 use super::{
-    ev_handler::handle_event, events::Event, utils::display::draw_full, utils::term, RunMode,
-};
-use crate::{
-    error::MinusError,
-    input::InputEvent,
-    minus_core::utils::{self, text::AppendStyle},
-    Pager, PagerState,
+    ev_handler::{self, handle_event},
+    events::Event,
+    utils::display::draw_full,
+    utils::term,
+    RunMode,
 };
+#[cfg(feature = "dynamic_output")]
+use crate::minus_core::utils;
+use crate::{error::MinusError, input::InputEvent, pager::ExitSignal, Pager, PagerState};
 
-use crossbeam_channel::{Receiver, Sender, TrySendError};
+#[cfg(feature = "dynamic_output")]
+use crossbeam_channel::RecvError;
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TrySendError};
 use crossterm::event;
 #[cfg(feature = "dynamic_output")]
 use crossterm::{
@@ -26,12 +29,13 @@ use crossterm::{
     terminal::{Clear, ClearType},
 };
 use std::{
-    io::{stdout, Stdout},
+    io::{stdout, Write},
     panic,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Once,
     },
+    time::Instant,
 };
 #[cfg(feature = "static_output")]
 use {super::utils::display::write_lines, crossterm::tty::IsTty};
@@ -42,6 +46,52 @@ use parking_lot::Mutex;
 
 pub static RUNMODE: parking_lot::Mutex<RunMode> = parking_lot::const_mutex(RunMode::Uninitialized);
 
+/// How the terminal is restored if a panic happens
+///
+/// This is `None` unless a pager holds the terminal with [`PagerState::panic_cleanup`] set, and
+/// tells whether the pager is on the alternate screen otherwise.
+static PANIC_CLEANUP: parking_lot::Mutex<Option<bool>> = parking_lot::const_mutex(None);
+
+/// Records a switch to or from the alternate screen in `cleanup`, which is [`PANIC_CLEANUP`]
+///
+/// Nothing is recorded while the panic hook leaves the terminal alone.
+#[cfg(feature = "dynamic_output")]
+fn switch_panic_cleanup_screen(cleanup: &Mutex<Option<bool>>, alternate_screen: bool) {
+    if let Some(screen) = cleanup.lock().as_mut() {
+        *screen = alternate_screen;
+    }
+}
+
+/// Installs the panic hook that restores the terminal, once for the whole process
+///
+/// The hook only does something while [`PANIC_CLEANUP`] is set. It always runs the hook that was
+/// set before it afterwards, so that the panic message is printed on the restored terminal.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let panic_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |pinfo| {
+            // Waiting for the lock could deadlock if the panicking thread holds it, so the
+            // terminal is left alone then
+            let alternate_screen = PANIC_CLEANUP
+                .try_lock()
+                .and_then(|mut cleanup| cleanup.take());
+            if let Some(alternate_screen) = alternate_screen {
+                // While silently ignoring error is considered a bad practice, we are forced to do it here
+                // as we cannot use the ? and panicking here will cause UB.
+                drop(term::cleanup(
+                    stdout(),
+                    &crate::ExitStrategy::PagerQuit,
+                    true,
+                    true,
+                    alternate_screen,
+                ));
+            }
+            panic_hook(pinfo);
+        }));
+    });
+}
+
 /// The main entry point of minus
 ///
 /// This is called by both [`dynamic_paging`](crate::dynamic_paging) and
@@ -52,12 +102,11 @@ pub static RUNMODE: parking_lot::Mutex<RunMode> = parking_lot::const_mutex(RunMo
 ///
 /// Then it checks if the minus is running in static mode and does some checks:-
 /// * If standard output is not a terminal screen, that is if it is a file or block
-/// device, minus will write all the data at once to the stdout and quit
+///   device, minus will write all the data at once to the stdout and quit
 ///
 /// * If the size of the data is less than the available number of rows in the terminal
-/// then it displays everything on the main stdout screen at once and quits. This
-/// behaviour can be turned off if [`Pager::set_run_no_overflow(true)`] is called
-/// by the main application
+///   then it displays everything on the main stdout screen at once and quits, if the main
+///   application asked for it with [`Pager::set_overflow_behavior`]
 // Sorry... this behaviour would have been cool to have in async mode, just think about it!!! Many
 // implementations were proposed but none were perfect
 // It is because implementing this especially with line wrapping and terminal scrolling
@@ -65,6 +114,7 @@ pub static RUNMODE: parking_lot::Mutex<RunMode> = parking_lot::const_mutex(RunMo
 // using your library... your only weapon
 // So we just don't take any more proposals about this. It is really frustating to
 // to throughly test each implementation and fix out all rough edges around it
+///
 /// Next it initializes the runtime and calls [`start_reactor`] and a [`event reader`]` which is
 /// selected based on the enabled feature set:-
 ///
@@ -74,8 +124,10 @@ pub static RUNMODE: parking_lot::Mutex<RunMode> = parking_lot::const_mutex(RunMo
 /// fail.
 ///
 /// [`event reader`]: event_reader
-#[allow(clippy::module_name_repetitions)]
+#[allow(clippy::module_name_repetitions, clippy::too_many_lines)]
 pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
+    pager.exit_signal.set(false);
+    let _exit_guard = ExitGuard(pager.exit_signal.clone());
     #[allow(unused_mut)]
     let mut out = stdout();
     // Is the event reader running
@@ -84,48 +136,62 @@ pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
 
     #[allow(unused_mut)]
     let mut ps = crate::state::PagerState::generate_initial_state(&mut pager.rx, &mut out)?;
+    // Draw into the writer given by the application instead of stdout, if there is one
+    #[allow(unused_mut)]
+    let mut output = ps.output.take();
+    ps.custom_output = output.is_some();
 
     // Static mode checks
     #[cfg(feature = "static_output")]
     if *RUNMODE.lock() == RunMode::Static {
         // If stdout is not a tty, write everyhting and quit
-        if !out.is_tty() {
+        if output.is_none() && !out.is_tty() {
             write_lines(&mut out, &mut ps)?;
             return Ok(());
         }
-        // If number of lines of text is less than available wors, write everything and quit
-        // unless run_no_overflow is set to true
-        if ps.num_lines() <= ps.rows && ps.run_no_overflow {
-            write_lines(&mut out, &mut ps)?;
-            ps.exit();
+        // If the text fits on the screen, write everything and quit if the application asked
+        // for it. PrintIfFits only counts the rows the pager would show text in, while
+        // PageIfOverflow keeps the old run_no_overflow check against the height of the terminal.
+        let fits = match ps.overflow_behavior {
+            crate::OverflowBehavior::AlwaysPage => false,
+            crate::OverflowBehavior::PrintIfFits => ps.num_lines() <= ps.content_rows(),
+            crate::OverflowBehavior::PageIfOverflow => ps.num_lines() <= ps.rows,
+        };
+        if fits {
+            match output.as_mut() {
+                Some(output) => write_lines(output, &mut ps)?,
+                None => write_lines(&mut out, &mut ps)?,
+            }
+            ps.exit(crate::ExitReason::EndOfInput);
             return Ok(());
         }
     }
 
     // Setup terminal, adjust line wraps and get rows
-    term::setup(&out)?;
+    match output.as_mut() {
+        Some(output) => term::setup(output, false, ps.mouse_capture, ps.alternate_screen)?,
+        None => term::setup(&mut out, true, ps.mouse_capture, ps.alternate_screen)?,
+    }
 
-    {
-        let panic_hook = panic::take_hook();
-        panic::set_hook(Box::new(move |pinfo| {
-            // While silently ignoring error is considered a bad practice, we are forced to do it here
-            // as we cannot use the ? and panicking here will cause UB.
-            drop(term::cleanup(
-                stdout(),
-                &crate::ExitStrategy::PagerQuit,
-                true,
-            ));
-            panic_hook(pinfo);
-        }));
+    // Only the terminal of the process has to be restored
+    if ps.panic_cleanup && output.is_none() {
+        install_panic_hook();
+        *PANIC_CLEANUP.lock() = Some(ps.alternate_screen);
     }
 
+    let external_input = ps.external_input;
+    #[cfg(feature = "follow_file")]
+    let followed_file = ps.followed_file.take();
     let ps_mutex = Arc::new(Mutex::new(ps));
+    *pager.state.lock() = Arc::downgrade(&ps_mutex);
 
     let evtx = pager.tx.clone();
+    #[cfg(feature = "follow_file")]
+    let follow_tx = pager.tx.clone();
     let rx = pager.rx.clone();
-    let out = stdout();
 
     let p1 = ps_mutex.clone();
+    let p2 = ps_mutex.clone();
 
     #[cfg(feature = "search")]
     let input_thread_running2 = input_thread_running.clone();
@@ -135,35 +201,74 @@ pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
             // Has the user quitted
             let is_exitted = Arc::new(AtomicBool::new(false));
             let is_exitted2 = is_exitted.clone();
+            #[cfg(feature = "follow_file")]
+            let is_exitted3 = is_exitted.clone();
 
-            let t1 = s.spawn(move |_| {
-                event_reader(
-                    &evtx,
-                    &p1,
-                    #[cfg(feature = "search")]
-                    &input_thread_running2,
-                    &is_exitted2,
-                )
+            // The application feeds the terminal events itself otherwise
+            let t1 = (!external_input).then(|| {
+                s.spawn(move |_| {
+                    event_reader(
+                        &evtx,
+                        &p1,
+                        #[cfg(feature = "search")]
+                        &input_thread_running2,
+                        &is_exitted2,
+                    )
+                })
+            });
+            #[cfg(feature = "follow_file")]
+            let t3 = followed_file.map(|path| {
+                let stop = is_exitted.clone();
+                let t3 =
+                    s.spawn(move |_| super::follow::follow_file(path, &follow_tx, &is_exitted3));
+                (t3, stop)
             });
             let t2 = s.spawn(move |_| {
                 start_reactor(
                     &rx,
                     &ps_mutex,
-                    &out,
+                    output,
                     #[cfg(feature = "search")]
                     &input_thread_running,
                     &is_exitted,
                 )
             });
-            let (r1, r2) = (t1.join().unwrap(), t2.join().unwrap());
+            let (r1, r2) = (
+                t1.map_or(Ok(()), |t1| t1.join().unwrap()),
+                t2.join().unwrap(),
+            );
+            #[cfg(feature = "follow_file")]
+            if let Some((t3, stop)) = t3 {
+                // The reactor can also return with an error, without the pager quitting
+                stop.store(true, Ordering::SeqCst);
+                t3.join().unwrap();
+            }
             (r1, r2)
         })
         .unwrap();
+    // The terminal has been cleaned up, a later panic must not touch it again
+    *PANIC_CLEANUP.lock() = None;
+    if r1.is_err() || r2.is_err() {
+        // Callbacks that already ran for another reason are not run again
+        p2.lock().exit(crate::ExitReason::Error);
+    }
     r1?;
     r2?;
     Ok(())
 }
 
+/// Marks the pager as quit for [`Pager::wait_for_exit`] when it is dropped
+///
+/// This way the waiting threads are woken up however [`init_core`] returns, including when it
+/// fails or prints the text without starting the pager.
+struct ExitGuard(Arc<ExitSignal>);
+
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
 /// Continously displays the output and reacts to events
 ///
 /// This function displays the output continously while also checking for user inputs.
@@ -177,26 +282,30 @@ pub fn init_core(mut pager: Pager) -> std::result::Result<(), MinusError> {
 /// [`AppendData`](super::events::Event::AppendData) event occurs, it is absolutely necessory
 /// to update the screen immidiately; while if all rows are filled, we can omit to redraw the
 /// screen.
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, clippy::significant_drop_tightening)]
 fn start_reactor(
     rx: &Receiver<Event>,
     ps: &Arc<Mutex<PagerState>>,
-    out: &Stdout,
+    out: Option<Box<dyn Write + Send>>,
     #[cfg(feature = "search")] input_thread_running: &Arc<(Mutex<bool>, Condvar)>,
     is_exitted: &Arc<AtomicBool>,
 ) -> Result<(), MinusError> {
-    let mut out_lock = out.lock();
+    // Stdout stays locked for as long as the pager runs
+    let mut out_lock: Box<dyn Write> = match out {
+        Some(out) => out,
+        None => Box::new(stdout().lock()),
+    };
 
     let mut p = ps.lock();
     draw_full(&mut out_lock, &mut p)?;
     drop(p);
 
-    let run_mode = *RUNMODE.lock();
+    let mode = *RUNMODE.lock();
     #[allow(clippy::match_same_arms)]
-    match run_mode {
+    match mode {
         #[cfg(feature = "dynamic_output")]
         RunMode::Dynamic => loop {
-            use std::{convert::TryInto, io::Write};
+            use std::convert::TryInto;
 
             if is_exitted.load(Ordering::SeqCst) {
                 let mut runmode = RUNMODE.lock();
@@ -205,18 +314,124 @@ fn start_reactor(
                 break;
             }
 
-            let event = rx.recv();
+            // If a reformat or redraw was deferred, or a tick, a frame of a smooth scroll or the
+            // end of a flash is due, wait for events only until then
+            let (deadline, tick, frame, append, flash) = {
+                let p = ps.lock();
+                (
+                    p.resize_deadline.or_else(|| p.redraw_deadline()),
+                    p.tick_deadline(),
+                    p.scroll_frame_deadline(),
+                    p.append_deadline,
+                    p.flash_deadline,
+                )
+            };
+            let next_deadline = [deadline, tick, frame, append, flash]
+                .iter()
+                .flatten()
+                .min()
+                .copied();
+            let event = match next_deadline.map(|d| rx.recv_deadline(d)) {
+                Some(Err(RecvTimeoutError::Timeout))
+                    if flash.map_or(false, |f| f <= Instant::now()) =>
+                {
+                    ev_handler::end_flash(&mut out_lock, &mut ps.lock())?;
+                    continue;
+                }
+                // The text gathered for coalescing has waited long enough, apply it
+                Some(Err(RecvTimeoutError::Timeout))
+                    if append.map_or(false, |a| a <= Instant::now()) =>
+                {
+                    Ok(Event::AppendData(String::new()))
+                }
+                Some(Err(RecvTimeoutError::Timeout))
+                    if frame.map_or(false, |f| f <= Instant::now()) =>
+                {
+                    Ok(Event::ScrollFrame)
+                }
+                Some(Err(RecvTimeoutError::Timeout))
+                    if tick.map_or(false, |t| t <= Instant::now()) =>
+                {
+                    Ok(Event::Tick)
+                }
+                Some(Err(RecvTimeoutError::Timeout)) => {
+                    let mut p = ps.lock();
+                    p.finish_resize();
+                    if !p.rendering_paused && p.resize_deadline.is_none() {
+                        draw_full(&mut out_lock, &mut p)?;
+                    }
+                    continue;
+                }
+                Some(res) => res.map_err(|_| RecvError),
+                None => rx.recv(),
+            };
 
             let mut p = ps.lock();
 
-            let rows: u16 = p.rows.try_into().unwrap();
-            let num_lines = p.num_lines();
+            // Decode bytes and styled lines right away so that they are drawn just like text
+            let event = match event {
+                // Fed input is handled just like input read from the terminal
+                Ok(Event::FeedInput(ev)) => match p.classify_input(ev) {
+                    Some(iev) => Ok(Event::UserInput(iev)),
+                    None => continue,
+                },
+                event => event.map(|ev| ev_handler::decode_text(&mut p, ev)),
+            };
+
+            // Text held back for coalescing was sent before this event, so it is applied first
+            if !matches!(event, Ok(Event::AppendData(_))) {
+                if let Some(text) = p.take_pending_append() {
+                    let first_changed = p.append(&text);
+                    draw_appended(&mut out_lock, &mut p, first_changed)?;
+                }
+            }
 
             #[allow(clippy::unnested_or_patterns)]
             match event {
+                // Apply the whole batch before drawing the screen once
+                Ok(Event::Batch(events)) => {
+                    for ev in events {
+                        match ev {
+                            Event::SetMouseCapture(enabled) => {
+                                if enabled != p.mouse_capture {
+                                    term::set_mouse_capture(&mut out_lock, enabled)?;
+                                }
+                                p.mouse_capture = enabled;
+                            }
+                            Event::SetAlternateScreen(enabled) => {
+                                if enabled != p.alternate_screen {
+                                    term::set_alternate_screen(&mut out_lock, enabled)?;
+                                    p.alternate_screen = enabled;
+                                    switch_panic_cleanup_screen(&PANIC_CLEANUP, enabled);
+                                }
+                            }
+                            ev => handle_event(
+                                ev,
+                                &mut out_lock,
+                                &mut p,
+                                is_exitted,
+                                #[cfg(feature = "search")]
+                                input_thread_running,
+                            )?,
+                        }
+                        if is_exitted.load(Ordering::SeqCst) {
+                            break;
+                        }
+                    }
+                    if !is_exitted.load(Ordering::SeqCst)
+                        && !p.rendering_paused
+                        && p.resize_deadline.is_none()
+                        && !p.throttle_redraw()
+                    {
+                        draw_full(&mut out_lock, &mut p)?;
+                    }
+                }
                 Ok(ev) if ev.required_immidiate_screen_update() => {
                     let is_exit_event = ev.is_exit_event();
                     let is_movement = ev.is_movement();
+                    // Keep user input responsive by never delaying its redraws, and neither delay
+                    // redraws that were asked for explicitly
+                    let is_user_input = matches!(ev, Event::UserInput(_) | Event::Redraw);
                     handle_event(
                         ev,
                         &mut out_lock,
@@ -225,66 +440,80 @@ fn start_reactor(
                         #[cfg(feature = "search")]
                         input_thread_running,
                     )?;
-                    if !is_exit_event && !is_movement {
+                    if !is_exit_event
+                        && !is_movement
+                        && !p.rendering_paused
+                        && p.resize_deadline.is_none()
+                        && (is_user_input || !p.throttle_redraw())
+                    {
                         draw_full(&mut out_lock, &mut p)?;
                     }
                 }
+                Ok(Event::SetMouseCapture(enabled)) => {
+                    if enabled != p.mouse_capture {
+                        term::set_mouse_capture(&mut out_lock, enabled)?;
+                    }
+                    p.mouse_capture = enabled;
+                }
+                // The pager keeps drawing where it started
+                Ok(Event::SetOutput(_)) => {}
+                Ok(Event::SetAlternateScreen(enabled)) => {
+                    if enabled != p.alternate_screen {
+                        term::set_alternate_screen(&mut out_lock, enabled)?;
+                        p.alternate_screen = enabled;
+                        switch_panic_cleanup_screen(&PANIC_CLEANUP, enabled);
+                        // The screen that was switched to still shows what was there before
+                        if !p.rendering_paused {
+                            draw_full(&mut out_lock, &mut p)?;
+                        }
+                    }
+                }
+                Ok(ev @ Event::Tick) => {
+                    handle_event(
+                        ev,
+                        &mut out_lock,
+                        &mut p,
+                        is_exitted,
+                        #[cfg(feature = "search")]
+                        input_thread_running,
+                    )?;
+                    // Only the prompt and status bar can change, so leave the text alone
+                    if !p.rendering_paused && p.resize_deadline.is_none() {
+                        utils::display::write_status_bar(&mut out_lock, &p)?;
+                        utils::display::write_prompt(
+                            &mut out_lock,
+                            &p.displayed_prompt,
+                            &p.displayed_prompt_style(),
+                            p.prompt_row().try_into().unwrap(),
+                        )?;
+                        out_lock.flush()?;
+                    }
+                }
                 Ok(Event::SetPrompt(ref text) | Event::SendMessage(ref text)) => {
                     if let Ok(Event::SetPrompt(_)) = event {
-                        p.prompt = text.to_string();
+                        p.prompt.clone_from(text);
+                        p.prompt_right.clear();
                     } else {
-                        p.message = Some(text.to_string());
+                        p.message = Some(text.clone());
                     }
                     p.format_prompt();
-                    term::move_cursor(&mut out_lock, 0, rows, false)?;
-                    super::utils::display::write_prompt(&mut out_lock, &p.displayed_prompt, rows)?;
-                }
-                Ok(Event::AppendData(text)) => {
-                    // Make the string that nneds to be appended
-                    let append_style = p.append_str(&text);
-
-                    if let AppendStyle::FullRedraw = append_style {
-                        // Append the formatted string to PagerState::formatted_lines vec
-                        p.format_lines();
-                        utils::display::draw_full(&mut out_lock, &mut p)?;
-                        continue;
-                    }
-
-                    let AppendStyle::PartialUpdate((fmt_text, unterminated)) = append_style else { unreachable!() };
-
-                    if p.num_lines() < p.rows {
-                        // Move the cursor to the very next line after the last displayed line
-                        term::move_cursor(
+                    if !p.rendering_paused && !p.throttle_redraw() {
+                        let prompt_row = p.prompt_row().try_into().unwrap();
+                        term::move_cursor(&mut out_lock, 0, prompt_row, false)?;
+                        super::utils::display::write_prompt(
                             &mut out_lock,
-                            0,
-                            num_lines.saturating_sub(p.unterminated).try_into().unwrap(),
-                            false,
+                            &p.displayed_prompt,
+                            &p.displayed_prompt_style(),
+                            prompt_row,
                         )?;
-                        // available_rows -> Rows that are still unfilled
-                        //      rows - number of lines displayed -1 (for prompt)
-                        // For example if 20 rows are in total in a terminal
-                        // and 10 rows are already occupied, then this will be equal to 9
-                        let available_rows = p.rows.saturating_sub(
-                            p.num_lines()
-                                .saturating_sub(p.unterminated)
-                                .saturating_add(1),
-                        );
-                        // Minimum amount of text that an be appended
-                        // If available_rows is less, than this will be available rows else it will be
-                        // the length of the formatted text
-                        //
-                        // If number of rows in terminal is 23 with 20 rows filled and another 5 lines are given
-                        // This woll be equal to 3 as available rows will be 3
-                        // If in the above example only 2 lines are needed to be added, this will be equal to 2
-                        let num_appendable = fmt_text.len().min(available_rows);
-                        if num_appendable >= 1 {
-                            execute!(out_lock, Clear(ClearType::CurrentLine))?;
-                        }
-                        write!(out_lock, "{}", fmt_text[0..num_appendable].join("\n\r"))?;
-                        out_lock.flush()?;
                     }
-                    // Append the formatted string to PagerState::formatted_lines vec
-                    p.append_str_on_unterminated(fmt_text, unterminated);
+                }
+                Ok(Event::AppendData(text)) => {
+                    let Some(text) = p.coalesce_append(text) else {
+                        continue;
+                    };
+                    let first_changed = p.append(&text);
+                    draw_appended(&mut out_lock, &mut p, first_changed)?;
                 }
                 Ok(ev) => {
                     handle_event(
@@ -306,7 +535,13 @@ fn start_reactor(
                 //
                 // This is not needed in dynamic paging because this is already handled by handle_event
                 let p = ps.lock();
-                term::cleanup(&mut out_lock, &p.exit_strategy, true)?;
+                term::cleanup(
+                    &mut out_lock,
+                    &p.exit_strategy,
+                    true,
+                    !p.custom_output,
+                    p.alternate_screen,
+                )?;
 
                 let mut runmode = RUNMODE.lock();
                 *runmode = RunMode::Uninitialized;
@@ -315,19 +550,63 @@ fn start_reactor(
                 break;
             }
 
-            if let Ok(Event::UserInput(inp)) = rx.recv() {
+            // If a reformat was deferred or a frame of a smooth scroll or the end of a flash is
+            // due, wait for events only until then
+            let (deadline, frame, flash) = {
+                let p = ps.lock();
+                (
+                    p.resize_deadline,
+                    p.scroll_frame_deadline(),
+                    p.flash_deadline,
+                )
+            };
+            let next_deadline = [deadline, frame, flash].iter().flatten().min().copied();
+            let event = match next_deadline.map(|d| rx.recv_deadline(d)) {
+                Some(Err(RecvTimeoutError::Timeout))
+                    if flash.map_or(false, |f| f <= Instant::now()) =>
+                {
+                    ev_handler::end_flash(&mut out_lock, &mut ps.lock())?;
+                    continue;
+                }
+                Some(Err(RecvTimeoutError::Timeout))
+                    if frame.map_or(false, |f| f <= Instant::now()) =>
+                {
+                    Some(Event::ScrollFrame)
+                }
+                Some(Err(RecvTimeoutError::Timeout)) => {
+                    let mut p = ps.lock();
+                    if p.finish_resize() {
+                        draw_full(&mut out_lock, &mut p)?;
+                    }
+                    continue;
+                }
+                Some(res) => res.ok(),
+                None => rx.recv().ok(),
+            };
+
+            let event = match event {
+                Some(Event::FeedInput(ev)) => ps.lock().classify_input(ev).map(Event::UserInput),
+                event => event,
+            };
+            if let Some(
+                ev @ (Event::UserInput(_)
+                | Event::ScrollTo(_)
+                | Event::ScrollBy(_)
+                | Event::ScrollFrame),
+            ) = event
+            {
                 let mut p = ps.lock();
-                let is_exit_event = Event::UserInput(inp).is_exit_event();
-                let is_movement = Event::UserInput(inp).is_movement();
+                let is_exit_event = ev.is_exit_event();
+                let is_movement = ev.is_movement();
                 handle_event(
-                    Event::UserInput(inp),
+                    ev,
                     &mut out_lock,
                     &mut p,
                     is_exitted,
                     #[cfg(feature = "search")]
                     input_thread_running,
                 )?;
-                if !is_exit_event && !is_movement {
+                if !is_exit_event && !is_movement && p.resize_deadline.is_none() {
                     draw_full(&mut out_lock, &mut p)?;
                 }
             }
@@ -340,12 +619,60 @@ This is most likely a bug. Please open an issue to the developers"
     Ok(())
 }
 
+/// Draws the text after something was appended to it
+///
+/// `first_changed` is the row returned by [`PagerState::append`].
+#[cfg(feature = "dynamic_output")]
+fn draw_appended(
+    out: &mut impl Write,
+    p: &mut PagerState,
+    first_changed: Option<usize>,
+) -> Result<(), MinusError> {
+    use std::convert::TryInto;
+
+    if p.rendering_paused || p.throttle_redraw() {
+        return Ok(());
+    }
+
+    match first_changed {
+        // All of the text fits on the screen, so only the changed rows at its end have to be
+        // written again
+        Some(row)
+            if p.upper_mark == 0
+                && p.frozen_rows() == 0
+                && p.num_lines() <= p.content_rows()
+                && p.highlight_row.is_none()
+                && p.cursor_line.is_none() =>
+        {
+            for (idx, line) in p.formatted_lines.iter().enumerate().skip(row) {
+                term::move_cursor(
+                    out,
+                    0,
+                    (p.first_content_row() + idx).try_into().unwrap(),
+                    false,
+                )?;
+                execute!(out, Clear(ClearType::CurrentLine))?;
+                write!(out, "{:1$}{line}", "", p.left_margin())?;
+            }
+            utils::display::write_scrollbar(out, p)?;
+            out.flush()?;
+        }
+        // The changed rows are below the screen, the user will see them once they scroll down
+        Some(row) if row >= p.upper_mark + p.content_rows() => {}
+        _ => draw_full(out, p)?,
+    }
+    Ok(())
+}
+
 fn event_reader(
     evtx: &Sender<Event>,
     ps: &Arc<Mutex<PagerState>>,
     #[cfg(feature = "search")] user_input_active: &Arc<(Mutex<bool>, Condvar)>,
     is_exitted: &Arc<AtomicBool>,
 ) -> Result<(), MinusError> {
+    // When the last number was typed, for discarding counts that are not followed by a command
+    let mut last_number = Instant::now();
+
     loop {
         if is_exitted.load(Ordering::SeqCst) {
             break;
@@ -364,25 +691,43 @@ fn event_reader(
             .map_err(|e| MinusError::HandleEvent(e.into()))?
         {
             let ev = event::read().map_err(|e| MinusError::HandleEvent(e.into()))?;
-            let mut guard = ps.lock();
-            // Get the events
-            let input = guard.input_classifier.classify_input(ev, &guard);
-            if let Some(iev) = input {
-                if let InputEvent::Number(n) = iev {
-                    guard.prefix_num.push(n);
-                    guard.format_prompt();
-                } else if !guard.prefix_num.is_empty() {
-                    guard.prefix_num.clear();
-                    guard.format_prompt();
-                }
-                if let Err(TrySendError::Disconnected(_)) = evtx.try_send(Event::UserInput(iev)) {
-                    break;
-                }
-            } else if !guard.prefix_num.is_empty() {
-                guard.prefix_num.clear();
-                guard.format_prompt();
+            let Some(iev) = ps.lock().classify_input(ev) else {
+                continue;
+            };
+            if let InputEvent::Number(_) = iev {
+                last_number = Instant::now();
+            }
+            if let Err(TrySendError::Disconnected(_)) = evtx.try_send(Event::UserInput(iev)) {
+                break;
+            }
+        } else if ps.lock().pending_count_expired(last_number.elapsed()) {
+            // Clearing the count through the reactor also redraws the prompt
+            if let Err(TrySendError::Disconnected(_)) =
+                evtx.try_send(Event::UserInput(InputEvent::ClearPrefix))
+            {
+                break;
             }
         }
     }
     Result::<(), MinusError>::Ok(())
 }
+
+#[cfg(all(test, feature = "dynamic_output"))]
+mod tests {
+    use super::switch_panic_cleanup_screen;
+    use parking_lot::Mutex;
+
+    #[test]
+    fn panic_cleanup_follows_screen() {
+        let cleanup = Mutex::new(Some(true));
+        switch_panic_cleanup_screen(&cleanup, false);
+        assert_eq!(*cleanup.lock(), Some(false));
+        switch_panic_cleanup_screen(&cleanup, true);
+        assert_eq!(*cleanup.lock(), Some(true));
+
+        // The terminal is still left alone if the hook isn't meant to restore it
+        let cleanup = Mutex::new(None);
+        switch_panic_cleanup_screen(&cleanup, false);
+        assert_eq!(*cleanup.lock(), None);
+    }
+}
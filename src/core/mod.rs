@@ -1,5 +1,9 @@
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
 pub mod ev_handler;
 pub mod events;
+#[cfg(feature = "follow_file")]
+pub mod follow;
 #[cfg(any(feature = "dynamic_output", feature = "static_output"))]
 pub mod init;
 #[cfg(feature = "search")]
@@ -6,56 +6,258 @@ use crate::PagerState;
 use crossterm::{
     cursor::{self, MoveTo},
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
-    style::Attribute,
+    style::{Attribute, Color, ContentStyle, SetBackgroundColor, SetForegroundColor},
     terminal::{Clear, ClearType},
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::{convert::TryFrom, time::Duration};
+use std::{convert::TryFrom, ops::Range, time::Duration};
 
+#[cfg(test)]
 static INVERT: Lazy<String> = Lazy::new(|| Attribute::Reverse.to_string());
+#[cfg(test)]
 static NORMAL: Lazy<String> = Lazy::new(|| Attribute::NoReverse.to_string());
+/// Escapes that start and end the highlighting of each highlight group
+///
+/// Each group gets a different background color. The colors are reused when there are more
+/// groups than colors.
+static GROUP_STYLES: Lazy<Vec<(String, String)>> = Lazy::new(|| {
+    [
+        Color::DarkYellow,
+        Color::DarkCyan,
+        Color::DarkMagenta,
+        Color::DarkGreen,
+    ]
+    .iter()
+    .map(|c| {
+        (
+            SetBackgroundColor(*c).to_string(),
+            SetBackgroundColor(Color::Reset).to_string(),
+        )
+    })
+    .collect()
+});
+/// Matches control sequences like `\x1b[31m` as well as OSC 8 hyperlink sequences
 static ANSI_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new("[\\u001b\\u009b]\\[[()#;?]*(?:[0-9]{1,4}(?:;[0-9]{0,4})*)?[0-9A-ORZcf-nqry=><]")
-        .unwrap()
+    Regex::new(concat!(
+        "[\\u001b\\u009b]\\[[()#;?]*(?:[0-9]{1,4}(?:;[0-9]{0,4})*)?[0-9A-ORZcf-nqry=><]",
+        "|\\u001b\\]8;[^\\u0007\\u001b]*(?:\\u0007|\\u001b\\\\)",
+    ))
+    .unwrap()
 });
 
 #[derive(Clone, Copy, Debug, Eq)]
 #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
 #[allow(clippy::module_name_repetitions)]
 /// Defines modes in which the search can run
+#[derive(Default)]
 pub enum SearchMode {
     /// Find matches from or after the current page
     Forward,
     /// Find matches before the current page
     Reverse,
     /// No search active
+    #[default]
     Unknown,
 }
 
-impl Default for SearchMode {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 impl PartialEq for SearchMode {
     fn eq(&self, other: &Self) -> bool {
         core::mem::discriminant(self) == core::mem::discriminant(other)
     }
 }
 
+/// The query being typed at the search prompt along with the position of the cursor inside it
+#[derive(Default)]
+struct SearchQuery {
+    text: String,
+    /// Byte index of the cursor in `text`
+    cursor: usize,
+}
+
+impl SearchQuery {
+    /// Byte index of the character before the cursor
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(idx, _)| idx)
+    }
+
+    /// Byte index of the character after the cursor
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    /// Byte index of the start of the word before the cursor
+    fn prev_word_boundary(&self) -> usize {
+        let before = self.text[..self.cursor].trim_end();
+        before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(idx, c)| idx + c.len_utf8())
+    }
+
+    /// Byte index of the end of the word after the cursor
+    fn next_word_boundary(&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let word_start = after.len() - after.trim_start().len();
+        after[word_start..]
+            .find(char::is_whitespace)
+            .map_or(self.text.len(), |idx| self.cursor + word_start + idx)
+    }
+
+    /// Edits the query or moves the cursor according to the given key
+    ///
+    /// Returns `false` if the key does nothing inside the query
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match (key.code, key.modifiers) {
+            (KeyCode::Left, KeyModifiers::CONTROL) | (KeyCode::Char('b'), KeyModifiers::ALT) => {
+                self.cursor = self.prev_word_boundary();
+            }
+            (KeyCode::Right, KeyModifiers::CONTROL) | (KeyCode::Char('f'), KeyModifiers::ALT) => {
+                self.cursor = self.next_word_boundary();
+            }
+            (KeyCode::Left, KeyModifiers::NONE) => self.cursor = self.prev_boundary(),
+            (KeyCode::Right, KeyModifiers::NONE) => self.cursor = self.next_boundary(),
+            (KeyCode::Home, KeyModifiers::NONE) | (KeyCode::Char('a'), KeyModifiers::CONTROL) => {
+                self.cursor = 0;
+            }
+            (KeyCode::End, KeyModifiers::NONE) | (KeyCode::Char('e'), KeyModifiers::CONTROL) => {
+                self.cursor = self.text.len();
+            }
+            (KeyCode::Backspace, KeyModifiers::NONE) => {
+                let prev = self.prev_boundary();
+                self.text.replace_range(prev..self.cursor, "");
+                self.cursor = prev;
+            }
+            (KeyCode::Delete, KeyModifiers::NONE) => {
+                let next = self.next_boundary();
+                self.text.replace_range(self.cursor..next, "");
+            }
+            (KeyCode::Char(c), m) if !m.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Inserts `text` at the cursor and moves the cursor after it
+    fn paste(&mut self, text: &str) {
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    /// Redraws the prompt line and places the terminal cursor at the query's cursor
+    fn draw(&self, out: &mut impl std::io::Write, prefix: &str) -> Result<(), MinusError> {
+        let cursor_col = prefix.len() + textwrap::core::display_width(&self.text[..self.cursor]);
+        write!(
+            out,
+            "\r{}{}{}{}",
+            Clear(ClearType::CurrentLine),
+            prefix,
+            self.text,
+            cursor::MoveToColumn(u16::try_from(cursor_col).unwrap_or(u16::MAX))
+        )?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Returns the text shown before the query at the search prompt
+///
+/// This is the key that starts the search in `search_mode`, out of the forward and reverse
+/// `keys`. In literal mode, it is preceded by an `f`, as the query is matched as a fixed string.
+fn prompt_prefix(search_mode: SearchMode, keys: (char, char), literal: bool) -> String {
+    let mode = if search_mode == SearchMode::Forward {
+        keys.0
+    } else {
+        keys.1
+    };
+    if literal {
+        format!("f{mode}")
+    } else {
+        mode.to_string()
+    }
+}
+
+/// Returns the text of a burst of events if it looks like it was pasted
+///
+/// This is a guess based on timing, as the terminal events read through crossterm 0.22 don't
+/// tell pasted text apart from typed text, bracketed paste is only supported by later versions.
+/// Terminals deliver pasted text as a quick burst of key presses. A burst of at least two events
+/// made up only of characters and line breaks is taken to be a paste, so that the characters
+/// are inserted as they are and a line break inside the pasted text doesn't end the prompt. Line
+/// breaks are dropped, as the query is a single line.
+///
+/// The guess can be wrong both ways. Keys typed faster than they are read, like a held down
+/// key, are taken as a paste, so a typed `Enter` then doesn't end the prompt. Pasted text that
+/// the terminal delivers in several parts is handled part by part, so a line break at the end
+/// of a part ends the prompt.
+fn guess_pasted_text(events: &[Event]) -> Option<String> {
+    if events.len() < 2 {
+        return None;
+    }
+    events
+        .iter()
+        .map(|ev| match ev {
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            }) => Some(Some(*c)),
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                modifiers: KeyModifiers::NONE,
+            }) => Some(None),
+            _ => None,
+        })
+        .collect::<Option<Vec<Option<char>>>>()
+        .map(|chars| chars.into_iter().flatten().collect())
+}
+
+/// Compiles the search query into a [`Regex`]
+///
+/// In `literal` mode, all regex metacharacters in `query` are escaped so that it is matched
+/// verbatim.
+///
+/// # Errors
+/// Returns an error if `query` is not a valid regex
+pub fn compile_query(query: &str, literal: bool) -> Result<Regex, regex::Error> {
+    if literal {
+        Regex::new(&regex::escape(query))
+    } else {
+        Regex::new(query)
+    }
+}
+
 /// Fetch the search query
 ///
-/// The function will change the prompt to `/` for Forward search or `?` for Reverse search
-/// It will then store the query in a String and return it when `Return` key is pressed
-/// or return with a empty string if so match is found.
+/// The function will change the prompt to the key of the search mode, out of the forward and
+/// reverse `keys`. It will then store the query in a String and return it when `Return` key is
+/// pressed or return with a empty string if so match is found.
+///
+/// `Ctrl+R` switches between regex and literal search, the mode in use is kept in `literal`.
+///
+/// The query can be edited at any position. `Left`/`Right` move the cursor by a character,
+/// `Ctrl+Left`/`Ctrl+Right` (or `Alt+B`/`Alt+F`) by a word and `Home`/`End` (or `Ctrl+A`/`Ctrl+E`)
+/// to the start or end of the query. `Backspace` and `Delete` remove the character before or
+/// after the cursor. Text that appears to be pasted is inserted as a whole, without its line
+/// breaks, see [`guess_pasted_text`].
 #[cfg(feature = "search")]
 pub fn fetch_input(
     out: &mut impl std::io::Write,
     search_mode: SearchMode,
+    keys: (char, char),
     rows: usize,
+    literal: &mut bool,
 ) -> Result<String, MinusError> {
+    let mut prefix = prompt_prefix(search_mode, keys, *literal);
     // Place the cursor at the beginning of very prompt line, clear
     // the prompt and show the cursor
     #[allow(clippy::cast_possible_truncation)]
@@ -64,18 +266,33 @@ pub fn fetch_input(
         "{}{}{}{}",
         MoveTo(0, rows as u16),
         Clear(ClearType::CurrentLine),
-        if search_mode == SearchMode::Forward {
-            "/"
-        } else {
-            "?"
-        },
+        prefix,
         cursor::Show
     )?;
     out.flush()?;
-    let mut string = String::new();
+    let mut query = SearchQuery::default();
     loop {
-        if event::poll(Duration::from_millis(100)).map_err(|e| MinusError::HandleEvent(e.into()))? {
-            match event::read().map_err(|e| MinusError::HandleEvent(e.into()))? {
+        if !event::poll(Duration::from_millis(100))
+            .map_err(|e| MinusError::HandleEvent(e.into()))?
+        {
+            continue;
+        }
+        // Collect all events that are already waiting, so that pasted text is seen as a whole.
+        // Whether they are a paste can only be guessed, see guess_pasted_text
+        let mut burst = Vec::new();
+        loop {
+            burst.push(event::read().map_err(|e| MinusError::HandleEvent(e.into()))?);
+            if !event::poll(Duration::ZERO).map_err(|e| MinusError::HandleEvent(e.into()))? {
+                break;
+            }
+        }
+        if let Some(text) = guess_pasted_text(&burst) {
+            query.paste(&text);
+            query.draw(out, &prefix)?;
+            continue;
+        }
+        for ev in burst {
+            match ev {
                 // If Esc is pressed, cancel the search
                 Event::Key(KeyEvent {
                     code: KeyCode::Esc,
@@ -84,34 +301,25 @@ pub fn fetch_input(
                     write!(out, "{}", cursor::Hide)?;
                     return Ok(String::new());
                 }
-                // On backspace, pop the last character from the string
-                Event::Key(KeyEvent {
-                    code: KeyCode::Backspace,
-                    modifiers: KeyModifiers::NONE,
-                }) => {
-                    string.pop();
-                    // Update the line
-                    write!(out, "\r{}/{}", Clear(ClearType::CurrentLine), string)?;
-                    out.flush()?;
-                }
                 Event::Key(KeyEvent {
                     code: KeyCode::Enter,
                     modifiers: KeyModifiers::NONE,
                 }) => {
                     write!(out, "{}", cursor::Hide)?;
                     // Return the string when enter is pressed
-                    return Ok(string);
+                    return Ok(query.text);
                 }
-                Event::Key(event) => {
-                    // For any character key, without a modifier, append it to the
-                    // string and update the line
-                    if let KeyCode::Char(c) = event.code {
-                        string.push(c);
-                        write!(out, "\r/{}", string)?;
-                        out.flush()?;
-                    }
+                // Edit the query and update the line
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('r'),
+                    modifiers: KeyModifiers::CONTROL,
+                }) => {
+                    *literal = !*literal;
+                    prefix = prompt_prefix(search_mode, keys, *literal);
+                    query.draw(out, &prefix)?;
                 }
-                _ => continue,
+                Event::Key(key) if query.handle_key(key) => query.draw(out, &prefix)?,
+                _ => {}
             }
         }
     }
@@ -119,9 +327,15 @@ pub fn fetch_input(
 
 /// Highlights the search match
 ///
+/// `style` holds the escapes placed before and after each match, see [`style_escapes`].
+///
 /// The first return value returns the line that has all the search matches highlighted
 /// The second tells whether a search match was actually found
-pub fn highlight_line_matches(line: &str, query: &regex::Regex) -> (String, bool) {
+pub fn highlight_line_matches(
+    line: &str,
+    query: &regex::Regex,
+    style: (&str, &str),
+) -> (String, bool) {
     // Remove all ansi escapes so we can look through it as if it had none
     let stripped_str = ANSI_REGEX.replace_all(line, "");
 
@@ -129,16 +343,255 @@ pub fn highlight_line_matches(line: &str, query: &regex::Regex) -> (String, bool
     if !query.is_match(&stripped_str) {
         return (line.to_string(), false);
     }
+    // An empty style has nothing to add
+    if style.0.is_empty() {
+        return (line.to_string(), true);
+    }
+
+    let spans = query
+        .find_iter(&stripped_str)
+        .map(|m| (m.range(), style.0, style.1))
+        .collect::<Vec<_>>();
+    (style_ranges(line, &stripped_str, &spans), true)
+}
+
+/// Highlights the search matches and the matches of the highlight groups in `row`
+///
+/// If `multiline` is given, it holds the parts of the line that are matched by a multiline
+/// search, along with the position of `row` in that line. See [`multiline_match_ranges`] and
+/// [`row_offsets`]. Otherwise `row` is searched on its own.
+///
+/// Search matches are styled with `style` and take precedence over the highlight groups. Earlier
+/// groups take precedence over later ones. The second return value tells whether a search match
+/// was found.
+pub fn highlight_row(
+    row: &str,
+    query: Option<&regex::Regex>,
+    multiline: Option<(&[Range<usize>], usize)>,
+    groups: &[Regex],
+    style: (&str, &str),
+) -> (String, bool) {
+    if let (Some(query), None, true) = (query, multiline, groups.is_empty()) {
+        return highlight_line_matches(row, query, style);
+    }
+    let stripped_str = ANSI_REGEX.replace_all(row, "");
+
+    let (ranges, is_match) = match (query, multiline) {
+        (Some(_), Some((ranges, offset))) => {
+            let end = offset + stripped_str.len();
+            // Parts of the matches that fall into this row, relative to the start of the row.
+            // Rows that got characters of their own when formatting, like the marker of a
+            // truncated row, may not line up with the line exactly
+            let row_ranges = ranges
+                .iter()
+                .filter(|r| r.start < end && r.end > offset)
+                .map(|r| r.start.max(offset) - offset..r.end.min(end) - offset)
+                .filter(|r| {
+                    stripped_str.is_char_boundary(r.start) && stripped_str.is_char_boundary(r.end)
+                })
+                .collect::<Vec<_>>();
+            let is_match = !row_ranges.is_empty();
+            (row_ranges, is_match)
+        }
+        (Some(query), None) => (
+            query.find_iter(&stripped_str).map(|m| m.range()).collect(),
+            query.is_match(&stripped_str),
+        ),
+        (None, _) => (Vec::new(), false),
+    };
+
+    let mut spans = ranges
+        .into_iter()
+        .map(|r| (r, style.0, style.1))
+        .collect::<Vec<_>>();
+    for (group, (start, end)) in groups.iter().zip(GROUP_STYLES.iter().cycle()) {
+        for r in group.find_iter(&stripped_str).map(|m| m.range()) {
+            if !r.is_empty()
+                && !spans
+                    .iter()
+                    .any(|(s, _, _)| s.start < r.end && r.start < s.end)
+            {
+                spans.push((r, start.as_str(), end.as_str()));
+            }
+        }
+    }
+    // Matches with an empty style still keep the highlight groups off them, but are left as they
+    // are, escapes inside them included
+    spans.retain(|(_, start, _)| !start.is_empty());
+    if spans.is_empty() {
+        return (row.to_string(), is_match);
+    }
+    spans.sort_by_key(|(r, _, _)| r.start);
+    (style_ranges(row, &stripped_str, &spans), is_match)
+}
+
+/// Finds the matches of `query` in `text`, which may span across lines
+///
+/// Returns the parts of each line of `text` that are covered by a match. The ranges are measured
+/// in the line with all ANSI escapes removed.
+pub fn multiline_match_ranges(text: &str, query: &regex::Regex) -> Vec<Vec<Range<usize>>> {
+    let stripped = ANSI_REGEX.replace_all(text, "");
+
+    // Position of each line in the text, without the line ending. This follows `str::lines`
+    let mut start = 0;
+    let lines = stripped
+        .split_inclusive('\n')
+        .map(|line| {
+            let content = line
+                .strip_suffix('\n')
+                .map_or(line, |l| l.strip_suffix('\r').unwrap_or(l));
+            let range = start..start + content.len();
+            start += line.len();
+            range
+        })
+        .collect::<Vec<_>>();
+
+    let mut ranges = vec![Vec::new(); lines.len()];
+    for m in query.find_iter(&stripped) {
+        let first = lines.partition_point(|l| l.end < m.start());
+        for (idx, line) in lines
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take_while(|(_, l)| l.start < m.end())
+        {
+            let part = m.start().max(line.start)..m.end().min(line.end);
+            if !part.is_empty() {
+                ranges[idx].push(part.start - line.start..part.end - line.start);
+            }
+        }
+    }
+    ranges
+}
+
+/// Returns the position of each of the wrapped `rows` in `line`
+///
+/// The positions are measured in the line with all ANSI escapes removed.
+pub fn row_offsets(line: &str, rows: &[String]) -> Vec<usize> {
+    let stripped_line = ANSI_REGEX.replace_all(line, "");
+    let mut pos = 0;
+    rows.iter()
+        .map(|row| {
+            let stripped_row = ANSI_REGEX.replace_all(row, "");
+            // Wrapping may drop whitespace between the rows, so search for where the row begins
+            let offset = stripped_line[pos..]
+                .find(stripped_row.as_ref())
+                .map_or(pos, |o| pos + o);
+            pos = (offset + stripped_row.len()).min(stripped_line.len());
+            offset
+        })
+        .collect()
+}
+
+/// Returns the escapes that turn `style` on and off again
+///
+/// Only the parts of the style that are set are turned off, so that the styling of the
+/// surrounding text is kept as far as possible.
+pub fn style_escapes(style: &ContentStyle) -> (String, String) {
+    let mut start = String::new();
+    let mut end = String::new();
+    if let Some(fg) = style.foreground_color {
+        start.push_str(&SetForegroundColor(fg).to_string());
+        end.push_str(&SetForegroundColor(Color::Reset).to_string());
+    }
+    if let Some(bg) = style.background_color {
+        start.push_str(&SetBackgroundColor(bg).to_string());
+        end.push_str(&SetBackgroundColor(Color::Reset).to_string());
+    }
+    for attr in Attribute::iterator().filter(|a| style.attributes.has(*a)) {
+        start.push_str(&attr.to_string());
+        let off = match attr {
+            Attribute::Bold | Attribute::Dim => Attribute::NormalIntensity,
+            Attribute::Italic | Attribute::Fraktur => Attribute::NoItalic,
+            Attribute::Underlined => Attribute::NoUnderline,
+            Attribute::SlowBlink | Attribute::RapidBlink => Attribute::NoBlink,
+            Attribute::Reverse => Attribute::NoReverse,
+            Attribute::Hidden => Attribute::NoHidden,
+            Attribute::CrossedOut => Attribute::NotCrossedOut,
+            Attribute::Framed | Attribute::Encircled => Attribute::NotFramedOrEncircled,
+            Attribute::OverLined => Attribute::NotOverLined,
+            // Attributes that turn something off don't need to be undone
+            _ => continue,
+        };
+        end.push_str(&off.to_string());
+    }
+    (start, end)
+}
 
+/// Restyles the matches in `row` from [`PagerState::other_match_style`] to
+/// [`PagerState::current_match_style`]
+///
+/// Matches are highlighted with the style of the other matches when the text is formatted. This
+/// is used to restyle the row of the current match when it is drawn, so that moving between
+/// matches does not require formatting the text again.
+///
+/// The matches can't be found in the row if they have no style, so the row is kept as it is then.
+pub fn restyle_current_match(ps: &PagerState, row: &str) -> String {
+    let (other_start, other_end) = &ps.match_escapes;
+    if other_start.is_empty() {
+        return row.to_string();
+    }
+    let (current_start, current_end) = style_escapes(&ps.current_match_style);
+    row.replace(other_start.as_str(), &current_start)
+        .replace(other_end.as_str(), &current_end)
+}
+
+/// Removes the styles of the search matches from `row`, for when their highlights are hidden
+///
+/// Like [`restyle_current_match`], this works on the row as it was formatted, so that hiding
+/// and showing the highlights does not require formatting the text again.
+pub fn unstyle_matches(ps: &PagerState, row: &str) -> String {
+    let (start, end) = ps.match_style();
+    if start.is_empty() {
+        return row.to_string();
+    }
+    row.replace(start, "").replace(end, "")
+}
+
+/// Returns `true` if the line of the text shown at the formatted row `row` has a search match
+///
+/// A wrapped line has a match if any of its rows does.
+pub fn line_has_match(ps: &PagerState, row: usize) -> bool {
+    let line = ps.line_at_row(row);
+    let start = ps.line_rows.get(line).copied().unwrap_or(row);
+    let end = ps
+        .line_rows
+        .get(line + 1)
+        .copied()
+        .unwrap_or_else(|| ps.num_lines());
+    ps.search_idx.range(start..end).next().is_some()
+}
+
+/// Draws `row` dimmed, for the rows without a match in [`PagerState::search_focus`]
+///
+/// A reset or normal intensity escape inside the row would end the dimming early, hence it is
+/// started again after each of them.
+pub fn dim_row(row: &str) -> String {
+    let dim = Attribute::Dim.to_string();
+    let normal = Attribute::NormalIntensity.to_string();
+    let reset = Attribute::Reset.to_string();
+    let row = row
+        .replace(&reset, &format!("{reset}{dim}"))
+        .replace(&normal, &format!("{normal}{dim}"));
+    format!("{dim}{row}{normal}")
+}
+
+/// Styles the given parts of `line`
+///
+/// Each span holds a range along with the escapes placed before and after it. The spans must be
+/// sorted and must not overlap. `stripped_str` must be `line` with all ANSI escapes removed. The
+/// ranges are measured in `stripped_str` and the escapes of `line` are kept in place, except for
+/// those inside a span as they would override its style.
+fn style_ranges(line: &str, stripped_str: &str, spans: &[(Range<usize>, &str, &str)]) -> String {
     // sum_width is used to calculate the total width of the ansi escapes
     // up to the point in the original string where it is being used
     let mut sum_width = 0;
 
     // find all ansi escapes in the original string, and map them
-    // to a Vec<(usize, &str)> where
+    // to (usize, &str) where
     //   .0 == the start index in the STRIPPED string
     //   .1 == the escape sequence itself
-    let escapes = ANSI_REGEX
+    let mut escapes = ANSI_REGEX
         .find_iter(line)
         .map(|escape| {
             let start = escape.start();
@@ -147,77 +600,260 @@ pub fn highlight_line_matches(line: &str, query: &regex::Regex) -> (String, bool
             sum_width += as_str.len();
             ret
         })
-        .collect::<Vec<_>>();
+        .peekable();
 
-    // The matches of the term you're looking for, so that you can easily determine where
-    // the invert attributes will be placed
-    let matches = query
-        .find_iter(&stripped_str)
-        .flat_map(|c| [c.start(), c.end()])
-        .collect::<Vec<_>>();
+    let mut styled = String::with_capacity(line.len());
+    // Position in the stripped string up to which the text has been added
+    let mut last = 0;
+    for (range, start, end) in spans {
+        // Put back the escapes that come before this span
+        while let Some((pos, esc)) = escapes.next_if(|(pos, _)| *pos < range.start) {
+            styled.push_str(&stripped_str[last..pos]);
+            styled.push_str(esc);
+            last = pos;
+        }
+        styled.push_str(&stripped_str[last..range.start]);
+        styled.push_str(start);
+        styled.push_str(&stripped_str[range.clone()]);
+        styled.push_str(end);
+        // Leave out the escapes inside the span
+        while escapes.next_if(|(pos, _)| *pos < range.end).is_some() {}
+        last = range.end;
+    }
+    for (pos, esc) in escapes {
+        styled.push_str(&stripped_str[last..pos]);
+        styled.push_str(esc);
+        last = pos;
+    }
+    styled.push_str(&stripped_str[last..]);
+    styled
+}
 
-    // Highlight all the instances of the search term in the stripped string
-    // by inverting their background/foreground colors
-    let mut inverted = query
-        .replace_all(&stripped_str, |caps: &regex::Captures| {
-            format!("{}{}{}", *INVERT, &caps[0], *NORMAL)
-        })
-        .to_string();
-
-    // inserted_escs_len == the total length of the ascii escapes which have been re-inserted
-    // into the stripped string at the point where it is being checked.
-    let mut inserted_escs_len = 0;
-    for esc in escapes {
-        // Find how many invert|normal markers appear before this escape
-        let match_count = matches.iter().take_while(|m| **m <= esc.0).count();
-
-        if match_count % 2 == 1 {
-            // if == 1, then it's either at the same spot as the start of an invert, or in the
-            // middle of an invert. Either way we don't want to place it in.
-            continue;
+/// Message shown when moving to the next match continues from the first match
+const WRAPPED_TO_TOP: &str = "Search hit BOTTOM, continuing at TOP";
+/// Message shown when moving to the previous match continues from the last match
+const WRAPPED_TO_BOTTOM: &str = "Search hit TOP, continuing at BOTTOM";
+
+/// Set [`PagerState::search_mark`] to move to the next match
+///
+/// This function will continue looping untill it finds a match that is after the
+/// [`PagerState::upper_mark`]
+///
+/// If [`PagerState::center_on_match`] is set, the match in focus is not at the top of the
+/// screen. In that case, the search continues after the match in focus as long as it is
+/// displayed on the screen.
+pub fn next_nth_match(ps: &mut PagerState, n: usize) {
+    let mut from = ps.upper_mark;
+    if ps.center_on_match {
+        let screen = ps.upper_mark..ps.upper_mark.saturating_add(ps.content_rows());
+        if let Some(row) = ps.search_idx.iter().nth(ps.search_mark) {
+            if screen.contains(row) {
+                from = *row;
+            }
         }
+    }
+    next_nth_match_after(ps, from, n);
+}
 
-        // find the number of invert strings and number of uninvert strings that have been
-        // inserted up to this point in the string
-        let num_invert = match_count / 2;
-        let num_normal = match_count - num_invert;
+/// Set [`PagerState::search_mark`] to move to the nth match after the formatted row `from`
+///
+/// If there are less than `n` matches after `from`, it continues from the first match when
+/// [`PagerState::search_wrap`] is set and stops at the last match otherwise.
+pub fn next_nth_match_after(ps: &mut PagerState, from: usize, n: usize) {
+    let num_matches = ps.search_idx.len();
+    // Find the first match that's after the given row, then move n-1 matches ahead of it
+    let target = ps
+        .search_idx
+        .iter()
+        .position(|i| *i > from)
+        .unwrap_or(num_matches)
+        .saturating_add(n.max(1) - 1);
 
-        // calculate the index which this escape should be re-inserted at by adding
-        // its position in the stripped string to the total length of the ansi escapes
-        // (both highlighting and the ones from the original string).
-        let pos =
-            esc.0 + inserted_escs_len + (num_invert * INVERT.len()) + (num_normal * NORMAL.len());
+    if target < num_matches {
+        ps.search_mark = target;
+    } else if ps.search_wrap && num_matches > 0 {
+        ps.search_mark = target % num_matches;
+        ps.message = Some(WRAPPED_TO_TOP.to_string());
+    } else {
+        ps.search_mark = num_matches.saturating_sub(1);
+    }
 
-        // insert the escape back in
-        inverted.insert_str(pos, esc.1);
+    // And set the upper_mark to that match so that we scroll to it
+    if let Some(idx) = ps.search_idx.iter().nth(ps.search_mark) {
+        scroll_to_match(ps, *idx, true);
+    }
+    // Update the match counter in the prompt
+    ps.format_prompt();
+}
 
-        // increment the length of the escapes inserted back in
-        inserted_escs_len += esc.1.len();
+/// Set [`PagerState::search_mark`] to the nth match, counting from 1, and scroll to it
+///
+/// If there are less than `n` matches, it goes to the last one and says so in the prompt.
+pub fn goto_nth_match(ps: &mut PagerState, n: usize) {
+    let num_matches = ps.search_idx.len();
+    if num_matches == 0 {
+        return;
+    }
+    if n > num_matches {
+        ps.message = Some(format!("Only {num_matches} matches, showing the last one"));
     }
+    ps.search_mark = n.clamp(1, num_matches) - 1;
 
-    (inverted, true)
+    if let Some(idx) = ps.search_idx.iter().nth(ps.search_mark) {
+        scroll_to_match(ps, *idx, true);
+    }
+    ps.format_prompt();
 }
 
-/// Set [`PagerState::search_mark`] to move to the next match
+/// Set [`PagerState::search_mark`] to move to the nth previous match
 ///
-/// This function will continue looping untill it finds a match that is after the
-/// [`PagerState::upper_mark`]
-pub fn next_nth_match(ps: &mut PagerState, n: usize) {
-    // Find the first match that's after the upper_mark, then set the mark to that match.
-    // If we can't find one, just set it to the last match
-    if let Some(nearest_idx) = ps.search_idx.iter().position(|i| *i > ps.upper_mark) {
-        ps.search_mark = nearest_idx.saturating_add(n).saturating_sub(1);
+/// If there are less than `n` matches before the current one, it continues from the last match
+/// when [`PagerState::search_wrap`] is set and stops at the first match otherwise.
+pub fn prev_nth_match(ps: &mut PagerState, n: usize) {
+    let num_matches = ps.search_idx.len();
+    // If no matches, return immediately
+    if num_matches == 0 {
+        return;
+    }
 
-        if ps.search_mark > ps.search_idx.len().saturating_sub(1) {
-            ps.search_mark = ps.search_idx.len().saturating_sub(1);
+    if n > ps.search_mark && ps.search_wrap {
+        ps.search_mark = (ps.search_mark + num_matches - n % num_matches) % num_matches;
+        ps.message = Some(WRAPPED_TO_BOTTOM.to_string());
+        if let Some(y) = ps.search_idx.iter().nth(ps.search_mark) {
+            scroll_to_match(ps, *y, true);
         }
     } else {
-        ps.search_mark = ps.search_idx.len().saturating_sub(1);
+        // Decrement the s_mark and get the preceeding index
+        ps.search_mark = ps.search_mark.saturating_sub(n);
+        if let Some(y) = ps.search_idx.iter().nth(ps.search_mark) {
+            // If the index is less than or equal to the upper_mark, then set y to the new upper_mark
+            let above_screen = *y < ps.upper_mark;
+            scroll_to_match(ps, *y, above_screen);
+        }
     }
+    ps.format_prompt();
+}
 
-    // And set the upper_mark to that match so that we scroll to it
-    if let Some(idx) = ps.search_idx.iter().nth(ps.search_mark) {
-        ps.upper_mark = *idx;
+/// Finds the next line after the top of the screen that matches a highlight group
+///
+/// If `group` is given, only that group is looked for, otherwise any of the
+/// [`PagerState::highlight_groups`]. Returns the formatted row where the line starts.
+pub fn next_highlight(ps: &PagerState, group: Option<usize>) -> Option<usize> {
+    let groups = match group {
+        Some(idx) => ps.highlight_groups.get(idx..=idx).unwrap_or_default(),
+        None => &ps.highlight_groups,
+    };
+    if groups.is_empty() {
+        return None;
+    }
+    let current = ps.line_at_row(ps.upper_mark);
+    ps.lines
+        .lines()
+        .enumerate()
+        .skip(current + 1)
+        .find(|(_, line)| {
+            let stripped = ANSI_REGEX.replace_all(line, "");
+            groups.iter().any(|g| g.is_match(&stripped))
+        })
+        .map(|(idx, _)| ps.row_of_line(idx))
+}
+
+/// Finds the `n`th line after the top of the screen that matches
+/// [`PagerState::section_pattern`], or before it if `forward` is `false`
+///
+/// If there are fewer than `n` such lines, the last one found is used. A count of 0 is treated
+/// as 1. Returns the formatted row where the line starts, or `None` if no line matches in that
+/// direction.
+pub fn next_section(ps: &PagerState, forward: bool, n: usize) -> Option<usize> {
+    let pattern = ps.section_pattern.as_ref()?;
+    let n = n.max(1);
+    let current = ps.line_at_row(ps.upper_mark);
+    let sections = ps
+        .lines
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(&ANSI_REGEX.replace_all(line, "")))
+        .map(|(idx, _)| idx);
+    let found = if forward {
+        sections.filter(|idx| *idx > current).take(n).last()
+    } else {
+        let before = sections
+            .take_while(|idx| *idx < current)
+            .collect::<Vec<_>>();
+        // The n-th section going back, or the first one if there are fewer
+        before
+            .len()
+            .checked_sub(1)
+            .map(|last| before[last.saturating_sub(n - 1)])
+    };
+    found.map(|idx| ps.row_of_line(idx))
+}
+
+/// Returns the indices of the lines in [`PagerState::lines`] that contain a search match
+///
+/// Each line is listed once, even if its match spans several formatted rows. The list is empty
+/// if there is no search.
+pub fn match_lines(ps: &PagerState) -> Vec<usize> {
+    if ps.search_term.is_none() {
+        return Vec::new();
+    }
+    let mut rows = ps.search_idx.iter().peekable();
+    let mut lines = Vec::new();
+    let mut rows_before = 0;
+    for (idx, line_rows) in ps.rows_per_line().enumerate() {
+        if rows.peek().is_none() {
+            break;
+        }
+        rows_before += line_rows;
+        let mut is_match = false;
+        while rows.next_if(|row| **row < rows_before).is_some() {
+            is_match = true;
+        }
+        if is_match {
+            lines.push(idx);
+        }
+    }
+    lines
+}
+
+/// Returns the columns of `line` that are covered by the matches of `query`
+///
+/// If `ranges` is given, it holds the parts of the line matched by a multiline search, as
+/// returned by [`multiline_match_ranges`], and `query` is not run again. Each span is the display
+/// column where a match starts and the one right after it ends, counted in the line with all ANSI
+/// escapes removed. Wide characters take two columns. Empty matches are left out.
+pub fn match_spans(
+    line: &str,
+    query: &Regex,
+    ranges: Option<&[Range<usize>]>,
+) -> Vec<(usize, usize)> {
+    let stripped = ANSI_REGEX.replace_all(line, "");
+    let ranges = ranges.map_or_else(
+        || query.find_iter(&stripped).map(|m| m.range()).collect(),
+        <[_]>::to_vec,
+    );
+    let column = |byte: usize| textwrap::core::display_width(&stripped[..byte]);
+    ranges
+        .into_iter()
+        .filter(|r| !r.is_empty())
+        .map(|r| (column(r.start), column(r.end)))
+        .collect()
+}
+
+/// Scrolls to the match at the formatted row `row`
+///
+/// If [`PagerState::center_on_match`] is set, the match is always placed in the middle of the
+/// screen as far as possible. Otherwise the match is placed at the top of the screen, only if
+/// `to_top` is `true`.
+fn scroll_to_match(ps: &mut PagerState, row: usize, to_top: bool) {
+    if ps.center_on_match {
+        // Don't scroll so far that the end of the text is above the bottom of the screen
+        let max_upper_mark = ps.num_lines().saturating_sub(ps.content_rows());
+        ps.upper_mark = row
+            .saturating_sub(ps.content_rows() / 2)
+            .min(max_upper_mark);
+    } else if to_top {
+        ps.upper_mark = row;
     }
 }
 
@@ -226,9 +862,17 @@ pub fn next_nth_match(ps: &mut PagerState, n: usize) {
 mod tests {
     use std::collections::BTreeSet;
 
-    use super::{highlight_line_matches, next_nth_match, INVERT, NORMAL};
+    use super::{
+        compile_query, guess_pasted_text, highlight_line_matches, match_lines, match_spans,
+        next_nth_match, next_nth_match_after, prev_nth_match, prompt_prefix, restyle_current_match,
+        style_escapes, SearchMode, SearchQuery, GROUP_STYLES, INVERT, NORMAL, WRAPPED_TO_BOTTOM,
+        WRAPPED_TO_TOP,
+    };
     use crate::PagerState;
-    use crossterm::style::Attribute;
+    use crossterm::{
+        event::{KeyCode, KeyEvent, KeyModifiers},
+        style::Attribute,
+    };
     use regex::Regex;
 
     // generic escape code
@@ -248,6 +892,422 @@ mod tests {
         }
     }
 
+    #[test]
+    fn next_match_wraps() {
+        let mut pager = PagerState::new().unwrap();
+        pager.search_idx = BTreeSet::from([2, 10, 15]);
+        pager.upper_mark = 20;
+        pager.search_mark = 2;
+
+        next_nth_match(&mut pager, 1);
+        assert_eq!(pager.search_mark, 0);
+        assert_eq!(pager.upper_mark, 2);
+        assert_eq!(pager.message.as_deref(), Some(WRAPPED_TO_TOP));
+
+        // Moving by more than the number of matches keeps cycling
+        pager.upper_mark = 15;
+        next_nth_match(&mut pager, 5);
+        assert_eq!(pager.upper_mark, 10);
+
+        pager.search_wrap = false;
+        pager.message = None;
+        pager.upper_mark = 20;
+        next_nth_match(&mut pager, 1);
+        assert_eq!(pager.upper_mark, 15);
+        assert!(pager.message.is_none());
+    }
+
+    #[test]
+    fn multiline_search() {
+        let mut pager = PagerState::new().unwrap();
+        pager.lines = "start\nerror: disk full\nretrying now\nend".to_string();
+        pager.search_term = Some(Regex::new("full\nretry").unwrap());
+        pager.format_lines();
+        // Without multiline search, rows are searched on their own
+        assert!(pager.search_idx.is_empty());
+
+        pager.search_multiline = true;
+        pager.format_lines();
+        assert_eq!(pager.search_idx, BTreeSet::from([1, 2]));
+        assert_eq!(
+            pager.formatted_lines,
+            vec![
+                "start".to_string(),
+                format!("error: disk {}full{}", *INVERT, *NORMAL),
+                format!("{}retry{}ing now", *INVERT, *NORMAL),
+                "end".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_range() {
+        use crate::minus_core::{ev_handler::handle_event, events::Event};
+        use parking_lot::{Condvar, Mutex};
+        use std::sync::{atomic::AtomicBool, Arc};
+
+        let mut pager = PagerState::new().unwrap();
+        pager.lines = "match\nother\nmatch\nmatch\n".to_string();
+        pager.search_term = Some(Regex::new("match").unwrap());
+        pager.format_lines();
+        assert_eq!(pager.search_idx, BTreeSet::from([0, 2, 3]));
+
+        // The bounds are put in order and may go past the end of the text
+        handle_event(
+            Event::SetSearchRange(Some((10, 1))),
+            &mut Vec::new(),
+            &mut pager,
+            &Arc::new(AtomicBool::new(false)),
+            &Arc::new((Mutex::new(true), Condvar::new())),
+        )
+        .unwrap();
+        assert_eq!(pager.search_range, Some((1, 10)));
+        // The match outside of the range is neither found nor highlighted
+        assert_eq!(pager.search_idx, BTreeSet::from([2, 3]));
+        assert_eq!(pager.formatted_lines[0], "match");
+        assert!(pager.displayed_prompt.contains(" 1/2 "));
+
+        pager.search_range = None;
+        pager.format_lines();
+        assert_eq!(pager.search_idx, BTreeSet::from([0, 2, 3]));
+    }
+
+    #[test]
+    fn match_spans_of_line() {
+        let mut pager = PagerState::new().unwrap();
+        pager.lines = format!("no hits\n漢 ab {ESC}ab{NONE} xab\nab\ncd");
+        assert!(pager.match_spans(1).is_empty());
+
+        pager.search_term = Some(Regex::new("ab").unwrap());
+        pager.format_lines();
+        // Columns are counted on screen, without the escapes
+        assert_eq!(pager.match_spans(1), vec![(3, 5), (6, 8), (10, 12)]);
+        assert!(pager.match_spans(0).is_empty());
+        assert!(pager.match_spans(4).is_empty());
+
+        pager.search_range = Some((2, 3));
+        assert!(pager.match_spans(1).is_empty());
+        assert_eq!(pager.match_spans(2), vec![(0, 2)]);
+
+        pager.search_range = None;
+        pager.search_multiline = true;
+        pager.search_term = Some(Regex::new("ab\ncd").unwrap());
+        assert_eq!(pager.match_spans(2), vec![(0, 2)]);
+        assert_eq!(pager.match_spans(3), vec![(0, 2)]);
+
+        // Empty matches are left out
+        let query = Regex::new("x?").unwrap();
+        assert_eq!(match_spans("axb", &query, None), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn multiline_search_wrapped_rows() {
+        let mut pager = PagerState::new().unwrap();
+        pager.cols = 10;
+        pager.lines = "aaaa bbbb cccc\ndddd".to_string();
+        pager.search_term = Some(Regex::new("(?s)cccc.dd").unwrap());
+        pager.search_multiline = true;
+        pager.format_lines();
+
+        // The first line is wrapped into "aaaa bbbb" and "cccc", only the second row matches
+        assert_eq!(pager.search_idx, BTreeSet::from([1, 2]));
+        assert_eq!(pager.formatted_lines[0], "aaaa bbbb");
+        assert_eq!(
+            pager.formatted_lines[1],
+            format!("{}cccc{}", *INVERT, *NORMAL)
+        );
+        assert_eq!(
+            pager.formatted_lines[2],
+            format!("{}dd{}dd", *INVERT, *NORMAL)
+        );
+    }
+
+    #[test]
+    fn multiline_search_rendered_rows() {
+        let mut pager = PagerState::new().unwrap();
+        pager.cols = 10;
+        pager.search_term = Some(Regex::new("(?s)é b.c").unwrap());
+        pager.search_multiline = true;
+
+        // Tabs are shown as markers that are longer than the tab itself
+        pager.lines = "\tx é b\ncd".to_string();
+        pager.show_whitespace = true;
+        pager.format_lines();
+        assert_eq!(pager.search_idx, BTreeSet::from([0, 1]));
+        assert_eq!(
+            pager.formatted_lines[0],
+            format!("\x1b[2m→\x1b[22mx {}é b{}", *INVERT, *NORMAL)
+        );
+        assert_eq!(
+            pager.formatted_lines[1],
+            format!("{}c{}d", *INVERT, *NORMAL)
+        );
+
+        // Truncated rows start at the columns that are scrolled past
+        pager.show_whitespace = false;
+        pager.lines = "aé aé b\ncd".to_string();
+        pager.line_wrapping = false;
+        pager.left_mark = 3;
+        pager.format_lines();
+        assert_eq!(
+            pager.formatted_lines[0],
+            format!("a{}é b{}", *INVERT, *NORMAL)
+        );
+    }
+
+    #[test]
+    fn literal_query() {
+        // As a regex, the brackets form a character class
+        let regex = compile_query("[id].rs", false).unwrap();
+        assert!(regex.is_match("src/d.rs"));
+        assert!(!regex.is_match("src/[id].rs"));
+        let literal = compile_query("[id].rs", true).unwrap();
+        assert!(literal.is_match("src/[id].rs"));
+        assert!(!literal.is_match("src/d.rs"));
+
+        // Queries that are invalid regexes are fine in literal mode
+        assert!(compile_query("a(b", false).is_err());
+        assert!(compile_query("a(b", true).unwrap().is_match("a(b"));
+
+        assert_eq!(prompt_prefix(SearchMode::Forward, ('/', '?'), false), "/");
+        assert_eq!(prompt_prefix(SearchMode::Reverse, ('/', '?'), true), "f?");
+        assert_eq!(prompt_prefix(SearchMode::Reverse, ('s', 'S'), false), "S");
+    }
+
+    #[test]
+    fn match_style_escapes() {
+        use crossterm::style::{ContentStyle, Stylize};
+
+        assert_eq!(
+            style_escapes(&ContentStyle::new().reverse()),
+            (INVERT.to_string(), NORMAL.to_string())
+        );
+        assert_eq!(
+            style_escapes(&ContentStyle::new().black().on_yellow().bold()),
+            (
+                "\x1b[38;5;0m\x1b[48;5;11m\x1b[1m".to_string(),
+                "\x1b[39m\x1b[49m\x1b[22m".to_string()
+            )
+        );
+        assert_eq!(style_escapes(&ContentStyle::new()), Default::default());
+    }
+
+    #[test]
+    fn empty_match_style() {
+        use crossterm::style::ContentStyle;
+
+        let mut pager = PagerState::new().unwrap();
+        pager.lines = "a \x1b[1mbold\x1b[0m match\nanother match".to_string();
+        pager.other_match_style = ContentStyle::new();
+        pager.match_escapes = style_escapes(&pager.other_match_style);
+        pager.search_term = Some(Regex::new("match|bold").unwrap());
+        pager.format_lines();
+
+        // The matches are found but the rows are left alone
+        assert_eq!(pager.search_idx, BTreeSet::from([0, 1]));
+        assert_eq!(pager.formatted_lines[0], "a \x1b[1mbold\x1b[0m match");
+        assert_eq!(pager.formatted_lines[1], "another match");
+        assert_eq!(
+            restyle_current_match(&pager, &pager.formatted_lines[1]),
+            "another match"
+        );
+
+        // Also with highlight groups, which don't style over the matches
+        pager.highlight_groups = vec![Regex::new("another m").unwrap(), Regex::new("a ").unwrap()];
+        pager.format_lines();
+        let (start, end) = &GROUP_STYLES[1];
+        assert_eq!(
+            pager.formatted_lines[0],
+            format!("{start}a {end}\x1b[1mbold\x1b[0m match")
+        );
+        assert_eq!(pager.formatted_lines[1], "another match");
+    }
+
+    #[test]
+    fn highlight_groups() {
+        let mut pager = PagerState::new().unwrap();
+        pager.cols = 80;
+        pager.lines = "error: disk full\nwarning: disk almost full".to_string();
+        pager.highlight_groups = vec![Regex::new("error").unwrap(), Regex::new("disk").unwrap()];
+        pager.search_term = Some(Regex::new("full").unwrap());
+        pager.format_lines();
+
+        let (err_start, err_end) = &GROUP_STYLES[0];
+        let (disk_start, disk_end) = &GROUP_STYLES[1];
+        assert_eq!(
+            pager.formatted_lines[0],
+            format!(
+                "{}error{}: {}disk{} {}full{}",
+                err_start, err_end, disk_start, disk_end, *INVERT, *NORMAL
+            )
+        );
+        assert_eq!(
+            pager.formatted_lines[1],
+            format!(
+                "warning: {}disk{} almost {}full{}",
+                disk_start, disk_end, *INVERT, *NORMAL
+            )
+        );
+        // Highlight groups are not search matches
+        assert_eq!(pager.search_idx, BTreeSet::from([0, 1]));
+    }
+
+    #[test]
+    fn prev_match_wraps() {
+        let mut pager = PagerState::new().unwrap();
+        pager.search_idx = BTreeSet::from([2, 10, 15]);
+        pager.upper_mark = 2;
+        pager.search_mark = 0;
+
+        prev_nth_match(&mut pager, 1);
+        assert_eq!(pager.search_mark, 2);
+        assert_eq!(pager.upper_mark, 15);
+        assert_eq!(pager.message.as_deref(), Some(WRAPPED_TO_BOTTOM));
+
+        prev_nth_match(&mut pager, 1);
+        assert_eq!(pager.search_mark, 1);
+        assert_eq!(pager.upper_mark, 10);
+
+        pager.search_wrap = false;
+        prev_nth_match(&mut pager, 4);
+        assert_eq!(pager.search_mark, 0);
+        assert_eq!(pager.upper_mark, 2);
+    }
+
+    #[test]
+    fn center_on_match() {
+        let mut pager = PagerState::new().unwrap();
+        pager.formatted_lines = vec![String::new(); 100];
+        pager.search_idx = BTreeSet::from([2, 50, 97]);
+        pager.center_on_match = true;
+
+        // Near the top, the screen can't scroll above the first line
+        next_nth_match_after(&mut pager, 0, 1);
+        assert_eq!(pager.upper_mark, 0);
+
+        // There are 9 rows above the prompt, hence the match should be placed on the 5th row
+        next_nth_match(&mut pager, 1);
+        assert_eq!(pager.upper_mark, 46);
+
+        // Near the bottom, the last page is displayed
+        next_nth_match(&mut pager, 1);
+        assert_eq!(pager.upper_mark, 91);
+
+        prev_nth_match(&mut pager, 1);
+        assert_eq!(pager.upper_mark, 46);
+    }
+
+    #[test]
+    fn edit_search_query() {
+        let mut query = SearchQuery::default();
+        let mut press = |code, modifiers| assert!(query.handle_key(KeyEvent { code, modifiers }));
+
+        for c in "helo wrld".chars() {
+            press(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        // Fix the second word
+        for _ in 0..3 {
+            press(KeyCode::Left, KeyModifiers::NONE);
+        }
+        press(KeyCode::Char('o'), KeyModifiers::NONE);
+        // Fix the first word
+        press(KeyCode::Left, KeyModifiers::CONTROL);
+        press(KeyCode::Left, KeyModifiers::CONTROL);
+        press(KeyCode::Right, KeyModifiers::NONE);
+        press(KeyCode::Right, KeyModifiers::NONE);
+        press(KeyCode::Char('l'), KeyModifiers::NONE);
+        // Edit the ends
+        press(KeyCode::Home, KeyModifiers::NONE);
+        press(KeyCode::Char('^'), KeyModifiers::NONE);
+        press(KeyCode::Delete, KeyModifiers::NONE);
+        press(KeyCode::Char('H'), KeyModifiers::SHIFT);
+        press(KeyCode::End, KeyModifiers::NONE);
+        press(KeyCode::Backspace, KeyModifiers::NONE);
+        press(KeyCode::Char('D'), KeyModifiers::SHIFT);
+
+        assert_eq!(query.text, "^Hello worlD");
+        assert_eq!(query.cursor, query.text.len());
+        assert!(!query.handle_key(KeyEvent {
+            code: KeyCode::Char('x'),
+            modifiers: KeyModifiers::CONTROL
+        }));
+    }
+
+    #[test]
+    fn paste_into_search_query() {
+        use crossterm::event::Event;
+
+        let key = |code, modifiers| Event::Key(KeyEvent { code, modifiers });
+        let mut burst = "foo.*bar"
+            .chars()
+            .map(|c| key(KeyCode::Char(c), KeyModifiers::NONE))
+            .collect::<Vec<Event>>();
+        burst.push(key(KeyCode::Enter, KeyModifiers::NONE));
+        burst.push(key(KeyCode::Char('G'), KeyModifiers::SHIFT));
+
+        // The line break doesn't end the prompt and nothing is taken as a command
+        let text = guess_pasted_text(&burst).unwrap();
+        assert_eq!(text, "foo.*barG");
+
+        let mut query = SearchQuery::default();
+        query.handle_key(KeyEvent {
+            code: KeyCode::Char('^'),
+            modifiers: KeyModifiers::NONE,
+        });
+        query.paste(&text);
+        assert_eq!(query.text, "^foo.*barG");
+        assert_eq!(query.cursor, query.text.len());
+
+        // Single key presses and bursts with other keys are handled one by one
+        assert_eq!(guess_pasted_text(&burst[..1]), None);
+        burst.push(key(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(guess_pasted_text(&burst), None);
+    }
+
+    #[test]
+    fn edit_search_query_multibyte() {
+        let mut query = SearchQuery::default();
+        for c in "caf\u{e9}s".chars() {
+            query.handle_key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE,
+            });
+        }
+        query.handle_key(KeyEvent {
+            code: KeyCode::Left,
+            modifiers: KeyModifiers::NONE,
+        });
+        query.handle_key(KeyEvent {
+            code: KeyCode::Backspace,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(query.text, "cafs");
+        assert_eq!(query.cursor, 3);
+    }
+
+    #[test]
+    fn match_counter_in_prompt() {
+        let mut pager = PagerState::new().unwrap();
+        pager.lines = "first\nmatch\nsecond\nmatch\n".to_string();
+        pager.search_term = Some(Regex::new("match").unwrap());
+        pager.format_lines();
+
+        next_nth_match(&mut pager, 1);
+        assert!(pager.displayed_prompt.contains(" 1/2 "));
+        next_nth_match(&mut pager, 1);
+        assert!(pager.displayed_prompt.contains(" 2/2 "));
+
+        pager.search_term = Some(Regex::new("nothing").unwrap());
+        pager.format_lines();
+        assert!(pager.displayed_prompt.contains(" Pattern not found "));
+
+        // No counter once there is no search
+        pager.search_term = None;
+        pager.format_lines();
+        assert!(!pager.displayed_prompt.contains("Pattern not found"));
+        assert!(!pager.displayed_prompt.contains('/'));
+    }
+
     #[test]
     fn test_highlight_matches() {
         let line = "Integer placerat tristique nisl. placerat non mollis, magna orci dolor, placerat at vulputate neque nulla lacinia eros.".to_string();
@@ -261,25 +1321,36 @@ eros.",
             noinverse = Attribute::NoReverse
         );
 
-        assert_eq!(highlight_line_matches(&line, &pat).0, result);
+        assert_eq!(
+            highlight_line_matches(&line, &pat, (&INVERT, &NORMAL)).0,
+            result
+        );
     }
 
     #[test]
     fn no_match() {
         let orig = "no match";
-        let res = highlight_line_matches(orig, &Regex::new("test").unwrap());
+        let res = highlight_line_matches(orig, &Regex::new("test").unwrap(), (&INVERT, &NORMAL));
         assert_eq!(res.0, orig.to_string());
     }
 
     #[test]
     fn single_match_no_esc() {
-        let res = highlight_line_matches("this is a test", &Regex::new(" a ").unwrap());
+        let res = highlight_line_matches(
+            "this is a test",
+            &Regex::new(" a ").unwrap(),
+            (&INVERT, &NORMAL),
+        );
         assert_eq!(res.0, format!("this is{} a {}test", *INVERT, *NORMAL));
     }
 
     #[test]
     fn multi_match_no_esc() {
-        let res = highlight_line_matches("test another test", &Regex::new("test").unwrap());
+        let res = highlight_line_matches(
+            "test another test",
+            &Regex::new("test").unwrap(),
+            (&INVERT, &NORMAL),
+        );
         assert_eq!(
             res.0,
             format!("{i}test{n} another {i}test{n}", i = *INVERT, n = *NORMAL)
@@ -289,8 +1360,9 @@ eros.",
     #[test]
     fn esc_outside_match() {
         let res = highlight_line_matches(
-            &format!("{}color{} and test", ESC, NONE),
+            &format!("{ESC}color{NONE} and test"),
             &Regex::new("test").unwrap(),
+            (&INVERT, &NORMAL),
         );
         assert_eq!(
             res.0,
@@ -300,8 +1372,8 @@ eros.",
 
     #[test]
     fn esc_end_in_match() {
-        let orig = format!("this {}is a te{}st", ESC, NONE);
-        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap());
+        let orig = format!("this {ESC}is a te{NONE}st");
+        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap(), (&INVERT, &NORMAL));
         assert_eq!(
             res.0,
             format!("this {}is a {}test{}", ESC, *INVERT, *NORMAL)
@@ -310,8 +1382,8 @@ eros.",
 
     #[test]
     fn esc_start_in_match() {
-        let orig = format!("this is a te{}st again{}", ESC, NONE);
-        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap());
+        let orig = format!("this is a te{ESC}st again{NONE}");
+        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap(), (&INVERT, &NORMAL));
         assert_eq!(
             res.0,
             format!("this is a {}test{} again{}", *INVERT, *NORMAL, NONE)
@@ -320,8 +1392,8 @@ eros.",
 
     #[test]
     fn esc_around_match() {
-        let orig = format!("this is {}a test again{}", ESC, NONE);
-        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap());
+        let orig = format!("this is {ESC}a test again{NONE}");
+        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap(), (&INVERT, &NORMAL));
         assert_eq!(
             res.0,
             format!("this is {}a {}test{} again{}", ESC, *INVERT, *NORMAL, NONE)
@@ -330,19 +1402,15 @@ eros.",
 
     #[test]
     fn esc_within_match() {
-        let orig = format!("this is a t{}es{}t again", ESC, NONE);
-        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap());
+        let orig = format!("this is a t{ESC}es{NONE}t again");
+        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap(), (&INVERT, &NORMAL));
         assert_eq!(res.0, format!("this is a {}test{} again", *INVERT, *NORMAL));
     }
 
     #[test]
     fn multi_escape_match() {
-        let orig = format!(
-            "this {e}is a te{n}st again {e}yeah{n} test",
-            e = ESC,
-            n = NONE
-        );
-        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap());
+        let orig = format!("this {ESC}is a te{NONE}st again {ESC}yeah{NONE} test");
+        let res = highlight_line_matches(&orig, &Regex::new("test").unwrap(), (&INVERT, &NORMAL));
         assert_eq!(
             res.0,
             format!(
@@ -354,4 +1422,62 @@ eros.",
             )
         );
     }
+
+    #[test]
+    #[allow(clippy::trivial_regex)]
+    fn lines_of_matches() {
+        let mut pager = PagerState::new().unwrap();
+        pager.cols = 10;
+        pager.lines = "first\nmatch that wraps\nsecond\nmatch\n".to_string();
+        assert!(match_lines(&pager).is_empty());
+
+        pager.search_term = Some(Regex::new("match|wraps").unwrap());
+        pager.format_lines();
+        // The second line matches on both of its rows, but is only listed once
+        assert_eq!(pager.search_idx.len(), 3);
+        assert_eq!(match_lines(&pager), vec![1, 3]);
+    }
+
+    #[test]
+    fn next_section() {
+        let mut pager = PagerState::new().unwrap();
+        pager.lines = [
+            "diff --git a/src/lib.rs b/src/lib.rs",
+            "--- a/src/lib.rs",
+            "+++ b/src/lib.rs",
+            "@@ -1,3 +1,3 @@",
+            " use std::io;",
+            "-use std::fmt;",
+            "+use std::fmt::Write;",
+            "\x1b[36m@@ -20,2 +20,2 @@\x1b[0m fn main() {",
+            "-    old();",
+            "+    new();",
+            "@@ -40,1 +40,1 @@",
+            "-}",
+            "+} // end",
+        ]
+        .join("\n");
+        pager.format_lines();
+
+        // Nothing to move to without a pattern
+        assert_eq!(super::next_section(&pager, true, 1), None);
+        pager.section_pattern = Some(Regex::new("^@@").unwrap());
+
+        assert_eq!(super::next_section(&pager, true, 1), Some(3));
+        // The colored hunk header is found as well
+        assert_eq!(super::next_section(&pager, true, 2), Some(7));
+        // Moving further than the last hunk stops at it
+        assert_eq!(super::next_section(&pager, true, 5), Some(10));
+        assert_eq!(super::next_section(&pager, false, 1), None);
+
+        pager.upper_mark = 10;
+        assert_eq!(super::next_section(&pager, true, 1), None);
+        assert_eq!(super::next_section(&pager, false, 1), Some(7));
+        assert_eq!(super::next_section(&pager, false, 2), Some(3));
+        assert_eq!(super::next_section(&pager, false, 9), Some(3));
+        // A count of 0 moves like a count of 1
+        assert_eq!(super::next_section(&pager, false, 0), Some(7));
+        pager.upper_mark = 0;
+        assert_eq!(super::next_section(&pager, true, 0), Some(3));
+    }
 }
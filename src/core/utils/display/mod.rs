@@ -1,14 +1,20 @@
 use crossterm::{
     cursor::MoveTo,
     execute, queue,
-    style::Attribute,
+    style::{
+        Attribute, Color, ContentStyle, SetAttributes, SetBackgroundColor, SetForegroundColor,
+        Stylize,
+    },
     terminal::{Clear, ClearType},
 };
 
-use std::{cmp::Ordering, convert::TryInto, io::Write};
+use std::{borrow::Cow, cmp::Ordering, convert::TryInto, io::Write};
 
-use super::term::move_cursor;
-use crate::{error::MinusError, PagerState};
+use super::{
+    term::move_cursor,
+    text::{display_width, strip_sgr},
+};
+use crate::{error::MinusError, DumpScope, PagerState, PromptPosition};
 
 /// Handles drawing of screen based on movement
 ///
@@ -16,31 +22,39 @@ use crate::{error::MinusError, PagerState};
 /// very high if that redrawing is required on every movement of the pager, even for small changes.
 /// This function calculates what part of screen needs to be redrawed on scrolling up/down and based on that, it redraws
 /// only that part of the terminal.
+#[allow(clippy::needless_pass_by_ref_mut)]
 pub fn draw_for_change(
     out: &mut impl Write,
     p: &mut PagerState,
     new_upper_mark: &mut usize,
 ) -> Result<(), MinusError> {
+    if p.prompt_position == PromptPosition::Top || p.frozen_rows() > 0 {
+        // Scrolling the terminal would move the prompt or the frozen header along with the text
+        *new_upper_mark = (*new_upper_mark)
+            .min(p.max_upper_mark())
+            .max(p.frozen_rows());
+        p.upper_mark = *new_upper_mark;
+        return draw_full(out, p);
+    }
     let line_count = p.num_lines();
 
-    // Reduce one row for prompt/messages
+    // Reduce one row for prompt/messages, and another one for the status bar if there is one
     //
     // NOTE This should be the value of rows that should be used throughout this function.
     // Don't use PagerState::rows, it might lead to wrong output
-    let writable_rows = p.rows.saturating_sub(1);
+    let content_rows = p.content_rows();
 
-    let delta = new_upper_mark.abs_diff(p.upper_mark);
+    // If the new lower bound would be greater than the avilable line count, we set the new upper
+    // mark to such a value so that the last page can be displayed entirely, i.e never scroll past
+    // the last line, unless scrolling past the end is enabled
+    *new_upper_mark = (*new_upper_mark).min(p.max_upper_mark());
 
-    // Calculate the lower_bound for current and new upper marks
-    // by adding either the rows or line_count depending on the minimality
-    let lower_bound = p.upper_mark.saturating_add(writable_rows.min(line_count));
-    let new_lower_bound = new_upper_mark.saturating_add(writable_rows.min(line_count));
+    let delta = new_upper_mark.abs_diff(p.upper_mark);
 
-    // If the lower_bound is greater than the avilable line count, we set it to such a value
-    // so that the last page can be displayed entirely, i.e never scroll past the last line
-    if new_lower_bound > line_count {
-        *new_upper_mark = line_count.saturating_sub(writable_rows);
-    }
+    // Calculate the lower_bound for current and new upper marks. Rows past the end of the text
+    // are only ever scrolled to if scrolling past the end is enabled
+    let lower_bound = p.upper_mark.saturating_add(content_rows);
+    let new_lower_bound = new_upper_mark.saturating_add(content_rows);
 
     // Sometimes the value of delta is too large that we can rather use the value of the writable rows to
     // achieve the same effect with better performance. This means that we have to less lines to the terminal
@@ -54,11 +68,11 @@ pub fn draw_for_change(
     // NOTE that the large delta case may not always be true in case of scrolling down. Actually this method produces
     // wrong output if this is not the case hence we still rely on using lower bounds method. But for scrolling up, we
     // need this value whatever the value of delta be.
-    let normalized_delta = delta.min(writable_rows);
+    let normalized_delta = delta.min(content_rows);
 
-    let lines = match (*new_upper_mark).cmp(&p.upper_mark) {
+    let (start, end) = match (*new_upper_mark).cmp(&p.upper_mark) {
         Ordering::Greater => {
-            // Scroll down `normalized_delta` lines, and put the cursor one line above, where the old prompt would present.
+            // Scroll down `normalized_delta` lines, and put the cursor where the old status bar or prompt would present.
             // Clear it off and start displaying new dta.
             queue!(
                 out,
@@ -67,18 +81,24 @@ pub fn draw_for_change(
             move_cursor(
                 out,
                 0,
-                p.rows
-                    .saturating_sub(normalized_delta + 1)
+                content_rows
+                    .saturating_sub(normalized_delta)
                     .try_into()
                     .unwrap(),
                 false,
             )?;
-            queue!(out, Clear(ClearType::CurrentLine))?;
+            // With a status bar, the old prompt is in the row below, so clear that one as well
+            let clear = if p.status_bar.is_some() {
+                ClearType::FromCursorDown
+            } else {
+                ClearType::CurrentLine
+            };
+            queue!(out, Clear(clear))?;
 
-            if delta < writable_rows {
-                p.get_flattened_lines_with_bounds(lower_bound, new_lower_bound)
+            if delta < content_rows {
+                (lower_bound, new_lower_bound)
             } else {
-                p.get_flattened_lines_with_bounds(
+                (
                     *new_upper_mark,
                     new_upper_mark.saturating_add(normalized_delta),
                 )
@@ -91,7 +111,7 @@ pub fn draw_for_change(
             )?;
             move_cursor(out, 0, 0, false)?;
 
-            p.get_flattened_lines_with_bounds(
+            (
                 *new_upper_mark,
                 new_upper_mark.saturating_add(normalized_delta),
             )
@@ -99,29 +119,103 @@ pub fn draw_for_change(
         Ordering::Equal => return Ok(()),
     };
 
-    for line in lines {
-        writeln!(out, "\r{line}")?;
+    p.ensure_formatted(start, end);
+    let margin = " ".repeat(p.left_margin());
+    let current_match = current_match_row(p);
+    for (idx, line) in p
+        .get_flattened_lines_with_bounds(start, end)
+        .iter()
+        .enumerate()
+    {
+        let mut line = restyle_current_match(p, current_match, start + idx, line);
+        if !p.color_enabled && current_match == Some(start + idx) {
+            line = Cow::Owned(mark_row(p, &line));
+        }
+        writeln!(out, "\r{margin}{line}")?;
     }
+    write_end_of_buffer(out, p, end.saturating_sub(start.max(line_count)))?;
+    // Scrolling the terminal moved the scrollbar along with the text
+    write_scrollbar(out, p)?;
 
-    super::display::write_prompt(out, &p.displayed_prompt, p.rows.try_into().unwrap())?;
+    write_status_bar(out, p)?;
+    if p.dynamic_prompt.is_some() {
+        p.format_prompt();
+    }
+    super::display::write_prompt(
+        out,
+        &p.displayed_prompt,
+        &p.displayed_prompt_style(),
+        p.prompt_row().try_into().unwrap(),
+    )?;
     out.flush()?;
 
     Ok(())
 }
 
-/// Write given text at the prompt site
-pub fn write_prompt(out: &mut impl Write, text: &str, rows: u16) -> Result<(), MinusError> {
-    write!(
-        out,
-        "{mv}\r{rev}{prompt}{reset}",
-        mv = MoveTo(0, rows),
-        rev = Attribute::Reverse,
-        prompt = text,
-        reset = Attribute::Reset,
-    )?;
+/// Write given text at the prompt site in the given style
+///
+/// All styling is reset after the text, so that it does not leak into the rest of the screen.
+pub fn write_prompt(
+    out: &mut impl Write,
+    text: &str,
+    style: &ContentStyle,
+    rows: u16,
+) -> Result<(), MinusError> {
+    queue!(out, MoveTo(0, rows))?;
+    write!(out, "\r")?;
+    // Without any style there is nothing to reset, which keeps the output plain if colors are off
+    if *style == ContentStyle::new() && !text.contains('\x1b') {
+        write!(out, "{text}")?;
+        return Ok(());
+    }
+    if let Some(fg) = style.foreground_color {
+        queue!(out, SetForegroundColor(fg))?;
+    }
+    if let Some(bg) = style.background_color {
+        queue!(out, SetBackgroundColor(bg))?;
+    }
+    queue!(out, SetAttributes(style.attributes))?;
+    write!(out, "{text}{reset}", reset = Attribute::Reset)?;
     Ok(())
 }
 
+/// Write the status bar in the row above the prompt, if one is set
+pub fn write_status_bar(out: &mut impl Write, p: &PagerState) -> Result<(), MinusError> {
+    let Some(text) = p.format_status_bar() else {
+        return Ok(());
+    };
+    let row: u16 = (p.first_content_row() + p.content_rows())
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    queue!(out, MoveTo(0, row), Clear(ClearType::CurrentLine))?;
+    if p.color_enabled {
+        write!(
+            out,
+            "\r{rev}{text}{reset}",
+            rev = Attribute::Reverse,
+            reset = Attribute::Reset,
+        )?;
+    } else {
+        write!(out, "\r{}", strip_sgr(&text))?;
+    }
+    Ok(())
+}
+
+/// Replace the prompt with `text` and show it right away
+///
+/// Unlike [`write_prompt`], this clears the rest of the row and flushes `out`, so that it can be
+/// used for showing progress while the rest of the screen is left alone.
+pub fn write_progress(
+    out: &mut impl Write,
+    text: &str,
+    style: &ContentStyle,
+    rows: u16,
+) -> Result<(), MinusError> {
+    queue!(out, MoveTo(0, rows), Clear(ClearType::CurrentLine))?;
+    write_prompt(out, text, style, rows)?;
+    out.flush().map_err(MinusError::Draw)
+}
+
 // The below functions are just a subset of functionality of the above draw_for_change function.
 // Although, separate they are tightly coupled together.
 
@@ -136,51 +230,335 @@ pub fn write_prompt(out: &mut impl Write, text: &str, rows: u16) -> Result<(), M
 pub fn draw_full(out: &mut impl Write, pager: &mut PagerState) -> Result<(), MinusError> {
     super::term::move_cursor(out, 0, 0, false)?;
     queue!(out, Clear(ClearType::All))?;
+    write_frozen_header(out, pager)?;
+    if pager.first_content_row() > 0 {
+        let row = pager
+            .first_content_row()
+            .try_into()
+            .map_err(|_| MinusError::Conversion)?;
+        super::term::move_cursor(out, 0, row, false)?;
+    }
 
     write_lines(out, pager)?;
+    let text_rows = pager.num_lines().saturating_sub(pager.upper_mark);
+    write_end_of_buffer(out, pager, pager.content_rows().saturating_sub(text_rows))?;
+    write_scrollbar(out, pager)?;
 
-    let pager_rows: u16 = pager.rows.try_into().map_err(|_| MinusError::Conversion)?;
+    let prompt_row: u16 = pager
+        .prompt_row()
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
 
-    write_prompt(out, &pager.displayed_prompt, pager_rows)?;
+    write_status_bar(out, pager)?;
+    if pager.dynamic_prompt.is_some() {
+        pager.format_prompt();
+    }
+    write_prompt(
+        out,
+        &pager.displayed_prompt,
+        &pager.displayed_prompt_style(),
+        prompt_row,
+    )?;
+
+    pager.last_draw = Some(std::time::Instant::now());
+    pager.redraw_pending = false;
 
     out.flush().map_err(MinusError::Draw)
 }
 
+/// Write the rows of the frozen header at the top of the text area
+///
+/// Nothing is written if [`PagerState::frozen_header_lines`] is 0.
+fn write_frozen_header(out: &mut impl Write, pager: &mut PagerState) -> Result<(), MinusError> {
+    let frozen = pager.frozen_rows();
+    if frozen == 0 {
+        return Ok(());
+    }
+    let row = pager
+        .first_header_row()
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    super::term::move_cursor(out, 0, row, false)?;
+
+    pager.ensure_formatted(0, frozen);
+    let margin = " ".repeat(pager.left_margin());
+    let current_match = current_match_row(pager);
+    for (row, line) in pager
+        .get_flattened_lines_with_bounds(0, frozen)
+        .iter()
+        .enumerate()
+    {
+        let line = restyle_current_match(pager, current_match, row, line);
+        if !pager.color_enabled && current_match == Some(row) {
+            writeln!(out, "\r{margin}{}", mark_row(pager, &line))?;
+        } else {
+            writeln!(out, "\r{margin}{line}")?;
+        }
+    }
+    Ok(())
+}
+
 /// Write the lines to the terminal
 ///
 /// Draws (at most) `rows -1` lines, where the first line to display is
 /// [`PagerState::upper_mark`]. This function will always try to display as much lines as
 /// possible within `rows -1`.
 ///
-/// It always skips one row at the botton as a site for the prompt or any message that may be sent.
+/// It always skips one row at the botton as a site for the prompt or any message that may be sent,
+/// and one more above it if there is a status bar.
 ///
 /// This function ensures that upper mark never exceeds a value such that adding upper mark and available rows exceeds
 /// the number of lines of text data. This rule is disobeyed in only one special case which is if number of lines of
 /// text is less than available rows. In this situation, upper mark is always 0.
 pub fn write_lines(out: &mut impl Write, pager: &mut PagerState) -> Result<(), MinusError> {
+    // Reduce one row for prompt/messages and the status bar
+    let content_rows = pager.content_rows();
+
+    pager.read_source(pager.upper_mark.saturating_add(content_rows));
     let line_count = pager.num_lines();
 
-    // Reduce one row for prompt/messages
-    let writable_rows = pager.rows.saturating_sub(1);
+    // If the lower_bound would be greater than the avilable line count, we set the upper mark
+    // to such a value so that the last page can be displayed entirely, i.e never scroll past the
+    // last line, unless scrolling past the end is enabled. The frozen header is never scrolled
+    // into the rows below it.
+    pager.upper_mark = pager
+        .upper_mark
+        .min(pager.max_upper_mark())
+        .max(pager.frozen_rows());
 
     // Calculate the lower_mark by adding either the rows or line_count depending
     // on the minimality
     let lower_mark = pager
         .upper_mark
-        .saturating_add(writable_rows.min(line_count));
-
-    // If the lower_bound is greater than the avilable line count, we set it to such a value
-    // so that the last page can be displayed entirely, i.e never scroll past the last line
-    if lower_mark > line_count {
-        pager.upper_mark = line_count.saturating_sub(writable_rows);
-    }
+        .saturating_add(content_rows)
+        .min(line_count);
 
+    pager.ensure_formatted(pager.upper_mark, lower_mark);
     // Add \r to ensure cursor is placed at the beginning of each row
     let lines = pager.get_flattened_lines_with_bounds(pager.upper_mark, lower_mark);
 
-    for line in lines {
-        writeln!(out, "\r{line}")?;
+    let margin = " ".repeat(pager.left_margin());
+    let current_match = current_match_row(pager);
+    let selection = pager.selected_rows();
+    for (row, line) in lines.iter().enumerate() {
+        let line = restyle_current_match(pager, current_match, pager.upper_mark + row, line);
+        // The cursor is drawn over the selection, which is drawn over the highlighted row
+        let text_row = pager.upper_mark + row;
+        let color = if pager.cursor_line == Some(text_row) {
+            Some(pager.cursor_color)
+        } else if selection.map_or(false, |(first, last)| (first..=last).contains(&text_row)) {
+            Some(pager.selection_color)
+        } else if pager.highlight_row == Some(row) {
+            Some(pager.highlight_color)
+        } else {
+            None
+        };
+        if !pager.color_enabled && (color.is_some() || current_match == Some(text_row)) {
+            writeln!(out, "\r{margin}{}", mark_row(pager, &line))?;
+        } else if let Some(color) = color {
+            let line = highlight_line(&line, pager.content_cols(), color);
+            writeln!(out, "\r{margin}{line}")?;
+        } else {
+            writeln!(out, "\r{margin}{line}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Character for the rows of the scrollbar that are not on the screen
+const SCROLLBAR_TRACK: char = '│';
+/// Character for the rows of the scrollbar that are on the screen
+const SCROLLBAR_THUMB: char = '█';
+/// Character for the rows of the scrollbar that contain search matches
+#[cfg(feature = "search")]
+const SCROLLBAR_MATCH: char = '-';
+
+/// Returns the character of the scrollbar for each row of the text area
+///
+/// The thumb takes up the share of the rows that the screen shows of the text, and all of them
+/// if the text fits on the screen. Rows with search matches are marked outside of the thumb.
+fn scrollbar_column(p: &PagerState) -> Vec<char> {
+    let rows = p.content_rows();
+    // The frozen header doesn't scroll, so it is not part of the scrollbar
+    let frozen = p.frozen_rows();
+    let total = p.num_lines().saturating_sub(frozen);
+    if rows == 0 {
+        return Vec::new();
+    }
+    if total <= rows {
+        return vec![SCROLLBAR_THUMB; rows];
+    }
+    let upper_mark = p.upper_mark.saturating_sub(frozen);
+    let thumb_len = (rows * rows / total).max(1);
+    // Reach the end of the scrollbar once the last page is shown, even if rounding down would
+    // leave a gap
+    let thumb_start = if upper_mark >= total.saturating_sub(rows) {
+        rows - thumb_len
+    } else {
+        (upper_mark * rows / total).min(rows - thumb_len)
+    };
+    #[cfg_attr(not(feature = "search"), allow(unused_mut))]
+    let mut column: Vec<char> = (0..rows)
+        .map(|row| {
+            if (thumb_start..thumb_start + thumb_len).contains(&row) {
+                SCROLLBAR_THUMB
+            } else {
+                SCROLLBAR_TRACK
+            }
+        })
+        .collect();
+    #[cfg(feature = "search")]
+    for idx in p
+        .search_idx
+        .iter()
+        .filter_map(|idx| idx.checked_sub(frozen))
+    {
+        if let Some(c) = column.get_mut(idx * rows / total) {
+            if *c == SCROLLBAR_TRACK {
+                *c = SCROLLBAR_MATCH;
+            }
+        }
+    }
+    column
+}
+
+/// Write the scrollbar in the last column of the text area, if it is shown
+pub fn write_scrollbar(out: &mut impl Write, p: &PagerState) -> Result<(), MinusError> {
+    if !p.scrollbar || p.cols == 0 {
+        return Ok(());
+    }
+    let col: u16 = (p.cols - 1)
+        .try_into()
+        .map_err(|_| MinusError::Conversion)?;
+    for (row, c) in scrollbar_column(p).into_iter().enumerate() {
+        let row: u16 = (p.first_content_row() + row)
+            .try_into()
+            .map_err(|_| MinusError::Conversion)?;
+        queue!(out, MoveTo(col, row))?;
+        write!(out, "{c}")?;
+    }
+    Ok(())
+}
+
+/// Write `count` rows that mark the space past the end of the text
+///
+/// Each row shows the [end of buffer character](PagerState::end_of_buffer_char) in a dim style,
+/// like vi does. Nothing is written if the character is not set.
+fn write_end_of_buffer(
+    out: &mut impl Write,
+    pager: &PagerState,
+    count: usize,
+) -> Result<(), MinusError> {
+    let Some(marker) = pager.end_of_buffer_char else {
+        return Ok(());
+    };
+    let marker = if pager.color_enabled {
+        marker.to_string().dim().to_string()
+    } else {
+        marker.to_string()
+    };
+    let margin = " ".repeat(pager.left_margin());
+    for _ in 0..count {
+        writeln!(out, "\r{margin}{marker}")?;
+    }
+    Ok(())
+}
+
+/// Returns the formatted row where the current search match is, if there is one
+#[cfg(feature = "search")]
+fn current_match_row(pager: &PagerState) -> Option<usize> {
+    pager.search_term.as_ref()?;
+    pager.search_idx.iter().nth(pager.search_mark).copied()
+}
+
+#[cfg(not(feature = "search"))]
+#[allow(clippy::unnecessary_wraps)]
+const fn current_match_row(_pager: &PagerState) -> Option<usize> {
+    None
+}
+
+/// Gives the row at `idx` the style of the current search match if it is `current_match`
+///
+/// The current match is not styled while the highlights of search matches are hidden. Rows of
+/// lines without a match are dimmed in [search focus](PagerState::search_focus).
+#[cfg_attr(
+    not(feature = "search"),
+    allow(unused_variables, clippy::missing_const_for_fn)
+)]
+fn restyle_current_match<'a>(
+    pager: &PagerState,
+    current_match: Option<usize>,
+    idx: usize,
+    line: &'a str,
+) -> Cow<'a, str> {
+    #[cfg(feature = "search")]
+    if pager.search_focus
+        && pager.color_enabled
+        && pager.search_term.is_some()
+        && !crate::minus_core::search::line_has_match(pager, idx)
+    {
+        return Cow::Owned(crate::minus_core::search::dim_row(line));
+    }
+    // Rows past PagerState::max_search_matches are highlighted but not in PagerState::search_idx
+    #[cfg(feature = "search")]
+    if !pager.highlight_visible {
+        return if pager.search_idx.contains(&idx) || pager.max_search_matches.is_some() {
+            Cow::Owned(crate::minus_core::search::unstyle_matches(pager, line))
+        } else {
+            Cow::Borrowed(line)
+        };
+    }
+    #[cfg(feature = "search")]
+    if current_match == Some(idx) {
+        return Cow::Owned(crate::minus_core::search::restyle_current_match(
+            pager, line,
+        ));
+    }
+    Cow::Borrowed(line)
+}
+
+/// Paints the background of `line` with `color` across the entire width of the screen
+fn highlight_line(line: &str, cols: usize, color: Color) -> String {
+    let bg = SetBackgroundColor(color).to_string();
+    // Any reset inside the line would also reset the background, hence set it again after each
+    let line = line.replace("\x1b[0m", &format!("\x1b[0m{bg}"));
+    let padding = cols.saturating_sub(display_width(&line));
+    format!(
+        "{bg}{line}{pad}{reset}",
+        pad = " ".repeat(padding),
+        reset = Attribute::Reset
+    )
+}
+
+/// Puts a `>` in front of `line`, to highlight it without colors
+///
+/// The end of the line is cut off if it no longer fits on the screen with the marker.
+fn mark_row(p: &PagerState, line: &str) -> String {
+    let line = crate::truncate_str(
+        line,
+        p.content_cols().saturating_sub(1),
+        p.truncation_marker,
+        p.ambiguous_width,
+    );
+    format!(">{line}")
+}
+
+/// Write the lines that come after the lines currently displayed on the screen
+///
+/// This is used for writing the rest of the output to the main screen after the pager has quit.
+/// Hence, unlike [`write_lines`], the lines are not prefixed with a `\r`. If
+/// [`PagerState::exit_dump_scope`] is [`DumpScope::Entire`], all the lines are written instead.
+pub fn write_remaining_lines(out: &mut impl Write, pager: &PagerState) -> Result<(), MinusError> {
+    let start = match pager.exit_dump_scope {
+        // Reduce one row for prompt/messages and the status bar
+        DumpScope::Remaining => pager.upper_mark.saturating_add(pager.content_rows()),
+        DumpScope::Entire => 0,
+    };
+
+    for line in pager.get_flattened_lines_with_bounds(start, pager.num_lines()) {
+        writeln!(out, "{line}")?;
     }
+    out.flush()?;
     Ok(())
 }
 
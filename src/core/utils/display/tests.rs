@@ -2,7 +2,7 @@
 #![allow(clippy::cast_possible_truncation)]
 use super::*;
 
-use crate::{LineNumbers, PagerState};
+use crate::{LineNumbers, PagerState, TextDirection};
 use std::fmt::Write;
 
 // * In some places, where test lines are close to the row, 1 should be added
@@ -416,6 +416,89 @@ fn draw_wrapping_line_numbers() {
     assert!(written.contains(expected));
 }
 
+#[test]
+fn draw_truncated_line_numbers() {
+    let lines = (0..3)
+        .map(|l| format!("Line {l}: This is the line who is {l}"))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = lines;
+    pager.cols = 30;
+    pager.upper_mark = 1;
+    pager.line_numbers = LineNumbers::Enabled;
+    pager.line_wrapping = false;
+    pager.format_lines();
+
+    assert!(draw_full(&mut out, &mut pager).is_ok());
+
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let expected = "     2. Line 1: This is the l>\n\r     3. Line 2: This is the l>\n";
+    assert!(written.contains(expected));
+}
+
+#[test]
+fn draw_rtl_with_line_numbers() {
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "A line\nAnother line".to_string();
+    pager.cols = 30;
+    pager.line_numbers = LineNumbers::Enabled;
+    pager.text_direction = TextDirection::Rtl;
+    pager.format_lines();
+
+    assert!(draw_full(&mut out, &mut pager).is_ok());
+
+    // The text takes up the first 22 columns, followed by the line number
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let expected = format!("{:>22} 1.\n\r{:>22} 2.\n", "A line", "Another line");
+    assert!(written.contains(&expected));
+}
+
+#[test]
+fn draw_rtl_no_line_numbers() {
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "A line\nAnother line".to_string();
+    pager.cols = 30;
+    pager.text_direction = TextDirection::Rtl;
+    pager.format_lines();
+
+    assert!(draw_full(&mut out, &mut pager).is_ok());
+
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let expected = format!("{:>30}\n\r{:>30}\n", "A line", "Another line");
+    assert!(written.contains(&expected));
+}
+
+#[test]
+fn draw_highlight_row() {
+    use crossterm::style::Color;
+
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "L1\nL2\nL3".to_string();
+    pager.cols = 10;
+    pager.rows = 3;
+    pager.highlight_row = Some(0);
+    pager.highlight_color = Color::DarkBlue;
+    pager.format_lines();
+    pager.upper_mark = 1;
+
+    write_lines(&mut out, &mut pager).unwrap();
+
+    // The highlight stays at the top row while the text scrolls under it
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let expected = format!(
+        "\r{bg}L2        {reset}\n\rL3\n",
+        bg = SetBackgroundColor(Color::DarkBlue),
+        reset = Attribute::Reset
+    );
+    assert_eq!(written, expected);
+}
+
 #[test]
 fn draw_help_message() {
     let lines = "A line\nAnother line";
@@ -445,6 +528,67 @@ fn test_draw_no_overflow() {
         .contains(TEXT));
 }
 
+#[test]
+fn draw_end_of_buffer() {
+    use crossterm::style::Stylize;
+
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 5;
+    pager.lines = "L1\nL2\n".to_string();
+    pager.format_lines();
+    let marker = format!("\r{}\n", "~".dim());
+
+    // The two rows below the text are marked
+    let mut out = Vec::new();
+    draw_full(&mut out, &mut pager).unwrap();
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    assert!(written.contains(&format!("\rL1\n\rL2\n{marker}{marker}")));
+    assert_eq!(written.matches(&marker).count(), 2);
+
+    // Nothing is marked when the text fills the screen
+    pager.lines = "L1\nL2\nL3\nL4\n".to_string();
+    pager.format_lines();
+    let mut out = Vec::new();
+    draw_full(&mut out, &mut pager).unwrap();
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    assert!(!written.contains(&marker));
+
+    // Scrolling past the end leaves rows for markers
+    pager.scroll_past_end = true;
+    let mut out = Vec::new();
+    draw_for_change(&mut out, &mut pager, &mut 3).unwrap();
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    assert_eq!(written.matches(&marker).count(), 3);
+
+    // The rows stay blank without a character
+    pager.end_of_buffer_char = None;
+    pager.upper_mark = 3;
+    let mut out = Vec::new();
+    draw_full(&mut out, &mut pager).unwrap();
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    assert!(!written.contains('~'));
+}
+
+#[test]
+fn remaining_lines() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 4;
+    pager.lines = "L1\nL2\nL3\nL4\nL5\nL6\n".to_string();
+    pager.format_lines();
+    pager.upper_mark = 1;
+
+    let mut out = Vec::new();
+    write_remaining_lines(&mut out, &pager).unwrap();
+    // L2, L3 and L4 are displayed on the screen
+    assert_eq!("L5\nL6\n", String::from_utf8(out).unwrap());
+
+    // Nothing is left if the last line is already on the screen
+    let mut out = Vec::new();
+    pager.upper_mark = 3;
+    write_remaining_lines(&mut out, &pager).unwrap();
+    assert!(out.is_empty());
+}
+
 #[cfg(test)]
 mod draw_for_change_tests {
     use super::{draw_for_change, write_prompt};
@@ -489,7 +633,13 @@ mod draw_for_change_tests {
         for line in &ps.formatted_lines[9..12] {
             writeln!(res, "\r{line}").unwrap();
         }
-        write_prompt(&mut res, &ps.displayed_prompt, ps.rows as u16).unwrap();
+        write_prompt(
+            &mut res,
+            &ps.displayed_prompt,
+            &ps.prompt_style,
+            ps.rows as u16,
+        )
+        .unwrap();
 
         draw_for_change(&mut out, &mut ps, &mut 3).unwrap();
 
@@ -513,7 +663,13 @@ mod draw_for_change_tests {
         for line in &ps.formatted_lines[50..59] {
             writeln!(res, "\r{line}").unwrap();
         }
-        write_prompt(&mut res, &ps.displayed_prompt, ps.rows as u16).unwrap();
+        write_prompt(
+            &mut res,
+            &ps.displayed_prompt,
+            &ps.prompt_style,
+            ps.rows as u16,
+        )
+        .unwrap();
 
         draw_for_change(&mut out, &mut ps, &mut 50).unwrap();
 
@@ -541,11 +697,17 @@ mod draw_for_change_tests {
         ps.upper_mark = 80;
 
         let mut res = Vec::new();
-        write!(res, "{}{}", ScrollDown(9), MoveTo(0, 0),).unwrap();
+        write!(res, "{}{}", ScrollDown(9), MoveTo(0, 0)).unwrap();
         for line in &ps.formatted_lines[20..29] {
             writeln!(res, "\r{line}").unwrap();
         }
-        write_prompt(&mut res, &ps.displayed_prompt, ps.rows as u16).unwrap();
+        write_prompt(
+            &mut res,
+            &ps.displayed_prompt,
+            &ps.prompt_style,
+            ps.rows as u16,
+        )
+        .unwrap();
 
         draw_for_change(&mut out, &mut ps, &mut 20).unwrap();
 
@@ -562,11 +724,17 @@ mod draw_for_change_tests {
         ps.upper_mark = 60;
 
         let mut res = Vec::new();
-        write!(res, "{}{}", ScrollDown(9), MoveTo(0, 0),).unwrap();
+        write!(res, "{}{}", ScrollDown(9), MoveTo(0, 0)).unwrap();
         for line in &ps.formatted_lines[50..59] {
             writeln!(res, "\r{line}").unwrap();
         }
-        write_prompt(&mut res, &ps.displayed_prompt, ps.rows as u16).unwrap();
+        write_prompt(
+            &mut res,
+            &ps.displayed_prompt,
+            &ps.prompt_style,
+            ps.rows as u16,
+        )
+        .unwrap();
 
         draw_for_change(&mut out, &mut ps, &mut 50).unwrap();
 
@@ -575,4 +743,262 @@ mod draw_for_change_tests {
 
         assert_eq!(out, res);
     }
+
+    #[test]
+    fn scrolldown_with_status_bar() {
+        let mut ps = create_pager_state();
+        ps.status_bar = Some(Box::new(|_| "status".to_string()));
+        let mut out = Vec::with_capacity(100);
+
+        // Only 8 rows of text are shown, the old status bar and prompt are cleared
+        let mut res = Vec::new();
+        write!(
+            res,
+            "{}{}{}",
+            ScrollUp(3),
+            MoveTo(0, ps.rows as u16 - 5),
+            Clear(ClearType::FromCursorDown)
+        )
+        .unwrap();
+        for line in &ps.formatted_lines[8..11] {
+            writeln!(res, "\r{line}").unwrap();
+        }
+        super::write_status_bar(&mut res, &ps).unwrap();
+        write_prompt(
+            &mut res,
+            &ps.displayed_prompt,
+            &ps.prompt_style,
+            ps.rows as u16,
+        )
+        .unwrap();
+
+        draw_for_change(&mut out, &mut ps, &mut 3).unwrap();
+
+        assert_eq!(out, res);
+    }
+}
+
+#[test]
+fn draw_status_bar() {
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 5;
+    pager.cols = 10;
+    pager.lines = (0..10).fold(String::new(), |mut s, i| {
+        writeln!(s, "L{i}").unwrap();
+        s
+    });
+    pager.status_bar = Some(Box::new(|_| "status".to_string()));
+    pager.format_lines();
+    assert_eq!(pager.content_rows(), 3);
+    assert_eq!(pager.format_status_bar().as_deref(), Some("status    "));
+
+    // The status bar takes the row above the prompt, leaving three rows for the text
+    pager.upper_mark = 9;
+    draw_full(&mut out, &mut pager).unwrap();
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    assert!(written.contains("\rL7\n\rL8\n\rL9\n"));
+    assert!(written.contains(&format!("{}", MoveTo(0, 3))));
+    assert!(written.contains("status    "));
+    assert_eq!(pager.upper_mark, 7);
+    assert!(pager.is_at_bottom());
+    pager.upper_mark = 6;
+    assert!(!pager.is_at_bottom());
+
+    // A status bar that is too wide is cut off by its width
+    pager.status_bar = Some(Box::new(|_| "状態: 読み込み中".to_string()));
+    assert_eq!(pager.format_status_bar().as_deref(), Some("状態: 読> "));
+
+    // Without it, the text gets the row back
+    pager.status_bar = None;
+    assert_eq!(pager.content_rows(), 4);
+    assert_eq!(pager.format_status_bar(), None);
+}
+
+#[test]
+#[cfg(feature = "search")]
+fn draw_current_match() {
+    use crossterm::style::{ContentStyle, Stylize};
+
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "a test\nb test\nc test".to_string();
+    pager.search_term = Some(regex::Regex::new("te.t").unwrap());
+    pager.current_match_style = ContentStyle::new().bold();
+    pager.format_lines();
+    pager.search_mark = 1;
+
+    write_lines(&mut out, &mut pager).unwrap();
+
+    // Only the match at the search mark gets the style of the current match
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let expected = format!(
+        "\ra {rev}test{norev}\n\rb {bold}test{normal}\n\rc {rev}test{norev}\n",
+        rev = Attribute::Reverse,
+        norev = Attribute::NoReverse,
+        bold = Attribute::Bold,
+        normal = Attribute::NormalIntensity,
+    );
+    assert_eq!(written, expected);
+
+    // Moving to another match doesn't need the text to be formatted again
+    pager.search_mark = 2;
+    let mut out = Vec::new();
+    write_lines(&mut out, &mut pager).unwrap();
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    assert!(written.ends_with(&format!(
+        "\rc {}test{}\n",
+        Attribute::Bold,
+        Attribute::NormalIntensity
+    )));
+}
+
+#[test]
+fn prompt_style() {
+    let mut out = Vec::new();
+    // Reverse video by default
+    write_prompt(&mut out, "prompt", &ContentStyle::new().reverse(), 9).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("{}\r\x1b[7mprompt\x1b[0m", MoveTo(0, 9))
+    );
+
+    // A plain prompt has nothing to reset
+    let mut out = Vec::new();
+    write_prompt(&mut out, "prompt", &ContentStyle::new(), 9).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("{}\rprompt", MoveTo(0, 9))
+    );
+
+    // The style is reset after the prompt, even if the prompt itself changes it
+    let mut out = Vec::new();
+    let style = ContentStyle::new().blue().on_white();
+    write_prompt(&mut out, "\x1b[1mbold", &style, 9).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!(
+            "{}\r{}{}\x1b[1mbold\x1b[0m",
+            MoveTo(0, 9),
+            SetForegroundColor(Color::Blue),
+            SetBackgroundColor(Color::White)
+        )
+    );
+}
+
+#[test]
+fn draw_cursor() {
+    use crossterm::style::Color;
+
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "L1\nL2\nL3".to_string();
+    pager.cols = 10;
+    pager.rows = 3;
+    pager.highlight_row = Some(0);
+    pager.highlight_color = Color::DarkGrey;
+    pager.cursor_line = Some(1);
+    pager.cursor_color = Color::DarkBlue;
+    pager.format_lines();
+    pager.upper_mark = 1;
+
+    write_lines(&mut out, &mut pager).unwrap();
+
+    // The cursor is on the line of the text, and is drawn over the highlighted row
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let expected = format!(
+        "\r{bg}L2        {reset}\n\rL3\n",
+        bg = SetBackgroundColor(Color::DarkBlue),
+        reset = Attribute::Reset
+    );
+    assert_eq!(written, expected);
+}
+
+#[test]
+fn draw_selection() {
+    use crossterm::style::Color;
+
+    let mut out = Vec::new();
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "L1\nL2\nL3\nL4".to_string();
+    pager.cols = 4;
+    pager.rows = 5;
+    pager.cursor_line = Some(1);
+    pager.selection_anchor = Some(3);
+    pager.format_lines();
+
+    write_lines(&mut out, &mut pager).unwrap();
+
+    let written = String::from_utf8(out).expect("Should have written valid UTF-8");
+    let expected = format!(
+        "\rL1\n\r{cursor}L2  {reset}\n\r{selected}L3  {reset}\n\r{selected}L4  {reset}\n",
+        cursor = SetBackgroundColor(Color::DarkBlue),
+        selected = SetBackgroundColor(Color::DarkGrey),
+        reset = Attribute::Reset
+    );
+    assert_eq!(written, expected);
+}
+
+#[test]
+fn scrollbar_column() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 6;
+    pager.lines = "line\n".repeat(20);
+    pager.format_lines();
+    let column = |pager: &PagerState| {
+        super::scrollbar_column(pager)
+            .into_iter()
+            .collect::<String>()
+    };
+
+    assert_eq!(column(&pager), "█││││");
+    pager.upper_mark = 8;
+    assert_eq!(column(&pager), "││█││");
+    // The last page always reaches the end
+    pager.upper_mark = 15;
+    assert_eq!(column(&pager), "││││█");
+
+    // Rows with search matches are marked outside of the thumb
+    #[cfg(feature = "search")]
+    {
+        pager.search_idx = [0, 10, 19].iter().copied().collect();
+        assert_eq!(column(&pager), "-│-│█");
+    }
+
+    // The thumb fills the scrollbar if all of the text fits on the screen
+    pager.lines = "line\n".repeat(3);
+    pager.format_lines();
+    pager.upper_mark = 0;
+    assert_eq!(column(&pager), "█████");
+
+    // There is no scrollbar if there are no rows for the text
+    pager.lines = "line\n".repeat(20);
+    pager.format_lines();
+    pager.rows = 1;
+    assert_eq!(column(&pager), "");
+}
+
+#[test]
+fn max_content_width() {
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = format!("{}\n", "a".repeat(25));
+    pager.max_content_width = Some(10);
+    pager.format_lines();
+    // Wrapped to the limit rather than to the 80 columns of the screen
+    assert_eq!(pager.num_lines(), 3);
+    assert_eq!(pager.left_margin(), 35);
+
+    let mut out = Vec::new();
+    write_lines(&mut out, &mut pager).unwrap();
+    let margin = " ".repeat(35);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("\r{margin}aaaaaaaaaa\n\r{margin}aaaaaaaaaa\n\r{margin}aaaaa\n")
+    );
+
+    // Line numbers are outside of the limit
+    pager.line_numbers = LineNumbers::Enabled;
+    pager.format_lines();
+    assert_eq!(pager.num_lines(), 3);
+    assert_eq!(pager.content_cols(), 10 + pager.line_number_width());
 }
@@ -1,38 +1,49 @@
 //! Contains functions for dealing with setup, cleanup
 
 use crate::error::{CleanupError, MinusError, SetupError};
-use crossterm::{cursor, event, execute, queue, terminal, tty::IsTty};
+use crossterm::{cursor, event, execute, queue, style, terminal, tty::IsTty};
 use std::io;
 
 /// Setup the terminal
 ///
 /// It will
-/// - Switch the terminal's view to the [alternate screen]
-/// - Then enable [raw mode]
+/// - Switch the terminal's view to the [alternate screen] if `alternate_screen` is `true`
+/// - Then enable [raw mode] if `raw_mode` is `true`
+/// - Enable mouse capture if `mouse_capture` is `true`
 /// - Clear the entire screen and hide the cursor.
 ///
+/// `raw_mode` tells whether `out` is the terminal of the process. Otherwise, only the commands
+/// are written to `out` and the terminal of the process is left alone.
+///
 /// # Errors
-/// The function will return with an error if `stdout` is not a terminal. It will qlso fail
-/// if it cannot executo commands on the terminal See [`SetupError`].
+/// The function will return with an error if `raw_mode` is `true` and `stdout` is not a terminal.
+/// It will qlso fail if it cannot executo commands on the terminal See [`SetupError`].
 ///
 /// [alternate screen]: ../../../crossterm/terminal/index.html#alternate-screen
 /// [raw mode]: ../../../crossterm/terminal/index.html#raw-mode
 // This function should be kept close to `cleanup` to help ensure both are
 // doing the opposite of the other.
-pub fn setup(stdout: &io::Stdout) -> std::result::Result<(), SetupError> {
-    let mut out = stdout.lock();
-
-    if out.is_tty() {
-        Ok(())
-    } else {
-        Err(SetupError::InvalidTerminal)
-    }?;
+pub fn setup(
+    mut out: impl io::Write,
+    raw_mode: bool,
+    mouse_capture: bool,
+    alternate_screen: bool,
+) -> std::result::Result<(), SetupError> {
+    if raw_mode && !io::stdout().is_tty() {
+        return Err(SetupError::InvalidTerminal);
+    }
 
-    execute!(out, terminal::EnterAlternateScreen)
-        .map_err(|e| SetupError::AlternateScreen(e.into()))?;
-    terminal::enable_raw_mode().map_err(|e| SetupError::RawMode(e.into()))?;
-    execute!(out, event::EnableMouseCapture)
-        .map_err(|e| SetupError::EnableMouseCapture(e.into()))?;
+    if alternate_screen {
+        execute!(out, terminal::EnterAlternateScreen)
+            .map_err(|e| SetupError::AlternateScreen(e.into()))?;
+    }
+    if raw_mode {
+        terminal::enable_raw_mode().map_err(|e| SetupError::RawMode(e.into()))?;
+    }
+    if mouse_capture {
+        execute!(out, event::EnableMouseCapture)
+            .map_err(|e| SetupError::EnableMouseCapture(e.into()))?;
+    }
     execute!(out, cursor::Hide).map_err(|e| SetupError::HideCursor(e.into()))?;
     Ok(())
 }
@@ -42,8 +53,9 @@ pub fn setup(stdout: &io::Stdout) -> std::result::Result<(), SetupError> {
 /// The function will clean up the terminal and set it back to its original state,
 /// before the pager was setup and called.
 /// - First the cursor is displayed
-/// - [Raw mode] is disabled
-/// - Switch the terminal's view to the main screen
+/// - [Raw mode] is disabled if `raw_mode` is `true`
+/// - Switch the terminal's view to the main screen if `alternate_screen` is `true`. Otherwise
+///   the prompt is erased and the rest of the last screen is left in place.
 ///
 /// ## Errors
 /// The function will return with an error if it fails to do execute commands on the
@@ -54,15 +66,29 @@ pub fn cleanup(
     mut out: impl io::Write,
     es: &crate::ExitStrategy,
     cleanup_screen: bool,
+    raw_mode: bool,
+    alternate_screen: bool,
 ) -> std::result::Result<(), CleanupError> {
     if cleanup_screen {
         // Reverse order of setup.
         execute!(out, cursor::Show).map_err(|e| CleanupError::ShowCursor(e.into()))?;
-        terminal::disable_raw_mode().map_err(|e| CleanupError::DisableRawMode(e.into()))?;
+        if raw_mode {
+            terminal::disable_raw_mode().map_err(|e| CleanupError::DisableRawMode(e.into()))?;
+        }
         execute!(out, event::DisableMouseCapture)
             .map_err(|e| CleanupError::DisableMouseCapture(e.into()))?;
-        execute!(out, terminal::LeaveAlternateScreen)
-            .map_err(|e| CleanupError::LeaveAlternateScreen(e.into()))?;
+        if alternate_screen {
+            execute!(out, terminal::LeaveAlternateScreen)
+                .map_err(|e| CleanupError::LeaveAlternateScreen(e.into()))?;
+        } else {
+            // The cursor is on the prompt, which is not part of the text
+            execute!(
+                out,
+                style::Print('\r'),
+                terminal::Clear(terminal::ClearType::CurrentLine)
+            )
+            .map_err(|e| CleanupError::ClearPrompt(e.into()))?;
+        }
     }
 
     if *es == crate::ExitStrategy::ProcessQuit {
@@ -72,6 +98,30 @@ pub fn cleanup(
     }
 }
 
+/// Enables or disables reporting of mouse events by the terminal
+///
+/// While mouse capture is enabled, the terminal's native text selection does not work.
+#[cfg(feature = "dynamic_output")]
+pub fn set_mouse_capture(out: &mut impl io::Write, enabled: bool) -> Result<(), MinusError> {
+    if enabled {
+        execute!(out, event::EnableMouseCapture)?;
+    } else {
+        execute!(out, event::DisableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Switches between the alternate screen and the main screen of the terminal
+#[cfg(feature = "dynamic_output")]
+pub fn set_alternate_screen(out: &mut impl io::Write, enabled: bool) -> Result<(), MinusError> {
+    if enabled {
+        execute!(out, terminal::EnterAlternateScreen)?;
+    } else {
+        execute!(out, terminal::LeaveAlternateScreen)?;
+    }
+    Ok(())
+}
+
 /// Moves the terminal cursor to given x, y coordinates
 ///
 /// The `flush` parameter will immidiately flush the buffer if it is set to `true`
@@ -1,6 +1,10 @@
+use std::borrow::Cow;
+#[cfg(feature = "search")]
 use std::collections::BTreeSet;
 
-use crate::PagerState;
+use crate::{AmbiguousWidth, PagerState};
+use crossterm::style::{ContentStyle, StyledContent};
+use unicode_width::UnicodeWidthChar;
 
 pub enum AppendStyle {
     PartialUpdate((Vec<String>, usize)),
@@ -10,19 +14,573 @@ pub enum AppendStyle {
 pub struct AppendProps {
     pub lines: Vec<String>,
     pub num_unterminated: usize,
+    /// The first row of each formatted line, see [`PagerState::line_rows`]
+    pub line_rows: Vec<usize>,
     #[cfg(feature = "search")]
     pub append_search_idx: BTreeSet<usize>,
 }
 
+/// Keeps track of the rows of [`PagerState::formatted_lines`] that have not been formatted yet
+///
+/// With lazy formatting, every row starts out as an empty placeholder and is only formatted once
+/// it is about to be displayed. Rows past the end of the cache are always formatted, as text that
+/// is appended later is formatted right away.
+#[derive(Default)]
+pub struct FormatCache {
+    /// `true` for every row that is still a placeholder
+    pending: Vec<bool>,
+    /// Number of digits of the last line number at the time the placeholders were made
+    pub len_line_number: usize,
+}
+
+impl FormatCache {
+    /// Marks the first `rows` rows as placeholders
+    pub fn reset(&mut self, rows: usize, len_line_number: usize) {
+        self.pending = vec![true; rows];
+        self.len_line_number = len_line_number;
+    }
+
+    /// Forgets about all placeholders, for when everything has been formatted
+    pub fn clear(&mut self) {
+        self.pending = Vec::new();
+    }
+
+    /// Forgets about the placeholders from `rows` onwards, for when those rows are replaced
+    pub fn truncate(&mut self, rows: usize) {
+        self.pending.truncate(rows);
+    }
+
+    /// Returns the number of rows tracked by the cache
+    pub fn rows(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns `true` if `row` is a placeholder and marks it as formatted
+    pub fn take(&mut self, row: usize) -> bool {
+        self.pending
+            .get_mut(row)
+            .map_or(false, |pending| std::mem::replace(pending, false))
+    }
+
+    /// Returns `true` if any of the rows in `rows` is a placeholder
+    pub fn any_pending(&self, rows: std::ops::Range<usize>) -> bool {
+        self.pending
+            .get(rows.start.min(self.rows())..rows.end.min(self.rows()))
+            .map_or(false, |pending| pending.contains(&true))
+    }
+}
+
+/// Decodes as much of `buf` as possible into a [`String`]
+///
+/// Invalid byte sequences are replaced by `U+FFFD REPLACEMENT CHARACTER`. If `buf` ends with an
+/// incomplete multibyte character, those bytes are left inside `buf` so that they can be completed
+/// by the bytes that come next. Everything else is drained from `buf`.
+pub fn decode_utf8_lossy(buf: &mut Vec<u8>) -> String {
+    let mut text = String::with_capacity(buf.len());
+    let mut consumed = 0;
+
+    loop {
+        match std::str::from_utf8(&buf[consumed..]) {
+            Ok(valid) => {
+                text.push_str(valid);
+                consumed = buf.len();
+                break;
+            }
+            Err(e) => {
+                let valid_up_to = consumed + e.valid_up_to();
+                // This range has just been validated by from_utf8, hence the unwrap can never fail
+                text.push_str(std::str::from_utf8(&buf[consumed..valid_up_to]).unwrap());
+                if let Some(invalid_len) = e.error_len() {
+                    text.push(char::REPLACEMENT_CHARACTER);
+                    consumed = valid_up_to + invalid_len;
+                } else {
+                    // The sequence is incomplete, wait for the remaining bytes
+                    consumed = valid_up_to;
+                    break;
+                }
+            }
+        }
+    }
+
+    buf.drain(..consumed);
+    text
+}
+
+/// Replaces non-printable control characters in `text` so that they are visible
+///
+/// Each control character is replaced by `placeholder` if one is given. Otherwise it is written in
+/// caret notation, for example `NUL` becomes `^@` and `DEL` becomes `^?`. Newlines, carriage
+/// returns, tabs and the escape character (needed for ANSI sequences) are kept as is.
+pub fn render_control_chars(text: String, placeholder: Option<char>) -> String {
+    let is_rendered = |c: char| c.is_ascii_control() && !matches!(c, '\n' | '\r' | '\t' | '\x1b');
+    if !text.contains(is_rendered) {
+        return text;
+    }
+
+    let mut rendered = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        if !is_rendered(c) {
+            rendered.push(c);
+        } else if let Some(p) = placeholder {
+            rendered.push(p);
+        } else {
+            rendered.push('^');
+            // Flipping bit 6 maps 0x00..=0x1f to '@'..='_' and 0x7f to '?'
+            rendered.push(char::from(c as u8 ^ 0x40));
+        }
+    }
+    rendered
+}
+
+/// Makes tabs and trailing spaces in `line` visible
+///
+/// Tabs are shown as `→` and spaces at the end of the line as `·`, both dimmed so that they
+/// stand out from the text. Each marker takes up a single column on the screen.
+pub fn render_whitespace(line: &str) -> Cow<'_, str> {
+    let text_end = line.trim_end_matches([' ', '\t']).len();
+    if text_end == line.len() && !line.contains('\t') {
+        return Cow::Borrowed(line);
+    }
+
+    let mut rendered = String::with_capacity(line.len() + 16);
+    let mut dimmed = false;
+    for (idx, c) in line.char_indices() {
+        let marker = match c {
+            '\t' => Some('→'),
+            ' ' if idx >= text_end => Some('·'),
+            _ => None,
+        };
+        // Runs of markers share a single pair of escapes
+        if marker.is_some() != dimmed {
+            dimmed = !dimmed;
+            rendered.push_str(if dimmed { "\x1b[2m" } else { "\x1b[22m" });
+        }
+        rendered.push(marker.unwrap_or(c));
+    }
+    if dimmed {
+        rendered.push_str("\x1b[22m");
+    }
+    Cow::Owned(rendered)
+}
+
+/// Writes the text after each carriage return in `line` over the start of the line
+///
+/// This gives the line as a terminal would show it after printing it. A wide character that is
+/// partly written over leaves a space behind. Escape sequences are kept in front of the
+/// character they came before, so styles carry over to the text written over it.
+pub fn overwrite_carriage_returns(line: &str) -> Cow<'_, str> {
+    if !line.contains('\r') {
+        return Cow::Borrowed(line);
+    }
+    Cow::Owned(
+        with_hidden_hyperlinks(line, |line| vec![overwrite_plain(line)])
+            .pop()
+            .unwrap_or_default(),
+    )
+}
+
+/// [`overwrite_carriage_returns`] for lines without hyperlinks
+fn overwrite_plain(line: &str) -> String {
+    // Each column holds the escape sequences written before it and its character. The second
+    // column of a wide character has no character
+    let mut cells: Vec<(String, String)> = Vec::new();
+    let mut escapes = String::new();
+    let mut col: usize = 0;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\r' {
+            col = 0;
+            continue;
+        }
+        if ch == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            escapes.push_str("\x1b[");
+            for esc_ch in chars.by_ref() {
+                escapes.push(esc_ch);
+                if ('\x40'..='\x7e').contains(&esc_ch) {
+                    break;
+                }
+            }
+            continue;
+        }
+        let width = textwrap::core::display_width(ch.encode_utf8(&mut [0; 4]));
+        if width == 0 {
+            // Combining characters belong to the character before them
+            match col.checked_sub(1).and_then(|prev| cells.get_mut(prev)) {
+                Some(cell) => cell.1.push(ch),
+                None => escapes.push(ch),
+            }
+            continue;
+        }
+        // Wide characters that are only partly written over are replaced by spaces
+        if col > 0 && cells.get(col).map_or(false, |cell| cell.1.is_empty()) {
+            cells[col - 1].1 = " ".to_string();
+        }
+        if let Some(next) = cells.get_mut(col + width) {
+            if next.1.is_empty() {
+                next.1 = " ".to_string();
+            }
+        }
+        if cells.len() < col + width {
+            cells.resize_with(col + width, Default::default);
+        }
+        cells[col].0.push_str(&escapes);
+        escapes.clear();
+        cells[col].1 = ch.to_string();
+        for cell in &mut cells[col + 1..col + width] {
+            cell.1.clear();
+        }
+        col += width;
+    }
+
+    // Escapes at the end still apply to the rest of the line, like a reset of the style
+    if let Some(cell) = cells.get_mut(col) {
+        cell.0.insert_str(0, &escapes);
+        escapes.clear();
+    }
+    let mut overwritten = String::with_capacity(line.len());
+    for (cell_escapes, ch) in cells {
+        overwritten.push_str(&cell_escapes);
+        overwritten.push_str(&ch);
+    }
+    overwritten.push_str(&escapes);
+    overwritten
+}
+
+/// Splits `text` into the parts that each become a single line when it is reflowed
+///
+/// Each part is either a run of consecutive non-blank lines or a single blank line, along with
+/// the newline at its end.
+pub fn paragraph_units(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                if end == 0 {
+                    end = line.len();
+                }
+                break;
+            }
+            end += line.len();
+        }
+        let (unit, tail) = rest.split_at(end);
+        rest = tail;
+        Some(unit)
+    })
+}
+
+/// Returns the byte offset at which the last of the [`paragraph_units`] of `text` starts
+///
+/// Only the lines after the last blank line of `text` are looked at.
+pub fn last_paragraph_start(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut start = text.len();
+    while start > 0 {
+        let line_start = bytes[..start - 1]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |idx| idx + 1);
+        if text[line_start..start].trim().is_empty() {
+            // A blank line is a unit of its own, after the paragraph before it
+            return if start == text.len() {
+                line_start
+            } else {
+                start
+            };
+        }
+        start = line_start;
+    }
+    0
+}
+
+/// Joins the lines of each paragraph in `text` into a single line
+///
+/// Blank lines separate paragraphs and are kept. The lines of a paragraph are joined with a single
+/// space, only the indentation of its first line is kept.
+pub fn reflow(text: &str) -> String {
+    let mut reflowed = String::with_capacity(text.len());
+    for unit in paragraph_units(text) {
+        let mut lines = unit.lines();
+        if let Some(first) = lines.next() {
+            reflowed.push_str(first.trim_end());
+        }
+        for line in lines {
+            reflowed.push(' ');
+            reflowed.push_str(line.trim());
+        }
+        if unit.ends_with('\n') {
+            reflowed.push('\n');
+        }
+    }
+    reflowed
+}
+
+/// Turns `spans` of styled text into a line of text with ANSI escape sequences
+///
+/// Each span is styled on its own and resets its style at its end. A newline in a span starts a
+/// new line, on which the rest of the span keeps its style. The returned text ends with a
+/// newline.
+///
+/// The text of the spans is taken literally. An escape character in it is written in caret
+/// notation as `^[`, so that it can't start an escape sequence of its own.
+pub fn styled_line(spans: &[(ContentStyle, String)]) -> String {
+    let mut line = String::new();
+    for (style, text) in spans {
+        for (idx, part) in text.split('\n').enumerate() {
+            if idx > 0 {
+                line.push('\n');
+            }
+            if !part.is_empty() {
+                let part = if part.contains('\x1b') {
+                    Cow::Owned(part.replace('\x1b', "^["))
+                } else {
+                    Cow::Borrowed(part)
+                };
+                line.push_str(&StyledContent::new(*style, part).to_string());
+            }
+        }
+    }
+    line.push('\n');
+    line
+}
+
+/// Removes the escape sequences that style text, like `\x1b[31m`, from `text`
+///
+/// Other escape sequences, like hyperlinks, are kept.
+pub fn strip_sgr(text: &str) -> Cow<'_, str> {
+    if !text.contains("\x1b[") {
+        return Cow::Borrowed(text);
+    }
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("\x1b[") {
+        stripped.push_str(&rest[..start]);
+        let seq = &rest[start + 2..];
+        // Control sequences end with a byte in the range @ to ~, styles end with m
+        let Some(end) = seq.find(|c| ('@'..='~').contains(&c)) else {
+            stripped.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        if seq.as_bytes()[end] != b'm' {
+            stripped.push_str(&rest[start..=start + 2 + end]);
+        }
+        rest = &seq[end + 1..];
+    }
+    stripped.push_str(rest);
+    Cow::Owned(stripped)
+}
+
+/// Removes all ANSI escape sequences from `text`
+///
+/// This covers control sequences like `\x1b[31m` as well as operating system commands like
+/// hyperlinks, which end with either `BEL` or `\x1b\\`.
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Control sequence, ends with a byte in the range @ to ~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // Operating system command, ends with BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next() == Some('\\')) {
+                        break;
+                    }
+                }
+            }
+            // Other escapes are two characters long
+            _ => {}
+        }
+    }
+    stripped
+}
+
+/// Start of an OSC 8 hyperlink sequence
+const HYPERLINK_START: &str = "\x1b]8;";
+/// Sequence that ends the current hyperlink
+const HYPERLINK_END: &str = "\x1b]8;;\x1b\\";
+/// Stands in for a hyperlink sequence while a line is processed by code that only knows about
+/// control sequences
+///
+/// This is a zero-width control sequence which is never written to the terminal, as it is always
+/// replaced by the original hyperlink sequence again.
+const HYPERLINK_PLACEHOLDER: &str = "\x1b[?8$~";
+
+/// Returns the length of the hyperlink sequence at the start of `text` and the URI it links to
+///
+/// A hyperlink sequence looks like `ESC ] 8 ; params ; URI ST`, where `ST` is either `BEL` or
+/// `ESC \`. An empty URI ends the current hyperlink. Returns `None` if `text` does not start
+/// with a complete hyperlink sequence.
+fn parse_hyperlink(text: &str) -> Option<(usize, &str)> {
+    let rest = text.strip_prefix(HYPERLINK_START)?;
+    let end = rest.find(['\x07', '\x1b'])?;
+    let terminator_len = if rest[end..].starts_with('\x07') {
+        1
+    } else if rest[end..].starts_with("\x1b\\") {
+        2
+    } else {
+        return None;
+    };
+    // The parameters come before the URI
+    let (_, uri) = rest[..end].split_once(';')?;
+    Some((HYPERLINK_START.len() + end + terminator_len, uri))
+}
+
+/// Replaces all hyperlink sequences in `line` with [`HYPERLINK_PLACEHOLDER`]
+///
+/// Returns the new line and the sequences that were replaced, in order.
+fn hide_hyperlinks(line: &str) -> (String, Vec<&str>) {
+    let mut hidden = String::with_capacity(line.len());
+    let mut links = Vec::new();
+    let mut rest = line;
+
+    while let Some(idx) = rest.find(HYPERLINK_START) {
+        hidden.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        if let Some((len, _)) = parse_hyperlink(rest) {
+            links.push(&rest[..len]);
+            hidden.push_str(HYPERLINK_PLACEHOLDER);
+            rest = &rest[len..];
+        } else {
+            // An incomplete sequence is left as is
+            hidden.push_str(HYPERLINK_START);
+            rest = &rest[HYPERLINK_START.len()..];
+        }
+    }
+    hidden.push_str(rest);
+    (hidden, links)
+}
+
+/// Runs `f` on `line` with its hyperlinks hidden and puts them back into the rows it returns
+///
+/// Wrapping and cutting lines is done by code that only skips control sequences like
+/// `\x1b[31m`, which would count the URI of a hyperlink as text. `f` gets a line where the
+/// hyperlinks are replaced by a zero-width control sequence instead. It must keep those in
+/// order, but may drop any of them at the end. A hyperlink that spans several rows is ended at
+/// the end of each row and started again at the beginning of the next, so that every row can be
+/// drawn on its own.
+pub fn with_hidden_hyperlinks(line: &str, f: impl FnOnce(&str) -> Vec<String>) -> Vec<String> {
+    if !line.contains(HYPERLINK_START) {
+        return f(line);
+    }
+    let (hidden, links) = hide_hyperlinks(line);
+    let mut links = links.into_iter();
+    let mut open = None;
+
+    f(&hidden)
+        .iter()
+        .map(|row| {
+            let mut restored = String::with_capacity(row.len());
+            if let Some(seq) = open {
+                restored.push_str(seq);
+            }
+            let mut parts = row.split(HYPERLINK_PLACEHOLDER);
+            restored.push_str(parts.next().unwrap_or_default());
+            for part in parts {
+                let seq = links.next().unwrap_or_default();
+                restored.push_str(seq);
+                open = parse_hyperlink(seq)
+                    .filter(|(_, uri)| !uri.is_empty())
+                    .map(|_| seq);
+                restored.push_str(part);
+            }
+            if open.is_some() {
+                restored.push_str(HYPERLINK_END);
+            }
+            restored
+        })
+        .collect()
+}
+
+/// Returns the number of columns that `text` takes up on the screen
+///
+/// This is [`textwrap::core::display_width`], except that hyperlink sequences are skipped too.
+pub fn display_width(text: &str) -> usize {
+    if text.contains(HYPERLINK_START) {
+        textwrap::core::display_width(&hide_hyperlinks(text).0)
+    } else {
+        textwrap::core::display_width(text)
+    }
+}
+
+/// Returns the number of columns that `text` takes up on the screen, like [`display_width`], with
+/// characters of ambiguous width measured as `ambiguous`
+pub fn display_width_as(text: &str, ambiguous: AmbiguousWidth) -> usize {
+    match ambiguous {
+        AmbiguousWidth::Narrow => display_width(text),
+        AmbiguousWidth::Wide => strip_ansi(text)
+            .chars()
+            .map(|ch| char_width(ch, ambiguous))
+            .sum(),
+    }
+}
+
+/// Returns the number of columns that `ch` takes up on the screen, with characters of ambiguous
+/// width measured as `ambiguous`
+///
+/// Control characters don't take up any column.
+pub fn char_width(ch: char, ambiguous: AmbiguousWidth) -> usize {
+    match ambiguous {
+        AmbiguousWidth::Narrow => ch.width(),
+        AmbiguousWidth::Wide => ch.width_cjk(),
+    }
+    .unwrap_or(0)
+}
+
+/// Returns the URI of the hyperlink that is displayed at `column` of a formatted row
+pub fn hyperlink_at(row: &str, column: usize) -> Option<String> {
+    let mut width = 0;
+    let mut uri = None;
+    let mut rest = row;
+
+    while let Some(ch) = rest.chars().next() {
+        if let Some((len, link)) = parse_hyperlink(rest) {
+            uri = (!link.is_empty()).then_some(link);
+            rest = &rest[len..];
+            continue;
+        }
+        if let Some(seq) = rest.strip_prefix("\x1b[") {
+            // Control sequence, ends with a byte in the range @ to ~
+            rest = seq
+                .find(|c| ('@'..='~').contains(&c))
+                .map_or("", |end| &seq[end + 1..]);
+            continue;
+        }
+        width += textwrap::core::display_width(ch.encode_utf8(&mut [0; 4]));
+        if column < width {
+            return uri.map(ToString::to_string);
+        }
+        rest = &rest[ch.len_utf8()..];
+    }
+    None
+}
+
 /// Makes the text that will be displayed and appended it to [`self.formatted_lines`]
 ///
 /// - The first output value is the actual text rows that needs to be appended. This is wrapped
-///     based on the available columns
+///   based on the available columns
 /// - The second value is the number of rows that should be truncated from [`self.formatted_lines`]
-///     before appending this line. This will be 0 if the given `text` is to be appended to
-///     [`self.formatted_lines`] but will be `>0` if the given text is actually part of the
-///     last appended line. This function determines this by checking whether self.lines ends with
-///     `\n` after appending the text
+///   before appending this line. This will be 0 if the given `text` is to be appended to
+///   [`self.formatted_lines`] but will be `>0` if the given text is actually part of the
+///   last appended line. This function determines this by checking whether self.lines ends with
+///   `\n` after appending the text
 pub fn make_append_str(
     p: &PagerState,
     text: &str,
@@ -30,107 +588,280 @@ pub fn make_append_str(
     to_skip: usize,
     len_line_number: usize,
 ) -> AppendProps {
-    let append;
-    let to_format = if let Some(attached_text) = attachment {
-        let mut s = String::with_capacity(text.len() + attached_text.len());
-        s.push_str(&attached_text);
-        s.push_str(text);
-        append = false;
-        s
+    let append = attachment.is_none();
+    let to_format = attachment.map_or_else(
+        || text.to_string(),
+        |attached_text| {
+            let mut s = String::with_capacity(text.len() + attached_text.len());
+            s.push_str(&attached_text);
+            s.push_str(text);
+            s
+        },
+    );
+
+    // If append is true, we take only the given text for formatting
+    // else we also take the last line of self.lines for formatting. This is because we nned to
+    // format the entire line rathar than just this part. Hence the first formatted line is either
+    // the line after the old text or the last line of the old text
+    let first_idx = if append {
+        to_skip
     } else {
-        append = true;
-        text.to_string()
+        to_skip.saturating_sub(1)
     };
 
     // This will get filled if there is an ongoing search. We just need to append it to
     // self.search_idx at the end
     #[cfg(feature = "search")]
     let mut append_search_idx = BTreeSet::new();
-
-    // If append is true, we take only the given text for formatting
-    // else we also take the last line of self.lines for formatting. This is because we nned to
-    // format the entire line rathar than just this part
-    let to_format_len = to_format.lines().count();
-    let lines = to_format
-        .lines()
-        .enumerate()
-        .map(|(idx, s)| (idx, s.to_string()))
-        .collect::<Vec<(usize, String)>>();
+    // Index of the row in self.formatted_lines that the line being formatted will start at
+    let mut formatted_idx = p.formatted_lines.len() - p.unterminated;
+    let mut line_rows = Vec::new();
 
     let mut fmtl = Vec::with_capacity(256);
+    let mut last_line_rows = 0;
 
-    // First line
-    let mut first_line = p.formatted_line(
-        // TODO: Remove unwrap from here
-        &lines.first().unwrap().1,
-        len_line_number,
-        to_skip.saturating_sub(1),
-        #[cfg(feature = "search")]
-        if append {
-            to_skip
-        } else {
-            to_skip.saturating_sub(1)
-        },
-        #[cfg(feature = "search")]
-        &mut append_search_idx,
-    );
-
-    // Format the last line, only if first line and last line are different. We can check this
-    // by seeing whether to_format_len is greater than 1
-    let last_line = if to_format_len > 1 {
-        Some(p.formatted_line(
-            &lines.last().unwrap().1,
+    for (idx, line) in to_format.lines().enumerate() {
+        let mut rows = p.formatted_line(
+            line,
             len_line_number,
-            to_format_len + to_skip.saturating_sub(1),
+            first_idx + idx,
             #[cfg(feature = "search")]
-            to_skip,
+            formatted_idx,
             #[cfg(feature = "search")]
             &mut append_search_idx,
-        ))
-    } else {
-        None
-    };
-
-    // Format all other lines except the first and last line
-    let mut mid_lines = lines
-        .iter()
-        .skip(1)
-        .take(lines.len().saturating_sub(2))
-        .flat_map(|(idx, line)| {
-            p.formatted_line(
-                line,
-                len_line_number,
-                idx + to_skip.saturating_sub(1),
-                #[cfg(feature = "search")]
-                to_skip,
-                #[cfg(feature = "search")]
-                &mut append_search_idx,
-            )
-        })
-        .collect::<Vec<String>>();
+            #[cfg(feature = "search")]
+            None,
+        );
+        line_rows.push(formatted_idx);
+        formatted_idx += rows.len();
+        last_line_rows = rows.len();
+        fmtl.append(&mut rows);
+    }
 
     let unterminated = if text.ends_with('\n') {
         0
-    } else if to_format_len > 1 {
-        last_line.as_ref().unwrap().len()
     } else {
-        first_line.len()
+        last_line_rows
     };
 
-    fmtl.append(&mut first_line);
-    fmtl.append(&mut mid_lines);
-    if let Some(mut ll) = last_line {
-        fmtl.append(&mut ll);
-    }
-
     AppendProps {
         lines: fmtl,
         num_unterminated: unterminated,
+        line_rows,
         #[cfg(feature = "search")]
         append_search_idx,
     }
 }
 
+#[cfg(test)]
+mod strip_ansi {
+    use super::strip_ansi;
+
+    #[test]
+    fn removes_escapes() {
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert_eq!(strip_ansi("\x1b[1;31mred\x1b[0m text"), "red text");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07"),
+            "link"
+        );
+    }
+}
+
+#[cfg(test)]
+mod overwrite_carriage_returns {
+    use super::overwrite_carriage_returns;
+
+    #[test]
+    fn overwrites_start_of_line() {
+        assert_eq!(overwrite_carriage_returns("plain"), "plain");
+        assert_eq!(overwrite_carriage_returns("50%\r100%"), "100%");
+        assert_eq!(overwrite_carriage_returns("100%\r50%"), "50%%");
+        assert_eq!(overwrite_carriage_returns("done\r"), "done");
+        assert_eq!(overwrite_carriage_returns("\r\rab\rc"), "cb");
+    }
+
+    #[test]
+    fn keeps_escapes() {
+        // The style that was active carries over to the text written over it
+        assert_eq!(
+            overwrite_carriage_returns("\x1b[31m50%\r100%\x1b[0m"),
+            "\x1b[31m100%\x1b[0m"
+        );
+        assert_eq!(
+            overwrite_carriage_returns("ab\r\x1b[1mc\x1b[0m"),
+            "\x1b[1mc\x1b[0mb"
+        );
+    }
+
+    #[test]
+    fn wide_chars() {
+        // Half of a wide character can't be displayed, so a space takes its place
+        assert_eq!(overwrite_carriage_returns("漢字\ra"), "a 字");
+        assert_eq!(overwrite_carriage_returns("ab\r漢"), "漢");
+        assert_eq!(overwrite_carriage_returns("a漢\r字"), "字 ");
+    }
+}
+
+#[cfg(test)]
+mod strip_sgr {
+    use super::strip_sgr;
+
+    #[test]
+    fn keeps_other_escapes() {
+        assert_eq!(strip_sgr("plain"), "plain");
+        assert_eq!(
+            strip_sgr("\x1b[1;31mred\x1b[0m \x1b[2Ktext"),
+            "red \x1b[2Ktext"
+        );
+        assert_eq!(
+            strip_sgr("\x1b]8;;https://example.com\x1b\\\x1b[4mlink\x1b[24m\x1b]8;;\x1b\\"),
+            "\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"
+        );
+        // An unfinished sequence is left alone
+        assert_eq!(strip_sgr("end\x1b[3"), "end\x1b[3");
+    }
+}
+
+#[cfg(test)]
+mod styled_line {
+    use super::styled_line;
+    use crossterm::style::{ContentStyle, Stylize};
+
+    #[test]
+    fn styles_spans() {
+        let spans = vec![
+            (ContentStyle::new().red(), "red".to_string()),
+            (ContentStyle::new(), " plain ".to_string()),
+            (ContentStyle::new().bold(), "bold\nnext".to_string()),
+        ];
+        assert_eq!(
+            styled_line(&spans),
+            "\x1b[38;5;9mred\x1b[39m plain \x1b[1mbold\x1b[0m\n\x1b[1mnext\x1b[0m\n"
+        );
+        assert_eq!(styled_line(&[]), "\n");
+    }
+}
+
+#[cfg(test)]
+mod hyperlinks {
+    use super::{display_width, hyperlink_at, with_hidden_hyperlinks};
+
+    const LINK: &str = "\x1b]8;;https://example.com\x1b\\";
+    const END: &str = "\x1b]8;;\x1b\\";
+
+    #[test]
+    fn width() {
+        assert_eq!(display_width(&format!("see {LINK}here{END}")), 8);
+        // BEL terminates the sequence as well
+        assert_eq!(
+            display_width("\x1b]8;id=1;https://example.com\x07here\x1b]8;;\x07"),
+            4
+        );
+        // Incomplete sequences are not skipped
+        assert_eq!(display_width("\x1b]8;;abc"), 6);
+    }
+
+    #[test]
+    fn wrap() {
+        let line = format!("see {LINK}this link{END} here");
+        let rows = with_hidden_hyperlinks(&line, |l| {
+            textwrap::wrap(l, 9)
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        });
+        // The link is ended and started again where it is wrapped
+        assert_eq!(
+            rows,
+            vec![
+                format!("see {LINK}this{END}"),
+                format!("{LINK}link{END} here"),
+            ]
+        );
+    }
+
+    #[test]
+    fn link_under_column() {
+        let row = format!("\x1b[1mab\x1b[0m {LINK}link{END} cd");
+        assert_eq!(hyperlink_at(&row, 2), None);
+        assert_eq!(
+            hyperlink_at(&row, 3),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(
+            hyperlink_at(&row, 6),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(hyperlink_at(&row, 7), None);
+        assert_eq!(hyperlink_at(&row, 20), None);
+    }
+}
+
+#[cfg(test)]
+mod render_whitespace {
+    use super::render_whitespace;
+
+    #[test]
+    fn markers() {
+        assert_eq!(render_whitespace("no markers here"), "no markers here");
+        assert_eq!(
+            render_whitespace("a\tb  "),
+            "a\x1b[2m→\x1b[22mb\x1b[2m··\x1b[22m"
+        );
+        // Spaces before a trailing tab are trailing as well
+        assert_eq!(render_whitespace("a \t"), "a\x1b[2m·→\x1b[22m");
+        // Markers take up a single column each, even the ones for tabs
+        assert_eq!(
+            textwrap::core::display_width(&render_whitespace("a\tb  ")),
+            5
+        );
+    }
+}
+
+#[cfg(test)]
+mod reflow {
+    use super::{last_paragraph_start, paragraph_units, reflow};
+
+    #[test]
+    fn joins_paragraphs() {
+        let text = "  A paragraph that\nwas wrapped\n  by hand.\n\n\nNext one\ncontinues";
+        assert_eq!(
+            paragraph_units(text).collect::<Vec<_>>(),
+            vec![
+                "  A paragraph that\nwas wrapped\n  by hand.\n",
+                "\n",
+                "\n",
+                "Next one\ncontinues"
+            ]
+        );
+        assert_eq!(
+            reflow(text),
+            "  A paragraph that was wrapped by hand.\n\n\nNext one continues"
+        );
+    }
+
+    #[test]
+    fn last_paragraph() {
+        for text in [
+            "",
+            "One line",
+            "A paragraph\nof two lines\n",
+            "First\n\nSecond\nparagraph",
+            "First\n\n",
+            "First\n  ",
+            "Ünicode\n\nparagraph ü",
+        ] {
+            let last = paragraph_units(text).last().unwrap_or_default();
+            assert_eq!(
+                last_paragraph_start(text),
+                text.len() - last.len(),
+                "{text:?}"
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod unterminated {
     use super::make_append_str;
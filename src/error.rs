@@ -30,6 +30,27 @@ pub struct RegexError(
     #[from] regex::Error,
 );
 
+/// A key or mouse event description could not be parsed
+///
+/// See [`keydefs`](crate::input::definitions::keydefs) and
+/// [`mousedefs`](crate::input::definitions::mousedefs) for the format of descriptions. Each
+/// variant holds the invalid part followed by the whole description.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum DescriptionError {
+    #[error("Invalid modifier '{0}' in description: '{1}'")]
+    InvalidModifier(String, String),
+
+    #[error("Repeated modifier '{0}' in description: '{1}'")]
+    RepeatedModifier(String, String),
+
+    #[error("Invalid key '{0}' in key description: '{1}'")]
+    InvalidKey(String, String),
+
+    #[error("Invalid mouse action '{0}' in mouse description: '{1}'")]
+    InvalidMouseAction(String, String),
+}
+
 /// Errors that can occur during setup
 #[derive(Debug, thiserror::Error)]
 #[allow(clippy::module_name_repetitions)]
@@ -66,6 +87,9 @@ pub enum CleanupError {
     #[error("Failed to disable raw mode")]
     DisableRawMode(TermError),
 
+    #[error("Failed to clear the prompt")]
+    ClearPrompt(TermError),
+
     #[error("Failed to switch back to main screen")]
     LeaveAlternateScreen(TermError),
 }
@@ -98,15 +122,15 @@ pub enum MinusError {
     #[error("Failed to convert between some primitives")]
     Conversion,
 
+    #[error("Failed to follow the file")]
+    #[cfg(feature = "follow_file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "follow_file")))]
+    FollowFile(#[source] std::io::Error),
+
     #[error(transparent)]
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
     SearchExpError(#[from] RegexError),
-
-    #[cfg(feature = "tokio")]
-    #[error(transparent)]
-    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
-    JoinError(#[from] tokio::task::JoinError),
 }
 
 // Just for  convinience helper which is useful in many places
@@ -0,0 +1,273 @@
+//! Provides the [`Highlighter`] trait, which can be used to color the text displayed by the
+//! pager, for example for syntax highlighting
+//!
+//! A highlighter is set with [`Pager::set_highlighter`](crate::Pager::set_highlighter). With the
+//! `syntect` feature enabled, [`SyntectHighlighter`] provides syntax highlighting for many
+//! languages out of the box.
+
+use crate::{wrap_str, AmbiguousWidth, WrapMode};
+use crossterm::style::ContentStyle;
+
+/// Splits lines of text into runs that are each displayed in their own style
+///
+/// # Example
+/// ```
+/// use crossterm::style::{ContentStyle, Stylize};
+/// use minus::highlight::Highlighter;
+///
+/// /// Shows everything after a `#` in grey
+/// struct Comments;
+///
+/// impl Highlighter for Comments {
+///     fn highlight(&self, line: &str, _line_no: usize) -> Vec<(ContentStyle, String)> {
+///         match line.find('#') {
+///             Some(pos) => vec![
+///                 (ContentStyle::new(), line[..pos].to_string()),
+///                 (ContentStyle::new().dark_grey(), line[pos..].to_string()),
+///             ],
+///             None => vec![(ContentStyle::new(), line.to_string())],
+///         }
+///     }
+/// }
+///
+/// let pager = minus::Pager::new();
+/// pager.set_highlighter(Box::new(Comments)).expect("Failed to send data to the pager");
+/// ```
+pub trait Highlighter {
+    /// Splits `line` into runs of text along with the style each is displayed in
+    ///
+    /// `line_no` is the index of the line in the text, counting from 0. The text of all runs put
+    /// together should be `line`.
+    fn highlight(&self, line: &str, line_no: usize) -> Vec<(ContentStyle, String)>;
+}
+
+/// Returns the text of `spans` with each run in its style
+pub(crate) fn render_spans(spans: &[(ContentStyle, String)]) -> String {
+    spans
+        .iter()
+        .map(|(style, text)| style.apply(text).to_string())
+        .collect()
+}
+
+/// Wraps the text of `spans` to `cols` columns like [`wrap_str`] does
+///
+/// The text is wrapped as if it had no styling and each row is styled afterwards, so that a run
+/// that is split across rows keeps its style on all of them.
+pub(crate) fn wrap_spans(
+    spans: &[(ContentStyle, String)],
+    cols: usize,
+    mode: WrapMode,
+    indent: usize,
+    ambiguous: AmbiguousWidth,
+) -> Vec<String> {
+    let plain = spans
+        .iter()
+        .map(|(_, text)| text.as_str())
+        .collect::<String>();
+    let mut pos = 0;
+    wrap_str(&plain, cols, mode, indent, ambiguous)
+        .into_iter()
+        .map(|row| {
+            // Wrapping may drop whitespace between the rows, so search for where the row begins
+            let Some(start) = plain[pos..].find(&row).map(|o| pos + o) else {
+                return row;
+            };
+            pos = start + row.len();
+            render_spans(&slice_spans(spans, start, pos))
+        })
+        .collect()
+}
+
+/// Returns the parts of `spans` between the byte positions `start` and `end` of their text
+fn slice_spans(
+    spans: &[(ContentStyle, String)],
+    start: usize,
+    end: usize,
+) -> Vec<(ContentStyle, String)> {
+    let mut offset = 0;
+    let mut sliced = Vec::new();
+    for (style, text) in spans {
+        let (span_start, span_end) = (offset, offset + text.len());
+        offset = span_end;
+        if span_end <= start || span_start >= end {
+            continue;
+        }
+        let from = start.max(span_start) - span_start;
+        let to = end.min(span_end) - span_start;
+        sliced.push((*style, text[from..to].to_string()));
+    }
+    sliced
+}
+
+#[cfg(feature = "syntect")]
+mod syntect_highlighter {
+    use super::Highlighter;
+    use crossterm::style::{Attribute, Color, ContentStyle};
+    use parking_lot::Mutex;
+    use syntect::{
+        highlighting::{self, FontStyle, HighlightIterator, HighlightState, Theme, ThemeSet},
+        parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet},
+    };
+
+    /// A [`Highlighter`] for source code, backed by [`syntect`]
+    ///
+    /// The syntaxes and themes that come with syntect are available. Only the foreground colors
+    /// and font styles of the theme are used, the background of the terminal is kept.
+    ///
+    /// Syntect parses a text from top to bottom, so the state after each line is remembered to
+    /// continue from there. This makes formatting the whole text, or appending to it, about as
+    /// fast as highlighting each line once. Lines that are highlighted without the lines before
+    /// them, as happens with [lazy formatting](crate::Pager::set_lazy_formatting), are
+    /// highlighted on their own, which may get constructs spanning several lines wrong.
+    #[cfg_attr(docsrs, doc(cfg(feature = "syntect")))]
+    pub struct SyntectHighlighter {
+        syntax_set: SyntaxSet,
+        syntax: SyntaxReference,
+        theme: Theme,
+        /// The state after each line that has been highlighted in order from the first line
+        states: Mutex<Vec<(ParseState, HighlightState)>>,
+    }
+
+    impl SyntectHighlighter {
+        /// Create a highlighter for files with the given `extension`, like `rs` or `py`, using
+        /// the theme called `theme`
+        ///
+        /// Returns `None` if there is no syntax for `extension` or no theme called `theme`. The
+        /// themes available are `base16-ocean.dark`, `base16-eighties.dark`,
+        /// `base16-mocha.dark`, `base16-ocean.light`, `InspiredGitHub`, `Solarized (dark)` and
+        /// `Solarized (light)`.
+        ///
+        /// # Example
+        /// ```
+        /// use minus::{highlight::SyntectHighlighter, Pager};
+        ///
+        /// let highlighter = SyntectHighlighter::new("rs", "base16-ocean.dark").unwrap();
+        /// let pager = Pager::new();
+        /// pager.set_highlighter(Box::new(highlighter)).expect("Failed to send data to the pager");
+        /// ```
+        #[must_use]
+        pub fn new(extension: &str, theme: &str) -> Option<Self> {
+            let syntax_set = SyntaxSet::load_defaults_newlines();
+            let syntax = syntax_set.find_syntax_by_extension(extension)?.clone();
+            let theme = ThemeSet::load_defaults().themes.remove(theme)?;
+            Some(Self {
+                syntax_set,
+                syntax,
+                theme,
+                states: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    impl Highlighter for SyntectHighlighter {
+        fn highlight(&self, line: &str, line_no: usize) -> Vec<(ContentStyle, String)> {
+            let highlighter = highlighting::Highlighter::new(&self.theme);
+            let mut states = self.states.lock();
+            // Continue from the line before if it has been highlighted, otherwise start over
+            let in_order = line_no <= states.len();
+            if in_order {
+                states.truncate(line_no);
+            }
+            let (mut parse_state, mut highlight_state) = states
+                .last()
+                .filter(|_| in_order && line_no > 0)
+                .cloned()
+                .unwrap_or_else(|| {
+                    (
+                        ParseState::new(&self.syntax),
+                        HighlightState::new(&highlighter, ScopeStack::new()),
+                    )
+                });
+
+            let text = format!("{line}\n");
+            let Ok(ops) = parse_state.parse_line(&text, &self.syntax_set) else {
+                return vec![(ContentStyle::new(), line.to_string())];
+            };
+            let spans = HighlightIterator::new(&mut highlight_state, &ops, &text, &highlighter)
+                .map(|(style, text)| {
+                    (
+                        convert_style(style),
+                        text.trim_end_matches('\n').to_string(),
+                    )
+                })
+                .filter(|(_, text)| !text.is_empty())
+                .collect();
+
+            if in_order {
+                states.push((parse_state, highlight_state));
+            }
+            spans
+        }
+    }
+
+    /// Converts the foreground color and font style of a syntect style
+    fn convert_style(style: highlighting::Style) -> ContentStyle {
+        let mut converted = ContentStyle::new();
+        let fg = style.foreground;
+        converted.foreground_color = Some(Color::Rgb {
+            r: fg.r,
+            g: fg.g,
+            b: fg.b,
+        });
+        if style.font_style.contains(FontStyle::BOLD) {
+            converted.attributes.set(Attribute::Bold);
+        }
+        if style.font_style.contains(FontStyle::ITALIC) {
+            converted.attributes.set(Attribute::Italic);
+        }
+        if style.font_style.contains(FontStyle::UNDERLINE) {
+            converted.attributes.set(Attribute::Underlined);
+        }
+        converted
+    }
+}
+
+#[cfg(feature = "syntect")]
+pub use syntect_highlighter::SyntectHighlighter;
+
+#[cfg(test)]
+mod tests {
+    use super::{slice_spans, wrap_spans};
+    use crate::{AmbiguousWidth, WrapMode};
+    use crossterm::style::{ContentStyle, Stylize};
+
+    #[test]
+    fn wrap_styled_runs() {
+        let red = ContentStyle::new().red();
+        let spans = vec![
+            (ContentStyle::new(), "let ".to_string()),
+            (red, "long name".to_string()),
+            (ContentStyle::new(), " = 1;".to_string()),
+        ];
+        assert_eq!(
+            slice_spans(&spans, 2, 6),
+            vec![
+                (ContentStyle::new(), "t ".to_string()),
+                (red, "lo".to_string())
+            ]
+        );
+        // The run keeps its style on both rows it is wrapped onto
+        assert_eq!(
+            wrap_spans(&spans, 10, WrapMode::Word, 0, AmbiguousWidth::Narrow),
+            vec![
+                format!("let {}", red.apply("long")),
+                format!("{} = 1;", red.apply("name")),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "syntect")]
+    fn syntect_continues_from_previous_line() {
+        use super::{Highlighter, SyntectHighlighter};
+
+        let highlighter = SyntectHighlighter::new("rs", "base16-ocean.dark").unwrap();
+        let comment = highlighter.highlight("/* a comment", 0)[0].0;
+        // The second line is still inside the comment
+        let spans = highlighter.highlight("still */ let x = 1;", 1);
+        assert_eq!(spans[0], (comment, "still ".to_string()));
+        // Without the first line, it is highlighted as code
+        let spans = highlighter.highlight("still */ let x = 1;", 0);
+        assert_ne!(spans[0].0, comment);
+    }
+}
@@ -0,0 +1,284 @@
+//! Parse key descriptions like `c-c` or `pagedown` into [`KeyEvent`]s
+//!
+//! A description is made up of zero or more modifiers followed by a key, all separated by `-`.
+//! The modifiers are
+//! * `c` for `Ctrl`
+//! * `m` for `Alt` (or `Meta`)
+//! * `s` for `Shift`
+//!
+//! Any combination of them can be given in any order, for example `c-s-up` for `Ctrl+Shift+Up`.
+//! The terminal backend used by minus does not report the `Super` key, so it can't be bound.
+//!
+//! The key is either a single character or one of the named keys below. For example `q`, `c-c`,
+//! `m-s-up`, `c-home` and `f5` are all valid descriptions.
+//!
+//! | Name                                | Key                                  |
+//! |-------------------------------------|--------------------------------------|
+//! | `enter`, `esc`, `tab`, `backspace`  | The key of the same name             |
+//! | `backtab`                           | `Shift+Tab`                          |
+//! | `space`                             | The space bar, the same as ` `       |
+//! | `dash`                              | `-`, which otherwise separates parts |
+//! | `up`, `down`, `left`, `right`       | The arrow keys                       |
+//! | `pageup`, `pagedown`                | `Page Up` and `Page Down`            |
+//! | `home`, `end`, `insert`, `delete`   | The key of the same name             |
+//! | `f1` to `f12`                       | The function keys                    |
+//!
+//! Names are lowercase. Media keys and keys beyond `F12` are not reported by the terminal
+//! backend used by minus, so they can't be bound.
+//!
+//! Shift is folded into the character for character keys, hence `s-g` and `G` describe the same
+//! key.
+
+use super::split_modifiers;
+use crate::error::DescriptionError;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Parse a key description into a [`KeyEvent`]
+///
+/// See the [module documentation](self) for the format. Use [`try_parse_key_event`] for
+/// descriptions that are not known to be valid, like ones read from a configuration file.
+///
+/// # Panics
+/// This function panics if `text` is not a valid key description.
+///
+/// # Example
+/// ```
+/// use minus::input::definitions::keydefs::parse_key_event;
+/// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+///
+/// assert_eq!(
+///     parse_key_event("c-c"),
+///     KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::CONTROL },
+/// );
+/// ```
+#[must_use]
+pub fn parse_key_event(text: &str) -> KeyEvent {
+    try_parse_key_event(text).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Parse a key description into a [`KeyEvent`], returning an error if it is invalid
+///
+/// # Errors
+/// This function returns a [`DescriptionError`] if `text` is not a valid key description.
+///
+/// # Example
+/// ```
+/// use minus::input::definitions::keydefs::try_parse_key_event;
+///
+/// assert!(try_parse_key_event("c-s-x").is_ok());
+/// assert!(try_parse_key_event("ctrl-x").is_err());
+/// ```
+pub fn try_parse_key_event(text: &str) -> Result<KeyEvent, DescriptionError> {
+    let (modifiers, key) = split_modifiers(text)?;
+
+    let code = match key {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        "dash" => KeyCode::Char('-'),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "insert" => KeyCode::Insert,
+        "delete" => KeyCode::Delete,
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => {
+                        return Err(DescriptionError::InvalidKey(
+                            key.to_string(),
+                            text.to_string(),
+                        ))
+                    }
+                },
+            }
+        }
+    };
+
+    Ok(normalize(KeyEvent { code, modifiers }))
+}
+
+/// Bring a [`KeyEvent`] into the form returned by [`parse_key_event`]
+///
+/// Terminals differ in whether they report `Shift` along with an uppercase character. This folds
+/// `Shift` into the character, so that key events can be compared with parsed descriptions.
+#[must_use]
+pub fn normalize(mut key: KeyEvent) -> KeyEvent {
+    if let KeyCode::Char(c) = key.code {
+        if key.modifiers.contains(KeyModifiers::SHIFT) {
+            key.modifiers.remove(KeyModifiers::SHIFT);
+            key.code = KeyCode::Char(c.to_ascii_uppercase());
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize, parse_key_event, try_parse_key_event};
+    use crate::error::DescriptionError;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn single_keys() {
+        assert_eq!(
+            parse_key_event("q"),
+            KeyEvent {
+                code: KeyCode::Char('q'),
+                modifiers: KeyModifiers::NONE
+            }
+        );
+        assert_eq!(parse_key_event("esc").code, KeyCode::Esc);
+        assert_eq!(parse_key_event("pagedown").code, KeyCode::PageDown);
+        assert_eq!(parse_key_event("dash").code, KeyCode::Char('-'));
+        assert_eq!(parse_key_event("f5").code, KeyCode::F(5));
+    }
+
+    #[test]
+    fn modifiers() {
+        assert_eq!(
+            parse_key_event("c-c"),
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL
+            }
+        );
+        assert_eq!(
+            parse_key_event("m-s-up"),
+            KeyEvent {
+                code: KeyCode::Up,
+                modifiers: KeyModifiers::ALT | KeyModifiers::SHIFT
+            }
+        );
+        // Shift is folded into characters
+        assert_eq!(parse_key_event("s-g"), parse_key_event("G"));
+        assert_eq!(
+            normalize(KeyEvent {
+                code: KeyCode::Char('G'),
+                modifiers: KeyModifiers::SHIFT
+            }),
+            parse_key_event("G")
+        );
+    }
+
+    #[test]
+    fn stacked_modifiers() {
+        assert_eq!(
+            parse_key_event("c-s-up"),
+            KeyEvent {
+                code: KeyCode::Up,
+                modifiers: KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            }
+        );
+        // The order of the modifiers doesn't matter
+        assert_eq!(parse_key_event("m-c-f"), parse_key_event("c-m-f"));
+        assert_eq!(
+            parse_key_event("m-c-f"),
+            KeyEvent {
+                code: KeyCode::Char('f'),
+                modifiers: KeyModifiers::ALT | KeyModifiers::CONTROL
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_descriptions() {
+        assert_eq!(
+            try_parse_key_event("ctrl-x"),
+            Err(DescriptionError::InvalidModifier(
+                "ctrl".to_string(),
+                "ctrl-x".to_string()
+            ))
+        );
+        assert_eq!(
+            try_parse_key_event("c-c-x"),
+            Err(DescriptionError::RepeatedModifier(
+                "c".to_string(),
+                "c-c-x".to_string()
+            ))
+        );
+        assert_eq!(
+            try_parse_key_event("f13"),
+            Err(DescriptionError::InvalidKey(
+                "f13".to_string(),
+                "f13".to_string()
+            ))
+        );
+        assert!(try_parse_key_event("c-").is_err());
+        assert!(try_parse_key_event("").is_err());
+    }
+
+    #[test]
+    fn named_keys() {
+        let named = [
+            ("enter", KeyCode::Enter),
+            ("esc", KeyCode::Esc),
+            ("tab", KeyCode::Tab),
+            ("backtab", KeyCode::BackTab),
+            ("backspace", KeyCode::Backspace),
+            ("space", KeyCode::Char(' ')),
+            ("dash", KeyCode::Char('-')),
+            ("up", KeyCode::Up),
+            ("down", KeyCode::Down),
+            ("left", KeyCode::Left),
+            ("right", KeyCode::Right),
+            ("pageup", KeyCode::PageUp),
+            ("pagedown", KeyCode::PageDown),
+            ("home", KeyCode::Home),
+            ("end", KeyCode::End),
+            ("insert", KeyCode::Insert),
+            ("delete", KeyCode::Delete),
+        ];
+        let function = (1..=12).map(|n| (format!("f{n}"), KeyCode::F(n)));
+        for (name, code) in named
+            .iter()
+            .map(|(name, code)| ((*name).to_string(), *code))
+            .chain(function)
+        {
+            assert_eq!(
+                parse_key_event(&name),
+                KeyEvent::new(code, KeyModifiers::NONE)
+            );
+            assert_eq!(
+                parse_key_event(&format!("c-{name}")),
+                KeyEvent::new(code, KeyModifiers::CONTROL)
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_descriptions() {
+        for desc in [
+            "-", "c--", "cq", "C-q", "c-ctrl", "pgdn", "f0", "f1x", "q-", "s-s-q", "c q",
+        ] {
+            assert!(
+                try_parse_key_event(desc).is_err(),
+                "'{}' should not be a valid key description",
+                desc
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid modifier")]
+    fn invalid_modifier() {
+        let _ = parse_key_event("x-q");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid key")]
+    fn invalid_key() {
+        let _ = parse_key_event("c-foo");
+    }
+}
@@ -0,0 +1,42 @@
+//! Helpers for describing input events with short, human readable strings
+
+use crate::error::DescriptionError;
+use crossterm::event::KeyModifiers;
+
+pub mod keydefs;
+pub mod mousedefs;
+
+/// Split the modifiers off the front of a description
+///
+/// Returns the modifiers and the rest of the description. See the
+/// [`keydefs` documentation](keydefs) for the modifiers.
+///
+/// # Errors
+/// This function returns an error if a modifier is invalid or repeated.
+fn split_modifiers(text: &str) -> Result<(KeyModifiers, &str), DescriptionError> {
+    let mut parts: Vec<&str> = text.split('-').collect();
+    let rest = parts.pop().unwrap();
+
+    let mut modifiers = KeyModifiers::NONE;
+    for part in parts {
+        let modifier = match part {
+            "c" => KeyModifiers::CONTROL,
+            "m" => KeyModifiers::ALT,
+            "s" => KeyModifiers::SHIFT,
+            _ => {
+                return Err(DescriptionError::InvalidModifier(
+                    part.to_string(),
+                    text.to_string(),
+                ))
+            }
+        };
+        if modifiers.contains(modifier) {
+            return Err(DescriptionError::RepeatedModifier(
+                part.to_string(),
+                text.to_string(),
+            ));
+        }
+        modifiers |= modifier;
+    }
+    Ok((modifiers, rest))
+}
@@ -0,0 +1,114 @@
+//! Parse mouse event descriptions like `left:down` or `c-scroll:up` into [`MouseEvent`]s
+//!
+//! A description is made up of zero or more modifiers followed by a mouse action, all separated
+//! by `-`. The modifiers are the same as for [key descriptions](super::keydefs). The action is one
+//! of
+//! * `left:down`, `left:up` and `left:drag`, and the same for the `right` and `middle` buttons
+//! * `scroll:up` and `scroll:down`
+//! * `move`
+//!
+//! For example `left:down` and `c-scroll:up` are both valid descriptions.
+
+use super::split_modifiers;
+use crate::error::DescriptionError;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+/// Parse a mouse event description into a [`MouseEvent`]
+///
+/// See the [module documentation](self) for the format. A description does not include a
+/// position, hence the row and column of the returned event are always 0.
+///
+/// # Panics
+/// This function panics if `text` is not a valid mouse event description.
+///
+/// # Example
+/// ```
+/// use minus::input::definitions::mousedefs::parse_mouse_event;
+/// use crossterm::event::{KeyModifiers, MouseButton, MouseEventKind};
+///
+/// let ev = parse_mouse_event("c-left:down");
+/// assert_eq!(ev.kind, MouseEventKind::Down(MouseButton::Left));
+/// assert_eq!(ev.modifiers, KeyModifiers::CONTROL);
+/// ```
+#[must_use]
+pub fn parse_mouse_event(text: &str) -> MouseEvent {
+    try_parse_mouse_event(text).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Parse a mouse event description into a [`MouseEvent`], returning an error if it is invalid
+///
+/// # Errors
+/// This function returns a [`DescriptionError`] if `text` is not a valid mouse event
+/// description.
+pub fn try_parse_mouse_event(text: &str) -> Result<MouseEvent, DescriptionError> {
+    let (modifiers, action) = split_modifiers(text)?;
+    let invalid = || DescriptionError::InvalidMouseAction(action.to_string(), text.to_string());
+
+    let kind = match action {
+        "scroll:up" => MouseEventKind::ScrollUp,
+        "scroll:down" => MouseEventKind::ScrollDown,
+        "move" => MouseEventKind::Moved,
+        _ => {
+            let (button, state) = action.split_once(':').unwrap_or((action, ""));
+            let button = match button {
+                "left" => MouseButton::Left,
+                "right" => MouseButton::Right,
+                "middle" => MouseButton::Middle,
+                _ => return Err(invalid()),
+            };
+            match state {
+                "down" => MouseEventKind::Down(button),
+                "up" => MouseEventKind::Up(button),
+                "drag" => MouseEventKind::Drag(button),
+                _ => return Err(invalid()),
+            }
+        }
+    };
+
+    Ok(MouseEvent {
+        kind,
+        column: 0,
+        row: 0,
+        modifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_mouse_event, try_parse_mouse_event};
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEventKind};
+
+    #[test]
+    fn actions() {
+        assert_eq!(
+            parse_mouse_event("left:down").kind,
+            MouseEventKind::Down(MouseButton::Left)
+        );
+        assert_eq!(
+            parse_mouse_event("middle:drag").kind,
+            MouseEventKind::Drag(MouseButton::Middle)
+        );
+        assert_eq!(
+            parse_mouse_event("scroll:up").kind,
+            MouseEventKind::ScrollUp
+        );
+        assert_eq!(parse_mouse_event("move").kind, MouseEventKind::Moved);
+
+        let ev = parse_mouse_event("m-s-right:up");
+        assert_eq!(ev.kind, MouseEventKind::Up(MouseButton::Right));
+        assert_eq!(ev.modifiers, KeyModifiers::ALT | KeyModifiers::SHIFT);
+    }
+
+    #[test]
+    fn invalid_descriptions() {
+        assert!(try_parse_mouse_event("left:click").is_err());
+        assert!(try_parse_mouse_event("x-scroll:up").is_err());
+        assert!(try_parse_mouse_event("c-s-scroll:up").is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid mouse action")]
+    fn invalid_action() {
+        let _ = parse_mouse_event("left:click");
+    }
+}
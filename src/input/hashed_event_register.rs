@@ -0,0 +1,477 @@
+//! Provides the [`HashedEventRegister`], an [`InputClassifier`] whose bindings can be changed
+//! one at a time
+
+use super::{
+    definitions::{
+        keydefs::{normalize, parse_key_event, try_parse_key_event},
+        mousedefs::{parse_mouse_event, try_parse_mouse_event},
+    },
+    InputClassifier, InputEvent,
+};
+use crate::{error::DescriptionError, PagerState};
+#[cfg(feature = "search")]
+use crossterm::event::KeyCode;
+use crossterm::event::{Event, KeyEvent, KeyModifiers, MouseEventKind};
+use std::{collections::HashMap, sync::Arc};
+
+/// Function that turns an event into the [`InputEvent`] it should trigger, if any
+type Callback = dyn Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync;
+
+/// Function that decides whether a guarded binding applies in the current state
+type Guard = dyn Fn(&PagerState) -> bool + Send + Sync;
+
+/// A callback registered for a binding, along with the guard it is registered with, if any
+#[derive(Clone)]
+struct Handler {
+    cb: Arc<Callback>,
+    guard: Option<Arc<Guard>>,
+    /// Name given to the binding and the description it was registered with, if it is named
+    label: Option<(Arc<str>, String)>,
+}
+
+impl Handler {
+    /// Returns the callback if it applies to the state `ps`
+    fn applies(&self, ps: &PagerState) -> Option<&Arc<Callback>> {
+        self.guard
+            .as_ref()
+            .map_or(true, |guard| guard(ps))
+            .then_some(&self.cb)
+    }
+}
+
+impl From<Arc<Callback>> for Handler {
+    fn from(cb: Arc<Callback>) -> Self {
+        Self {
+            cb,
+            guard: None,
+            label: None,
+        }
+    }
+}
+
+/// The part of an event that a callback is registered for
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Binding {
+    Key(KeyEvent),
+    Mouse(MouseEventKind, KeyModifiers),
+    Resize,
+}
+
+/// An [`InputClassifier`] that looks up the callback for each event in a hash map
+///
+/// Keys and mouse events are registered with short descriptions, see
+/// [`keydefs`](super::definitions::keydefs) and [`mousedefs`](super::definitions::mousedefs) for
+/// the format. Each callback is given the event and the current [`PagerState`] and returns the
+/// [`InputEvent`] to run, or `None` to ignore the event.
+///
+/// Start from [`HashedEventRegister::with_default_bindings`] to change only some of the default
+/// bindings, or from [`HashedEventRegister::new`] to define all of them yourself. The keys set
+/// with [`Pager::set_exit_keys`](crate::Pager::set_exit_keys) always quit the pager, no matter
+/// what is registered for them. With the `search` feature, the keys set with
+/// [`Pager::set_search_keys`](crate::Pager::set_search_keys) run what is registered for `/` and
+/// `?`, the default search keys, which are only reached through them.
+///
+/// A mouse binding without modifiers also applies while modifiers are held, unless something
+/// else is registered for that combination.
+///
+/// A key can also be bound for some states only with
+/// [`add_key_event_guarded`](Self::add_key_event_guarded).
+///
+/// Keys registered with [`add_key_events_named`](Self::add_key_events_named) can be looked up by
+/// name with [`bindings_for`](Self::bindings_for), for example to show them in a help screen.
+///
+/// Events that nothing is registered for are ignored by default. A fallback can be set for
+/// each kind of event with [`set_unmatched_key_handler`](Self::set_unmatched_key_handler),
+/// [`set_unmatched_mouse_handler`](Self::set_unmatched_mouse_handler) and
+/// [`set_unmatched_resize_handler`](Self::set_unmatched_resize_handler). Unmatched events of a
+/// kind without its own fallback go to the one set with
+/// [`set_unmatched_handler`](Self::set_unmatched_handler).
+///
+/// [`add_key_events`](Self::add_key_events) and [`add_mouse_events`](Self::add_mouse_events)
+/// panic on invalid descriptions, which suits bindings written out in code. Bindings that come
+/// from elsewhere, like a configuration file, should be added with
+/// [`try_add_key_events`](Self::try_add_key_events) and
+/// [`try_add_mouse_events`](Self::try_add_mouse_events) instead, which return a
+/// [`DescriptionError`] that can be shown to the user.
+///
+/// # Example
+/// ```
+/// use minus::{input::{HashedEventRegister, InputEvent}, Pager};
+///
+/// let mut register = HashedEventRegister::with_default_bindings();
+/// // Scroll down by two lines at a time
+/// register.add_key_events(&["down", "j"], |_, ps| {
+///     Some(InputEvent::UpdateUpperMark(ps.upper_mark.saturating_add(2)))
+/// });
+/// // Don't use `k` at all
+/// register.remove_key_events(&["k"]);
+///
+/// let pager = Pager::new();
+/// pager.set_input_classifier(Box::new(register))
+///     .expect("Failed to send data to the pager");
+/// ```
+#[derive(Default, Clone)]
+pub struct HashedEventRegister {
+    bindings: HashMap<Binding, Handler>,
+    unmatched_key: Option<Arc<Callback>>,
+    unmatched_mouse: Option<Arc<Callback>>,
+    unmatched_resize: Option<Arc<Callback>>,
+    unmatched: Option<Arc<Callback>>,
+}
+
+impl HashedEventRegister {
+    /// Create a register without any bindings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a register with the default bindings of minus
+    ///
+    /// These are the bindings listed in the [crate documentation](crate#standard-actions), which
+    /// are also used by the [`DefaultInputClassifier`](super::DefaultInputClassifier).
+    #[must_use]
+    pub fn with_default_bindings() -> Self {
+        let mut register = Self::new();
+        super::define_default_bindings(&mut register);
+        register
+    }
+
+    /// Register `cb` for all keys in `descs`, replacing what was registered for them before
+    ///
+    /// # Panics
+    /// This function panics if any of `descs` is not a valid
+    /// [key description](super::definitions::keydefs).
+    pub fn add_key_events(
+        &mut self,
+        descs: &[&str],
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        let cb: Arc<Callback> = Arc::new(cb);
+        for desc in descs {
+            self.bindings
+                .insert(Binding::Key(parse_key_event(desc)), cb.clone().into());
+        }
+    }
+
+    /// Register `cb` for the key `desc`, but only for states where `guard` returns `true`
+    ///
+    /// In other states the key is handled as if nothing was registered for it, so it goes to
+    /// the [fallback for unmatched keys](Self::set_unmatched_key_handler). This replaces what
+    /// was registered for the key before, guarded or not.
+    ///
+    /// # Panics
+    /// This function panics if `desc` is not a valid
+    /// [key description](super::definitions::keydefs).
+    ///
+    /// # Example
+    /// ```
+    /// use minus::input::{HashedEventRegister, InputEvent};
+    ///
+    /// let mut register = HashedEventRegister::with_default_bindings();
+    /// // Jump to the top with `h`, but only when not already there
+    /// register.add_key_event_guarded(
+    ///     "h",
+    ///     |ps| ps.upper_mark > 0,
+    ///     |_, _| Some(InputEvent::UpdateUpperMark(0)),
+    /// );
+    /// ```
+    pub fn add_key_event_guarded(
+        &mut self,
+        desc: &str,
+        guard: impl Fn(&PagerState) -> bool + Send + Sync + 'static,
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        self.bindings.insert(
+            Binding::Key(parse_key_event(desc)),
+            Handler {
+                cb: Arc::new(cb),
+                guard: Some(Arc::new(guard)),
+                label: None,
+            },
+        );
+    }
+
+    /// Register `cb` for all keys in `descs` under `name`, like
+    /// [`add_key_events`](Self::add_key_events) does
+    ///
+    /// The keys can then be looked up with [`bindings_for`](Self::bindings_for). A key stops
+    /// being listed under `name` once something else is registered for it or it is removed.
+    ///
+    /// # Panics
+    /// This function panics if any of `descs` is not a valid
+    /// [key description](super::definitions::keydefs).
+    ///
+    /// # Example
+    /// ```
+    /// use minus::input::{HashedEventRegister, InputEvent};
+    ///
+    /// let mut register = HashedEventRegister::with_default_bindings();
+    /// register.add_key_events_named("top", &["g", "home"], |_, _| {
+    ///     Some(InputEvent::UpdateUpperMark(0))
+    /// });
+    /// assert_eq!(register.bindings_for("top"), ["g", "home"]);
+    /// ```
+    pub fn add_key_events_named(
+        &mut self,
+        name: &str,
+        descs: &[&str],
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        let cb: Arc<Callback> = Arc::new(cb);
+        let name: Arc<str> = name.into();
+        for desc in descs {
+            self.bindings.insert(
+                Binding::Key(parse_key_event(desc)),
+                Handler {
+                    cb: cb.clone(),
+                    guard: None,
+                    label: Some((name.clone(), (*desc).to_string())),
+                },
+            );
+        }
+    }
+
+    /// Returns the descriptions of the keys registered under `name`, in sorted order
+    ///
+    /// Only keys registered with [`add_key_events_named`](Self::add_key_events_named) have a name.
+    /// The descriptions are returned as they were given when registering.
+    #[must_use]
+    pub fn bindings_for(&self, name: &str) -> Vec<String> {
+        let mut descs: Vec<String> = self
+            .bindings
+            .values()
+            .filter_map(|handler| handler.label.as_ref())
+            .filter(|(label, _)| &**label == name)
+            .map(|(_, desc)| desc.clone())
+            .collect();
+        descs.sort();
+        descs
+    }
+
+    /// Register `cb` for all keys in `descs`, like [`add_key_events`](Self::add_key_events) does
+    ///
+    /// Nothing is registered if any of the descriptions is invalid.
+    ///
+    /// # Errors
+    /// This function returns the error for the first description in `descs` that is not a valid
+    /// [key description](super::definitions::keydefs).
+    pub fn try_add_key_events(
+        &mut self,
+        descs: &[&str],
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) -> Result<(), DescriptionError> {
+        let keys = descs
+            .iter()
+            .map(|desc| try_parse_key_event(desc))
+            .collect::<Result<Vec<_>, _>>()?;
+        let cb: Arc<Callback> = Arc::new(cb);
+        for key in keys {
+            self.bindings.insert(Binding::Key(key), cb.clone().into());
+        }
+        Ok(())
+    }
+
+    /// Remove the bindings of all keys in `descs`
+    ///
+    /// # Panics
+    /// This function panics if any of `descs` is not a valid
+    /// [key description](super::definitions::keydefs).
+    pub fn remove_key_events(&mut self, descs: &[&str]) {
+        for desc in descs {
+            self.bindings.remove(&Binding::Key(parse_key_event(desc)));
+        }
+    }
+
+    /// Register `cb` for all mouse events in `descs`, replacing what was registered for them
+    /// before
+    ///
+    /// # Panics
+    /// This function panics if any of `descs` is not a valid
+    /// [mouse event description](super::definitions::mousedefs).
+    pub fn add_mouse_events(
+        &mut self,
+        descs: &[&str],
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        let cb: Arc<Callback> = Arc::new(cb);
+        for desc in descs {
+            let ev = parse_mouse_event(desc);
+            self.bindings
+                .insert(Binding::Mouse(ev.kind, ev.modifiers), cb.clone().into());
+        }
+    }
+
+    /// Register `cb` for all mouse events in `descs`, like
+    /// [`add_mouse_events`](Self::add_mouse_events) does
+    ///
+    /// Nothing is registered if any of the descriptions is invalid.
+    ///
+    /// # Errors
+    /// This function returns the error for the first description in `descs` that is not a valid
+    /// [mouse event description](super::definitions::mousedefs).
+    pub fn try_add_mouse_events(
+        &mut self,
+        descs: &[&str],
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) -> Result<(), DescriptionError> {
+        let events = descs
+            .iter()
+            .map(|desc| try_parse_mouse_event(desc))
+            .collect::<Result<Vec<_>, _>>()?;
+        let cb: Arc<Callback> = Arc::new(cb);
+        for ev in events {
+            self.bindings
+                .insert(Binding::Mouse(ev.kind, ev.modifiers), cb.clone().into());
+        }
+        Ok(())
+    }
+
+    /// Remove the bindings of all mouse events in `descs`
+    ///
+    /// # Panics
+    /// This function panics if any of `descs` is not a valid
+    /// [mouse event description](super::definitions::mousedefs).
+    pub fn remove_mouse_events(&mut self, descs: &[&str]) {
+        for desc in descs {
+            let ev = parse_mouse_event(desc);
+            self.bindings.remove(&Binding::Mouse(ev.kind, ev.modifiers));
+        }
+    }
+
+    /// Register `cb` for resizes of the terminal, replacing what was registered before
+    pub fn add_resize_event(
+        &mut self,
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        let cb: Arc<Callback> = Arc::new(cb);
+        self.bindings.insert(Binding::Resize, cb.into());
+    }
+
+    /// Remove the binding for resizes of the terminal
+    pub fn remove_resize_event(&mut self) {
+        self.bindings.remove(&Binding::Resize);
+    }
+
+    /// Call `cb` for key presses that nothing is registered for, replacing the previous fallback
+    pub fn set_unmatched_key_handler(
+        &mut self,
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        self.unmatched_key = Some(Arc::new(cb));
+    }
+
+    /// Remove the fallback for unmatched key presses, so they go to the
+    /// [general fallback](Self::set_unmatched_handler) again
+    pub fn remove_unmatched_key_handler(&mut self) {
+        self.unmatched_key = None;
+    }
+
+    /// Call `cb` for mouse events that nothing is registered for, replacing the previous fallback
+    pub fn set_unmatched_mouse_handler(
+        &mut self,
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        self.unmatched_mouse = Some(Arc::new(cb));
+    }
+
+    /// Remove the fallback for unmatched mouse events, so they go to the
+    /// [general fallback](Self::set_unmatched_handler) again
+    pub fn remove_unmatched_mouse_handler(&mut self) {
+        self.unmatched_mouse = None;
+    }
+
+    /// Call `cb` for resizes of the terminal if nothing is registered for them, replacing the previous fallback
+    pub fn set_unmatched_resize_handler(
+        &mut self,
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        self.unmatched_resize = Some(Arc::new(cb));
+    }
+
+    /// Remove the fallback for unmatched resizes, so they go to the
+    /// [general fallback](Self::set_unmatched_handler) again
+    pub fn remove_unmatched_resize_handler(&mut self) {
+        self.unmatched_resize = None;
+    }
+
+    /// Call `cb` for any event that nothing is registered for and that has no fallback for its
+    /// kind, replacing the previous fallback
+    pub fn set_unmatched_handler(
+        &mut self,
+        cb: impl Fn(Event, &PagerState) -> Option<InputEvent> + Send + Sync + 'static,
+    ) {
+        self.unmatched = Some(Arc::new(cb));
+    }
+
+    /// Remove the general fallback, so that unmatched events without a fallback for their kind
+    /// are ignored again
+    pub fn remove_unmatched_handler(&mut self) {
+        self.unmatched = None;
+    }
+}
+
+/// Returns the key whose binding `key` runs, or `None` if it runs none
+///
+/// With the `search` feature, the keys in [`PagerState::search_keys`] run the bindings of `/`
+/// and `?`, while `/` and `?` themselves run nothing unless they are search keys.
+#[cfg_attr(
+    not(feature = "search"),
+    allow(clippy::unnecessary_wraps, unused_variables)
+)]
+fn binding_key(key: KeyEvent, ps: &PagerState) -> Option<KeyEvent> {
+    let key = normalize(key);
+    #[cfg(feature = "search")]
+    if let KeyEvent {
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::NONE,
+    } = key
+    {
+        let search_key = |c| KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+        };
+        if c == ps.search_keys.0 {
+            return Some(search_key('/'));
+        }
+        if c == ps.search_keys.1 {
+            return Some(search_key('?'));
+        }
+        if c == '/' || c == '?' {
+            return None;
+        }
+    }
+    Some(key)
+}
+
+impl InputClassifier for HashedEventRegister {
+    fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent> {
+        let (bound, unmatched) = match ev {
+            Event::Key(key) if ps.exit_keys.contains(&normalize(key)) => {
+                return Some(InputEvent::Exit)
+            }
+            Event::Key(key) if ps.dump_exit_keys.contains(&normalize(key)) => {
+                return Some(InputEvent::ExitAndDump)
+            }
+            Event::Key(key) => (
+                binding_key(key, ps).and_then(|key| self.bindings.get(&Binding::Key(key))),
+                &self.unmatched_key,
+            ),
+            Event::Mouse(mouse) => (
+                self.bindings
+                    .get(&Binding::Mouse(mouse.kind, mouse.modifiers))
+                    .or_else(|| {
+                        self.bindings
+                            .get(&Binding::Mouse(mouse.kind, KeyModifiers::NONE))
+                    }),
+                &self.unmatched_mouse,
+            ),
+            Event::Resize(..) => (self.bindings.get(&Binding::Resize), &self.unmatched_resize),
+        };
+        let cb = bound
+            .and_then(|handler| handler.applies(ps))
+            .or(unmatched.as_ref())
+            .or(self.unmatched.as_ref())?;
+        cb(ev, ps)
+    }
+}
@@ -1,29 +1,90 @@
 //! Provides the [`InputClassifier`] trait, which can be used
 //! to customize the default keybindings of minus
 
+pub mod definitions;
+mod hashed_event_register;
+
+pub use hashed_event_register::HashedEventRegister;
+
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use once_cell::sync::Lazy;
+use std::convert::TryFrom;
 
 #[cfg(feature = "search")]
 use crate::minus_core::search::SearchMode;
-use crate::{LineNumbers, PagerState};
+use crate::{EnterAction, LineNumbers, PagerState, TextDirection};
 
 /// Events handled by the `minus` pager.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(clippy::module_name_repetitions)]
 pub enum InputEvent {
-    /// `Ctrl+C` or `Q`, exits the application.
+    /// `Ctrl+C` or `Q` by default, exits the application. See [`Pager::set_exit_keys`](crate::Pager::set_exit_keys).
     Exit,
+    /// `Q` by default, exits the application and writes the text to the main screen. See
+    /// [`Pager::set_dump_exit_keys`](crate::Pager::set_dump_exit_keys).
+    ExitAndDump,
     /// The terminal was resized. Contains the new number of rows.
     UpdateTermArea(usize, usize),
     /// Sent by movement keys like `Up` `Down`, `PageUp`, 'PageDown', 'g', `G` etc. Contains the new value for the upper mark.
     UpdateUpperMark(usize),
+    /// Sent instead of [`InputEvent::UpdateUpperMark`] by `Up`, `Down`, `j`, `k` and `Enter` in
+    /// cursor mode, see [`Pager::set_cursor_mode`](crate::Pager::set_cursor_mode). Contains the
+    /// new row of the cursor, counting the formatted rows from the start of the text.
+    UpdateCursor(usize),
+    /// Moves the upper mark by the given number of rows, saturating at the top. Sent by
+    /// [`Pager::scroll_by`](crate::Pager::scroll_by).
+    ScrollBy(isize),
+    /// Sent by horizontal movement keys like `Left`, `Right` etc. Contains the new value for the
+    /// left mark, which is clamped so that the longest line stays on the screen.
+    UpdateLeftMark(usize),
+    /// `%` preceded by a number, goes to that percentage of the text. Contains the percentage.
+    GotoPercent(usize),
+    /// `}`, moves to the blank line after the next paragraph, or the last line if there is none.
+    /// Contains the number of paragraphs to move by.
+    NextParagraph(usize),
+    /// `{`, moves to the blank line before the previous paragraph, or the first line if there is
+    /// none. Contains the number of paragraphs to move by.
+    PrevParagraph(usize),
     /// `Ctrl+L`, inverts the line number display. Contains the new value.
     UpdateLineNumber(LineNumbers),
+    /// `Ctrl+S`, switches between wrapping and truncating long lines. Contains the new value.
+    UpdateLineWrapping(bool),
+    /// `Ctrl+W`, switches between showing and hiding markers for tabs and trailing spaces.
+    /// Contains the new value.
+    UpdateShowWhitespace(bool),
+    /// `Ctrl+R`, draws the screen again without changing anything
+    Redraw,
     /// A number key has been pressed. This inner value is stored as a `char`.
     /// The input loop will append this number to its `count` string variable
     Number(char),
     /// Restore the original prompt
     RestorePrompt,
+    /// Discard the number typed before a command. Sent when no command follows the number for a
+    /// while.
+    ClearPrefix,
+    /// The first key of a command made of two keys was pressed, like the first `]` of `]]`.
+    /// Contains the key, which the next key press completes the command with. Any other input
+    /// discards it, a number typed before it is kept for the command.
+    PendingKey(char),
+    /// The left mouse button was pressed on the text while drag scrolling is enabled. Contains
+    /// the row of the mouse, where the drag starts from. See
+    /// [`Pager::set_drag_scroll`](crate::Pager::set_drag_scroll).
+    StartDrag(usize),
+    /// The mouse was dragged with the left button held while drag scrolling is enabled. Contains
+    /// the row of the mouse, the text is scrolled by the rows moved since the last one.
+    Drag(usize),
+    /// The left mouse button was released while drag scrolling is enabled, ends the drag
+    EndDrag,
+    /// A line number was clicked, toggles the mark on that line. Contains the index of the line.
+    ToggleGutterMark(usize),
+    /// A hyperlink was clicked. Contains the index of the formatted row and the column in it
+    /// where the link was clicked. See
+    /// [`Pager::set_on_link_activate`](crate::Pager::set_on_link_activate).
+    ActivateLink(usize, usize),
+    /// Display the next buffer, or the first one after the last
+    NextBuffer,
+    /// Display the previous buffer, or the last one before the first
+    PrevBuffer,
     /// `/`, Searching for certain pattern of text
     #[cfg(feature = "search")]
     Search(SearchMode),
@@ -39,6 +100,110 @@ pub enum InputEvent {
     /// Move to the previous nth match in the given direction
     #[cfg(feature = "search")]
     MoveToPrevMatch(usize),
+    /// `[n] M`, go to the nth search match, counting from 1
+    #[cfg(feature = "search")]
+    GotoMatch(usize),
+    /// `Esc`, clear the current search and its highlights
+    #[cfg(feature = "search")]
+    ClearSearch,
+    /// `Alt+U`, shows or hides the highlights of search matches without clearing the search.
+    /// Contains the new value.
+    #[cfg(feature = "search")]
+    UpdateHighlightVisible(bool),
+    /// `Alt+F`, switches dimming the lines without a search match on or off. Contains the new
+    /// value.
+    #[cfg(feature = "search")]
+    UpdateSearchFocus(bool),
+    /// `)`, move to the next line matching a highlight group. Contains the index of the group
+    /// to look for, or `None` to look for any group. A number before `)` selects the group,
+    /// starting from 1.
+    #[cfg(feature = "search")]
+    NextHighlight(Option<usize>),
+    /// `]]`, move to the next line matching the section pattern, see
+    /// [`Pager::set_section_pattern`](crate::Pager::set_section_pattern). Contains the number of
+    /// sections to move by.
+    #[cfg(feature = "search")]
+    NextSection(usize),
+    /// `[[`, move to the previous line matching the section pattern. Contains the number of
+    /// sections to move by.
+    #[cfg(feature = "search")]
+    PrevSection(usize),
+    /// `y`, copy the text on the screen to the clipboard
+    #[cfg(feature = "clipboard")]
+    CopyVisible,
+    /// `Enter` with [`EnterAction::EmitSelect`](crate::EnterAction::EmitSelect), selects the
+    /// current line. Contains the index of the line in the text, counting from 0. The line is
+    /// given to the function set with [`Pager::set_on_select`](crate::Pager::set_on_select).
+    Select(usize),
+    /// `v` in cursor mode, starts selecting lines at the cursor, or stops if lines are already
+    /// selected. The selection extends to wherever the cursor is moved.
+    ToggleSelection,
+    /// `Esc` while lines are selected, stops selecting them
+    ClearSelection,
+    /// `y` while lines are selected, yanks them and stops selecting. The lines are given to the
+    /// function set with [`Pager::set_on_yank`](crate::Pager::set_on_yank), and copied to the
+    /// clipboard with the `clipboard` feature.
+    YankSelection,
+    /// `Y`, copy a reference to the line at the top of the screen, or the cursor's line, to the
+    /// clipboard
+    ///
+    /// The reference is made from the template set with
+    /// [`Pager::set_line_ref_template`](crate::Pager::set_line_ref_template).
+    #[cfg(feature = "clipboard")]
+    CopyLineRef,
+}
+
+impl InputEvent {
+    /// Returns the event that `.` repeats after this one, or `None` if it is not repeated
+    ///
+    /// Jumps to percentages, paragraphs, search matches, highlights and sections are repeated as
+    /// they are. Scrolls by more than one row, like a page jump, are repeated as a scroll by the
+    /// same number of rows from wherever the screen is then. Everything else is not repeated.
+    pub(crate) fn repeatable(&self, upper_mark: usize) -> Option<Self> {
+        match *self {
+            Self::UpdateUpperMark(um) if um.abs_diff(upper_mark) > 1 => {
+                let rows = isize::try_from(um.abs_diff(upper_mark)).unwrap_or(isize::MAX);
+                Some(Self::ScrollBy(if um > upper_mark { rows } else { -rows }))
+            }
+            Self::ScrollBy(delta) if delta.unsigned_abs() > 1 => Some(*self),
+            Self::GotoPercent(_) | Self::NextParagraph(_) | Self::PrevParagraph(_) => Some(*self),
+            #[cfg(feature = "search")]
+            Self::MoveToNextMatch(_)
+            | Self::MoveToPrevMatch(_)
+            | Self::GotoMatch(_)
+            | Self::NextHighlight(_)
+            | Self::NextSection(_)
+            | Self::PrevSection(_) => Some(*self),
+            _ => None,
+        }
+    }
+
+    /// Returns the event with the count typed before `.` in place of the one it was given
+    ///
+    /// Scrolls are repeated `count` times and a percentage is replaced by `count`.
+    fn with_count(self, count: usize) -> Self {
+        match self {
+            Self::ScrollBy(delta) => {
+                Self::ScrollBy(delta.saturating_mul(isize::try_from(count).unwrap_or(isize::MAX)))
+            }
+            Self::GotoPercent(_) => Self::GotoPercent(count),
+            Self::NextParagraph(_) => Self::NextParagraph(count),
+            Self::PrevParagraph(_) => Self::PrevParagraph(count),
+            #[cfg(feature = "search")]
+            Self::MoveToNextMatch(_) => Self::MoveToNextMatch(count),
+            #[cfg(feature = "search")]
+            Self::MoveToPrevMatch(_) => Self::MoveToPrevMatch(count),
+            #[cfg(feature = "search")]
+            Self::GotoMatch(_) => Self::GotoMatch(count),
+            #[cfg(feature = "search")]
+            Self::NextHighlight(_) => Self::NextHighlight(Some(count.saturating_sub(1))),
+            #[cfg(feature = "search")]
+            Self::NextSection(_) => Self::NextSection(count),
+            #[cfg(feature = "search")]
+            Self::PrevSection(_) => Self::PrevSection(count),
+            ev => ev,
+        }
+    }
 }
 
 /// Define custom keybindings
@@ -89,188 +254,369 @@ pub trait InputClassifier {
     fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent>;
 }
 
-/// The default keybindings in `minus`. These can be overriden by
-/// making a custom input handler struct and implementing the [`InputClassifier`] trait
+/// The default keybindings in `minus`
+///
+/// This classifies input with the bindings of [`HashedEventRegister::with_default_bindings`].
+/// To change only some of them, start from that register instead. The bindings can also be
+/// replaced entirely by making a custom input handler struct and implementing the
+/// [`InputClassifier`] trait.
 pub struct DefaultInputClassifier;
 
+/// The register used by [`DefaultInputClassifier`], built once on first use
+static DEFAULT_BINDINGS: Lazy<HashedEventRegister> =
+    Lazy::new(HashedEventRegister::with_default_bindings);
+
 impl InputClassifier for DefaultInputClassifier {
-    #[allow(clippy::too_many_lines)]
     fn classify_input(&self, ev: Event, ps: &PagerState) -> Option<InputEvent> {
-        #[allow(clippy::unnested_or_patterns)]
-        match ev {
-            // Scroll up by one.
-            Event::Key(KeyEvent {
-                code,
-                modifiers: KeyModifiers::NONE,
-            }) if code == KeyCode::Up || code == KeyCode::Char('k') => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
-                Some(InputEvent::UpdateUpperMark(
-                    ps.upper_mark.saturating_sub(position),
-                ))
-            }
+        DEFAULT_BINDINGS.classify_input(ev, ps)
+    }
+}
 
-            // Scroll down by one.
-            Event::Key(KeyEvent {
-                code,
-                modifiers: KeyModifiers::NONE,
-            }) if code == KeyCode::Down || code == KeyCode::Char('j') => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
-                Some(InputEvent::UpdateUpperMark(
-                    ps.upper_mark.saturating_add(position),
-                ))
-            }
+/// Returns the number typed before a command, or `default` if there is none
+fn prefix_num(ps: &PagerState, default: usize) -> usize {
+    ps.prefix_num.parse::<usize>().unwrap_or(default)
+}
+
+/// Returns the event for moving by `n` lines, down if `down` is set
+///
+/// This moves the cursor in cursor mode and scrolls the text otherwise.
+fn move_lines(ps: &PagerState, n: usize, down: bool) -> InputEvent {
+    match ps.cursor_line {
+        Some(cursor) if down => InputEvent::UpdateCursor(cursor.saturating_add(n)),
+        Some(cursor) => InputEvent::UpdateCursor(cursor.saturating_sub(n)),
+        None => InputEvent::UpdateUpperMark(scroll_lines(ps, n, down)),
+    }
+}
+
+/// Returns the upper mark after scrolling by `n` lines, down if `down` is set
+///
+/// With [`PagerState::scroll_wrap`] set, the position wraps around the ends of the text instead
+/// of stopping at them.
+fn scroll_lines(ps: &PagerState, n: usize, down: bool) -> usize {
+    if !ps.scroll_wrap {
+        return if down {
+            ps.upper_mark.saturating_add(n)
+        } else {
+            ps.upper_mark.saturating_sub(n)
+        };
+    }
+    let positions = ps.max_upper_mark() + 1;
+    let upper_mark = ps.upper_mark.min(positions - 1);
+    let n = n % positions;
+    if down {
+        (upper_mark + n) % positions
+    } else {
+        (upper_mark + positions - n) % positions
+    }
+}
+
+/// Adds the default bindings of minus to `register`
+#[allow(clippy::too_many_lines)]
+fn define_default_bindings(register: &mut HashedEventRegister) {
+    // Scroll up by one.
+    register.add_key_events(&["up", "k"], |_, ps| {
+        Some(move_lines(ps, prefix_num(ps, 1), false))
+    });
+    // Scroll down by one.
+    register.add_key_events(&["down", "j"], |_, ps| {
+        Some(move_lines(ps, prefix_num(ps, 1), true))
+    });
 
-            // For number keys
+    // For number keys, unless counts are turned off
+    register.add_key_events(
+        &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"],
+        |ev, ps| match ev {
             Event::Key(KeyEvent {
                 code: KeyCode::Char(c),
-                modifiers: KeyModifiers::NONE,
-            }) if c.is_ascii_digit() => Some(InputEvent::Number(c)),
+                ..
+            }) if ps.count_prefix_enabled => Some(InputEvent::Number(c)),
+            _ => None,
+        },
+    );
 
-            // Enter key
-            Event::Key(KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                if ps.message.is_some() {
-                    Some(InputEvent::RestorePrompt)
-                } else {
-                    let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
-                    Some(InputEvent::UpdateUpperMark(
-                        ps.upper_mark.saturating_add(position),
-                    ))
-                }
-            }
+    // Enter key
+    register.add_key_events(&["enter"], |_, ps| {
+        if ps.message.is_some() {
+            return Some(InputEvent::RestorePrompt);
+        }
+        match ps.enter_action {
+            EnterAction::ScrollDown => Some(move_lines(ps, prefix_num(ps, 1), true)),
+            EnterAction::EmitSelect => Some(InputEvent::Select(
+                ps.line_at_row(ps.cursor_line.unwrap_or(ps.upper_mark)),
+            )),
+            EnterAction::Ignore => None,
+        }
+    });
 
-            // Scroll up by half screen height.
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('u'),
-                modifiers,
-            }) if modifiers == KeyModifiers::CONTROL || modifiers == KeyModifiers::NONE => {
-                let half_screen = ps.rows / 2;
-                Some(InputEvent::UpdateUpperMark(
-                    ps.upper_mark.saturating_sub(half_screen),
-                ))
+    // Scroll up by half screen height.
+    register.add_key_events(&["c-u", "u"], |_, ps| {
+        Some(InputEvent::UpdateUpperMark(
+            ps.upper_mark.saturating_sub(ps.content_rows() / 2),
+        ))
+    });
+    // Scroll down by half screen height.
+    register.add_key_events(&["c-d", "d"], |_, ps| {
+        Some(InputEvent::UpdateUpperMark(
+            ps.upper_mark.saturating_add(ps.content_rows() / 2),
+        ))
+    });
+
+    // Mouse scroll up/down
+    register.add_mouse_events(&["scroll:up"], |_, ps| {
+        Some(InputEvent::UpdateUpperMark(
+            ps.upper_mark.saturating_sub(ps.mouse_scroll_amount),
+        ))
+    });
+    register.add_mouse_events(&["scroll:down"], |_, ps| {
+        Some(InputEvent::UpdateUpperMark(
+            ps.upper_mark.saturating_add(ps.mouse_scroll_amount),
+        ))
+    });
+    // Mouse scroll left/right. Terminals send the horizontal wheel as the vertical one with Shift
+    // held, which scrolls vertically while lines are wrapped
+    register.add_mouse_events(&["s-scroll:up", "s-scroll:down"], |ev, ps| {
+        let Event::Mouse(MouseEvent { kind, .. }) = ev else {
+            return None;
+        };
+        let up = kind == MouseEventKind::ScrollUp;
+        Some(match (ps.line_wrapping, up) {
+            (true, true) => {
+                InputEvent::UpdateUpperMark(ps.upper_mark.saturating_sub(ps.mouse_scroll_amount))
             }
-            // Scroll down by half screen height.
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('d'),
-                modifiers,
-            }) if modifiers == KeyModifiers::CONTROL || modifiers == KeyModifiers::NONE => {
-                let half_screen = ps.rows / 2;
-                Some(InputEvent::UpdateUpperMark(
-                    ps.upper_mark.saturating_add(half_screen),
-                ))
+            (true, false) => {
+                InputEvent::UpdateUpperMark(ps.upper_mark.saturating_add(ps.mouse_scroll_amount))
             }
+            (false, true) => InputEvent::UpdateLeftMark(
+                ps.left_mark
+                    .saturating_sub(ps.horizontal_mouse_scroll_amount),
+            ),
+            (false, false) => InputEvent::UpdateLeftMark(
+                ps.left_mark
+                    .saturating_add(ps.horizontal_mouse_scroll_amount),
+            ),
+        })
+    });
+    // Click on a line number or a hyperlink
+    register.add_mouse_events(&["left:down"], |ev, ps| {
+        let Event::Mouse(MouseEvent { column, row, .. }) = ev else {
+            return None;
+        };
+        let width = ps.line_number_width();
+        // Clicks left of centered text don't hit it
+        let column = usize::from(column).checked_sub(ps.left_margin())?;
+        let in_gutter = width > 0
+            && if ps.text_direction == TextDirection::Rtl {
+                column >= ps.content_cols().saturating_sub(width)
+            } else {
+                column < width
+            };
+        if in_gutter {
+            ps.locate(row).map(InputEvent::ToggleGutterMark)
+        } else {
+            ps.hyperlink_row_at(row, column)
+                .map(|formatted_row| InputEvent::ActivateLink(formatted_row, column))
+                .or_else(|| {
+                    ps.drag_scroll
+                        .then(|| InputEvent::StartDrag(usize::from(row)))
+                })
+        }
+    });
+    // Drag the text around
+    register.add_mouse_events(&["left:drag"], |ev, ps| {
+        let Event::Mouse(MouseEvent { row, .. }) = ev else {
+            return None;
+        };
+        ps.drag_scroll.then(|| InputEvent::Drag(usize::from(row)))
+    });
+    register.add_mouse_events(&["left:up"], |_, ps| {
+        ps.drag_scroll.then_some(InputEvent::EndDrag)
+    });
 
-            // Mouse scroll up/down
-            Event::Mouse(MouseEvent {
-                kind: MouseEventKind::ScrollUp,
-                ..
-            }) => Some(InputEvent::UpdateUpperMark(ps.upper_mark.saturating_sub(5))),
-            Event::Mouse(MouseEvent {
-                kind: MouseEventKind::ScrollDown,
-                ..
-            }) => Some(InputEvent::UpdateUpperMark(ps.upper_mark.saturating_add(5))),
-            // Go to top.
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('g'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(InputEvent::UpdateUpperMark(0)),
-            // Go to bottom.
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('g'),
-                modifiers: KeyModifiers::SHIFT,
-            })
-            | Event::Key(KeyEvent {
-                code: KeyCode::Char('G'),
-                modifiers: KeyModifiers::SHIFT,
-            })
-            | Event::Key(KeyEvent {
-                code: KeyCode::Char('G'),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                let mut position = ps
-                    .prefix_num
-                    .parse::<usize>()
-                    .unwrap_or(usize::MAX)
-                    // Reduce 1 here, because line numbering starts from 1
-                    // while upper_mark starts from 0
-                    .saturating_sub(1);
-                if position == 0 {
-                    position = usize::MAX;
-                }
-                Some(InputEvent::UpdateUpperMark(position))
-            }
+    // Go to a percentage of the text.
+    register.add_key_events(&["%"], |_, ps| {
+        ps.prefix_num
+            .parse::<usize>()
+            .ok()
+            .map(InputEvent::GotoPercent)
+    });
+    // Go to the next or previous paragraph.
+    register.add_key_events(&["}"], |_, ps| {
+        Some(InputEvent::NextParagraph(prefix_num(ps, 1)))
+    });
+    register.add_key_events(&["{"], |_, ps| {
+        Some(InputEvent::PrevParagraph(prefix_num(ps, 1)))
+    });
+    // Repeat the last jump, with the count typed before `.` if there is one
+    register.add_key_events(&["."], |_, ps| {
+        let ev = ps.last_repeatable?;
+        Some(
+            ps.prefix_num
+                .parse::<usize>()
+                .map_or(ev, |count| ev.with_count(count)),
+        )
+    });
+    // Go to top.
+    register.add_key_events(&["g"], |_, _| Some(InputEvent::UpdateUpperMark(0)));
+    // Go to bottom.
+    register.add_key_events(&["G"], |_, ps| {
+        let mut position = prefix_num(ps, usize::MAX)
+            // Reduce 1 here, because line numbering starts from 1
+            // while upper_mark starts from 0
+            .saturating_sub(1);
+        if position == 0 {
+            position = usize::MAX;
+        }
+        if ps.scroll_past_end {
+            // Show the last page in full, even if the text can be scrolled past its end
+            position = position.min(ps.num_lines().saturating_sub(ps.content_rows()));
+        }
+        Some(InputEvent::UpdateUpperMark(position))
+    });
 
-            // Page Up/Down
-            Event::Key(KeyEvent {
-                code: KeyCode::PageUp,
-                modifiers: KeyModifiers::NONE,
-            }) => Some(InputEvent::UpdateUpperMark(
-                ps.upper_mark.saturating_sub(ps.rows - 1),
-            )),
-            Event::Key(KeyEvent {
-                code: c,
-                modifiers: KeyModifiers::NONE,
-            }) if c == KeyCode::PageDown || c == KeyCode::Char(' ') => Some(
-                InputEvent::UpdateUpperMark(ps.upper_mark.saturating_add(ps.rows - 1)),
-            ),
+    // Page Up/Down
+    register.add_key_events(&["pageup"], |_, ps| {
+        Some(InputEvent::UpdateUpperMark(
+            ps.upper_mark.saturating_sub(ps.page_step()),
+        ))
+    });
+    register.add_key_events(&["pagedown", "space"], |_, ps| {
+        Some(InputEvent::UpdateUpperMark(
+            ps.upper_mark.saturating_add(ps.page_step()),
+        ))
+    });
 
-            // Resize event from the terminal.
-            Event::Resize(cols, rows) => {
-                Some(InputEvent::UpdateTermArea(cols as usize, rows as usize))
-            }
-            // Switch line number display.
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('l'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => Some(InputEvent::UpdateLineNumber(!ps.line_numbers)),
-            // Quit.
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('q'),
-                modifiers: KeyModifiers::NONE,
-            })
-            | Event::Key(KeyEvent {
-                code: KeyCode::Char('c'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => Some(InputEvent::Exit),
-            #[cfg(feature = "search")]
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('/'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(InputEvent::Search(SearchMode::Forward)),
-            #[cfg(feature = "search")]
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('?'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(InputEvent::Search(SearchMode::Reverse)),
-            #[cfg(feature = "search")]
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('n'),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
-                if ps.search_mode == SearchMode::Reverse {
-                    Some(InputEvent::MoveToPrevMatch(position))
-                } else {
-                    Some(InputEvent::MoveToNextMatch(position))
-                }
+    // Scroll left/right by half screen width, or by full screen width with Shift.
+    register.add_key_events(&["left", "right", "s-left", "s-right"], |ev, ps| {
+        let Event::Key(KeyEvent { code, modifiers }) = ev else {
+            return None;
+        };
+        let step = if modifiers == KeyModifiers::SHIFT {
+            ps.cols
+        } else {
+            ps.cols / 2
+        };
+        let step = step.saturating_mul(prefix_num(ps, 1));
+        Some(InputEvent::UpdateLeftMark(if code == KeyCode::Left {
+            ps.left_mark.saturating_sub(step)
+        } else {
+            ps.left_mark.saturating_add(step)
+        }))
+    });
+
+    // Resize event from the terminal.
+    register.add_resize_event(|ev, _| match ev {
+        Event::Resize(cols, rows) => Some(InputEvent::UpdateTermArea(cols as usize, rows as usize)),
+        _ => None,
+    });
+    // Switch line number display.
+    register.add_key_events(&["c-l"], |_, ps| {
+        Some(InputEvent::UpdateLineNumber(!ps.line_numbers))
+    });
+    // Turn line numbers on or off.
+    register.add_key_events(&["m-l"], |_, _| {
+        Some(InputEvent::UpdateLineNumber(LineNumbers::Enabled))
+    });
+    register.add_key_events(&["m-s-l"], |_, _| {
+        Some(InputEvent::UpdateLineNumber(LineNumbers::Disabled))
+    });
+    // Switch between wrapping and truncating lines.
+    register.add_key_events(&["c-s"], |_, ps| {
+        Some(InputEvent::UpdateLineWrapping(!ps.line_wrapping))
+    });
+    // Draw everything again.
+    register.add_key_events(&["c-r"], |_, _| Some(InputEvent::Redraw));
+    // Switch between buffers.
+    register.add_key_events(&["c-n"], |_, _| Some(InputEvent::NextBuffer));
+    register.add_key_events(&["c-p"], |_, _| Some(InputEvent::PrevBuffer));
+    // Show or hide tabs and trailing spaces.
+    register.add_key_events(&["c-w"], |_, ps| {
+        Some(InputEvent::UpdateShowWhitespace(!ps.show_whitespace))
+    });
+
+    // A pending count is cancelled first, then the selection and then the search
+    register.add_key_events(&["esc"], |_, ps| {
+        if !ps.prefix_num.is_empty() {
+            return Some(InputEvent::ClearPrefix);
+        }
+        if ps.selection_anchor.is_some() {
+            return Some(InputEvent::ClearSelection);
+        }
+        #[cfg(feature = "search")]
+        return Some(InputEvent::ClearSearch);
+        #[cfg(not(feature = "search"))]
+        Some(InputEvent::ClearPrefix)
+    });
+    register.add_key_events(&["v"], |_, ps| {
+        ps.cursor_line.map(|_| InputEvent::ToggleSelection)
+    });
+    // Yank the selected lines, or copy the screen if nothing is selected
+    register.add_key_events(&["y"], |_, ps| {
+        if ps.selection_anchor.is_some() {
+            return Some(InputEvent::YankSelection);
+        }
+        #[cfg(feature = "clipboard")]
+        return Some(InputEvent::CopyVisible);
+        #[cfg(not(feature = "clipboard"))]
+        None
+    });
+
+    #[cfg(feature = "search")]
+    {
+        register.add_key_events(&["/"], |_, _| Some(InputEvent::Search(SearchMode::Forward)));
+        register.add_key_events(&["?"], |_, _| Some(InputEvent::Search(SearchMode::Reverse)));
+        register.add_key_events(&["n"], |_, ps| {
+            let position = prefix_num(ps, 1);
+            if ps.search_mode == SearchMode::Reverse {
+                Some(InputEvent::MoveToPrevMatch(position))
+            } else {
+                Some(InputEvent::MoveToNextMatch(position))
             }
-            #[cfg(feature = "search")]
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('p'),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                let position = ps.prefix_num.parse::<usize>().unwrap_or(1);
-                if ps.search_mode == SearchMode::Reverse {
-                    Some(InputEvent::MoveToNextMatch(position))
-                } else {
-                    Some(InputEvent::MoveToPrevMatch(position))
-                }
+        });
+        register.add_key_events(&["p"], |_, ps| {
+            let position = prefix_num(ps, 1);
+            if ps.search_mode == SearchMode::Reverse {
+                Some(InputEvent::MoveToNextMatch(position))
+            } else {
+                Some(InputEvent::MoveToPrevMatch(position))
             }
-            _ => None,
-        }
+        });
+        register.add_key_events(&["M"], |_, ps| {
+            Some(InputEvent::GotoMatch(prefix_num(ps, 1)))
+        });
+        register.add_key_events(&["m-u"], |_, ps| {
+            Some(InputEvent::UpdateHighlightVisible(!ps.highlight_visible))
+        });
+        register.add_key_events(&["m-f"], |_, ps| {
+            Some(InputEvent::UpdateSearchFocus(!ps.search_focus))
+        });
+        register.add_key_events(&[")"], |_, ps| {
+            Some(InputEvent::NextHighlight(
+                ps.prefix_num
+                    .parse::<usize>()
+                    .ok()
+                    .map(|n| n.saturating_sub(1)),
+            ))
+        });
+        // `]]` and `[[` move between sections
+        register.add_key_events(&["]"], |_, ps| {
+            Some(if ps.pending_key == Some(']') {
+                InputEvent::NextSection(prefix_num(ps, 1))
+            } else {
+                InputEvent::PendingKey(']')
+            })
+        });
+        register.add_key_events(&["["], |_, ps| {
+            Some(if ps.pending_key == Some('[') {
+                InputEvent::PrevSection(prefix_num(ps, 1))
+            } else {
+                InputEvent::PendingKey('[')
+            })
+        });
     }
+
+    #[cfg(feature = "clipboard")]
+    register.add_key_events(&["Y"], |_, _| Some(InputEvent::CopyLineRef));
 }
+
 #[cfg(test)]
 mod tests;
@@ -1,7 +1,12 @@
 #[cfg(feature = "search")]
 use crate::SearchMode;
-use crate::{input::InputEvent, LineNumbers, PagerState};
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use crate::{
+    input::{definitions::keydefs::parse_key_event, InputEvent},
+    LineNumbers, PagerState,
+};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 
 // Just a transparent function to fix incompatiblity issues between
 // versions
@@ -97,6 +102,22 @@ fn test_kb_nav() {
         );
     }
 
+    {
+        // With scrolling past the end, the last page is shown in full
+        let mut pager = PagerState::new().unwrap();
+        pager.lines = "line\n".repeat(20);
+        pager.format_lines();
+        pager.scroll_past_end = true;
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('G'),
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateUpperMark(11)),
+            handle_input(ev, &pager)
+        );
+    }
+
     {
         let ev = Event::Key(KeyEvent {
             code: KeyCode::PageDown,
@@ -160,6 +181,20 @@ fn test_kb_nav() {
     }
 }
 
+#[test]
+fn test_goto_percent() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('%'),
+        modifiers: KeyModifiers::NONE,
+    });
+    // Nothing happens without a number
+    assert_eq!(None, handle_input(ev, &pager));
+
+    pager.prefix_num = "50".to_string();
+    assert_eq!(Some(InputEvent::GotoPercent(50)), handle_input(ev, &pager));
+}
+
 #[test]
 fn test_restore_prompt() {
     let mut pager = PagerState::new().unwrap();
@@ -212,6 +247,151 @@ fn test_mouse_nav() {
     }
 }
 
+#[test]
+fn test_exit_keys() {
+    let mut pager = PagerState::new().unwrap();
+    let key = |code, modifiers| Event::Key(KeyEvent { code, modifiers });
+
+    pager.exit_keys = vec![parse_key_event("esc"), parse_key_event("s-x")];
+    assert_eq!(
+        Some(InputEvent::Exit),
+        handle_input(key(KeyCode::Esc, KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::Exit),
+        handle_input(key(KeyCode::Char('X'), KeyModifiers::SHIFT), &pager)
+    );
+    // The default exit keys no longer quit
+    assert_eq!(
+        None,
+        handle_input(key(KeyCode::Char('q'), KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        None,
+        handle_input(key(KeyCode::Char('c'), KeyModifiers::CONTROL), &pager)
+    );
+
+    // Exit keys take over other bindings
+    pager.exit_keys = vec![parse_key_event("j")];
+    assert_eq!(
+        Some(InputEvent::Exit),
+        handle_input(key(KeyCode::Char('j'), KeyModifiers::NONE), &pager)
+    );
+
+    pager.exit_keys.clear();
+    assert_eq!(
+        None,
+        handle_input(key(KeyCode::Char('q'), KeyModifiers::NONE), &pager)
+    );
+}
+
+#[test]
+fn test_dump_exit_keys() {
+    let mut pager = PagerState::new().unwrap();
+    let key = |code, modifiers| Event::Key(KeyEvent { code, modifiers });
+
+    assert_eq!(
+        Some(InputEvent::ExitAndDump),
+        handle_input(key(KeyCode::Char('Q'), KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::ExitAndDump),
+        handle_input(key(KeyCode::Char('q'), KeyModifiers::SHIFT), &pager)
+    );
+
+    // Exit keys win if a key is in both lists
+    pager.dump_exit_keys = vec![parse_key_event("q"), parse_key_event("x")];
+    assert_eq!(
+        Some(InputEvent::Exit),
+        handle_input(key(KeyCode::Char('q'), KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::ExitAndDump),
+        handle_input(key(KeyCode::Char('x'), KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        None,
+        handle_input(key(KeyCode::Char('Q'), KeyModifiers::NONE), &pager)
+    );
+}
+
+#[test]
+fn test_gutter_click() {
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = (1..=20)
+        .map(|i| format!("Line {i}"))
+        .collect::<Vec<String>>()
+        .join("\n");
+    pager.line_numbers = LineNumbers::Enabled;
+    pager.cols = 80;
+    pager.rows = 5;
+    pager.format_lines();
+    pager.upper_mark = 10;
+
+    let click = |column, row| {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            row,
+            column,
+            modifiers: KeyModifiers::NONE,
+        })
+    };
+
+    // The gutter is 9 columns wide: 2 digits, 5 for padding and the `.` with a space
+    assert_eq!(
+        Some(InputEvent::ToggleGutterMark(12)),
+        handle_input(click(8, 2), &pager)
+    );
+    // Clicks on the text or the prompt are ignored
+    assert_eq!(None, handle_input(click(9, 2), &pager));
+    assert_eq!(None, handle_input(click(0, 4), &pager));
+
+    pager.line_numbers = LineNumbers::Disabled;
+    assert_eq!(None, handle_input(click(0, 2), &pager));
+}
+
+#[test]
+fn test_link_click() {
+    let mut pager = PagerState::new().unwrap();
+    pager.lines =
+        "see \x1b]8;;https://example.com\x1b\\the docs\x1b]8;;\x1b\\ for more".to_string();
+    pager.cols = 80;
+    pager.rows = 5;
+    pager.format_lines();
+
+    let click = |column, row| {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            row,
+            column,
+            modifiers: KeyModifiers::NONE,
+        })
+    };
+
+    assert_eq!(
+        Some(InputEvent::ActivateLink(0, 4)),
+        handle_input(click(4, 0), &pager)
+    );
+    // Clicks next to the link or below the text are ignored
+    assert_eq!(None, handle_input(click(3, 0), &pager));
+    assert_eq!(None, handle_input(click(12, 0), &pager));
+    assert_eq!(None, handle_input(click(4, 1), &pager));
+
+    // Lines that are scrolled horizontally are clicked where they are displayed
+    pager.line_wrapping = false;
+    pager.left_mark = 4;
+    pager.format_lines();
+    assert_eq!(
+        Some(InputEvent::ActivateLink(0, 0)),
+        handle_input(click(0, 0), &pager)
+    );
+    assert_eq!(
+        Some("https://example.com".to_string()),
+        pager.hyperlink_at(0, 7)
+    );
+    assert_eq!(None, handle_input(click(8, 0), &pager));
+}
+
 #[test]
 fn test_saturation() {
     let mut pager = PagerState::new().unwrap();
@@ -278,6 +458,36 @@ fn test_misc_events() {
         );
     }
 
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('l'),
+            modifiers: KeyModifiers::ALT,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateLineNumber(LineNumbers::Enabled)),
+            handle_input(ev, &pager)
+        );
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('L'),
+            modifiers: KeyModifiers::ALT | KeyModifiers::SHIFT,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateLineNumber(LineNumbers::Disabled)),
+            handle_input(ev, &pager)
+        );
+    }
+
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+        });
+        assert_eq!(
+            Some(InputEvent::UpdateLineWrapping(!pager.line_wrapping)),
+            handle_input(ev, &pager)
+        );
+    }
+
     {
         let ev = Event::Key(KeyEvent {
             code: KeyCode::Char('q'),
@@ -375,4 +585,765 @@ fn test_search_bindings() {
             Some(InputEvent::MoveToNextMatch(1))
         );
     }
+
+    {
+        let ev = Event::Key(KeyEvent {
+            code: KeyCode::Esc,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(Some(InputEvent::ClearSearch), handle_input(ev, &pager));
+    }
+}
+
+#[test]
+#[cfg(feature = "search")]
+fn test_next_highlight() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char(')'),
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(
+        Some(InputEvent::NextHighlight(None)),
+        handle_input(ev, &pager)
+    );
+
+    // The number selects the group, starting from 1
+    pager.prefix_num = "2".to_string();
+    assert_eq!(
+        Some(InputEvent::NextHighlight(Some(1))),
+        handle_input(ev, &pager)
+    );
+}
+
+#[test]
+#[cfg(feature = "clipboard")]
+fn test_copy_visible() {
+    let pager = PagerState::new().unwrap();
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('y'),
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(Some(InputEvent::CopyVisible), handle_input(ev, &pager));
+    let ev = Event::Key(parse_key_event("Y"));
+    assert_eq!(Some(InputEvent::CopyLineRef), handle_input(ev, &pager));
+}
+
+#[test]
+fn test_horizontal_scroll() {
+    let mut pager = PagerState::new().unwrap();
+    pager.cols = 80;
+    pager.left_mark = 100;
+
+    let key = |code, modifiers| Event::Key(KeyEvent { code, modifiers });
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(140)),
+        handle_input(key(KeyCode::Right, KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(60)),
+        handle_input(key(KeyCode::Left, KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(180)),
+        handle_input(key(KeyCode::Right, KeyModifiers::SHIFT), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(20)),
+        handle_input(key(KeyCode::Left, KeyModifiers::SHIFT), &pager)
+    );
+
+    // A count multiplies the step
+    pager.prefix_num = "2".to_string();
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(180)),
+        handle_input(key(KeyCode::Right, KeyModifiers::NONE), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(0)),
+        handle_input(key(KeyCode::Left, KeyModifiers::SHIFT), &pager)
+    );
+}
+
+#[test]
+fn test_show_whitespace() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('w'),
+        modifiers: KeyModifiers::CONTROL,
+    });
+    assert_eq!(
+        Some(InputEvent::UpdateShowWhitespace(true)),
+        handle_input(ev, &pager)
+    );
+    pager.show_whitespace = true;
+    assert_eq!(
+        Some(InputEvent::UpdateShowWhitespace(false)),
+        handle_input(ev, &pager)
+    );
+}
+
+#[test]
+fn test_register_overrides() {
+    use crate::input::{HashedEventRegister, InputClassifier};
+
+    let mut register = HashedEventRegister::with_default_bindings();
+    register.add_key_events(&["j"], |_, ps| {
+        Some(InputEvent::UpdateUpperMark(ps.upper_mark.saturating_add(2)))
+    });
+    register.remove_key_events(&["k"]);
+    register.add_mouse_events(&["c-scroll:up"], |_, _| {
+        Some(InputEvent::UpdateUpperMark(0))
+    });
+
+    let mut pager = PagerState::new().unwrap();
+    pager.upper_mark = 12;
+    let key = |c| {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+        })
+    };
+    let scroll_up = |modifiers| {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::ScrollUp,
+            row: 0,
+            column: 0,
+            modifiers,
+        })
+    };
+
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(14)),
+        register.classify_input(key('j'), &pager)
+    );
+    assert_eq!(None, register.classify_input(key('k'), &pager));
+    // Other bindings are kept
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        register.classify_input(key('g'), &pager)
+    );
+    // Exit keys always quit
+    register.add_key_events(&["q"], |_, _| None);
+    assert_eq!(
+        Some(InputEvent::Exit),
+        register.classify_input(key('q'), &pager)
+    );
+    // Mouse bindings without modifiers are used unless the modifiers are bound separately
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(7)),
+        register.classify_input(scroll_up(KeyModifiers::ALT), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        register.classify_input(scroll_up(KeyModifiers::CONTROL), &pager)
+    );
+
+    // An empty register ignores everything but the exit keys
+    let register = HashedEventRegister::new();
+    assert_eq!(None, register.classify_input(key('j'), &pager));
+    assert_eq!(
+        Some(InputEvent::Exit),
+        register.classify_input(key('q'), &pager)
+    );
+}
+
+#[test]
+fn test_count_prefix_disabled() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(KeyEvent {
+        code: KeyCode::Char('5'),
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(Some(InputEvent::Number('5')), handle_input(ev, &pager));
+
+    pager.count_prefix_enabled = false;
+    assert_eq!(None, handle_input(ev, &pager));
+}
+
+#[test]
+fn test_page_overlap() {
+    let mut pager = PagerState::new().unwrap();
+    pager.upper_mark = 20;
+    pager.rows = 10;
+    let page_down = Event::Key(KeyEvent {
+        code: KeyCode::PageDown,
+        modifiers: KeyModifiers::NONE,
+    });
+    let page_up = Event::Key(KeyEvent {
+        code: KeyCode::PageUp,
+        modifiers: KeyModifiers::NONE,
+    });
+
+    // By default, paging moves by the rows above the prompt
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(29)),
+        handle_input(page_down, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(11)),
+        handle_input(page_up, &pager)
+    );
+    pager.page_overlap = 0;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(30)),
+        handle_input(page_down, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(10)),
+        handle_input(page_up, &pager)
+    );
+    pager.page_overlap = 3;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(27)),
+        handle_input(page_down, &pager)
+    );
+    // Keeping all but one row of the previous page in view
+    pager.page_overlap = pager.content_rows();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(21)),
+        handle_input(page_down, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(19)),
+        handle_input(page_up, &pager)
+    );
+    // The status bar takes a row from the page
+    pager.page_overlap = 1;
+    pager.status_bar = Some(Box::new(|_| String::new()));
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(28)),
+        handle_input(page_down, &pager)
+    );
+    pager.status_bar = None;
+    // Paging still makes progress if the overlap is larger than the screen
+    pager.page_overlap = 20;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(21)),
+        handle_input(page_down, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(19)),
+        handle_input(page_up, &pager)
+    );
+}
+
+#[test]
+#[cfg(feature = "search")]
+fn test_toggle_highlights() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(parse_key_event("m-u"));
+    assert_eq!(
+        Some(InputEvent::UpdateHighlightVisible(false)),
+        handle_input(ev, &pager)
+    );
+    pager.highlight_visible = false;
+    assert_eq!(
+        Some(InputEvent::UpdateHighlightVisible(true)),
+        handle_input(ev, &pager)
+    );
+}
+
+#[test]
+#[cfg(feature = "search")]
+fn test_toggle_search_focus() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(parse_key_event("m-f"));
+    assert_eq!(
+        Some(InputEvent::UpdateSearchFocus(true)),
+        handle_input(ev, &pager)
+    );
+    pager.search_focus = true;
+    assert_eq!(
+        Some(InputEvent::UpdateSearchFocus(false)),
+        handle_input(ev, &pager)
+    );
+}
+
+#[test]
+#[cfg(feature = "search")]
+fn test_goto_match() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(parse_key_event("M"));
+    assert_eq!(Some(InputEvent::GotoMatch(1)), handle_input(ev, &pager));
+    pager.prefix_num = "7".to_string();
+    assert_eq!(Some(InputEvent::GotoMatch(7)), handle_input(ev, &pager));
+}
+
+#[test]
+fn test_redraw() {
+    let pager = PagerState::new().unwrap();
+    let ev = Event::Key(parse_key_event("c-r"));
+    assert_eq!(Some(InputEvent::Redraw), handle_input(ev, &pager));
+}
+
+#[test]
+fn test_paragraph_keys() {
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(parse_key_event("}"));
+    assert_eq!(Some(InputEvent::NextParagraph(1)), handle_input(ev, &pager));
+    pager.prefix_num = "3".to_string();
+    let ev = Event::Key(parse_key_event("{"));
+    assert_eq!(Some(InputEvent::PrevParagraph(3)), handle_input(ev, &pager));
+}
+
+#[test]
+fn test_drag_scroll() {
+    let mut pager = PagerState::new().unwrap();
+    let mouse = |kind, row| {
+        Event::Mouse(MouseEvent {
+            kind,
+            row,
+            column: 0,
+            modifiers: KeyModifiers::NONE,
+        })
+    };
+    let down = mouse(MouseEventKind::Down(MouseButton::Left), 3);
+    let drag = mouse(MouseEventKind::Drag(MouseButton::Left), 5);
+    let up = mouse(MouseEventKind::Up(MouseButton::Left), 5);
+
+    // Dragging is ignored unless it is enabled
+    assert_eq!(None, handle_input(down, &pager));
+    assert_eq!(None, handle_input(drag, &pager));
+    assert_eq!(None, handle_input(up, &pager));
+
+    pager.drag_scroll = true;
+    assert_eq!(Some(InputEvent::StartDrag(3)), handle_input(down, &pager));
+    assert_eq!(Some(InputEvent::Drag(5)), handle_input(drag, &pager));
+    assert_eq!(Some(InputEvent::EndDrag), handle_input(up, &pager));
+}
+
+#[test]
+fn test_esc_cancels_count() {
+    let mut pager = PagerState::new().unwrap();
+    pager.prefix_num = "25".to_string();
+    let ev = Event::Key(parse_key_event("esc"));
+    assert_eq!(Some(InputEvent::ClearPrefix), handle_input(ev, &pager));
+    #[cfg(feature = "search")]
+    {
+        pager.prefix_num.clear();
+        assert_eq!(Some(InputEvent::ClearSearch), handle_input(ev, &pager));
+    }
+}
+
+#[test]
+fn test_register_validates_bindings() {
+    use crate::input::{HashedEventRegister, InputClassifier};
+
+    let mut register = HashedEventRegister::new();
+    let pager = PagerState::new().unwrap();
+    let bind = |_: Event, _: &PagerState| Some(InputEvent::Redraw);
+
+    // Nothing is bound if one of the descriptions is invalid
+    assert!(register
+        .try_add_key_events(&["x", "super-x"], bind)
+        .is_err());
+    let ev = Event::Key(parse_key_event("x"));
+    assert_eq!(None, register.classify_input(ev, &pager));
+
+    assert!(register.try_add_key_events(&["x", "c-s-x"], bind).is_ok());
+    assert_eq!(
+        Some(InputEvent::Redraw),
+        register.classify_input(ev, &pager)
+    );
+    assert!(register
+        .try_add_mouse_events(&["left:click"], bind)
+        .is_err());
+}
+
+#[test]
+fn test_named_bindings() {
+    use crate::input::{HashedEventRegister, InputClassifier};
+
+    let mut register = HashedEventRegister::with_default_bindings();
+    let pager = PagerState::new().unwrap();
+
+    register.add_key_events_named("redraw", &["r", "c-l"], |_, _| Some(InputEvent::Redraw));
+    assert_eq!(register.bindings_for("redraw"), ["c-l", "r"]);
+    assert_eq!(
+        Some(InputEvent::Redraw),
+        register.classify_input(Event::Key(parse_key_event("c-l")), &pager)
+    );
+    // Default and unnamed bindings have no name
+    assert!(register.bindings_for("quit").is_empty());
+
+    // Rebinding or removing a key drops it from the name
+    register.add_key_events(&["r"], |_, _| None);
+    assert_eq!(register.bindings_for("redraw"), ["c-l"]);
+    register.remove_key_events(&["c-l"]);
+    assert!(register.bindings_for("redraw").is_empty());
+}
+
+#[test]
+fn test_scroll_wrap() {
+    let mut pager = PagerState::new().unwrap();
+    pager.rows = 5;
+    pager.lines = "line\n".repeat(20);
+    pager.format_lines();
+    let up = Event::Key(parse_key_event("k"));
+    let down = Event::Key(parse_key_event("j"));
+
+    // Without wrapping, scrolling stops at the top and the view is only clamped at the bottom
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        handle_input(up, &pager)
+    );
+    pager.upper_mark = 16;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(17)),
+        handle_input(down, &pager)
+    );
+
+    pager.scroll_wrap = true;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        handle_input(down, &pager)
+    );
+    pager.upper_mark = 0;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(16)),
+        handle_input(up, &pager)
+    );
+    // Paging doesn't wrap
+    let page_up = Event::Key(parse_key_event("pageup"));
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        handle_input(page_up, &pager)
+    );
+}
+
+#[test]
+#[cfg(feature = "search")]
+fn test_custom_search_keys() {
+    use crate::input::{HashedEventRegister, InputClassifier};
+
+    let mut pager = PagerState::new().unwrap();
+    pager.search_keys = ('s', 'S');
+    let key = |desc| Event::Key(parse_key_event(desc));
+
+    assert_eq!(
+        Some(InputEvent::Search(SearchMode::Forward)),
+        handle_input(key("s"), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::Search(SearchMode::Reverse)),
+        handle_input(key("s-s"), &pager)
+    );
+    assert_eq!(None, handle_input(key("/"), &pager));
+
+    // The search keys go through the bindings of `/` and `?` in a register
+    let mut register = HashedEventRegister::with_default_bindings();
+    register.add_key_events(&["/"], |_, _| Some(InputEvent::Redraw));
+    register.remove_key_events(&["?"]);
+    assert_eq!(
+        Some(InputEvent::Redraw),
+        register.classify_input(key("s"), &pager)
+    );
+    assert_eq!(None, register.classify_input(key("s-s"), &pager));
+    assert_eq!(None, register.classify_input(key("/"), &pager));
+}
+
+#[test]
+fn test_mouse_scroll_amounts() {
+    let mut pager = PagerState::new().unwrap();
+    pager.upper_mark = 20;
+    pager.left_mark = 20;
+    pager.mouse_scroll_amount = 3;
+    pager.horizontal_mouse_scroll_amount = 8;
+    let scroll = |kind, modifiers| {
+        Event::Mouse(MouseEvent {
+            kind,
+            row: 0,
+            column: 0,
+            modifiers,
+        })
+    };
+    let up = scroll(MouseEventKind::ScrollUp, KeyModifiers::NONE);
+    let down = scroll(MouseEventKind::ScrollDown, KeyModifiers::NONE);
+    let left = scroll(MouseEventKind::ScrollUp, KeyModifiers::SHIFT);
+    let right = scroll(MouseEventKind::ScrollDown, KeyModifiers::SHIFT);
+
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(17)),
+        handle_input(up, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(23)),
+        handle_input(down, &pager)
+    );
+    // Sideways scrolling moves the text vertically while lines are wrapped
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(17)),
+        handle_input(left, &pager)
+    );
+    pager.line_wrapping = false;
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(12)),
+        handle_input(left, &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateLeftMark(28)),
+        handle_input(right, &pager)
+    );
+}
+
+#[test]
+fn test_content_rows() {
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "line\n".repeat(100);
+    pager.format_lines();
+    pager.upper_mark = 20;
+    pager.rows = 11;
+    pager.scroll_past_end = true;
+    let key = |code| {
+        Event::Key(KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+        })
+    };
+
+    // The prompt takes up the last row
+    assert_eq!(pager.content_rows(), 10);
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(30)),
+        handle_input(key(KeyCode::PageDown), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(25)),
+        handle_input(key(KeyCode::Char('d')), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(90)),
+        handle_input(key(KeyCode::Char('G')), &pager)
+    );
+
+    // The status bar takes up the row above it
+    pager.status_bar = Some(Box::new(|_| "status".to_string()));
+    assert_eq!(pager.content_rows(), 9);
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(29)),
+        handle_input(key(KeyCode::PageDown), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(11)),
+        handle_input(key(KeyCode::PageUp), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(16)),
+        handle_input(key(KeyCode::Char('u')), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(91)),
+        handle_input(key(KeyCode::Char('G')), &pager)
+    );
+}
+
+#[test]
+fn test_cursor_mode() {
+    let mut pager = PagerState::new().unwrap();
+    pager.upper_mark = 10;
+    pager.cursor_line = Some(12);
+    pager.prefix_num = "3".to_string();
+    let key = |desc| Event::Key(parse_key_event(desc));
+
+    // Line movements move the cursor
+    assert_eq!(
+        Some(InputEvent::UpdateCursor(15)),
+        handle_input(key("j"), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::UpdateCursor(9)),
+        handle_input(key("up"), &pager)
+    );
+    // Other movements still scroll the text
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        handle_input(key("g"), &pager)
+    );
+
+    pager.cursor_line = None;
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(13)),
+        handle_input(key("j"), &pager)
+    );
+}
+
+#[test]
+fn test_selection_keys() {
+    let mut pager = PagerState::new().unwrap();
+    let key = |desc| Event::Key(parse_key_event(desc));
+
+    // Selecting needs a cursor
+    assert_eq!(None, handle_input(key("v"), &pager));
+    pager.cursor_line = Some(0);
+    assert_eq!(
+        Some(InputEvent::ToggleSelection),
+        handle_input(key("v"), &pager)
+    );
+
+    pager.selection_anchor = Some(0);
+    assert_eq!(
+        Some(InputEvent::YankSelection),
+        handle_input(key("y"), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::ClearSelection),
+        handle_input(key("esc"), &pager)
+    );
+    // A pending count is discarded first
+    pager.prefix_num = "2".to_string();
+    assert_eq!(
+        Some(InputEvent::ClearPrefix),
+        handle_input(key("esc"), &pager)
+    );
+}
+
+#[test]
+fn test_enter_action() {
+    let mut pager = PagerState::new().unwrap();
+    pager.lines = "line\n".repeat(30);
+    pager.format_lines();
+    pager.upper_mark = 10;
+    let enter = Event::Key(parse_key_event("enter"));
+
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(11)),
+        handle_input(enter, &pager)
+    );
+    pager.enter_action = crate::EnterAction::EmitSelect;
+    assert_eq!(Some(InputEvent::Select(10)), handle_input(enter, &pager));
+    pager.cursor_line = Some(14);
+    assert_eq!(Some(InputEvent::Select(14)), handle_input(enter, &pager));
+    pager.enter_action = crate::EnterAction::Ignore;
+    assert_eq!(None, handle_input(enter, &pager));
+
+    // Dismissing a message takes precedence
+    pager.message = Some("message".to_string());
+    assert_eq!(Some(InputEvent::RestorePrompt), handle_input(enter, &pager));
+}
+
+#[test]
+#[cfg(feature = "search")]
+fn test_section_keys() {
+    let mut pager = PagerState::new().unwrap();
+    let key = |desc| Event::Key(parse_key_event(desc));
+
+    // The first key only waits for the second one
+    assert_eq!(
+        Some(InputEvent::PendingKey(']')),
+        handle_input(key("]"), &pager)
+    );
+    pager.pending_key = Some(']');
+    assert_eq!(
+        Some(InputEvent::NextSection(1)),
+        handle_input(key("]"), &pager)
+    );
+    assert_eq!(
+        Some(InputEvent::PendingKey('[')),
+        handle_input(key("["), &pager)
+    );
+    pager.pending_key = Some('[');
+    pager.prefix_num = "3".to_string();
+    assert_eq!(
+        Some(InputEvent::PrevSection(3)),
+        handle_input(key("["), &pager)
+    );
+
+    // Going through the pager state, the count is kept for the second key and any other key
+    // discards the first one
+    let mut pager = PagerState::new().unwrap();
+    pager.classify_input(key("2"));
+    pager.classify_input(key("]"));
+    assert_eq!(pager.prefix_num, "2");
+    assert_eq!(
+        Some(InputEvent::NextSection(2)),
+        pager.classify_input(key("]"))
+    );
+    assert_eq!(pager.pending_key, None);
+    pager.classify_input(key("]"));
+    pager.classify_input(key("j"));
+    assert_eq!(
+        Some(InputEvent::PendingKey(']')),
+        pager.classify_input(key("]"))
+    );
+}
+
+#[test]
+fn test_register_unmatched_handlers() {
+    use crate::input::{HashedEventRegister, InputClassifier};
+
+    let mut register = HashedEventRegister::new();
+    register.add_key_events(&["j"], |_, _| Some(InputEvent::Redraw));
+    let pager = PagerState::new().unwrap();
+    let key = |desc| Event::Key(parse_key_event(desc));
+    let click = Event::Mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        row: 0,
+        column: 0,
+        modifiers: KeyModifiers::NONE,
+    });
+    let resize = Event::Resize(10, 10);
+
+    register.set_unmatched_handler(|_, _| Some(InputEvent::UpdateUpperMark(0)));
+    register.set_unmatched_key_handler(|_, _| None);
+    // Unbound keys go to their own fallback, bound ones are left alone
+    assert_eq!(None, register.classify_input(key("x"), &pager));
+    assert_eq!(
+        Some(InputEvent::Redraw),
+        register.classify_input(key("j"), &pager)
+    );
+    // Other kinds go to the general fallback
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        register.classify_input(click, &pager)
+    );
+    register.set_unmatched_resize_handler(|_, _| Some(InputEvent::RestorePrompt));
+    assert_eq!(
+        Some(InputEvent::RestorePrompt),
+        register.classify_input(resize, &pager)
+    );
+
+    // Exit keys still quit
+    assert_eq!(
+        Some(InputEvent::Exit),
+        register.classify_input(key("q"), &pager)
+    );
+
+    // Removing a fallback goes back to the general one
+    register.remove_unmatched_key_handler();
+    assert_eq!(
+        Some(InputEvent::UpdateUpperMark(0)),
+        register.classify_input(key("x"), &pager)
+    );
+}
+
+#[test]
+fn test_register_guarded_bindings() {
+    use crate::input::{HashedEventRegister, InputClassifier};
+
+    let mut register = HashedEventRegister::new();
+    register.add_key_event_guarded(
+        "x",
+        |ps| ps.message.is_some(),
+        |_, _| Some(InputEvent::RestorePrompt),
+    );
+    register.set_unmatched_key_handler(|_, _| Some(InputEvent::Redraw));
+    let mut pager = PagerState::new().unwrap();
+    let ev = Event::Key(parse_key_event("x"));
+
+    // Without a message the guard fails and the key is treated as unbound
+    assert_eq!(
+        Some(InputEvent::Redraw),
+        register.classify_input(ev, &pager)
+    );
+    pager.message = Some("message".to_string());
+    assert_eq!(
+        Some(InputEvent::RestorePrompt),
+        register.classify_input(ev, &pager)
+    );
+
+    // Binding the key again drops the guard
+    register.add_key_events(&["x"], |_, _| Some(InputEvent::Exit));
+    pager.message = None;
+    assert_eq!(Some(InputEvent::Exit), register.classify_input(ev, &pager));
 }
@@ -70,8 +70,13 @@
 //! Add minus as a dependency in your `Cargo.toml` file and enable features as you like.
 //! * If you only want a pager to display static data, enable the `static_output` feature
 //! * If you want a pager to display dynamic data and be configurable at runtime, enable the `dynamic_output`
-//! feature
+//!   feature
 //! * If you want search support inside the pager, you need to enable the `search` feature
+//! * If you want to page a file and keep up with the lines written to it, like `tail -f`, enable the
+//!   `follow_file` feature
+//! * If you want to copy the text on the screen to the clipboard, enable the `clipboard` feature
+//! * If you want syntax highlighting for source code, enable the `syntect` feature. See
+//!   [`highlight`] for adding other highlighters.
 //! ```toml
 //! [dependencies.minus]
 //! version = "^5.0"
@@ -177,6 +182,7 @@
 //! | Action            | Description                                                                                                               |
 //! |-------------------|---------------------------------------------------------------------------------------------------------------------------|
 //! | Ctrl+C/q          | Quit the pager                                                                                                            |
+//! | Q                 | Quit the pager and write the rest of the text to the main screen                                                          |
 //! | \[n\] Arrow Up/k    | Scroll up by n number of line(s). If n is omitted, scroll up by 1 line                                                    |
 //! | \[n\] Arrow Down/j  | Scroll down by n number of line(s). If n is omitted, scroll down by 1 line                                                |
 //! | Page Up           | Scroll up by entire page                                                                                                  |
@@ -187,16 +193,46 @@
 //! | Ctrl+D/d          | Scroll down by half a screen                                                                                              |
 //! | g                 | Go to the very top of the output                                                                                          |
 //! | \[n\] G             | Go to the very bottom of the output. If n is present, goes to that line                                                   |
+//! | n %               | Go to n percent of the output                                                                                             |
+//! | \[n\] }             | Go to the blank line after the next paragraph. If n is present, moves by n paragraphs                                     |
+//! | \[n\] {             | Go to the blank line before the previous paragraph. If n is present, moves by n paragraphs                                |
+//! | \[n\] .             | Repeat the last jump, like a search match, paragraph or page jump. If n is present, it replaces the count of the jump      |
 //! | Mouse scroll Up   | Scroll up by 5 lines                                                                                                      |
 //! | Mouse scroll Down | Scroll down by 5 lines                                                                                                    |
+//! | Shift+Scroll      | Scroll left or right by 5 columns while lines are truncated                                                               |
+//! | Drag              | Scroll the text along with the mouse, if drag scrolling is enabled                                                        |
+//! | Click line number | Toggle the mark on that line                                                                                              |
+//! | Click hyperlink   | Pass the URI of the link to the application                                                                               |
 //! | Ctrl+L            | Toggle line numbers if not forced enabled/disabled                                                                        |
+//! | Alt+L             | Turn line numbers on if not forced enabled/disabled                                                                       |
+//! | Alt+Shift+L       | Turn line numbers off if not forced enabled/disabled                                                                      |
+//! | Ctrl+S            | Toggle between wrapping and truncating long lines                                                                         |
+//! | Ctrl+W            | Toggle markers for tabs and trailing spaces                                                                               |
+//! | Ctrl+R            | Redraw the screen, for when other programs have messed it up                                                              |
+//! | Ctrl+N            | Show the next buffer                                                                                                      |
+//! | Ctrl+P            | Show the previous buffer                                                                                                  |
+//! | \[n\] Arrow Left    | Scroll left by n half screens. If n is omitted, scroll left by half a screen                                              |
+//! | \[n\] Arrow Right   | Scroll right by n half screens. If n is omitted, scroll right by half a screen                                            |
+//! | \[n\] Shift+Left    | Scroll left by n entire screens. If n is omitted, scroll left by one screen                                               |
+//! | \[n\] Shift+Right   | Scroll right by n entire screens. If n is omitted, scroll right by one screen                                             |
 //! | /                 | Start forward search                                                                                                      |
 //! | ?                 | Start backward search                                                                                                     |
-//! | Esc               | Cancel search input                                                                                                       |
+//! | Esc               | Discard a typed count, otherwise stop selecting lines, cancel search input or clear the current search                    |
+//! | Ctrl+R            | Switch between regex and literal search while typing a search query                                                       |
+//! | Alt+U             | Hide or show the highlights of search matches, keeping the search                                                         |
+//! | Alt+F             | Dim or undim the lines without a search match                                                                             |
 //! | n                 | Go to the next search match                                                                                               |
 //! | p                 | Go to the next previous match                                                                                             |
+//! | \[n\] M             | Go to the nth search match, or the last one if there are fewer than n                                                     |
+//! | \[n\] )             | Go to the next line matching a highlight group. If n is present, only the nth group is considered                         |
+//! | \[n\] \]\]           | Go to the nth next line matching the section pattern. If n is omitted, go to the next one                                 |
+//! | \[n\] \[\[           | Go to the nth previous line matching the section pattern. If n is omitted, go to the previous one                         |
+//! | v                 | Start or stop selecting lines at the cursor, in cursor mode                                                               |
+//! | y                 | Yank the selected lines, otherwise copy the text on the screen to the clipboard                                           |
+//! | Y                 | Copy a reference to the line at the top of the screen, like its number, to the clipboard                                  |
 //!
-//! End-applications are free to change these bindings to better suit their needs.
+//! End-applications are free to change these bindings to better suit their needs. To change only
+//! some of them, start from [`HashedEventRegister::with_default_bindings`](input::HashedEventRegister::with_default_bindings).
 //!
 //! [`tokio`]: https://docs.rs/tokio
 //! [`async-std`]: https://docs.rs/async-std
@@ -205,13 +241,16 @@
 #[cfg(feature = "dynamic_output")]
 mod dynamic_pager;
 pub mod error;
+pub mod highlight;
 pub mod input;
 #[path = "core/mod.rs"]
 mod minus_core;
 mod pager;
+pub mod source;
 mod state;
 #[cfg(feature = "static_output")]
 mod static_pager;
+mod test_pager;
 
 #[cfg(feature = "dynamic_output")]
 pub use dynamic_pager::dynamic_paging;
@@ -220,11 +259,13 @@ pub use static_pager::page_all;
 
 #[cfg(feature = "search")]
 pub use minus_core::search::SearchMode;
+use minus_core::utils::text::{char_width, display_width_as, strip_ansi, with_hidden_hyperlinks};
 use std::string::ToString;
 
 pub use error::MinusError;
-pub use pager::Pager;
-pub use state::PagerState;
+pub use pager::{Pager, WriteSink};
+pub use state::{DynamicPrompt, LineStyler, PagerState};
+pub use test_pager::TestPager;
 
 /// A convenient type for `Vec<Box<dyn FnMut() + Send + Sync + 'static>>`
 pub type ExitCallbacks = Vec<Box<dyn FnMut() + Send + Sync + 'static>>;
@@ -248,6 +289,116 @@ pub enum ExitStrategy {
     PagerQuit,
 }
 
+/// Which lines are written to the main screen when the pager quits
+///
+/// Lines are only written when the user quits with a
+/// [dump exit key](crate::Pager::set_dump_exit_keys) or when
+/// [`Pager::set_exit_dump_remaining`](crate::Pager::set_exit_dump_remaining) is enabled. See
+/// [`Pager::set_exit_dump_scope`](crate::Pager::set_exit_dump_scope).
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum DumpScope {
+    /// Only the lines after the ones displayed on the screen are written, so that the text
+    /// continues seamlessly below what was last visible.
+    ///
+    /// **This is the default.**
+    #[default]
+    Remaining,
+    /// All of the text is written, from its first line.
+    Entire,
+}
+
+/// Whether static output is paged when it fits on the screen
+///
+/// See [`Pager::set_overflow_behavior`](crate::Pager::set_overflow_behavior).
+#[cfg(feature = "static_output")]
+#[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Default)]
+pub enum OverflowBehavior {
+    /// Always start the pager, even if the text fits on the screen.
+    ///
+    /// **This is the default.**
+    #[default]
+    AlwaysPage,
+    /// Print the text and quit if it fits in the rows above the prompt, so that the text and
+    /// the prompt of the shell afterwards are both visible.
+    ///
+    /// The text fits if it has at most as many lines as the pager shows rows of text, which is
+    /// the height of the terminal minus the prompt, the status bar and any frozen header rows.
+    PrintIfFits,
+    /// Print the text and quit unless it has more lines than the terminal has rows.
+    ///
+    /// This compares against the full height of the terminal, so text of exactly that height is
+    /// printed even though the prompt of the shell afterwards scrolls its first line out of
+    /// view. It is what [`Pager::set_run_no_overflow`](crate::Pager::set_run_no_overflow) has
+    /// always done and is kept so that switching to
+    /// [`Pager::set_overflow_behavior`](crate::Pager::set_overflow_behavior) doesn't change when
+    /// the pager starts.
+    PageIfOverflow,
+}
+
+/// Why the pager exited
+///
+/// This is passed to the functions added with
+/// [`Pager::add_exit_callback_with_reason`](crate::Pager::add_exit_callback_with_reason).
+#[derive(PartialEq, Clone, Copy, Debug, Eq)]
+pub enum ExitReason {
+    /// The user quit the pager, for example by pressing `q`
+    UserQuit,
+    /// The pager quit by itself as all the text fit on the screen, or the input finished while
+    /// the end of the text was on the screen.
+    /// See [`Pager::set_overflow_behavior`](crate::Pager::set_overflow_behavior) and
+    /// [`Pager::set_exit_on_finish`](crate::Pager::set_exit_on_finish).
+    EndOfInput,
+    /// The pager quit because of an error, like failing to read input or to write to the terminal
+    Error,
+}
+
+/// How the screen was updated after text was appended to the pager
+///
+/// This is passed to the function set with
+/// [`Pager::set_append_feedback`](crate::Pager::set_append_feedback).
+#[derive(PartialEq, Clone, Copy, Debug, Eq)]
+pub enum AppendFeedback {
+    /// Only the new text was formatted, which is cheap.
+    PartialUpdate,
+    /// All of the text was formatted and drawn again, for example because the line numbers
+    /// became wider or the oldest lines were dropped. This gets expensive for large texts.
+    FullRedraw,
+}
+
+/// Something that happened in the pager
+///
+/// This is passed to the function set with
+/// [`Pager::set_event_observer`](crate::Pager::set_event_observer). New kinds of observations may
+/// be added in future versions.
+#[derive(PartialEq, Clone, Debug, Eq)]
+#[non_exhaustive]
+pub enum PagerObservation {
+    /// The screen scrolled so that `row` is at its top
+    ///
+    /// Rows are counted after wrapping, starting from 0.
+    Scrolled {
+        /// The row at the top of the screen
+        row: usize,
+    },
+    /// The user searched for `query`
+    Searched {
+        /// The query as the user typed it
+        query: String,
+        /// The number of rows with a match, 0 if nothing matched
+        matches: usize,
+    },
+    /// The terminal was resized
+    Resized {
+        /// The new number of columns
+        cols: usize,
+        /// The new number of rows
+        rows: usize,
+    },
+    /// The pager quit
+    Exited(ExitReason),
+}
+
 /// Enum indicating whether to display the line numbers or not.
 ///
 /// Note that displaying line numbers may be less performant than not doing it.
@@ -276,8 +427,7 @@ impl LineNumbers {
     /// Returns `true` if `self` can be inverted (i.e, `!self != self`), see
     /// the documentation for the variants to know if they are invertible or
     /// not.
-    #[allow(dead_code)]
-    const fn is_invertible(self) -> bool {
+    pub(crate) const fn is_invertible(self) -> bool {
         matches!(self, Self::Enabled | Self::Disabled)
     }
 }
@@ -296,12 +446,447 @@ impl std::ops::Not for LineNumbers {
     }
 }
 
+/// Side of the gutter that line numbers are aligned to
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum LineNumberAlignment {
+    /// Numbers start at the left edge of the gutter.
+    Left,
+    /// Numbers end right before the separator.
+    ///
+    /// **This is the default alignment.**
+    #[default]
+    Right,
+}
+
+/// What the gutter shows on the rows that continue a wrapped line
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum ContinuationNumberStyle {
+    /// Show the number of the line again.
+    Repeat,
+    /// Leave the gutter empty, without a separator.
+    ///
+    /// **This is the default style.**
+    #[default]
+    Blank,
+    /// Show the given character in place of the number, followed by the separator.
+    Marker(char),
+}
+
+/// Layout of the gutter in which line numbers are displayed
+///
+/// The gutter is made up of the line number followed by the separator. Numbers are padded to
+/// the number of digits of the last line number or to `min_width`, whichever is larger, so that
+/// the text of all lines starts in the same column.
+///
+/// # Example
+/// ```
+/// use minus::{LineNumberAlignment, LineNumberStyle};
+///
+/// let style = LineNumberStyle {
+///     separator: "│ ".to_string(),
+///     alignment: LineNumberAlignment::Left,
+///     min_width: 4,
+/// };
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LineNumberStyle {
+    /// Text placed between the line number and the line. Defaults to `". "`.
+    pub separator: String,
+    /// Alignment of the line numbers. Defaults to [`LineNumberAlignment::Right`].
+    pub alignment: LineNumberAlignment,
+    /// Minimum number of columns reserved for the line numbers. Defaults to 0.
+    pub min_width: usize,
+}
+
+impl Default for LineNumberStyle {
+    fn default() -> Self {
+        Self {
+            separator: ". ".to_string(),
+            alignment: LineNumberAlignment::default(),
+            min_width: 0,
+        }
+    }
+}
+
+/// How carriage returns (`\r`) that are not part of a line ending are displayed
+///
+/// Programs that show progress, like a progress bar, write `\r` to go back to the start of the
+/// line and draw over it. A `\r` right before a newline is always dropped.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum CrMode {
+    /// Carriage returns are passed on to the terminal as they are, which moves the cursor back to
+    /// the start of the row when the line is drawn.
+    ///
+    /// **This is the default.**
+    #[default]
+    Literal,
+    /// Text after a carriage return is written over the start of the line, like a terminal
+    /// does. Only the last state of a progress bar is displayed.
+    Overwrite,
+    /// Carriage returns are removed and the text around them is joined.
+    Strip,
+}
+
+/// Direction in which the text is laid out on the screen
+///
+/// This only changes the alignment of the text, the order of the characters inside each line is
+/// left to the terminal. Full bidirectional text layout is not supported.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum TextDirection {
+    /// Left-aligned text with line numbers on the left side of the screen.
+    ///
+    /// **This is the default direction.**
+    #[default]
+    Ltr,
+    /// Right-aligned text with line numbers on the right side of the screen. Useful for
+    /// right-to-left scripts like Arabic or Hebrew.
+    Rtl,
+}
+
+/// Where long lines are broken when they are wrapped onto the next rows
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum WrapMode {
+    /// Break lines at the last column, even in the middle of a word.
+    Character,
+    /// Break lines at the last whitespace that fits into the row. Words that are longer than a
+    /// row are broken at the last column.
+    ///
+    /// **This is the default mode.**
+    #[default]
+    Word,
+}
+
+/// Where the prompt is displayed
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum PromptPosition {
+    /// In the first row of the screen, above the text. The status bar stays below the text.
+    Top,
+    /// In the last row of the screen, below the text and the status bar.
+    ///
+    /// **This is the default position.**
+    #[default]
+    Bottom,
+}
+
+/// How the user is told that the text can't be scrolled further
+///
+/// This happens when scrolling up at the top of the text or down at its end.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum EdgeFeedback {
+    /// Nothing happens.
+    ///
+    /// **This is the default.**
+    #[default]
+    None,
+    /// The terminal bell rings.
+    Bell,
+    /// The colors of the screen are inverted for a moment.
+    Flash,
+}
+
+/// What the `Enter` key does while no message is shown
+///
+/// A message at the prompt is always dismissed by `Enter` first.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum EnterAction {
+    /// Scroll down by one line, or by the count typed before it.
+    ///
+    /// **This is the default action.**
+    #[default]
+    ScrollDown,
+    /// Select the current line, which is the line of the cursor in cursor mode and the line at
+    /// the top of the screen otherwise. The function set with
+    /// [`Pager::set_on_select`] is called with the index of the line.
+    EmitSelect,
+    /// Do nothing.
+    Ignore,
+}
+
+/// What happens to a double-width character that doesn't fit into the last column of a row
+///
+/// Such characters are always moved to the next row, as they can't be split. This only decides
+/// what is left in the column they don't fit into.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum WideCharWrap {
+    /// The column is left empty, so the row is one column shorter.
+    ///
+    /// **This is the default.**
+    #[default]
+    PushDown,
+    /// A space is put into the column, so the row fills all columns like the rows around it.
+    PadAndBreak,
+}
+
+/// How many columns characters of ambiguous width take up
+///
+/// Unicode leaves the width of some characters up to the context, like box drawing characters,
+/// circled numbers and the ellipsis. Terminals in Chinese, Japanese and Korean locales usually
+/// display them over two columns, others over one. If minus measures them differently
+/// than the terminal, rows are wrapped and aligned wrongly.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum AmbiguousWidth {
+    /// They take up one column.
+    ///
+    /// **This is the default.**
+    #[default]
+    Narrow,
+    /// They take up two columns.
+    Wide,
+}
+
 /// Wrap a line of string into a `Vec<String>` based on the number of columns
-pub(crate) fn wrap_str(line: &str, cols: usize) -> Vec<String> {
-    textwrap::wrap(line, cols)
-        .iter()
-        .map(ToString::to_string)
-        .collect::<Vec<String>>()
+///
+/// The rows after the first one are `indent` columns shorter, leaving space for something to be
+/// put in front of them. Characters of ambiguous width are measured as `ambiguous`.
+pub(crate) fn wrap_str(
+    line: &str,
+    cols: usize,
+    mode: WrapMode,
+    indent: usize,
+    ambiguous: AmbiguousWidth,
+) -> Vec<String> {
+    with_hidden_hyperlinks(line, |line| match mode {
+        WrapMode::Character => wrap_chars(line, cols, cols.saturating_sub(indent), ambiguous),
+        // textwrap can only measure them as narrow
+        WrapMode::Word if ambiguous == AmbiguousWidth::Wide => {
+            wrap_words(line, cols, cols.saturating_sub(indent), ambiguous)
+        }
+        WrapMode::Word => {
+            let indent = " ".repeat(indent);
+            let options = textwrap::Options::new(cols).subsequent_indent(&indent);
+            let mut rows = textwrap::wrap(line, options)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>();
+            // Only the space is needed, the indent itself is added by the caller
+            for row in rows.iter_mut().skip(1) {
+                row.drain(..indent.len());
+            }
+            rows
+        }
+    })
+}
+
+/// Wrap a line of string into rows at the spaces between words, like [`textwrap::wrap`] does
+///
+/// The first row has `cols` columns and all others `rest_cols`. Words that don't fit into a row
+/// of their own are broken like [`wrap_chars`] does. Unlike [`textwrap::wrap`], this measures
+/// characters of ambiguous width as `ambiguous`.
+fn wrap_words(line: &str, cols: usize, rest_cols: usize, ambiguous: AmbiguousWidth) -> Vec<String> {
+    use textwrap::core::Fragment;
+
+    let mut cols = cols.max(1);
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut width = 0;
+    // Spaces after the last word, which are dropped if the next word starts a new row
+    let mut spaces = 0;
+
+    for word in textwrap::core::find_words(line) {
+        let word_width = display_width_as(&word, ambiguous);
+        if width > 0 && width + spaces + word_width > cols {
+            rows.push(std::mem::take(&mut row));
+            width = 0;
+            cols = rest_cols.max(1);
+        } else {
+            row.extend(std::iter::repeat(' ').take(spaces));
+            width += spaces;
+        }
+        if width + word_width > cols {
+            // The row is empty here, as the word would have started a new row otherwise
+            let mut pieces = wrap_chars(&word, cols, rest_cols, ambiguous);
+            row = pieces.pop().unwrap_or_default();
+            if !pieces.is_empty() {
+                cols = rest_cols.max(1);
+            }
+            rows.extend(pieces);
+            width = display_width_as(&row, ambiguous);
+        } else {
+            row.push_str(&word);
+            width += word_width;
+        }
+        spaces = word.whitespace_width();
+    }
+    rows.push(row);
+    rows
+}
+
+/// Wrap a line of string into rows that fill all columns, breaking words anywhere
+///
+/// The first row has `cols` columns and all others `rest_cols`. ANSI escape sequences are
+/// copied as is and do not take up any column.
+fn wrap_chars(line: &str, cols: usize, rest_cols: usize, ambiguous: AmbiguousWidth) -> Vec<String> {
+    let mut cols = cols.max(1);
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut width = 0;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            row.push_str("\x1b[");
+            for esc_ch in chars.by_ref() {
+                row.push(esc_ch);
+                // The final byte of the sequence
+                if ('\x40'..='\x7e').contains(&esc_ch) {
+                    break;
+                }
+            }
+            continue;
+        }
+        let ch_width = char_width(ch, ambiguous);
+        if width + ch_width > cols && width > 0 {
+            rows.push(std::mem::take(&mut row));
+            width = 0;
+            cols = rest_cols.max(1);
+        }
+        width += ch_width;
+        row.push(ch);
+    }
+    rows.push(row);
+    rows
+}
+
+/// Fill the column left empty by a double-width character that was moved to the next row
+///
+/// `rows` are the rows that [`wrap_str`] returned for `cols` columns and `indent`. A row is only
+/// padded if it is one column short of full and the next row starts with a double-width
+/// character.
+pub(crate) fn pad_wide_breaks(
+    rows: &mut [String],
+    cols: usize,
+    indent: usize,
+    ambiguous: AmbiguousWidth,
+) {
+    for idx in 1..rows.len() {
+        let starts_wide = strip_ansi(&rows[idx])
+            .chars()
+            .next()
+            .map_or(false, |ch| char_width(ch, ambiguous) > 1);
+        let cols = if idx == 1 {
+            cols
+        } else {
+            cols.saturating_sub(indent)
+        };
+        let row = &mut rows[idx - 1];
+        if starts_wide && display_width_as(row, ambiguous) + 1 == cols {
+            row.push(' ');
+        }
+    }
+}
+
+/// Pad a line of string on the left so that it ends at the last of the given number of columns
+pub(crate) fn align_right(line: &str, cols: usize, ambiguous: AmbiguousWidth) -> String {
+    " ".repeat(cols.saturating_sub(display_width_as(line, ambiguous))) + line
+}
+
+/// Cut a line of string to fit into the given number of columns
+///
+/// If the line is longer than `cols`, it is cut and `marker` is placed in the last column to
+/// show that the line continues beyond the screen. ANSI escape sequences are copied as is and do
+/// not take up any column.
+pub(crate) fn truncate_str(
+    line: &str,
+    cols: usize,
+    marker: char,
+    ambiguous: AmbiguousWidth,
+) -> String {
+    with_hidden_hyperlinks(line, |line| {
+        vec![truncate_plain(line, cols, marker, ambiguous)]
+    })
+    .pop()
+    .unwrap_or_default()
+}
+
+/// [`truncate_str`] for lines without hyperlinks
+fn truncate_plain(line: &str, cols: usize, marker: char, ambiguous: AmbiguousWidth) -> String {
+    if display_width_as(line, ambiguous) <= cols {
+        return line.to_string();
+    }
+    let marker_width = char_width(marker, ambiguous);
+    let available = cols.saturating_sub(marker_width);
+
+    let mut truncated = String::with_capacity(line.len());
+    let mut width = 0;
+    let mut has_escapes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        // Copy ANSI escape sequences, so that any styling before the cut is kept intact
+        if ch == '\x1b' && chars.peek() == Some(&'[') {
+            has_escapes = true;
+            chars.next();
+            truncated.push_str("\x1b[");
+            for esc_ch in chars.by_ref() {
+                truncated.push(esc_ch);
+                // The final byte of the sequence
+                if ('\x40'..='\x7e').contains(&esc_ch) {
+                    break;
+                }
+            }
+            continue;
+        }
+        let ch_width = char_width(ch, ambiguous);
+        if width + ch_width > available {
+            break;
+        }
+        width += ch_width;
+        truncated.push(ch);
+    }
+    // Make sure that styles don't leak into the marker
+    if has_escapes {
+        truncated.push_str("\x1b[0m");
+    }
+    if marker_width <= cols {
+        truncated.push(marker);
+    }
+    truncated
+}
+
+/// Remove the first `cols` columns from a line of string
+///
+/// This is the counterpart of [`truncate_str`] for lines that are scrolled horizontally. ANSI
+/// escape sequences in the removed part are kept, so that styling which starts before the cut
+/// still applies to the rest of the line. A wide character that is cut in half is replaced by
+/// spaces.
+pub(crate) fn skip_columns(line: &str, cols: usize, ambiguous: AmbiguousWidth) -> String {
+    if cols == 0 {
+        return line.to_string();
+    }
+    with_hidden_hyperlinks(line, |line| vec![skip_plain(line, cols, ambiguous)])
+        .pop()
+        .unwrap_or_default()
+}
+
+/// [`skip_columns`] for lines without hyperlinks
+fn skip_plain(line: &str, cols: usize, ambiguous: AmbiguousWidth) -> String {
+    let mut skipped = String::with_capacity(line.len());
+    let mut width = 0;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((idx, ch)) = chars.next() {
+        if width >= cols {
+            skipped.push_str(&line[idx..]);
+            break;
+        }
+        if ch == '\x1b' && chars.peek().map(|(_, c)| *c) == Some('[') {
+            chars.next();
+            skipped.push_str("\x1b[");
+            for (_, esc_ch) in chars.by_ref() {
+                skipped.push(esc_ch);
+                if ('\x40'..='\x7e').contains(&esc_ch) {
+                    break;
+                }
+            }
+            continue;
+        }
+        width += char_width(ch, ambiguous);
+        if width > cols {
+            skipped.push_str(&" ".repeat(width - cols));
+        }
+    }
+    skipped
 }
 
 #[cfg(test)]
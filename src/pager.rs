@@ -1,6 +1,23 @@
-use crate::{error::MinusError, input, minus_core::events::Event, ExitStrategy, LineNumbers};
+#[cfg(feature = "static_output")]
+use crate::OverflowBehavior;
+use crate::{
+    error::MinusError,
+    highlight::Highlighter,
+    input::{self, definitions::keydefs::parse_key_event},
+    minus_core::events::Event,
+    minus_core::utils::text,
+    AmbiguousWidth, AppendFeedback, ContinuationNumberStyle, CrMode, DumpScope, DynamicPrompt,
+    EdgeFeedback, EnterAction, ExitReason, ExitStrategy, LineNumberStyle, LineNumbers, LineStyler,
+    PagerObservation, PagerState, PromptPosition, TextDirection, WideCharWrap, WrapMode,
+};
 use crossbeam_channel::{Receiver, Sender};
-use std::fmt;
+use crossterm::style::{Color, ContentStyle};
+use parking_lot::{Condvar, Mutex};
+use std::{
+    fmt, io,
+    sync::{Arc, Weak},
+    time::Duration,
+};
 
 /// A pager acts as a middleman for communication between the main application
 /// and the user with the core functions of minus
@@ -17,6 +34,27 @@ use std::fmt;
 pub struct Pager {
     pub(crate) tx: Sender<Event>,
     pub(crate) rx: Receiver<Event>,
+    /// State of the running pager, for reading from it. This is empty until the pager starts
+    /// and after it quits
+    pub(crate) state: Arc<Mutex<Weak<Mutex<PagerState>>>>,
+    /// Whether the pager has quit, see [`Pager::wait_for_exit`]
+    pub(crate) exit_signal: Arc<ExitSignal>,
+}
+
+/// Whether the pager has quit, along with a way to wait until it does
+#[derive(Default)]
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) struct ExitSignal {
+    exited: Mutex<bool>,
+    cvar: Condvar,
+}
+
+impl ExitSignal {
+    /// Sets whether the pager has quit and wakes up the threads waiting for it
+    pub(crate) fn set(&self, val: bool) {
+        *self.exited.lock() = val;
+        self.cvar.notify_all();
+    }
 }
 
 impl Pager {
@@ -29,7 +67,12 @@ impl Pager {
     #[must_use]
     pub fn new() -> Self {
         let (tx, rx) = crossbeam_channel::unbounded();
-        Self { tx, rx }
+        Self {
+            tx,
+            rx,
+            state: Arc::default(),
+            exit_signal: Arc::default(),
+        }
     }
 
     /// Set the output text to this `t`
@@ -51,6 +94,196 @@ impl Pager {
         Ok(self.tx.send(Event::SetData(s.into()))?)
     }
 
+    /// Replace the output text with `t` while keeping the view in place
+    ///
+    /// Unlike [`Pager::set_text`], the line that is at the top of the screen stays there, or the
+    /// last line if the new text has fewer lines. An active search is run again on the new text,
+    /// so moving between matches continues to work. This is useful for refreshing contents that
+    /// only change slightly, like a live preview.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_text("First version").expect("Failed to send data to the pager");
+    /// pager.set_text_keep_position("Second version").expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_text_keep_position(&self, s: impl Into<String>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetDataKeepPosition(s.into()))?)
+    }
+
+    /// Insert `lines` before the line at index `idx`
+    ///
+    /// The lines after `idx` move down to make room. Each item is a line of its own, without its
+    /// newline. If there are fewer than `idx` lines, the new lines are appended. The text that
+    /// is on the screen stays there, even if the lines are inserted above it, so this can be used
+    /// to add more context around a part of the text while the user is reading it.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_text("first\nlast\n").expect("Failed to send data to the pager");
+    /// pager.insert_lines(1, ["second", "third"]).expect("Failed to send data to the pager");
+    /// ```
+    pub fn insert_lines(
+        &self,
+        idx: usize,
+        lines: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<(), MinusError> {
+        let lines = lines.into_iter().map(Into::into).collect();
+        Ok(self.tx.send(Event::InsertLines(idx, lines))?)
+    }
+
+    /// Remove the lines whose indices are in `range`
+    ///
+    /// The lines after the range move up to close the gap. The range is clamped to the lines
+    /// there are, so an empty range or one that starts past the last line does nothing. The text
+    /// that is on the screen stays there if it is not removed. If the line at the top of the
+    /// screen is removed, the next remaining line takes its place.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_text("first\nsecond\nthird\nlast\n").expect("Failed to send data to the pager");
+    /// pager.delete_lines(1..3).expect("Failed to send data to the pager");
+    /// ```
+    pub fn delete_lines(&self, range: std::ops::Range<usize>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::DeleteLines(range))?)
+    }
+
+    /// Add another buffer with the text `s`
+    ///
+    /// Buffers are independent texts that the user can switch between, like the files given to
+    /// `less`. Each buffer remembers the position and search of the user. The new buffer is added
+    /// after the last one and the displayed buffer stays the same. While there is more than one
+    /// buffer, the prompt shows the position of the displayed one, for example `[2/4]`.
+    ///
+    /// Text that is appended, like with [`Pager::push_str`], goes to the buffer that was added
+    /// last, so that output can keep coming in while the user looks at another buffer. The other
+    /// functions that change the text, like [`Pager::set_text`], apply to the displayed buffer.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_text("Output of the first command").expect("Failed to send data to the pager");
+    /// pager.add_buffer("Output of the second command").expect("Failed to send data to the pager");
+    /// ```
+    pub fn add_buffer(&self, s: impl Into<String>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::AddBuffer(s.into()))?)
+    }
+
+    /// Display the next buffer, or the first one if the last buffer is displayed
+    ///
+    /// See [`Pager::add_buffer`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn next_buffer(&self) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::NextBuffer)?)
+    }
+
+    /// Display the previous buffer, or the last one if the first buffer is displayed
+    ///
+    /// See [`Pager::add_buffer`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn prev_buffer(&self) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::PrevBuffer)?)
+    }
+
+    /// Replace the output text with `bytes`, decoded as UTF-8
+    ///
+    /// Invalid byte sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`, so that output
+    /// of other processes can be passed on without validating it first. An incomplete multibyte
+    /// character at the end of `bytes` is completed by the bytes appended next with
+    /// [`Pager::push_bytes`]. Non-printable control characters are made visible, see
+    /// [`Pager::set_control_char_placeholder`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.set_text_bytes(b"Invalid bytes \xff are replaced".to_vec()).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_text_bytes(&self, bytes: impl Into<Vec<u8>>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetDataBytes(bytes.into()))?)
+    }
+
+    /// Appends `bytes`, decoded as UTF-8, to the pager output
+    ///
+    /// Invalid byte sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`. A multibyte
+    /// character may be split across several calls, its bytes are held back until it is complete.
+    /// Non-printable control characters are made visible, see
+    /// [`Pager::set_control_char_placeholder`]. See also [`Pager::write_sink`] for an
+    /// [`io::Write`] adapter.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// // An `é` split in two
+    /// pager.push_bytes(b"caf\xc3".to_vec()).expect("Failed to send data to the pager");
+    /// pager.push_bytes(b"\xa9\n".to_vec()).expect("Failed to send data to the pager");
+    /// ```
+    pub fn push_bytes(&self, bytes: impl Into<Vec<u8>>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::AppendDataBytes(bytes.into()))?)
+    }
+
+    /// Appends a line made of styled spans to the pager output
+    ///
+    /// This saves writing the escape sequences for styles by hand. Each span is shown with its
+    /// own style, the line is wrapped and searched just like text appended with
+    /// [`Pager::push_str`]. A newline is added after the last span, a newline within a span
+    /// starts a new line that keeps the style of the span. The text of the spans is shown
+    /// literally, an escape character in it is shown as `^[` instead of starting an escape
+    /// sequence.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::style::{ContentStyle, Stylize};
+    ///
+    /// let pager = minus::Pager::new();
+    /// pager
+    ///     .push_styled_line(vec![
+    ///         (ContentStyle::new().bold().red(), "error".to_string()),
+    ///         (ContentStyle::new(), ": file not found".to_string()),
+    ///     ])
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn push_styled_line(&self, spans: Vec<(ContentStyle, String)>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::AppendStyledLine(spans))?)
+    }
+
     /// Appends text to the pager output.
     ///
     /// You can also use [`write!`]/[`writeln!`] macros to append data to the pager.
@@ -79,7 +312,8 @@ impl Pager {
 
     /// Set line number configuration for the pager
     ///
-    /// See [`LineNumbers`] for available options
+    /// See [`LineNumbers`] for available options. With [`LineNumbers::AlwaysOn`] or
+    /// [`LineNumbers::AlwaysOff`] the user cannot turn line numbers on or off from the keyboard.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
@@ -96,36 +330,35 @@ impl Pager {
         Ok(self.tx.send(Event::SetLineNumbers(l))?)
     }
 
-    /// Set the text displayed at the bottom prompt
+    /// Set the separator, alignment and minimum width of the line numbers
     ///
-    /// # Panics
-    /// This function panics if the given text contains newline characters.
-    /// This is because, the pager reserves only one line for showing the prompt
-    /// and a newline will cause it to span multiple lines, breaking the display
+    /// See [`LineNumberStyle`] for the defaults. This only has a visible effect while line
+    /// numbers are displayed.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
     ///
-    /// Example
+    /// # Example
     /// ```
-    /// use minus::Pager;
+    /// use minus::{LineNumberAlignment, LineNumberStyle, Pager};
     ///
     /// let pager = Pager::new();
-    /// pager.set_prompt("my prompt").expect("Failed to send data to the pager");
+    /// let style = LineNumberStyle {
+    ///     separator: "│ ".to_string(),
+    ///     alignment: LineNumberAlignment::Left,
+    ///     min_width: 4,
+    /// };
+    /// pager.set_line_number_style(style).expect("Failed to send data to the pager");
     /// ```
-    pub fn set_prompt(&self, text: impl Into<String>) -> Result<(), MinusError> {
-        let text = text.into();
-        assert!(!text.contains('\n'), "Prompt cannot contain newlines");
-        Ok(self.tx.send(Event::SetPrompt(text))?)
+    pub fn set_line_number_style(&self, style: LineNumberStyle) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineNumberStyle(style))?)
     }
 
-    /// Display a temporary message at the prompt area
+    /// Set what the gutter shows on the rows that continue a wrapped line
     ///
-    /// # Panics
-    /// This function panics if the given text contains newline characters.
-    /// This is because, the pager reserves only one line for showing the prompt
-    /// and a newline will cause it to span multiple lines, breaking the display
+    /// See [`ContinuationNumberStyle`] for available options. This only has a visible effect
+    /// while line numbers are displayed and lines are wrapped.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
@@ -133,92 +366,101 @@ impl Pager {
     ///
     /// # Example
     /// ```
-    /// use minus::Pager;
+    /// use minus::{ContinuationNumberStyle, Pager};
     ///
     /// let pager = Pager::new();
-    /// pager.send_message("An error occurred").expect("Failed to send data to the pager");
+    /// pager.set_continuation_number_style(ContinuationNumberStyle::Marker('+'))
+    ///     .expect("Failed to send data to the pager");
     /// ```
-    pub fn send_message(&self, text: impl Into<String>) -> Result<(), MinusError> {
-        let text = text.into();
-        assert!(!text.contains('\n'), "Message cannot contain newlines");
-        Ok(self.tx.send(Event::SendMessage(text))?)
+    pub fn set_continuation_number_style(
+        &self,
+        style: ContinuationNumberStyle,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetContinuationNumberStyle(style))?)
     }
 
-    /// Set the default exit strategy.
+    /// Pause drawing to the terminal
     ///
-    /// This controls how the pager will behave when the user presses `q` or `Ctrl+C`.
-    /// See [`ExitStrategy`] for available options
+    /// All data and configuration sent to the pager is still processed while paused, but nothing
+    /// is drawn on the screen until [`Pager::resume`] is called. Wrapping a bulk load of data
+    /// between these two calls avoids redrawing the screen for every single append.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
     ///
+    /// # Example
     /// ```
-    /// use minus::{Pager, ExitStrategy};
+    /// use minus::Pager;
     ///
     /// let pager = Pager::new();
-    /// pager.set_exit_strategy(ExitStrategy::ProcessQuit).expect("Failed to send data to the pager");
+    /// pager.pause().expect("Failed to send data to the pager");
+    /// for i in 0..10_000 {
+    ///     pager.push_str(format!("{i}\n")).expect("Failed to send data to the pager");
+    /// }
+    /// pager.resume().expect("Failed to send data to the pager");
     /// ```
-    pub fn set_exit_strategy(&self, es: ExitStrategy) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::SetExitStrategy(es))?)
+    pub fn pause(&self) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::PauseRendering)?)
     }
 
-    /// Set whether to display pager if there's less data than
-    /// available screen height
+    /// Resume drawing to the terminal after [`Pager::pause`]
     ///
-    /// When this is set to false, the pager will simply print all the lines
-    /// to the main screen and immediately quit if the number of lines to
-    /// display is less than the available columns in the terminal.
-    /// Setting this to true will cause a full pager to start and display the data
-    /// even if there is less number of lines to display than available rows.
+    /// The entire screen is redrawn once to show all the changes made while paused.
     ///
-    /// This is only available in static output mode as the size of the data is
-    /// known beforehand.
-    /// In async output the pager can receive more data anytime
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn resume(&self) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::ResumeRendering)?)
+    }
+
+    /// Scroll so that the row at `line` is at the top of the screen
     ///
-    /// By default this is set to false
+    /// Rows are counted after wrapping, starting from 0. The view never scrolls past the last
+    /// page of the text.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
     ///
+    /// # Example
     /// ```
     /// use minus::Pager;
     ///
     /// let pager = Pager::new();
-    /// pager.set_run_no_overflow(true).expect("Failed to send data to the pager");
+    /// pager.scroll_to(100).expect("Failed to send data to the pager");
     /// ```
-    #[cfg(feature = "static_output")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
-    pub fn set_run_no_overflow(&self, val: bool) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::SetRunNoOverflow(val))?)
+    pub fn scroll_to(&self, line: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::ScrollTo(line))?)
     }
 
-    /// Set a custom input classifer function.
-    ///
-    /// When the pager encounters a user input, it calls the input classifer with
-    /// the event and [PagerState](crate::state::PagerState) as parameters.
+    /// Scroll to `line` as soon as the text has that many lines
     ///
-    /// A input classifier is a type implementing the [`InputClassifier`](input::InputClassifier)
-    /// trait. It only has one required function, [`InputClassifier::classify_input`](input::InputClassifier::classify_input)
-    /// which matches user input events and maps them to a [`InputEvent`](input::InputEvent)s.
+    /// Unlike [`Pager::scroll_to`], `line` is a line of the text, counted from 1 like the line
+    /// numbers shown by the pager, and not a row after wrapping. This makes it possible to open
+    /// the pager at a position like the location of a compiler error. If the text is not long
+    /// enough yet, the pager scrolls once enough text has been appended. Until then, and after
+    /// it has scrolled, the user can scroll freely.
     ///
-    /// See the [`InputHandler`](input::InputClassifier) trait for information about implementing
-    /// it.
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
     /// could not be sent to the receiver
-    pub fn set_input_classifier(
-        &self,
-        handler: Box<dyn input::InputClassifier + Send + Sync>,
-    ) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::SetInputClassifier(handler))?)
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_start_line(42).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_start_line(&self, line: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetStartLine(line))?)
     }
 
-    /// Adds a function that will be called when the user quits the pager
+    /// Scroll down by `delta` rows, or up if `delta` is negative
     ///
-    /// Multiple functions can be stored for calling when the user quits. These functions
-    /// run sequentially in the order they were added
+    /// Scrolling up stops at the top of the text.
     ///
     /// # Errors
     /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
@@ -228,29 +470,2849 @@ impl Pager {
     /// ```
     /// use minus::Pager;
     ///
-    /// fn hello() {
-    ///     println!("Hello");
-    /// }
-    ///
     /// let pager = Pager::new();
-    /// pager.add_exit_callback(Box::new(hello)).expect("Failed to send data to the pager");
+    /// pager.scroll_by(-5).expect("Failed to send data to the pager");
     /// ```
-    pub fn add_exit_callback(
-        &self,
-        cb: Box<dyn FnMut() + Send + Sync + 'static>,
-    ) -> Result<(), MinusError> {
-        Ok(self.tx.send(Event::AddExitCallback(cb))?)
+    pub fn scroll_by(&self, delta: isize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::ScrollBy(delta))?)
     }
-}
 
-impl Default for Pager {
-    fn default() -> Self {
-        Self::new()
+    /// Scroll to the top of the text
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn scroll_to_top(&self) -> Result<(), MinusError> {
+        self.scroll_to(0)
     }
-}
 
-impl fmt::Write for Pager {
-    fn write_str(&mut self, s: &str) -> fmt::Result {
-        self.push_str(s).map_err(|_| fmt::Error)
+    /// Scroll to the bottom of the text
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn scroll_to_bottom(&self) -> Result<(), MinusError> {
+        self.scroll_to(usize::MAX)
+    }
+
+    /// Returns a copy of the text of the displayed buffer
+    ///
+    /// This is the text as it was given to the pager, without any wrapping or formatting. It is
+    /// taken from the running pager at the time of the call and is empty if the pager has not
+    /// started yet or has already quit. The whole text is copied, which takes a while for large
+    /// texts.
+    ///
+    /// Like [`Pager::search_matches`], this must not be called from a function that the pager runs
+    /// itself.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// // The pager hasn't been started, hence there is no text to read
+    /// assert!(pager.buffer_text().is_empty());
+    /// ```
+    #[must_use]
+    pub fn buffer_text(&self) -> String {
+        self.state
+            .lock()
+            .upgrade()
+            .map(|ps| ps.lock().lines.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns a copy of the lines of the displayed buffer
+    ///
+    /// This works like [`Pager::buffer_text`], except that the text is split into its lines.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// assert!(pager.buffer_lines().is_empty());
+    /// ```
+    #[must_use]
+    pub fn buffer_lines(&self) -> Vec<String> {
+        self.state.lock().upgrade().map_or_else(Vec::new, |ps| {
+            ps.lock().lines.lines().map(String::from).collect()
+        })
+    }
+
+    /// Returns `true` if the first row of the text is displayed on the screen
+    ///
+    /// Like [`Pager::buffer_text`], this is taken from the running pager at the time of the call
+    /// and must not be called from a function that the pager runs itself. It returns `false` if
+    /// the pager has not started yet or has already quit.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// assert!(!pager.is_at_top());
+    /// ```
+    #[must_use]
+    pub fn is_at_top(&self) -> bool {
+        self.state
+            .lock()
+            .upgrade()
+            .map_or(false, |ps| ps.lock().is_at_top())
+    }
+
+    /// Returns `true` if the last row of the text is displayed on the screen
+    ///
+    /// This is the same check that decides when the function set with
+    /// [`Pager::set_on_reach_bottom`] runs, so it can be polled instead. Like
+    /// [`Pager::is_at_top`], it returns `false` if the pager is not running.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// assert!(!pager.is_at_bottom());
+    /// ```
+    #[must_use]
+    pub fn is_at_bottom(&self) -> bool {
+        self.state
+            .lock()
+            .upgrade()
+            .map_or(false, |ps| ps.lock().is_at_bottom())
+    }
+
+    /// Returns `true` if the pager has quit
+    ///
+    /// This becomes `true` once the function that ran the pager, like
+    /// [`dynamic_paging`](crate::dynamic_paging), has restored the terminal and is about to
+    /// return, whether the user quit, the text was printed without paging or an error occurred.
+    /// It is `false` before the pager has started and becomes `false` again if it is started
+    /// once more. See [`Pager::wait_for_exit`] to block until then instead of polling.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// assert!(!pager.has_exited());
+    /// ```
+    #[must_use]
+    pub fn has_exited(&self) -> bool {
+        *self.exit_signal.exited.lock()
+    }
+
+    /// Blocks the current thread until the pager has quit
+    ///
+    /// This is meant for running the pager on a background thread, while another thread has to
+    /// know when the user is done with it. It returns right away if the pager has already quit,
+    /// see [`Pager::has_exited`] for when that is the case. It also blocks if the pager has not
+    /// started yet, so it must not be called from the thread that is about to start it.
+    ///
+    /// With [`ExitStrategy::ProcessQuit`], the process is quit along with the pager, so this
+    /// never returns.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use minus::{ExitStrategy, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_strategy(ExitStrategy::PagerQuit).expect("Failed to send data to the pager");
+    /// let pager2 = pager.clone();
+    /// std::thread::spawn(move || minus::dynamic_paging(pager2));
+    ///
+    /// pager.wait_for_exit();
+    /// println!("The user quit the pager");
+    /// ```
+    pub fn wait_for_exit(&self) {
+        let mut exited = self.exit_signal.exited.lock();
+        while !*exited {
+            self.exit_signal.cvar.wait(&mut exited);
+        }
+    }
+
+    /// Draw the screen again without changing anything
+    ///
+    /// This is useful when the screen has been messed up by something outside of the pager, like
+    /// another program writing to the terminal. Users can do the same with `Ctrl+R`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.redraw().expect("Failed to send data to the pager");
+    /// ```
+    pub fn redraw(&self) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::Redraw)?)
+    }
+
+    /// Set the maximum size of the text held by the pager in bytes
+    ///
+    /// When the text grows beyond this size, the oldest lines are dropped and a message is shown
+    /// at the prompt. Enough lines are dropped to bring the text a quarter of the limit below it,
+    /// so that the text doesn't have to be reformatted on every append once the limit is reached.
+    /// Only whole lines are dropped and the last line is always kept. This is
+    /// useful for limiting the memory used by long running streams of output. Pass `None` to
+    /// remove the limit, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// // Keep at most 1 MiB of text
+    /// pager.set_max_buffer_bytes(Some(1024 * 1024)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_max_buffer_bytes(&self, max_bytes: Option<usize>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMaxBufferBytes(max_bytes))?)
+    }
+
+    /// Tell the pager that no more text will be sent
+    ///
+    /// This is only needed for quitting once the text has been read, see
+    /// [`Pager::set_exit_on_finish`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// pager.push_str("All of the text\n").expect("Failed to send data to the pager");
+    /// pager.finish_input().expect("Failed to send data to the pager");
+    /// ```
+    pub fn finish_input(&self) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::InputFinished)?)
+    }
+
+    /// Set whether the pager quits once all of the text has been read
+    ///
+    /// When enabled, the pager quits as soon as the application has called
+    /// [`Pager::finish_input`] and the end of the text is on the screen. If the user is still
+    /// reading further up when the input finishes, the pager quits once they scroll to the end.
+    /// A text that fits on the screen is therefore never paged. The pager quits with
+    /// [`ExitReason::EndOfInput`], the [`ExitStrategy`] still decides what happens afterwards.
+    ///
+    /// A [followed file](Pager::follow_file) can always grow, hence the input never finishes by
+    /// itself while following a file.
+    ///
+    /// By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_on_finish(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_exit_on_finish(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExitOnFinish(val))?)
+    }
+
+    /// Follow a file, showing the text appended to it as it is written
+    ///
+    /// This works like `tail -f`. The contents of the file are appended to the pager and the file
+    /// is then checked for changes a few times every second. Text written to the end of the file
+    /// is appended to the pager. If the file gets shorter or is replaced by a new file, as happens
+    /// when logs are rotated, the text in the pager is replaced by the new contents of the file and
+    /// the view moves back to the top.
+    ///
+    /// The file is followed on a separate thread while the pager runs. It is started along with
+    /// the pager and stopped when the pager quits.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::FollowFile)`](MinusError::FollowFile) if the
+    /// file cannot be opened or a [`Err(MinusError::Communication)`](MinusError::Communication) if
+    /// the data could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```no_run
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.follow_file("/var/log/syslog".into()).expect("Failed to follow the file");
+    /// ```
+    #[cfg(feature = "follow_file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "follow_file")))]
+    pub fn follow_file(&self, path: std::path::PathBuf) -> Result<(), MinusError> {
+        // Report a missing or unreadable file right away
+        std::fs::File::open(&path).map_err(MinusError::FollowFile)?;
+        Ok(self.tx.send(Event::FollowFile(path))?)
+    }
+
+    /// Set the minimum time between two redraws
+    ///
+    /// When the application sends text faster than the terminal can keep up with, redrawing the
+    /// screen on every change wastes a lot of CPU time. With an interval set, changes that arrive
+    /// within the interval after a redraw are applied right away but only drawn once, at the end
+    /// of the interval. User input is always drawn immediately. Pass `None` to redraw on every
+    /// change, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use std::time::Duration;
+    ///
+    /// let pager = Pager::new();
+    /// // Redraw at most 30 times per second
+    /// pager.set_redraw_interval(Some(Duration::from_millis(33)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_redraw_interval(&self, interval: Option<Duration>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetRedrawInterval(interval))?)
+    }
+
+    /// Set how long the terminal size must settle before the text is reformatted for it
+    ///
+    /// Dragging the edge of a terminal window resizes it many times in quick succession, and
+    /// reformatting a large text on every resize makes the pager lag behind. With a debounce set,
+    /// each resize only records the new size and the text is reformatted and redrawn once, after
+    /// no other resize has happened for the given duration. The final size is always honored.
+    /// Pass [`Duration::ZERO`] to reformat on every resize, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use std::time::Duration;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_resize_debounce(Duration::from_millis(100))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_resize_debounce(&self, debounce: Duration) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetResizeDebounce(debounce))?)
+    }
+
+    /// Set how long appended text is gathered before it is applied in one go
+    ///
+    /// Applications that stream their output in tiny pieces, like a word or a token at a time,
+    /// make the pager format and draw the text again for each piece. With a window set, text
+    /// that doesn't end a line is held back for at most that long and applied together with
+    /// what follows it, so the text is formatted and drawn once. Text that ends a line, and any
+    /// other event, applies everything that was held back right away, so the text always ends up
+    /// the same. Pass `None` to apply every append right away, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use std::time::Duration;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_append_coalesce(Some(Duration::from_millis(20)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_append_coalesce(&self, window: Option<Duration>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetAppendCoalesce(window))?)
+    }
+
+    /// Refresh the prompt at a regular interval, even while no events arrive
+    ///
+    /// Normally the pager only redraws when something happens, like the user pressing a key or
+    /// the application sending text. With an interval set, the prompt and the status bar are
+    /// computed again and redrawn at that cadence, which makes it possible to animate a
+    /// [dynamic prompt](Pager::set_dynamic_prompt), for example with a spinner while data is
+    /// loading. The text itself is not redrawn on a tick. Pass `None` to stop refreshing, which
+    /// is the default.
+    ///
+    /// Ticks only happen with dynamic paging and are ignored in
+    /// [deterministic mode](Pager::set_deterministic_mode).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use std::time::Duration;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_tick_interval(Some(Duration::from_millis(100)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_tick_interval(&self, interval: Option<Duration>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetTickInterval(interval))?)
+    }
+
+    /// Set how long a scroll by a page or more takes
+    ///
+    /// Large jumps, like going to the end of the text with `G`, are then drawn over several
+    /// frames instead of all at once, which makes it easier to follow where the view went.
+    /// Scrolls by less than a page always happen right away. Scrolling again while a smooth
+    /// scroll is running stops it where it is. Pass `None` to turn smooth scrolling off, which is
+    /// the default.
+    ///
+    /// Nothing is animated in [deterministic mode](Pager::set_deterministic_mode).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use std::time::Duration;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_smooth_scroll(Some(Duration::from_millis(150)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_smooth_scroll(&self, duration: Option<Duration>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSmoothScroll(duration))?)
+    }
+
+    /// Set whether the output should be independent of timing
+    ///
+    /// When enabled, nothing that is drawn depends on when events arrive. Currently this means
+    /// that the [redraw interval](Pager::set_redraw_interval), the
+    /// [resize debounce](Pager::set_resize_debounce), the
+    /// [append coalescing](Pager::set_append_coalesce) and the
+    /// [tick interval](Pager::set_tick_interval) are ignored and every change is drawn right
+    /// away. This makes the output stable enough to be compared against golden files in
+    /// snapshot tests. It is disabled by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_deterministic_mode(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_deterministic_mode(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetDeterministicMode(val))?)
+    }
+
+    /// Set the size of the terminal that the text is formatted for
+    ///
+    /// The pager normally asks the terminal for its size when it starts, which is not possible
+    /// when stdout is not a terminal, like in tests, in CI or when the output is redirected. In
+    /// that case the pager assumes a single row and column. This sets the size to use instead, so
+    /// that the text is wrapped to `cols` columns and `rows` rows are drawn. Together with
+    /// [`Pager::set_output`] and [`Pager::set_deterministic_mode`], this makes the output
+    /// independent of the terminal the process runs in.
+    ///
+    /// If the real terminal is resized later, its new size replaces the one given here.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_terminal_size(80, 24).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_terminal_size(&self, cols: usize, rows: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetTerminalSize(cols, rows))?)
+    }
+
+    /// Set the direction in which the text is laid out
+    ///
+    /// See [`TextDirection`] for available options
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Pager, TextDirection};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_text_direction(TextDirection::Rtl).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_text_direction(&self, dir: TextDirection) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetTextDirection(dir))?)
+    }
+
+    /// Set whether long lines should be wrapped or truncated
+    ///
+    /// When set to `false`, lines longer than the terminal width are cut at the edge of the screen
+    /// and a marker is displayed in the last column, similar to `less -S`. By default, lines are
+    /// wrapped. Users can toggle this with `Ctrl+S`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_line_wrapping(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_line_wrapping(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineWrapping(val))?)
+    }
+
+    /// Set where long lines are broken when they are wrapped
+    ///
+    /// By default, lines are broken between words. With [`WrapMode::Character`], each row is
+    /// filled up to the last column instead, even if that splits a word. This has no effect
+    /// while lines are truncated, see [`Pager::set_line_wrapping`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Pager, WrapMode};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_wrap_mode(WrapMode::Character).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_wrap_mode(&self, mode: WrapMode) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetWrapMode(mode))?)
+    }
+
+    /// Set what is left in the last column of a row when a double-width character doesn't fit
+    ///
+    /// Characters like those of Chinese, Japanese and Korean take up two columns and are moved
+    /// to the next row if only one column is left. By default, that column is left empty. With
+    /// [`WideCharWrap::PadAndBreak`], a space is put there so that the row fills all columns.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Pager, WideCharWrap};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_wide_char_wrap(WideCharWrap::PadAndBreak).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_wide_char_wrap(&self, val: WideCharWrap) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetWideCharWrap(val))?)
+    }
+
+    /// Set how many columns characters of ambiguous width take up
+    ///
+    /// Some characters, like box drawing characters, circled numbers and the ellipsis, are
+    /// displayed over two columns by terminals in Chinese, Japanese and Korean locales and over
+    /// one column elsewhere. This should match the terminal, so that rows are wrapped and
+    /// truncated where the terminal ends them. The default is [`AmbiguousWidth::Narrow`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{AmbiguousWidth, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_ambiguous_width(AmbiguousWidth::Wide).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_ambiguous_width(&self, val: AmbiguousWidth) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetAmbiguousWidth(val))?)
+    }
+
+    /// Set the text displayed at the start of each row that continues a wrapped line
+    ///
+    /// This works like the `showbreak` option of vim and makes it easy to tell whether a row
+    /// starts a new line or continues the one above, for example with `"↪ "`. The indicator
+    /// takes up columns of the text area, so less text fits into the rows it is put in front of.
+    /// With line numbers, it is placed after the blank gutter. Pass `None` to not show any
+    /// indicator, which is the default.
+    ///
+    /// # Panics
+    /// This function panics if the given text contains newline characters, since the indicator
+    /// must fit into a single row.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_wrap_indicator(Some("↪ ".to_string()))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_wrap_indicator(&self, indicator: Option<String>) -> Result<(), MinusError> {
+        assert!(
+            !indicator.as_ref().map_or(false, |text| text.contains('\n')),
+            "Wrap indicator cannot contain newlines"
+        );
+        Ok(self.tx.send(Event::SetWrapIndicator(indicator))?)
+    }
+
+    /// Join the lines of each paragraph and wrap them to the width of the terminal
+    ///
+    /// This is meant for text that has been wrapped by hand to a fixed width, which looks ragged
+    /// in a terminal of another width. Consecutive lines that are not blank are joined into one
+    /// line with single spaces between them, blank lines separate paragraphs and are kept. The
+    /// joined lines are then wrapped like any other line, as long as lines are wrapped at all.
+    ///
+    /// Everything else sees a paragraph as a single line: line numbers count paragraphs and
+    /// searches can match across the original line breaks. Turning this off again restores the
+    /// lines as they were given. It is turned off by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_reflow(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_reflow(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetReflow(val))?)
+    }
+
+    /// Set whether the pager displays colors and other styles
+    ///
+    /// When turned off, all styles are removed: those in the text itself, those of line stylers
+    /// and highlighters, and those of search matches, the prompt and the status bar. Rows that
+    /// would be highlighted, like the row of the cursor or of the current search match, get a
+    /// `>` in front of them instead.
+    ///
+    /// Colors are on by default, unless the `NO_COLOR` environment variable is set to a
+    /// non-empty value when the pager starts, see <https://no-color.org>. This overrides the
+    /// variable.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_color(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_color(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetColor(val))?)
+    }
+
+    /// Set whether a scrollbar is shown in the last column of the screen
+    ///
+    /// The scrollbar shows which part of the text is on the screen, relative to all of it. With
+    /// the `search` feature, rows of the text that contain search matches are marked on it too.
+    /// The text is one column narrower while the scrollbar is shown.
+    ///
+    /// This is off by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_scrollbar(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_scrollbar(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetScrollbar(val))?)
+    }
+
+    /// Set the largest number of columns that the text is wrapped to
+    ///
+    /// On a wider screen, the text is centered with blank margins on both sides, which makes
+    /// long lines of prose easier to read. Line numbers and the scrollbar are displayed outside
+    /// of this width, while the prompt and the status bar still span the whole screen. `None`
+    /// uses the whole width of the screen, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_max_content_width(Some(80)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_max_content_width(&self, width: Option<usize>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMaxContentWidth(width))?)
+    }
+
+    /// Show tabs and trailing spaces as visible markers
+    ///
+    /// Tabs are displayed as a dimmed `→` and spaces at the end of a line as a dimmed `·`. This can
+    /// also be toggled by the user with `Ctrl+W`. It is turned off by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_show_whitespace(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_show_whitespace(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetShowWhitespace(val))?)
+    }
+
+    /// Set the character displayed in place of non-printable control characters in bytes
+    ///
+    /// This applies to the bytes given with [`Pager::push_bytes`] and [`Pager::set_text_bytes`].
+    /// Control characters like `NUL` or `BEL` in them would mess up the terminal, so they are
+    /// shown in caret notation (`^@`) by default. Newlines, tabs and ANSI escape sequences are
+    /// passed through. Passing `None` restores the caret notation. A [`WriteSink`] has its own
+    /// [placeholder](WriteSink::set_control_char_placeholder).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_control_char_placeholder(Some('.')).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_control_char_placeholder(
+        &self,
+        placeholder: Option<char>,
+    ) -> Result<(), MinusError> {
+        Ok(self
+            .tx
+            .send(Event::SetControlCharPlaceholder(placeholder))?)
+    }
+
+    /// Set how carriage returns (`\r`) inside lines are displayed
+    ///
+    /// Piped output of programs with progress bars contains lines like `50%\r100%\n`. With
+    /// [`CrMode::Overwrite`], the text after each `\r` is written over the start of the line like
+    /// a terminal would, so that only `100%` is displayed. By default, carriage returns are
+    /// passed on to the terminal as they are, see [`CrMode`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{CrMode, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_carriage_return_mode(CrMode::Overwrite).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_carriage_return_mode(&self, mode: CrMode) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCarriageReturnMode(mode))?)
+    }
+
+    /// Allow scrolling the last line of the text up to the top of the screen
+    ///
+    /// By default the text can only be scrolled down until its last line is at the bottom of
+    /// the screen, so that the last page always fills the screen. With this enabled, the text can
+    /// be scrolled further, leaving empty rows below its end like `less` does. Going to the end
+    /// with `G` still shows the last page in full.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_scroll_past_end(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_scroll_past_end(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetScrollPastEnd(val))?)
+    }
+
+    /// Keep the first `lines` lines of the text at the top of the screen
+    ///
+    /// The rest of the text scrolls beneath them, like the header row of a spreadsheet. Scrolling,
+    /// paging and jumping to the start or end only move the text below the header. At least one
+    /// row is left for that text, so a header taller than the screen is cut off. Set this to 0 to
+    /// let the entire text scroll again, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_text("NAME  SIZE\nfoo   12\nbar   34\n").expect("Failed to send data to the pager");
+    /// pager.set_frozen_header_lines(1).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_frozen_header_lines(&self, lines: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetFrozenHeaderLines(lines))?)
+    }
+
+    /// Set how many rows less than the height of the screen a page movement scrolls by
+    ///
+    /// This applies to `PageUp`, `PageDown` and `Space`. The height counts the prompt row, so the
+    /// default of 1 makes paging neither repeat nor skip any lines. Larger values keep that many
+    /// lines minus one of the previous page in view for context, 0 scrolls one line further than
+    /// a page. Paging always scrolls by at least one line.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// // Keep the last two lines of the previous page in view
+    /// pager.set_page_overlap(3).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_page_overlap(&self, overlap: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPageOverlap(overlap))?)
+    }
+
+    /// Set the character displayed in the rows below the end of the text
+    ///
+    /// When the text does not fill the screen, or has been scrolled past its end with
+    /// [`Pager::set_scroll_past_end`], each empty row shows this character in a dim style, like
+    /// vi does. The default is `~`. Pass `None` to leave the rows blank.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_end_of_buffer_char(None).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_end_of_buffer_char(&self, c: Option<char>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetEndOfBufferChar(c))?)
+    }
+
+    /// Set the [`Highlighter`](crate::highlight::Highlighter) that splits each line into
+    /// styled runs of text
+    ///
+    /// The highlighter is run on every line whenever the text is formatted. Long lines are
+    /// wrapped by their text alone, so a run that is wrapped onto several rows keeps its style on
+    /// all of them. This works together with [`Pager::set_line_styler`] and search highlighting.
+    ///
+    /// See the [`highlight`](crate::highlight) module for an example.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn set_highlighter(
+        &self,
+        highlighter: Box<dyn Highlighter + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetHighlighter(highlighter))?)
+    }
+
+    /// Read the text from a [`LineSource`](crate::source::LineSource) as it is needed, in place
+    /// of the current text
+    ///
+    /// Only the lines that are about to be displayed are read, so the text can be generated as the
+    /// user scrolls. The lines that have been read are kept, so set a limit with
+    /// [`Pager::set_max_buffer_bytes`] for text that may not fit in memory. Setting the text with
+    /// [`Pager::set_text`] stops reading from the source. Text sent with [`Pager::push_str`] is
+    /// added after the lines that have been read so far.
+    ///
+    /// See the [`source`](crate::source) module for an example.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn set_line_source(
+        &self,
+        source: Box<dyn crate::source::LineSource + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineSource(source))?)
+    }
+
+    /// Set a function that picks the style of each line
+    ///
+    /// The function is given the index of a line, counting from 0, and its text. The returned
+    /// [`ContentStyle`](crossterm::style::ContentStyle) is applied to the entire line, including all rows it is wrapped onto,
+    /// while `None` leaves the line as it is. This makes it easy to color lines based on their
+    /// content, like errors in red and warnings in yellow, without adding ANSI escapes to the text.
+    ///
+    /// The function is called for every line whenever the text is formatted, so it should be
+    /// cheap.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::style::{ContentStyle, Stylize};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_line_styler(Box::new(|_, line| {
+    ///     if line.contains("ERROR") {
+    ///         Some(ContentStyle::new().red())
+    ///     } else if line.contains("WARN") {
+    ///         Some(ContentStyle::new().yellow())
+    ///     } else {
+    ///         None
+    ///     }
+    /// }))
+    /// .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_line_styler(&self, func: LineStyler) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineStyler(func))?)
+    }
+
+    /// Set the marker displayed at the end of truncated lines
+    ///
+    /// This is only used when line wrapping is turned off. See [`Pager::set_line_wrapping`]. The
+    /// default marker is `>`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_truncation_marker('…').expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_truncation_marker(&self, marker: char) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetTruncationMarker(marker))?)
+    }
+
+    /// Only format the lines that are about to be displayed
+    ///
+    /// Normally all of the text is formatted whenever it is set or the layout changes, which can
+    /// take a while for huge inputs. With lazy formatting, only the lines on the screen and a
+    /// small margin around them are formatted, so that even huge inputs are displayed right away.
+    ///
+    /// Lazy formatting does nothing while line wrapping is turned on, which it is by default, as
+    /// the rows taken up by wrapped lines are only known after formatting them. See
+    /// [`Pager::set_line_wrapping`]. It also does nothing while a search or highlight groups are
+    /// active, as those need to look at all of the text.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_line_wrapping(false).expect("Failed to send data to the pager");
+    /// pager.set_lazy_formatting(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_lazy_formatting(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLazyFormatting(val))?)
+    }
+
+    /// Set the text displayed at the bottom prompt
+    ///
+    /// # Panics
+    /// This function panics if the given text contains newline characters.
+    /// This is because, the pager reserves only one line for showing the prompt
+    /// and a newline will cause it to span multiple lines, breaking the display
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_prompt("my prompt").expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_prompt(&self, text: impl Into<String>) -> Result<(), MinusError> {
+        let text = text.into();
+        assert!(!text.contains('\n'), "Prompt cannot contain newlines");
+        Ok(self.tx.send(Event::SetPrompt(text))?)
+    }
+
+    /// Set the text displayed at the bottom prompt as a left and a right segment
+    ///
+    /// The left segment is displayed at the start of the prompt row and the right one at its end,
+    /// with the space in between filled up, like the file name and the position in the prompt of
+    /// `less`. If the row is too narrow for both, the left segment is cut off first. Like the text
+    /// given to [`Pager::set_prompt`], both segments are replaced by messages and by a
+    /// [dynamic prompt](Pager::set_dynamic_prompt). Calling [`Pager::set_prompt`] removes the
+    /// right segment.
+    ///
+    /// # Panics
+    /// This function panics if either segment contains newline characters, for the same reason as
+    /// [`Pager::set_prompt`]
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_prompt_segments("notes.txt", "lines 1-20")
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_prompt_segments(
+        &self,
+        left: impl Into<String>,
+        right: impl Into<String>,
+    ) -> Result<(), MinusError> {
+        let (left, right) = (left.into(), right.into());
+        assert!(
+            !left.contains('\n') && !right.contains('\n'),
+            "Prompt cannot contain newlines"
+        );
+        Ok(self.tx.send(Event::SetPromptSegments(left, right))?)
+    }
+
+    /// Set a function that computes the text displayed at the bottom prompt
+    ///
+    /// The function is given the current state of the pager and is called again every time the
+    /// prompt is displayed, which makes it possible to show live information like a clock or the
+    /// number of bytes received. Since it runs on every redraw, it should be cheap. While it is
+    /// set, it is used instead of the text given to [`Pager::set_prompt`]. Messages sent with
+    /// [`Pager::send_message`] are still displayed in its place until they are dismissed.
+    ///
+    /// Like [`Pager::set_prompt`], the returned text must not contain newline characters.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_dynamic_prompt(Box::new(|ps| format!("Line {}", ps.upper_mark + 1)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_dynamic_prompt(&self, func: DynamicPrompt) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetDynamicPrompt(func))?)
+    }
+
+    /// Set the style of the row that shows the prompt or a message
+    ///
+    /// The style applies to the whole row and is reset after it. The default is reverse video,
+    /// like `less` does. Use [`ContentStyle::new`] for a plain prompt.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use crossterm::style::{ContentStyle, Stylize};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_prompt_style(ContentStyle::new().bold().blue())
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_prompt_style(&self, style: ContentStyle) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPromptStyle(style))?)
+    }
+
+    /// Show a status bar with `text` in the row above the prompt
+    ///
+    /// Unlike the prompt, the status bar is not used for messages or search input, so it can
+    /// show information that should stay visible all the time. It takes up a row of its own,
+    /// hence one row less is available for the text while it is shown. Pass `None` to remove
+    /// the status bar, which is the default.
+    ///
+    /// The text must not contain newline characters and is cut off at the edge of the screen.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_status_bar(Some("build.log".to_string()))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_status_bar(&self, text: Option<String>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetStatusBar(text))?)
+    }
+
+    /// Set a function that computes the text of the status bar
+    ///
+    /// This shows the status bar like [`Pager::set_status_bar`], but its text is computed from
+    /// the current state of the pager every time it is displayed, the same way as with
+    /// [`Pager::set_dynamic_prompt`]. Use [`Pager::set_status_bar`] with `None` to remove it.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_dynamic_status_bar(Box::new(|ps| format!("{} rows", ps.rows)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_dynamic_status_bar(&self, func: DynamicPrompt) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetDynamicStatusBar(func))?)
+    }
+
+    /// Display a temporary message at the prompt area
+    ///
+    /// # Panics
+    /// This function panics if the given text contains newline characters.
+    /// This is because, the pager reserves only one line for showing the prompt
+    /// and a newline will cause it to span multiple lines, breaking the display
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.send_message("An error occurred").expect("Failed to send data to the pager");
+    /// ```
+    pub fn send_message(&self, text: impl Into<String>) -> Result<(), MinusError> {
+        let text = text.into();
+        assert!(!text.contains('\n'), "Message cannot contain newlines");
+        Ok(self.tx.send(Event::SendMessage(text))?)
+    }
+
+    /// Restore all display options to their defaults
+    ///
+    /// This resets line numbers, line wrapping along with its modes and indicator, the truncation
+    /// marker, the text direction, the gutter mark glyph and the highlighted row along with its
+    /// color. The text, the marks and the position in the text are kept.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{LineNumbers, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_line_numbers(LineNumbers::Enabled).expect("Failed to send data to the pager");
+    /// pager.set_line_wrapping(false).expect("Failed to send data to the pager");
+    /// // Go back to the defaults
+    /// pager.reset_display_options().expect("Failed to send data to the pager");
+    /// ```
+    pub fn reset_display_options(&self) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::ResetDisplayOptions)?)
+    }
+
+    /// Highlight the background of a row on the screen
+    ///
+    /// The row is counted from the top of the screen, starting from 0, and stays at the same
+    /// place while scrolling. This can be used to show the user which part of the text some
+    /// other part of the application refers to. Pass `None` to remove the highlight, which is
+    /// the default. The color can be changed with [`Pager::set_highlight_color`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// // Highlight the first row on the screen
+    /// pager.set_highlight_row(Some(0)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_highlight_row(&self, row: Option<usize>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetHighlightRow(row))?)
+    }
+
+    /// Set the background color of the row highlighted with [`Pager::set_highlight_row`]
+    ///
+    /// The default is [`Color::DarkGrey`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use crossterm::style::Color;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_highlight_color(Color::DarkBlue).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_highlight_color(&self, color: Color) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetHighlightColor(color))?)
+    }
+
+    /// Turn cursor mode on or off
+    ///
+    /// In cursor mode, a highlighted cursor row marks the current line. `Up`, `Down`, `j`, `k`
+    /// and `Enter` move the cursor instead of scrolling, and the text only scrolls once the
+    /// cursor reaches the top or bottom of the screen, see [`Pager::set_scroll_off`]. Other
+    /// movements scroll the text as usual and take the cursor along if it would leave the
+    /// screen. The cursor starts at the top of the screen. This is off by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_cursor_mode(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_cursor_mode(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCursorMode(val))?)
+    }
+
+    /// Set the background color of the cursor row in [cursor mode](Pager::set_cursor_mode)
+    ///
+    /// The default is [`Color::DarkBlue`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use crossterm::style::Color;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_cursor_color(Color::DarkGreen).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_cursor_color(&self, color: Color) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCursorColor(color))?)
+    }
+
+    /// Set how many rows are kept between the cursor and the top or bottom of the screen
+    ///
+    /// In [cursor mode](Pager::set_cursor_mode), the text scrolls once the cursor comes closer
+    /// to the edge of the screen than this, like the `scrolloff` option of Vim. Near the start
+    /// and end of the text the cursor can still reach the edge. The default is 0.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_scroll_off(3).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_scroll_off(&self, rows: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetScrollOff(rows))?)
+    }
+
+    /// Set the background color of the selected rows in [cursor mode](Pager::set_cursor_mode)
+    ///
+    /// Pressing `v` starts selecting lines at the cursor, see [`Pager::set_on_yank`]. The default
+    /// is [`Color::DarkGrey`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use crossterm::style::Color;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_selection_color(Color::DarkMagenta).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_selection_color(&self, color: Color) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSelectionColor(color))?)
+    }
+
+    /// Set the number of lines that a turn of the mouse wheel scrolls by
+    ///
+    /// By default this is set to 5
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_mouse_scroll_amount(3).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_mouse_scroll_amount(&self, lines: usize) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMouseScrollAmount(lines))?)
+    }
+
+    /// Set the number of columns that scrolling the mouse wheel sideways scrolls by
+    ///
+    /// Terminals report a sideways turn of the wheel, as well as turning it with `Shift` held, as
+    /// vertical scrolling with `Shift` held. While long lines are truncated, this scrolls the text
+    /// left and right. While they are wrapped, it scrolls up and down like the plain wheel.
+    ///
+    /// By default this is set to 5
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_horizontal_mouse_scroll_amount(8).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_horizontal_mouse_scroll_amount(&self, columns: usize) -> Result<(), MinusError> {
+        Ok(self
+            .tx
+            .send(Event::SetHorizontalMouseScrollAmount(columns))?)
+    }
+
+    /// Set whether scrolling by lines wraps around the ends of the text
+    ///
+    /// When enabled, scrolling down by a line at the end of the text continues from the top and
+    /// scrolling up at the top continues from the end. This applies to the keys that scroll by
+    /// lines, like `j`, `k`, `Up` and `Down`, but not to paging or jumping to either end. It is
+    /// disabled by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_scroll_wrap(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_scroll_wrap(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetScrollWrap(val))?)
+    }
+
+    /// Set whether dragging the mouse scrolls the text
+    ///
+    /// When enabled, holding the left mouse button on the text and moving the mouse drags the
+    /// text along, like dragging a document. This needs [mouse capture](Pager::set_mouse_capture).
+    /// It is disabled by default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_drag_scroll(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_drag_scroll(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetDragScroll(val))?)
+    }
+
+    /// Set whether mouse events should be captured
+    ///
+    /// Mouse capture is needed for scrolling with the mouse wheel and clicking on line numbers,
+    /// but while it is enabled, text cannot be selected with the terminal's native selection. It
+    /// is enabled by default. This can be changed while the pager is running.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_mouse_capture(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_mouse_capture(&self, enabled: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMouseCapture(enabled))?)
+    }
+
+    /// Set whether the pager is displayed on the alternate screen of the terminal
+    ///
+    /// By default, the pager switches to the alternate screen and its contents disappear when it
+    /// quits. With this set to `false`, the pager draws over the main screen instead and the last
+    /// screen of text stays in the terminal's scrollback after quitting, like `less --no-init`.
+    /// Only the prompt is erased. This can be changed while the pager is running.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_alternate_screen(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_alternate_screen(&self, enabled: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetAlternateScreen(enabled))?)
+    }
+
+    /// Set whether the terminal is restored when a panic happens while the pager is running
+    ///
+    /// By default, minus installs a panic hook that leaves raw mode and the alternate screen
+    /// before the panic message is printed, so that neither the message nor the shell is garbled.
+    /// The hook then runs the hook that was set before it, so custom hooks keep working. After
+    /// the pager quits, the hook does nothing more than run the previous one.
+    ///
+    /// Applications that restore the terminal in their own panic hook can turn this off. It
+    /// only takes effect when the pager starts.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_panic_cleanup(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_panic_cleanup(&self, enabled: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPanicCleanup(enabled))?)
+    }
+
+    /// Set whether the application feeds terminal events to the pager itself
+    ///
+    /// By default, minus reads key presses, mouse events and resizes from the terminal on a
+    /// thread of its own. When this is turned on, no such thread is started and the pager only
+    /// reacts to the events passed to [`Pager::feed_event`]. This is useful when minus is part of
+    /// a larger application that already reads the terminal events in its own loop.
+    ///
+    /// Searches still read their query from the terminal directly. This only takes effect when
+    /// the pager starts.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_external_input(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_external_input(&self, enabled: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExternalInput(enabled))?)
+    }
+
+    /// Set where the pager draws instead of stdout
+    ///
+    /// This is useful for drawing into a pseudo terminal or a terminal emulated by the
+    /// application, or for capturing the output in tests. Everything the pager would write to
+    /// stdout is written to `output` instead, including the commands that switch to the
+    /// [alternate screen](Pager::set_alternate_screen) and hide the cursor. The terminal of the
+    /// process is not put into raw mode and static paging doesn't check whether stdout is a
+    /// terminal. Only the text written on quitting, see [`Pager::set_exit_dump_scope`], still
+    /// goes to stdout.
+    ///
+    /// The size of the text area is still taken from the terminal of the process. Since minus
+    /// reads key presses from that terminal as well, this is usually combined with
+    /// [`Pager::set_external_input`]. This only takes effect when the pager starts, it is ignored
+    /// while the pager runs.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_output(Box::new(std::io::sink())).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_output(&self, output: Box<dyn io::Write + Send>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOutput(output))?)
+    }
+
+    /// Handle a terminal event as if minus had read it from the terminal
+    ///
+    /// The event goes through the [input classifier](Pager::set_input_classifier) and the
+    /// resulting action is run, including counts typed before a command. This is meant to be
+    /// used together with [`Pager::set_external_input`], but works without it too.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_external_input(true).expect("Failed to send data to the pager");
+    /// // In the application's event loop
+    /// let ev = Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+    /// pager.feed_event(ev).expect("Failed to send data to the pager");
+    /// ```
+    pub fn feed_event(&self, ev: crossterm::event::Event) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::FeedInput(ev))?)
+    }
+
+    /// Set the keys that quit the pager
+    ///
+    /// By default, the pager quits when the user presses `q` or `Ctrl+C`. The keys are given as
+    /// descriptions like `"esc"` or `"c-c"`, see [`keydefs`](crate::input::definitions::keydefs)
+    /// for the format. Exit keys take precedence over any other default binding of the same key.
+    /// Passing an empty slice disables quitting from the keyboard entirely, which is useful for
+    /// applications that manage the lifecycle of the pager themselves.
+    ///
+    /// Note that the terminal is in raw mode while the pager is running, so `Ctrl+C` does not
+    /// send `SIGINT` to the process. If `c-c` is not among the exit keys, pressing it does nothing
+    /// and the application must provide another way to quit.
+    ///
+    /// This only applies to the default input classifier and to
+    /// [`HashedEventRegister`](crate::input::HashedEventRegister). Other classifiers set with
+    /// [`Pager::set_input_classifier`] decide on their own when to send
+    /// [`InputEvent::Exit`](crate::input::InputEvent::Exit).
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Panics
+    /// This function panics if any of the keys is not a valid key description.
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// // Quit with Esc instead of q, but keep Ctrl+C
+    /// pager.set_exit_keys(&["esc", "c-c"]).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_exit_keys(&self, keys: &[&str]) -> Result<(), MinusError> {
+        let keys = keys.iter().map(|k| parse_key_event(k)).collect();
+        Ok(self.tx.send(Event::SetExitKeys(keys))?)
+    }
+
+    /// Set the keys that quit the pager and write the text to the main screen
+    ///
+    /// By default, pressing `Q` quits the pager and writes the lines below the screen to the
+    /// standard output, like [`Pager::set_exit_dump_remaining`] does for all exit keys. This is
+    /// useful when the user decides that they want the whole output in the scrollback of their
+    /// terminal after all. Which lines are written is set with [`Pager::set_exit_dump_scope`].
+    /// The keys are given in the same format as for [`Pager::set_exit_keys`], which take
+    /// precedence if a key is in both lists. Passing an empty slice disables this.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Panics
+    /// This function panics if any of the keys is not a valid key description.
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_dump_exit_keys(&["Q", "c-q"]).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_dump_exit_keys(&self, keys: &[&str]) -> Result<(), MinusError> {
+        let keys = keys.iter().map(|k| parse_key_event(k)).collect();
+        Ok(self.tx.send(Event::SetDumpExitKeys(keys))?)
+    }
+
+    /// Set whether numbers typed before a command are taken as a count for it
+    ///
+    /// By default, typing `5j` scrolls down by five lines. Applications that bind number keys to
+    /// their own actions can turn this off, in which case the default bindings ignore number
+    /// keys entirely. A count that is not followed by a command is discarded after the
+    /// [count timeout](Pager::set_count_timeout) either way.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_count_prefix_enabled(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_count_prefix_enabled(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCountPrefixEnabled(val))?)
+    }
+
+    /// Set whether a count is shown in the prompt while it is being typed
+    ///
+    /// By default this is set to true
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_count_display(false).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_count_display(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCountDisplay(val))?)
+    }
+
+    /// Set how long a count is kept when no command follows it
+    ///
+    /// A count is discarded once no further digit has been typed for this long, so that a
+    /// mistyped number does not apply to a command given much later. Pressing `Esc` discards it
+    /// right away.
+    ///
+    /// By default this is set to three seconds
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use std::time::Duration;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_count_timeout(Duration::from_secs(1)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_count_timeout(&self, val: Duration) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCountTimeout(val))?)
+    }
+
+    /// Set the default exit strategy.
+    ///
+    /// This controls how the pager will behave when the user presses `q` or `Ctrl+C`.
+    /// See [`ExitStrategy`] for available options
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::{Pager, ExitStrategy};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_strategy(ExitStrategy::ProcessQuit).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_exit_strategy(&self, es: ExitStrategy) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExitStrategy(es))?)
+    }
+
+    /// Set whether to write the lines below the current screen to the main screen on quitting
+    ///
+    /// When this is set to true, all the lines that come after the last line displayed
+    /// on the screen are written to the standard output after the pager has quit. This way
+    /// the rest of the output continues seamlessly in the main screen, much like `less -e`
+    /// does. See [`Pager::set_exit_dump_scope`] to write all of the text instead.
+    ///
+    /// By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_dump_remaining(true).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_exit_dump_remaining(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExitDumpRemaining(val))?)
+    }
+
+    /// Set which lines are written to the main screen when the pager quits
+    ///
+    /// This applies to the [dump exit keys](Pager::set_dump_exit_keys) and to
+    /// [`Pager::set_exit_dump_remaining`]. The lines are written as they are formatted on the
+    /// screen, after the terminal has been restored and before the process is quit if the
+    /// [exit strategy](Pager::set_exit_strategy) is [`ExitStrategy::ProcessQuit`]. They are
+    /// written to stdout even if the pager is drawn on a [custom output](Pager::set_output).
+    /// With the alternate screen disabled, the lines on the screen are already part of the main
+    /// screen, so [`DumpScope::Entire`] repeats them.
+    ///
+    /// See [`DumpScope`] for the available options.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{DumpScope, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_exit_dump_scope(DumpScope::Entire).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_exit_dump_scope(&self, scope: DumpScope) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetExitDumpScope(scope))?)
+    }
+
+    /// Set whether to display pager if there's less data than
+    /// available screen height
+    ///
+    /// Setting this to true prints all the lines to the main screen and quits immediately if
+    /// they fit on the screen, like [`OverflowBehavior::PageIfOverflow`]. Setting it to false
+    /// always starts the pager, like [`OverflowBehavior::AlwaysPage`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    #[cfg(feature = "static_output")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
+    #[deprecated(since = "5.3.0", note = "Use Pager::set_overflow_behavior instead")]
+    pub fn set_run_no_overflow(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetRunNoOverflow(val))?)
+    }
+
+    /// Set whether to start the pager if the text fits on the screen
+    ///
+    /// Instead of starting the pager, all the lines can be printed to the main screen, after
+    /// which the pager quits immediately. See [`OverflowBehavior`] for the options.
+    ///
+    /// This is only available in static output mode as the size of the data is
+    /// known beforehand.
+    /// In async output the pager can receive more data anytime
+    ///
+    /// By default this is set to [`OverflowBehavior::AlwaysPage`]
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{OverflowBehavior, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_overflow_behavior(OverflowBehavior::PrintIfFits)
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "static_output")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "static_output")))]
+    pub fn set_overflow_behavior(&self, behavior: OverflowBehavior) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOverflowBehavior(behavior))?)
+    }
+
+    /// Set whether moving to the next or previous match wraps around the ends of the text
+    ///
+    /// When enabled, moving to the next match from the last match goes to the first match and
+    /// vice versa. A message is shown at the prompt whenever this happens. When disabled, the
+    /// movement stops at the first or last match.
+    ///
+    /// By default this is set to true
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_search_wrap(false).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_wrap(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSearchWrap(val))?)
+    }
+
+    /// Set whether to place search matches in the middle of the screen
+    ///
+    /// When enabled, moving to a search match scrolls so that the match is vertically centered on
+    /// the screen. Matches that are too close to the start or end of the text are placed as near
+    /// to the center as possible. When disabled, the match is scrolled to the top of the screen.
+    ///
+    /// By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_center_on_match(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_center_on_match(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCenterOnMatch(val))?)
+    }
+
+    /// Set whether search matches are highlighted
+    ///
+    /// Unlike clearing the search with `Esc`, hiding the highlights keeps the search, so `n` and
+    /// `p` still move between its matches and the query doesn't have to be entered again to show
+    /// them. Users can switch this with `Alt+U`.
+    ///
+    /// By default this is set to true
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_highlight_visible(false).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_highlight_visible(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetHighlightVisible(val))?)
+    }
+
+    /// Set whether lines without a search match are dimmed
+    ///
+    /// While there is a search, the lines that have no match are drawn dimmed, so that the
+    /// matches stand out while the text around them stays readable. A wrapped line counts as
+    /// matching if any of its rows has a match. Nothing is dimmed when there is no search or
+    /// when colors are disabled. Users can switch this with `Alt+F`.
+    ///
+    /// By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_search_focus(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_focus(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSearchFocus(val))?)
+    }
+
+    /// Set the template for the line reference that `Y` copies to the clipboard
+    ///
+    /// The reference is about the line at the top of the screen, or the line of the cursor in
+    /// [cursor mode](Pager::set_cursor_mode). In the template, `%L` is replaced by the number of
+    /// the line, counting from 1, `%C` by the number of the first column on the screen, counting
+    /// from 1, and `%T` by the text of the line without any styling. `%%` stands for a single
+    /// `%`. The default is `%L:%C`.
+    ///
+    /// Anything else in the template is copied as it is, which is the way to add the name of
+    /// the file being displayed.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// // Copies something like "src/main.rs:123"
+    /// pager.set_line_ref_template("src/main.rs:%L").expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "clipboard")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "clipboard")))]
+    pub fn set_line_ref_template(&self, template: impl Into<String>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetLineRefTemplate(template.into()))?)
+    }
+
+    /// Returns the indices of the lines that contain a match of the current search
+    ///
+    /// The indices count the lines of the text from 0, hence they can be matched up with the
+    /// data the application gave to the pager, regardless of how the lines are wrapped on the
+    /// screen. Each line is listed once and the list is sorted.
+    ///
+    /// The list is taken from the running pager at the time of the call. It is empty if no search
+    /// is active, or if the pager has not started yet or has already quit.
+    ///
+    /// This must not be called from a function that the pager runs itself, like the one set
+    /// with [`Pager::set_on_reach_bottom`], as the pager's state is locked while it runs.
+    ///
+    /// # Example
+    /// ```
+    /// let pager = minus::Pager::new();
+    /// // The pager hasn't been started, hence there is no search
+    /// assert!(pager.search_matches().is_empty());
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    #[must_use]
+    pub fn search_matches(&self) -> Vec<usize> {
+        let Some(ps) = self.state.lock().upgrade() else {
+            return Vec::new();
+        };
+        let ps = ps.lock();
+        crate::minus_core::search::match_lines(&ps)
+    }
+
+    /// Set whether search patterns can match across lines
+    ///
+    /// When enabled, the pattern is run against the entire text instead of each row on its own,
+    /// so that a pattern like `error\n.*retrying` can match a log entry that spans two lines.
+    /// Add the `(?s)` flag to the pattern to make `.` match line breaks as well. Matches spanning
+    /// several lines are highlighted on all of them.
+    ///
+    /// This is slower than the default line by line search as the entire text has to be searched
+    /// again whenever text is appended. By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_search_multiline(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_multiline(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSearchMultiline(val))?)
+    }
+
+    /// Set the number of search matches after which the search stops
+    ///
+    /// A pattern like `.` matches nearly every row, which makes searching and highlighting a large
+    /// text slow. With a limit, only the first `max` rows with a match are collected and
+    /// highlighted and the user is told so at the prompt. Moving between matches with `n` and `p`
+    /// stays within these. Passing `None` removes the limit, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_max_search_matches(Some(10_000)).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_max_search_matches(&self, max: Option<usize>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetMaxSearchMatches(max))?)
+    }
+
+    /// Set the keys that start a forward and a reverse search
+    ///
+    /// The key is also shown at the prompt before the query. Like the
+    /// [exit keys](Pager::set_exit_keys), these keys start a search regardless of what the
+    /// [input classifier](Pager::set_input_classifier) binds them to. By default these are `/`
+    /// and `?`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_search_keys('s', 'S').expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_keys(&self, forward: char, reverse: char) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSearchKeys(forward, reverse))?)
+    }
+
+    /// Restrict searches to the lines from index `start` to `end`, inclusive
+    ///
+    /// Lines are counted from 0 in the text given to the pager, regardless of how they are
+    /// wrapped. Matches outside of the range are neither highlighted nor moved to with `n` and
+    /// `p`. The range may extend past the end of the text and the bounds are swapped if `start`
+    /// comes after `end`. Pass `None` to search the entire text again, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// // Only search the first hundred lines
+    /// pager.set_search_range(Some((0, 99))).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_range(&self, range: Option<(usize, usize)>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSearchRange(range))?)
+    }
+
+    /// Set whether search queries are matched literally
+    ///
+    /// By default, search queries are regular expressions. With this enabled, characters like
+    /// `.`, `*` or `[` in the query are matched as they are, which is handy for searching paths
+    /// or code. The mode can also be switched with `Ctrl+R` while typing a query, the prompt
+    /// shows `f/` instead of `/` in literal mode.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_search_literal(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_literal(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetSearchLiteral(val))?)
+    }
+
+    /// Set whether a search that matches nothing replaces the previous search
+    ///
+    /// A search without matches always shows a message saying so. By default, the search is
+    /// kept and the prompt says that the pattern was not found until another search is made.
+    /// When disabled, the previous search and its matches stay active instead, like in `less`.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_keep_unmatched_search(false).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_keep_unmatched_search(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetKeepUnmatchedSearch(val))?)
+    }
+
+    /// Set whether to ring the terminal bell when a search matches nothing
+    ///
+    /// By default this is set to false
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_beep_on_no_match(true).expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_beep_on_no_match(&self, val: bool) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetBeepOnNoMatch(val))?)
+    }
+
+    /// Set the style of the search match that is currently in focus
+    ///
+    /// This is the match that was moved to last, for example with `n` or `p`. It is shown with
+    /// black text on a yellow background by default, to set it apart from the other matches. See
+    /// also [`Pager::set_other_match_style`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::style::{ContentStyle, Stylize};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_current_match_style(ContentStyle::new().bold().underlined())
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_current_match_style(&self, style: ContentStyle) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetCurrentMatchStyle(style))?)
+    }
+
+    /// Set the style of the search matches that are not in focus
+    ///
+    /// The matches are shown in reverse video by default. See also
+    /// [`Pager::set_current_match_style`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use crossterm::style::{ContentStyle, Stylize};
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_other_match_style(ContentStyle::new().underlined())
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_other_match_style(&self, style: ContentStyle) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOtherMatchStyle(style))?)
+    }
+
+    /// Set the patterns that are always highlighted
+    ///
+    /// Each pattern forms a highlight group whose matches are shown with their own background
+    /// color, independent of the current search. Pressing `]` moves to the next line matching any
+    /// group, while `n]` moves to the next line matching the `n`th group. Passing an empty slice
+    /// removes all highlight groups.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::SearchExpError)`](MinusError::SearchExpError)
+    /// if any of the patterns is not a valid regex, or a
+    /// [`Err(MinusError::Communication)`](MinusError::Communication) if the data could not be sent
+    /// to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_highlight_groups(&["ERROR", "WARN(ING)?"]).expect("Failed to set highlight groups");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_highlight_groups(&self, patterns: &[&str]) -> Result<(), MinusError> {
+        let groups = patterns
+            .iter()
+            .map(|p| regex::Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.tx.send(Event::SetHighlightGroups(groups))?)
+    }
+
+    /// Set the pattern for the lines that start a section of the text
+    ///
+    /// Pressing `]]` moves to the next line matching the pattern and `[[` to the previous one. A
+    /// count before them moves by that many sections. Unlike a search, the matches are not
+    /// highlighted and the pattern stays in place while searching. Styling is ignored when
+    /// matching. Pass `None` to remove the pattern, which is the default.
+    ///
+    /// For example, `^@@` moves between the hunks of a diff and `^#` between the headings of a
+    /// Markdown document.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::SearchExpError)`](MinusError::SearchExpError)
+    /// if the pattern is not a valid regex, or a
+    /// [`Err(MinusError::Communication)`](MinusError::Communication) if the data could not be sent
+    /// to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_section_pattern(Some("^@@")).expect("Failed to set the section pattern");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_section_pattern(&self, pattern: Option<&str>) -> Result<(), MinusError> {
+        let pattern = pattern.map(regex::Regex::new).transpose()?;
+        Ok(self.tx.send(Event::SetSectionPattern(pattern))?)
+    }
+
+    /// Set a custom input classifer function.
+    ///
+    /// When the pager encounters a user input, it calls the input classifer with
+    /// the event and [PagerState](crate::state::PagerState) as parameters.
+    ///
+    /// A input classifier is a type implementing the [`InputClassifier`](input::InputClassifier)
+    /// trait. It only has one required function, [`InputClassifier::classify_input`](input::InputClassifier::classify_input)
+    /// which matches user input events and maps them to a [`InputEvent`](input::InputEvent)s.
+    ///
+    /// See the [`InputHandler`](input::InputClassifier) trait for information about implementing
+    /// it.
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    pub fn set_input_classifier(
+        &self,
+        handler: Box<dyn input::InputClassifier + Send + Sync>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetInputClassifier(handler))?)
+    }
+
+    /// Adds a function that will be called when the user quits the pager
+    ///
+    /// Multiple functions can be stored for calling when the user quits. These functions
+    /// run sequentially in the order they were added
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// fn hello() {
+    ///     println!("Hello");
+    /// }
+    ///
+    /// let pager = Pager::new();
+    /// pager.add_exit_callback(Box::new(hello)).expect("Failed to send data to the pager");
+    /// ```
+    pub fn add_exit_callback(
+        &self,
+        cb: Box<dyn FnMut() + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::AddExitCallback(cb))?)
+    }
+
+    /// Runs a function with the current state of the pager
+    ///
+    /// The function is run by the pager in between handling other events, so all the values it
+    /// reads are consistent with each other. It only gets shared access, hence it cannot change
+    /// the state. Note that the function runs on the pager's thread, use a channel or similar to
+    /// get values out of it.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    /// use std::sync::mpsc::channel;
+    ///
+    /// let pager = Pager::new();
+    /// let (tx, rx) = channel();
+    /// pager.with_state(move |ps| {
+    ///     let _ = tx.send((ps.upper_mark, ps.rows));
+    /// }).expect("Failed to send data to the pager");
+    /// ```
+    pub fn with_state(
+        &self,
+        func: impl FnOnce(&PagerState) + Send + 'static,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::WithState(Box::new(func)))?)
+    }
+
+    /// Adds a function that will be called with the reason why the pager quit
+    ///
+    /// This works like [`Pager::add_exit_callback`], except that the function learns whether
+    /// the user quit, the pager quit by itself or it quit because of an error. See
+    /// [`ExitReason`] for the possible reasons. Both kinds of functions run together, in the
+    /// order they were added.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{ExitReason, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.add_exit_callback_with_reason(Box::new(|reason| {
+    ///     if reason == ExitReason::UserQuit {
+    ///         println!("Bye");
+    ///     }
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn add_exit_callback_with_reason(
+        &self,
+        cb: Box<dyn FnMut(ExitReason) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::AddExitCallbackWithReason(cb))?)
+    }
+
+    /// Sets a function that is told how the screen was updated after each append
+    ///
+    /// Appending usually only formats the new text, but sometimes all of the text has to be
+    /// formatted and drawn again, see [`AppendFeedback`]. An application that produces data
+    /// quickly can use this to notice when appends get expensive and send its data in larger
+    /// batches.
+    ///
+    /// The function runs on the pager's thread for every append, hence it should return quickly.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{AppendFeedback, Pager};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let pager = Pager::new();
+    /// let full_redraws = Arc::new(AtomicUsize::new(0));
+    /// let counter = full_redraws.clone();
+    /// pager.set_append_feedback(Box::new(move |feedback| {
+    ///     if feedback == AppendFeedback::FullRedraw {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_append_feedback(
+        &self,
+        cb: Box<dyn Fn(AppendFeedback) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetAppendFeedback(cb))?)
+    }
+
+    /// Sets a function that will be called when the user scrolls to the bottom of the text
+    ///
+    /// The function is called once each time the last page comes into view and not again until
+    /// the user scrolls away from it or more data is appended to the pager. This makes it useful
+    /// for lazily loading more data when the user reaches the end.
+    ///
+    /// The function runs on the pager's thread, hence it should not block for long. Any data sent to
+    /// the pager from inside the function is displayed after it returns.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// let pager2 = pager.clone();
+    /// pager.set_on_reach_bottom(Box::new(move || {
+    ///     pager2.push_str("More data\n").expect("Failed to send data to the pager");
+    /// })).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_reach_bottom(
+        &self,
+        cb: Box<dyn FnMut() + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnReachBottom(cb))?)
+    }
+
+    /// Sets a function that will be called when the user scrolls to the top of the text
+    ///
+    /// This works the same way as [`Pager::set_on_reach_bottom`] but for the first page.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_on_reach_top(Box::new(|| println!("At the top")))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_reach_top(
+        &self,
+        cb: Box<dyn FnMut() + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnReachTop(cb))?)
+    }
+
+    /// Sets a function that will be called when the user toggles the mark on a line
+    ///
+    /// Lines are marked and unmarked by clicking on their line number, hence this only works
+    /// while line numbers are displayed. The function is given the index of the line, starting
+    /// from 0, and whether the line is now marked. This can be used to build things like a
+    /// breakpoint list.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_on_gutter_mark(Box::new(|line, marked| {
+    ///     println!("Line {} is {}", line + 1, if marked { "marked" } else { "unmarked" });
+    /// }))
+    /// .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_gutter_mark(
+        &self,
+        cb: Box<dyn FnMut(usize, bool) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnGutterMark(cb))?)
+    }
+
+    /// Sets a function that will be called when the user yanks the selected lines
+    ///
+    /// In [cursor mode](Pager::set_cursor_mode), `v` starts selecting lines at the cursor and
+    /// moving the cursor extends the selection. `y` then yanks the selected lines and `Esc` drops
+    /// the selection. The function is given the text of the selected lines, without any styling,
+    /// joined by newlines. With the `clipboard` feature, the text is also copied to the
+    /// clipboard.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_cursor_mode(true).expect("Failed to send data to the pager");
+    /// pager.set_on_yank(Box::new(|text| println!("Yanked:\n{text}")))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_yank(
+        &self,
+        cb: Box<dyn FnMut(String) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnYank(cb))?)
+    }
+
+    /// Set what the `Enter` key does while no message is shown
+    ///
+    /// See [`EnterAction`] for available options. A message at the prompt is always dismissed
+    /// first. The default is [`EnterAction::ScrollDown`].
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{EnterAction, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_enter_action(EnterAction::Ignore).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_enter_action(&self, action: EnterAction) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetEnterAction(action))?)
+    }
+
+    /// Send several updates to the pager at once
+    ///
+    /// `f` is called with a [`Pager`] that collects everything sent through it instead of
+    /// passing it on. Once `f` returns, the collected updates are sent to the running pager
+    /// together. The pager applies them in the order they were made, without handling anything
+    /// else in between, and draws the screen only once at the end. This avoids both the cost of
+    /// drawing after each update and showing the text in a state that is only halfway updated.
+    ///
+    /// Nothing is sent if `f` returns an error. Reading from the [`Pager`] given to `f`, like with
+    /// [`Pager::buffer_text`], shows the state from before the batch is applied.
+    ///
+    /// # Errors
+    /// This function returns the error that `f` returns, or a
+    /// [`Err(MinusError::Communication)`](MinusError::Communication) if the data could not be
+    /// sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{LineNumbers, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager
+    ///     .batch(|batch| {
+    ///         batch.set_text("Hello\nWorld\n")?;
+    ///         batch.set_prompt("greeting")?;
+    ///         batch.set_line_numbers(LineNumbers::Enabled)?;
+    ///         batch.set_start_line(2)
+    ///     })
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn batch(&self, f: impl FnOnce(&Self) -> Result<(), MinusError>) -> Result<(), MinusError> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let batch = Self {
+            tx,
+            rx,
+            state: self.state.clone(),
+            exit_signal: self.exit_signal.clone(),
+        };
+        f(&batch)?;
+        // Batches made inside of `f` are part of this one
+        let events = batch
+            .rx
+            .try_iter()
+            .flat_map(|ev| match ev {
+                Event::Batch(events) => events,
+                ev => vec![ev],
+            })
+            .collect();
+        Ok(self.tx.send(Event::Batch(events))?)
+    }
+
+    /// Set how the user is told that the text can't be scrolled further
+    ///
+    /// See [`EdgeFeedback`] for the options. The feedback is given when the user scrolls up at the
+    /// top of the text or down at its end, but not for scrolls like [`Pager::scroll_to`]. By
+    /// default, nothing happens.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{EdgeFeedback, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_edge_feedback(EdgeFeedback::Bell).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_edge_feedback(&self, feedback: EdgeFeedback) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetEdgeFeedback(feedback))?)
+    }
+
+    /// Set whether the prompt is displayed above or below the text
+    ///
+    /// See [`PromptPosition`] for the options. Search queries are typed where the prompt is. A
+    /// prompt at the top is best used with the [alternate screen](Pager::set_alternate_screen),
+    /// as it is left at the top of the main screen otherwise.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Pager, PromptPosition};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_prompt_position(PromptPosition::Top).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_prompt_position(&self, position: PromptPosition) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetPromptPosition(position))?)
+    }
+
+    /// Sets a function that will be called when the user selects a line with `Enter`
+    ///
+    /// This only happens with [`EnterAction::EmitSelect`]. The function is given the index of
+    /// the selected line, starting from 0. The selected line is the line of the cursor in
+    /// [cursor mode](Pager::set_cursor_mode) and the line at the top of the screen otherwise.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{EnterAction, Pager};
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_enter_action(EnterAction::EmitSelect).expect("Failed to send data to the pager");
+    /// pager.set_on_select(Box::new(|line| println!("Selected line {}", line + 1)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_select(
+        &self,
+        cb: Box<dyn FnMut(usize) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnSelect(cb))?)
+    }
+
+    /// Sets a function that is told about what happens in the pager
+    ///
+    /// The function is given a [`PagerObservation`] whenever the screen scrolls, the user
+    /// searches, the terminal is resized or the pager quits. This is meant for logging and
+    /// metrics and has no influence on the pager. As it runs while the pager handles the event,
+    /// it should return quickly. Nothing is collected while no function is set.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::{Pager, PagerObservation};
+    ///
+    /// let pager = Pager::new();
+    /// pager
+    ///     .set_event_observer(Box::new(|observation| {
+    ///         if let PagerObservation::Searched { query, matches } = observation {
+    ///             eprintln!("{query:?} matched {matches} rows");
+    ///         }
+    ///     }))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_event_observer(
+        &self,
+        cb: Box<dyn Fn(PagerObservation) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetEventObserver(cb))?)
+    }
+
+    /// Sets a function that will be called when the user clicks a hyperlink
+    ///
+    /// Hyperlinks are written with OSC 8 escape sequences, which terminals that support them
+    /// already make clickable. Those clicks only reach the terminal while mouse capture is off
+    /// though. With [mouse capture](Pager::set_mouse_capture) on, minus receives the click
+    /// instead and calls this function with the URI of the link, so that the application can
+    /// open it.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_on_link_activate(Box::new(|uri| println!("Opening {}", uri)))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_link_activate(
+        &self,
+        cb: Box<dyn FnMut(String) + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnLinkActivate(cb))?)
+    }
+
+    /// Set the character shown next to the line numbers of marked lines
+    ///
+    /// See [`Pager::set_on_gutter_mark`] for how lines are marked. Pass `None` to not show marks
+    /// at all, which is the default.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_gutter_mark_glyph(Some('*')).expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_gutter_mark_glyph(&self, glyph: Option<char>) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetGutterMarkGlyph(glyph))?)
+    }
+
+    /// Sets a function that will be called right before the text is reformatted
+    ///
+    /// The text is reformatted whenever the layout of the rows on the screen changes, for example
+    /// when the terminal is resized or line numbers are toggled. This invalidates anything that
+    /// refers to rows of the formatted text, hence this is the place to save such positions so
+    /// that they can be restored in the function given to [`Pager::set_on_after_reflow`].
+    ///
+    /// The function runs on the pager's thread, hence it should not block for long.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_on_before_reflow(Box::new(|| println!("Reformatting")))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_before_reflow(
+        &self,
+        cb: Box<dyn FnMut() + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnBeforeReflow(cb))?)
+    }
+
+    /// Sets a function that will be called right after the text is reformatted
+    ///
+    /// See [`Pager::set_on_before_reflow`] for more information.
+    ///
+    /// # Errors
+    /// This function will return a [`Err(MinusError::Communication)`](MinusError::Communication) if the data
+    /// could not be sent to the receiver
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_on_after_reflow(Box::new(|| println!("Reformatted")))
+    ///     .expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_on_after_reflow(
+        &self,
+        cb: Box<dyn FnMut() + Send + Sync + 'static>,
+    ) -> Result<(), MinusError> {
+        Ok(self.tx.send(Event::SetOnAfterReflow(cb))?)
+    }
+
+    /// Returns a [`WriteSink`] which implements [`std::io::Write`] for appending bytes to the pager
+    ///
+    /// This is useful for pointing things that only know how to write bytes, like the output of a
+    /// [`std::process::Command`] or a logger, directly at the pager.
+    /// See [`WriteSink`] for how the bytes are converted into text.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// let pager = minus::Pager::new();
+    /// let mut sink = pager.write_sink();
+    /// sink.write_all(b"Some bytes\n").expect("Failed to send data to the pager");
+    /// ```
+    #[must_use]
+    pub fn write_sink(&self) -> WriteSink {
+        WriteSink {
+            pager: self.clone(),
+            incomplete: Vec::new(),
+            control_char_placeholder: None,
+        }
+    }
+}
+
+impl Default for Pager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Allows the [`write!`]/[`writeln!`] macros to be used for appending data to the pager
+///
+/// Each call to [`write_str`](fmt::Write::write_str) is sent as a separate
+/// [`AppendData`](Event::AppendData) event, exactly like [`Pager::push_str`]. A single macro call
+/// can therefore be split into multiple appends, but this is fine as unterminated lines are
+/// joined with the text that follows them once it is received.
+impl fmt::Write for Pager {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+/// A [`std::io::Write`] adapter that appends everything written to it to the pager
+///
+/// This is created by [`Pager::write_sink`].
+///
+/// The bytes are converted to UTF-8 lossily, i.e. invalid sequences are replaced with
+/// `U+FFFD REPLACEMENT CHARACTER`. If a write ends in the middle of a multibyte character, the
+/// incomplete bytes are held back until the rest of the character is written. Any bytes that are
+/// still held back when the sink is dropped are sent as a replacement character.
+///
+/// Non-printable control characters like `NUL` are shown in caret notation (`^@`) so that they
+/// don't mess up the terminal. Newlines, tabs and ANSI escape sequences are passed through. See
+/// [`WriteSink::set_control_char_placeholder`] to show a fixed character instead.
+///
+/// [`flush`](io::Write::flush) is a no-op as every write is immediately sent to the pager.
+pub struct WriteSink {
+    pager: Pager,
+    incomplete: Vec<u8>,
+    control_char_placeholder: Option<char>,
+}
+
+impl WriteSink {
+    /// Set the character displayed in place of non-printable control characters
+    ///
+    /// Passing `None` restores the default caret notation, where for example `NUL` is shown as `^@`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// let pager = minus::Pager::new();
+    /// let mut sink = pager.write_sink();
+    /// sink.set_control_char_placeholder(Some('.'));
+    /// sink.write_all(b"NUL is shown as a dot: \0\n").expect("Failed to send data to the pager");
+    /// ```
+    pub fn set_control_char_placeholder(&mut self, placeholder: Option<char>) {
+        self.control_char_placeholder = placeholder;
+    }
+}
+
+impl io::Write for WriteSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.incomplete.extend_from_slice(buf);
+        let text = text::render_control_chars(
+            text::decode_utf8_lossy(&mut self.incomplete),
+            self.control_char_placeholder,
+        );
+        if !text.is_empty() {
+            self.pager
+                .push_str(text)
+                .map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e.to_string()))?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for WriteSink {
+    fn drop(&mut self) {
+        if !self.incomplete.is_empty() {
+            // Nothing can be done about a failure here so it is ignored
+            drop(self.pager.push_str(char::REPLACEMENT_CHARACTER));
+        }
     }
 }
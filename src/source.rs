@@ -0,0 +1,60 @@
+//! Provides the [`LineSource`] trait, which lets the pager read the text as it is needed
+//!
+//! A line source is set with [`Pager::set_line_source`](crate::Pager::set_line_source). Instead
+//! of being sent all of the text up front, the pager asks the source for the lines it is about to
+//! display. Text that is large or generated on the fly is then only read as far as the user
+//! scrolls.
+
+use std::borrow::Cow;
+
+/// Text that the pager reads one line at a time, as it needs them
+///
+/// Lines are read in order, starting with the line at index 0, once they are about to be
+/// displayed. Lines that have been read are kept like text sent with
+/// [`Pager::push_str`](crate::Pager::push_str), hence each line is read only once. Searches only
+/// cover the lines that have been read so far. Like any other text, lines that have been read
+/// are dropped again once the text grows past
+/// [`Pager::set_max_buffer_bytes`](crate::Pager::set_max_buffer_bytes), and are not read again
+/// after that.
+///
+/// # Example
+/// ```
+/// use minus::{source::LineSource, Pager};
+/// use std::borrow::Cow;
+///
+/// /// The squares of the first million numbers
+/// struct Squares;
+///
+/// impl LineSource for Squares {
+///     fn line(&self, idx: usize) -> Option<Cow<'_, str>> {
+///         (idx < 1_000_000).then(|| Cow::Owned((idx * idx).to_string()))
+///     }
+///
+///     fn len(&self) -> Option<usize> {
+///         Some(1_000_000)
+///     }
+/// }
+///
+/// let pager = Pager::new();
+/// pager.set_line_source(Box::new(Squares)).expect("Failed to send data to the pager");
+/// ```
+// The length is only a hint for how far to read, whether the source is empty is never needed
+#[allow(clippy::len_without_is_empty)]
+pub trait LineSource {
+    /// Returns the line at index `idx` without its newline, or `None` if there is no such line
+    ///
+    /// The pager stops reading at the first `None`. It asks for the same index again once the
+    /// user scrolls past the last line that has been read, so a source that grows can return
+    /// the line by then.
+    fn line(&self, idx: usize) -> Option<Cow<'_, str>>;
+
+    /// Returns the number of lines, or `None` if it is not known
+    ///
+    /// No more lines than this are read. Without a known number of lines, going to the end of
+    /// the text with `G` reads the next 10,000 lines, or fewer if [`line`](Self::line) returns
+    /// `None` before that. A message at the prompt tells the user if there may be more lines,
+    /// which another `G` reads.
+    fn len(&self) -> Option<usize> {
+        None
+    }
+}
@@ -1,25 +1,56 @@
+#[cfg(feature = "clipboard")]
+use crate::minus_core::clipboard::{self, ClipboardBackend};
 #[cfg(feature = "search")]
 use crate::minus_core::search::{self, SearchMode};
+#[cfg(feature = "static_output")]
+use crate::OverflowBehavior;
 use crate::{
+    align_right,
     error::{MinusError, TermError},
-    input,
-    minus_core::utils::text::AppendStyle,
-    wrap_str, ExitStrategy, LineNumbers,
+    highlight::{self, Highlighter},
+    input::{self, definitions::keydefs::parse_key_event, InputEvent},
+    minus_core::utils::text::{
+        decode_utf8_lossy, display_width, display_width_as, hyperlink_at, last_paragraph_start,
+        overwrite_carriage_returns, paragraph_units, reflow, render_control_chars,
+        render_whitespace, strip_ansi, strip_sgr, AppendStyle, FormatCache,
+    },
+    pad_wide_breaks, skip_columns,
+    source::LineSource,
+    truncate_str, wrap_str, AmbiguousWidth, AppendFeedback, ContinuationNumberStyle, CrMode,
+    DumpScope, EdgeFeedback, EnterAction, ExitReason, ExitStrategy, LineNumberAlignment,
+    LineNumberStyle, LineNumbers, PagerObservation, PromptPosition, TextDirection, WideCharWrap,
+    WrapMode,
+};
+use crossterm::{
+    event::KeyEvent,
+    style::{Color, ContentStyle, Stylize},
+    terminal,
+    tty::IsTty,
 };
-use crossterm::{terminal, tty::IsTty};
 #[cfg(feature = "search")]
 use parking_lot::{Condvar, Mutex};
-#[cfg(feature = "search")]
+use std::borrow::Cow;
 use std::collections::BTreeSet;
-use std::{convert::TryInto, io::Stdout};
+use std::ops::Range;
+use std::{
+    convert::TryInto,
+    io::{Stdout, Write},
+};
 use std::{
     io::stdout,
     sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
 };
 
 use crate::minus_core::{ev_handler::handle_event, events::Event};
 use crossbeam_channel::Receiver;
 
+/// Function that computes the prompt, see [`Pager::set_dynamic_prompt`](crate::Pager::set_dynamic_prompt)
+pub type DynamicPrompt = Box<dyn Fn(&PagerState) -> String + Send + Sync + 'static>;
+
+/// Function that picks the style of each line, see [`Pager::set_line_styler`](crate::Pager::set_line_styler)
+pub type LineStyler = Box<dyn Fn(usize, &str) -> Option<ContentStyle> + Send + Sync + 'static>;
+
 /// Holds all information and configuration about the pager during
 /// its un time.
 ///
@@ -29,27 +60,306 @@ use crossbeam_channel::Receiver;
 ///
 /// Various fields are made public so that their values can be accessed while implementing the
 /// trait.
-#[allow(clippy::module_name_repetitions)]
+#[allow(clippy::module_name_repetitions, clippy::struct_excessive_bools)]
 pub struct PagerState {
     /// The text the pager has been told to be displayed
     pub(crate) lines: String,
+    /// Where more lines of the text are read from as they are needed, see [`LineSource`]
+    pub(crate) line_source: Option<Box<dyn LineSource + Send + Sync>>,
+    /// Number of lines that have been read from the [`line_source`](PagerState::line_source)
+    pub(crate) source_lines_read: usize,
+    /// Byte position in [`PagerState::lines`] where each line ends, including its newline
+    ///
+    /// This is the running total of the byte lengths of the lines, hence the line at index `i`
+    /// starts where the line at `i - 1` ends.
+    pub(crate) line_ends: Vec<usize>,
     /// The output, flattened and formatted into the lines that should be displayed
     pub(crate) formatted_lines: Vec<String>,
+    /// Index of the first row in [`PagerState::formatted_lines`] of each line
+    ///
+    /// This is kept up to date along with the formatted rows, so that finding the line at a row
+    /// or the row of a line doesn't need to wrap the text again.
+    pub(crate) line_rows: Vec<usize>,
+    /// Whether lines are only formatted once they are about to be displayed
+    ///
+    /// See [`PagerState::formats_lazily`] for when this takes effect.
+    pub(crate) lazy_formatting: bool,
+    /// Rows of [`PagerState::formatted_lines`] that are still waiting to be formatted
+    pub(crate) format_cache: FormatCache,
+    /// Whether drawing to the terminal is paused
+    ///
+    /// The state is still updated while this is set, but nothing is drawn until rendering is
+    /// resumed.
+    pub(crate) rendering_paused: bool,
+    /// Minimum time between two redraws caused by the application
+    ///
+    /// Redraws that would happen sooner are coalesced into a single redraw at the end of the
+    /// interval. Redraws caused by user input are never delayed.
+    pub(crate) redraw_interval: Option<Duration>,
+    /// When the screen was last fully redrawn
+    pub(crate) last_draw: Option<Instant>,
+    /// Whether a redraw was deferred because of [`PagerState::redraw_interval`]
+    pub(crate) redraw_pending: bool,
+    /// How long the terminal size must stay the same before the text is reformatted for it
+    ///
+    /// While the terminal is being resized, the new size is recorded right away but formatting
+    /// and drawing wait until no resize has happened for this long. Zero reformats on every
+    /// resize.
+    pub(crate) resize_debounce: Duration,
+    /// When the reformat deferred by [`PagerState::resize_debounce`] is due
+    pub(crate) resize_deadline: Option<Instant>,
+    /// How long appended text is gathered before it is applied in one go, see
+    /// [`Pager::set_append_coalesce`](crate::Pager::set_append_coalesce)
+    pub(crate) append_coalesce: Option<Duration>,
+    /// Appended text that is held back because of [`PagerState::append_coalesce`]
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) pending_append: String,
+    /// When the text in [`PagerState::pending_append`] must be applied at the latest
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) append_deadline: Option<Instant>,
+    /// How often the prompt is refreshed while no events arrive, see
+    /// [`Pager::set_tick_interval`](crate::Pager::set_tick_interval)
+    pub(crate) tick_interval: Option<Duration>,
+    /// When the next tick is due, if ticks are enabled
+    pub(crate) next_tick: Option<Instant>,
+    /// How long a scroll by a page or more takes, see
+    /// [`Pager::set_smooth_scroll`](crate::Pager::set_smooth_scroll)
+    pub(crate) smooth_scroll: Option<Duration>,
+    /// The smooth scroll that is being drawn, if any
+    pub(crate) scroll_animation: Option<ScrollAnimation>,
+    /// Whether the output must not depend on timing, see [`Pager::set_deterministic_mode`]
+    ///
+    /// [`Pager::set_deterministic_mode`]: crate::Pager::set_deterministic_mode
+    pub(crate) deterministic_mode: bool,
+    /// Bytes of an incomplete multibyte character at the end of the last bytes given to the pager
+    ///
+    /// They are decoded together with the bytes that are appended next.
+    pub(crate) incomplete_bytes: Vec<u8>,
+    /// Character shown in place of non-printable control characters in bytes given to the
+    /// pager, see [`Pager::set_control_char_placeholder`]
+    ///
+    /// [`Pager::set_control_char_placeholder`]: crate::Pager::set_control_char_placeholder
+    pub(crate) control_char_placeholder: Option<char>,
+    /// Maximum size of [`PagerState::lines`] in bytes
+    ///
+    /// The oldest lines are dropped when the text grows beyond this size.
+    pub(crate) max_buffer_bytes: Option<usize>,
     /// Configuration for line numbers. See [`LineNumbers`]
     pub line_numbers: LineNumbers,
+    /// Separator, alignment and width of the line numbers. See [`LineNumberStyle`]
+    pub(crate) line_number_style: LineNumberStyle,
+    /// What the gutter shows on the rows that continue a wrapped line. See
+    /// [`ContinuationNumberStyle`]
+    pub(crate) continuation_number_style: ContinuationNumberStyle,
+    /// Whether long lines are wrapped onto the next rows or truncated at the edge of the screen
+    pub(crate) line_wrapping: bool,
+    /// Where long lines are broken when they are wrapped, see [`WrapMode`]
+    pub(crate) wrap_mode: WrapMode,
+    /// How double-width characters that don't fit into a row are wrapped, see [`WideCharWrap`]
+    pub(crate) wide_char_wrap: WideCharWrap,
+    /// How many columns characters of ambiguous width take up, see [`AmbiguousWidth`]
+    pub(crate) ambiguous_width: AmbiguousWidth,
+    /// Text put at the start of each row that continues a wrapped line
+    pub(crate) wrap_indicator: Option<String>,
+    /// Function that returns the style of a line, given its index and its text
+    ///
+    /// It is called once for every line of [`PagerState::lines`] when it is formatted and the
+    /// style is applied to all rows of the line.
+    pub(crate) line_styler: Option<LineStyler>,
+    /// Splits each line into styled runs when it is formatted, see [`Highlighter`]
+    pub(crate) highlighter: Option<Box<dyn Highlighter + Send + Sync>>,
+    /// Whether tabs and trailing spaces are displayed as visible markers
+    pub(crate) show_whitespace: bool,
+    /// How carriage returns inside lines are displayed, see [`CrMode`]
+    pub(crate) carriage_return_mode: CrMode,
+    /// Whether anything is displayed in color or another style
+    ///
+    /// Without it, all styles are removed from the text and the prompt and rows that would be
+    /// highlighted get a `>` in front of them instead. It is turned off when the pager starts if
+    /// the `NO_COLOR` environment variable is set, see [`color_by_default`].
+    pub(crate) color_enabled: bool,
+    /// Whether a scrollbar is shown in the last column of the screen
+    ///
+    /// It shows which part of the text is on the screen and the rows with search matches. The
+    /// text is formatted one column narrower to make room for it.
+    pub(crate) scrollbar: bool,
+    /// The largest number of columns that the text is wrapped to, if it is limited
+    ///
+    /// If the screen is wider, the text is centered with blank margins on both sides. Line
+    /// numbers are displayed outside of this width.
+    pub(crate) max_content_width: Option<usize>,
+    /// Whether the last line can be scrolled up to the top of the screen
+    ///
+    /// Otherwise the [`upper_mark`](PagerState::upper_mark) is kept low enough for the last page
+    /// to fill the screen.
+    pub(crate) scroll_past_end: bool,
+    /// Number of lines at the start of the text that stay at the top of the screen
+    ///
+    /// They are displayed above the rest of the text, which scrolls beneath them. At least one
+    /// row is always left for the scrolling text, so a header taller than the screen is cut off.
+    pub(crate) frozen_header_lines: usize,
+    /// Number of rows less than the height of the screen that a page movement scrolls by
+    ///
+    /// The height counts the prompt row, so the default of 1 makes paging neither repeat nor
+    /// skip any lines. Larger values keep some lines of the previous page in view.
+    pub(crate) page_overlap: usize,
+    /// Character displayed in the rows below the end of the text, if any
+    pub(crate) end_of_buffer_char: Option<char>,
+    /// Character displayed in the last column of a line that has been truncated
+    pub(crate) truncation_marker: char,
+    /// Direction in which the text is laid out. See [`TextDirection`]
+    pub(crate) text_direction: TextDirection,
+    /// Whether the lines of each paragraph are joined into one, see
+    /// [`Pager::set_reflow`](crate::Pager::set_reflow)
+    pub(crate) reflow: bool,
+    /// The text as it was given while [`PagerState::reflow`] is set
+    ///
+    /// [`PagerState::lines`] then holds the reflowed version of it.
+    pub(crate) reflow_source: String,
     /// Unterminated lines
     /// Keeps track of the number of lines at the last of [PagerState::formatted_lines] which are
     /// not terminated by a newline
     pub(crate) unterminated: usize,
     /// The prompt displayed at the bottom wrapped to available terminal width
     pub(crate) prompt: String,
+    /// Text shown at the right end of the prompt row, after [`PagerState::prompt`], see
+    /// [`Pager::set_prompt_segments`](crate::Pager::set_prompt_segments)
+    pub(crate) prompt_right: String,
+    /// Function that computes the prompt from the state of the pager
+    ///
+    /// When set, this is used instead of [`PagerState::prompt`] and is called again every time
+    /// the prompt is displayed.
+    pub(crate) dynamic_prompt: Option<DynamicPrompt>,
+    /// Style of the row that shows the prompt or a message
+    pub(crate) prompt_style: ContentStyle,
+    /// Function that computes the status bar displayed above the prompt
+    ///
+    /// The status bar takes up a row of its own, hence the text gets one row less while it is
+    /// set. A fixed text is stored as a function that always returns it.
+    pub(crate) status_bar: Option<DynamicPrompt>,
     /// The input classifier to be called when a input is detected
     pub(crate) input_classifier: Box<dyn input::InputClassifier + Sync + Send>,
     /// Functions to run when the pager quits
-    pub(crate) exit_callbacks: Vec<Box<dyn FnMut() + Send + Sync + 'static>>,
+    pub(crate) exit_callbacks: Vec<Box<dyn FnMut(ExitReason) + Send + Sync + 'static>>,
+    /// Why the pager exited, `None` while it is still running
+    pub(crate) exit_reason: Option<ExitReason>,
+    /// Function that is told how the screen was updated after each append
+    pub(crate) append_feedback: Option<Box<dyn Fn(AppendFeedback) + Send + Sync + 'static>>,
+    /// Function to run when the user scrolls to the last page
+    pub(crate) on_reach_bottom: Option<Box<dyn FnMut() + Send + Sync + 'static>>,
+    /// Function to run when the user scrolls to the first page
+    pub(crate) on_reach_top: Option<Box<dyn FnMut() + Send + Sync + 'static>>,
+    /// Function to run right before the text is reformatted
+    pub(crate) on_before_reflow: Option<Box<dyn FnMut() + Send + Sync + 'static>>,
+    /// Function to run right after the text is reformatted
+    pub(crate) on_after_reflow: Option<Box<dyn FnMut() + Send + Sync + 'static>>,
+    /// Function to run when the user toggles the mark on a line by clicking its line number
+    ///
+    /// It is given the index of the line and whether the line is now marked.
+    pub(crate) on_gutter_mark: Option<Box<dyn FnMut(usize, bool) + Send + Sync + 'static>>,
+    /// Function to run when the user selects a line with `Enter`, it is given the index of the line
+    pub(crate) on_select: Option<Box<dyn FnMut(usize) + Send + Sync + 'static>>,
+    /// Function to run when the user yanks the selected lines, it is given their text
+    pub(crate) on_yank: Option<Box<dyn FnMut(String) + Send + Sync + 'static>>,
+    /// Function to run when the user clicks a hyperlink, it is given the URI of the link
+    pub(crate) on_link_activate: Option<Box<dyn FnMut(String) + Send + Sync + 'static>>,
+    /// Function that is told about what happens in the pager
+    pub(crate) event_observer: Option<Box<dyn Fn(PagerObservation) + Send + Sync + 'static>>,
+    /// The upper mark that [`PagerState::event_observer`] was last told about
+    pub(crate) observed_upper_mark: usize,
+    /// Indices of the lines in [`PagerState::lines`] that are marked
+    pub(crate) gutter_marks: BTreeSet<usize>,
+    /// All buffers of the pager, see [`Buffer`]
+    ///
+    /// The buffer at [`PagerState::active_buffer`] is the one being displayed. Its contents live
+    /// in the fields of the `PagerState` itself while it is displayed and its slot here is empty.
+    pub(crate) buffers: Vec<Buffer>,
+    /// Index of the buffer being displayed in [`PagerState::buffers`]
+    pub(crate) active_buffer: usize,
+    /// Character shown in front of the line numbers of marked lines
+    pub(crate) gutter_mark_glyph: Option<char>,
+    /// Whether the last page was in view after the last scroll
+    ///
+    /// This makes sure that [`PagerState::on_reach_bottom`] only runs when the user newly reaches
+    /// the bottom
+    pub(crate) at_bottom: bool,
+    /// Whether the application has said that it won't send any more text
+    pub(crate) input_finished: bool,
+    /// Whether to quit once the input has finished and the user has scrolled to the end of the
+    /// text
+    pub(crate) exit_on_finish: bool,
+    /// Whether the first page was in view after the last scroll
+    pub(crate) at_top: bool,
     /// The behaviour to do when user quits the program using `q` or `Ctrl+C`
     /// See [`ExitStrategy`] for available options
     pub(crate) exit_strategy: ExitStrategy,
+    /// Keys that quit the pager when using the [`DefaultInputClassifier`](input::DefaultInputClassifier)
+    pub(crate) exit_keys: Vec<KeyEvent>,
+    /// Keys that quit the pager and write the text to the main screen when using the
+    /// [`DefaultInputClassifier`](input::DefaultInputClassifier)
+    pub(crate) dump_exit_keys: Vec<KeyEvent>,
+    /// Row of the screen, counting from the top, whose background is highlighted
+    pub(crate) highlight_row: Option<usize>,
+    /// Background color of [`PagerState::highlight_row`]
+    pub(crate) highlight_color: Color,
+    /// Formatted row of the cursor, counting from the start of the text, while in cursor mode
+    ///
+    /// In cursor mode, the keys that scroll by lines move the cursor instead, and the text only
+    /// scrolls when the cursor would leave the screen. `None` when not in cursor mode.
+    pub(crate) cursor_line: Option<usize>,
+    /// Background color of the row at [`PagerState::cursor_line`]
+    pub(crate) cursor_color: Color,
+    /// Number of rows kept between the cursor and the top or bottom of the screen where possible
+    pub(crate) scroll_off: usize,
+    /// Formatted row where the selection was started, while lines are selected in cursor mode
+    ///
+    /// The selection covers all lines from this row to [`PagerState::cursor_line`].
+    pub(crate) selection_anchor: Option<usize>,
+    /// Background color of the selected rows
+    pub(crate) selection_color: Color,
+    /// Whether mouse events are captured from the terminal
+    ///
+    /// This is needed for scrolling with the mouse but prevents selecting text with the terminal's
+    /// native selection.
+    pub(crate) mouse_capture: bool,
+    /// Number of rows that a turn of the mouse wheel scrolls by
+    pub(crate) mouse_scroll_amount: usize,
+    /// Number of columns that a turn of the mouse wheel with `Shift` held scrolls by
+    pub(crate) horizontal_mouse_scroll_amount: usize,
+    /// Whether dragging the mouse with the left button held scrolls the text
+    pub(crate) drag_scroll: bool,
+    /// Whether scrolling by lines past either end of the text continues from the other end
+    pub(crate) scroll_wrap: bool,
+    /// What `Enter` does while no message is shown
+    pub(crate) enter_action: EnterAction,
+    /// Whether the prompt is displayed above or below the text
+    pub(crate) prompt_position: PromptPosition,
+    /// Row of the screen where the mouse was during the last drag event, or where the drag
+    /// started
+    pub(crate) drag_row: Option<usize>,
+    /// Whether the pager is displayed on the alternate screen of the terminal
+    ///
+    /// Without it, the pager draws over the main screen and the last screen stays in the
+    /// terminal's scrollback after quitting.
+    pub(crate) alternate_screen: bool,
+    /// Whether the terminal is restored when a panic happens while the pager is running
+    pub(crate) panic_cleanup: bool,
+    /// Whether the application feeds terminal events to the pager with
+    /// [`Pager::feed_event`](crate::Pager::feed_event) instead of minus reading them itself
+    pub(crate) external_input: bool,
+    /// Where the pager draws if the application gave a writer with
+    /// [`Pager::set_output`](crate::Pager::set_output)
+    ///
+    /// It is taken out when the pager starts.
+    pub(crate) output: Option<Box<dyn Write + Send>>,
+    /// Whether the pager draws into a writer given by the application instead of stdout
+    ///
+    /// The terminal of the process is not put into raw mode then.
+    pub(crate) custom_output: bool,
+    /// Whether to write the lines below the current screen to the main screen on quitting
+    pub(crate) exit_dump_remaining: bool,
+    /// Which lines are written to the main screen when they are dumped on quitting
+    pub(crate) exit_dump_scope: DumpScope,
     /// Any message to display to the user at the prompt
     /// The first element contains the actual message, while the second element tells
     /// whether the message has changed since the last display.
@@ -62,15 +372,24 @@ pub struct PagerState {
     ///
     /// This is useful for keeping track of the range of lines which are currently being displayed on
     /// the terminal.
-    /// When `rows - 1` is added to the `upper_mark`, it gives the lower bound of scroll.
+    /// When `rows - 1` (or `rows - 2` while a status bar is shown) is added to the `upper_mark`,
+    /// it gives the lower bound of scroll.
     ///
     /// For example if there are 10 rows is a terminal and the data to display has 50 lines in it/
     /// If the `upper_mark` is 15, then the first row of the terminal is the 16th line of the data
     /// and last row is the 24th line of the data.
     pub upper_mark: usize,
-    /// Do we want to page if there is no overflow
+    /// Line to scroll to once the text has that many lines, counted from 1
+    ///
+    /// It is cleared once the pager has scrolled to it.
+    pub(crate) start_line: Option<usize>,
+    /// The first column of the text that is displayed when long lines are truncated
+    ///
+    /// This is ignored while lines are wrapped.
+    pub(crate) left_mark: usize,
+    /// Whether static output is paged when it fits on the screen
     #[cfg(feature = "static_output")]
-    pub(crate) run_no_overflow: bool,
+    pub(crate) overflow_behavior: OverflowBehavior,
     /// Stores the most recent search term
     #[cfg(feature = "search")]
     pub(crate) search_term: Option<regex::Regex>,
@@ -88,7 +407,87 @@ pub struct PagerState {
     /// It should be 0 even when no search is in action
     #[cfg(feature = "search")]
     pub(crate) search_mark: usize,
+    /// Whether search matches are highlighted on the screen
+    ///
+    /// Hiding them keeps the search and its matches, so that moving between matches still
+    /// works. The matches stay styled in the formatted rows, their styles are only left out when
+    /// drawing, so that toggling this doesn't format the text again.
+    #[cfg(feature = "search")]
+    pub(crate) highlight_visible: bool,
+    /// Whether the lines without a search match are dimmed while there is a search
+    ///
+    /// Unlike hiding those lines, this keeps the context of the matches on the screen.
+    #[cfg(feature = "search")]
+    pub(crate) search_focus: bool,
+    /// Whether moving past the last or first match continues from the other end
+    #[cfg(feature = "search")]
+    pub(crate) search_wrap: bool,
+    /// Whether to place the match in the middle of the screen when moving to it
+    #[cfg(feature = "search")]
+    pub(crate) center_on_match: bool,
+    /// Whether search patterns are run against the entire text so that they can match across lines
+    #[cfg(feature = "search")]
+    pub(crate) search_multiline: bool,
+    /// The number of search matches after which no more are collected or highlighted
+    ///
+    /// This keeps patterns that match nearly every row of a large text from slowing the pager
+    /// down.
+    #[cfg(feature = "search")]
+    pub(crate) max_search_matches: Option<usize>,
+    /// Whether search queries are matched verbatim instead of as regular expressions
+    #[cfg(feature = "search")]
+    pub(crate) search_literal: bool,
+    /// Whether a search that matches nothing replaces the previous search
+    #[cfg(feature = "search")]
+    pub(crate) keep_unmatched_search: bool,
+    /// How the user is told that a scroll hit the top or the end of the text
+    pub(crate) edge_feedback: EdgeFeedback,
+    /// When the screen that is inverted by [`EdgeFeedback::Flash`] is turned back, if it is
+    pub(crate) flash_deadline: Option<Instant>,
+    /// Whether to ring the terminal bell when a search matches nothing
+    #[cfg(feature = "search")]
+    pub(crate) beep_on_no_match: bool,
+    /// Keys that start a forward and a reverse search, which are also shown before the query
+    #[cfg(feature = "search")]
+    pub(crate) search_keys: (char, char),
+    /// First and last index of the lines in [`PagerState::lines`] that are searched
+    ///
+    /// Matches in other lines are neither highlighted nor moved to. `None` searches all lines.
+    #[cfg(feature = "search")]
+    pub(crate) search_range: Option<(usize, usize)>,
+    /// Patterns whose matches are always highlighted, each in its own color
+    #[cfg(feature = "search")]
+    pub(crate) highlight_groups: Vec<regex::Regex>,
+    /// Pattern for the lines that start a section, which `(` and `)` move between
+    #[cfg(feature = "search")]
+    pub(crate) section_pattern: Option<regex::Regex>,
+    /// Style of the search match at [`PagerState::search_mark`]
+    #[cfg(feature = "search")]
+    pub(crate) current_match_style: ContentStyle,
+    /// Style of all search matches other than the current one
+    #[cfg(feature = "search")]
+    pub(crate) other_match_style: ContentStyle,
+    /// Escapes that turn [`PagerState::other_match_style`] on and off
+    ///
+    /// These are kept here so that they don't need to be generated for every line that is
+    /// formatted.
+    #[cfg(feature = "search")]
+    pub(crate) match_escapes: (String, String),
+    /// Template for the reference to the line at the top of the screen that is copied to the
+    /// clipboard, see [`Pager::set_line_ref_template`](crate::Pager::set_line_ref_template)
+    #[cfg(feature = "clipboard")]
+    pub(crate) line_ref_template: String,
+    /// Where copied text is put, the system clipboard outside of tests
+    #[cfg(feature = "clipboard")]
+    pub(crate) clipboard: Box<dyn ClipboardBackend + Send + Sync>,
+    /// File that is followed while the pager runs, see
+    /// [`Pager::follow_file`](crate::Pager::follow_file)
+    #[cfg(feature = "follow_file")]
+    pub(crate) followed_file: Option<std::path::PathBuf>,
     /// Available rows in the terminal
+    ///
+    /// This includes the rows taken by the prompt and the status bar, see
+    /// [`PagerState::content_rows`] for the rows left for the text.
     pub rows: usize,
     /// Available columns in the terminal
     pub cols: usize,
@@ -96,6 +495,141 @@ pub struct PagerState {
     /// It keeps track of all the numbers that have been entered by the user
     /// untill any of `j`, `k`, `G`, `Up` or `Down` is pressed
     pub prefix_num: String,
+    /// The first key of a command made of two keys, if it was the last key pressed
+    ///
+    /// It is set by [`InputEvent::PendingKey`] and cleared by any other input.
+    pub(crate) pending_key: Option<char>,
+    /// The last command typed by the user that `.` repeats
+    ///
+    /// Only jumps are kept, like those to search matches, paragraphs or by a page. A scroll is
+    /// kept as an [`InputEvent::ScrollBy`] by the rows it moved. Custom input classifiers that
+    /// keep the default binding of `.` repeat it as well.
+    pub(crate) last_repeatable: Option<InputEvent>,
+    /// Whether number keys are taken as a count for the next command
+    ///
+    /// When this is `false`, the default bindings ignore number keys so that they can be bound
+    /// to other actions.
+    pub(crate) count_prefix_enabled: bool,
+    /// Whether a count that is being typed is shown in the prompt
+    pub(crate) count_display: bool,
+    /// How long a count is kept without a command following it
+    pub(crate) count_timeout: Duration,
+}
+
+/// The options that affect how the text is displayed, along with their defaults
+///
+/// This is the only place where the defaults of these options are set.
+/// [`PagerState::with_size`] starts out with them and [`PagerState::reset_display_options`]
+/// restores them. Both take the struct apart without `..`, so an option added here can't be left
+/// out by either of them.
+#[allow(clippy::struct_excessive_bools)]
+struct DisplayOptions {
+    smooth_scroll: Option<Duration>,
+    line_numbers: LineNumbers,
+    line_number_style: LineNumberStyle,
+    continuation_number_style: ContinuationNumberStyle,
+    line_wrapping: bool,
+    wrap_mode: WrapMode,
+    wide_char_wrap: WideCharWrap,
+    ambiguous_width: AmbiguousWidth,
+    wrap_indicator: Option<String>,
+    show_whitespace: bool,
+    carriage_return_mode: CrMode,
+    color_enabled: bool,
+    scrollbar: bool,
+    max_content_width: Option<usize>,
+    scroll_past_end: bool,
+    frozen_header_lines: usize,
+    page_overlap: usize,
+    end_of_buffer_char: Option<char>,
+    truncation_marker: char,
+    text_direction: TextDirection,
+    reflow: bool,
+    prompt_style: ContentStyle,
+    mouse_scroll_amount: usize,
+    horizontal_mouse_scroll_amount: usize,
+    scroll_wrap: bool,
+    prompt_position: PromptPosition,
+    highlight_row: Option<usize>,
+    highlight_color: Color,
+    cursor_color: Color,
+    scroll_off: usize,
+    selection_color: Color,
+    gutter_mark_glyph: Option<char>,
+    edge_feedback: EdgeFeedback,
+    #[cfg(feature = "search")]
+    highlight_visible: bool,
+    #[cfg(feature = "search")]
+    search_focus: bool,
+    #[cfg(feature = "search")]
+    search_wrap: bool,
+    #[cfg(feature = "search")]
+    center_on_match: bool,
+    #[cfg(feature = "search")]
+    max_search_matches: Option<usize>,
+    #[cfg(feature = "search")]
+    current_match_style: ContentStyle,
+    #[cfg(feature = "search")]
+    other_match_style: ContentStyle,
+    #[cfg(feature = "search")]
+    match_escapes: (String, String),
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            smooth_scroll: None,
+            line_numbers: LineNumbers::Disabled,
+            line_number_style: LineNumberStyle::default(),
+            continuation_number_style: ContinuationNumberStyle::Blank,
+            line_wrapping: true,
+            wrap_mode: WrapMode::default(),
+            wide_char_wrap: WideCharWrap::default(),
+            ambiguous_width: AmbiguousWidth::default(),
+            wrap_indicator: None,
+            show_whitespace: false,
+            carriage_return_mode: CrMode::default(),
+            // Turned off when the pager starts if NO_COLOR is set, see `color_by_default`
+            color_enabled: true,
+            scrollbar: false,
+            max_content_width: None,
+            scroll_past_end: false,
+            frozen_header_lines: 0,
+            page_overlap: 1,
+            end_of_buffer_char: Some('~'),
+            truncation_marker: '>',
+            text_direction: TextDirection::Ltr,
+            reflow: false,
+            prompt_style: ContentStyle::new().reverse(),
+            mouse_scroll_amount: 5,
+            horizontal_mouse_scroll_amount: 5,
+            scroll_wrap: false,
+            prompt_position: PromptPosition::Bottom,
+            highlight_row: None,
+            highlight_color: Color::DarkGrey,
+            cursor_color: Color::DarkBlue,
+            scroll_off: 0,
+            selection_color: Color::DarkGrey,
+            gutter_mark_glyph: None,
+            edge_feedback: EdgeFeedback::None,
+            #[cfg(feature = "search")]
+            highlight_visible: true,
+            #[cfg(feature = "search")]
+            search_focus: false,
+            #[cfg(feature = "search")]
+            search_wrap: true,
+            #[cfg(feature = "search")]
+            center_on_match: false,
+            #[cfg(feature = "search")]
+            max_search_matches: None,
+            #[cfg(feature = "search")]
+            current_match_style: ContentStyle::new().black().on_yellow(),
+            #[cfg(feature = "search")]
+            other_match_style: ContentStyle::new().reverse(),
+            #[cfg(feature = "search")]
+            match_escapes: search::style_escapes(&ContentStyle::new().reverse()),
+        }
+    }
 }
 
 impl PagerState {
@@ -115,8 +649,17 @@ impl PagerState {
             // For other cases beyond control
             cols = 1;
             rows = 1;
-        };
+        }
 
+        Ok(Self::with_size(cols, rows))
+    }
+
+    /// Creates the state for a terminal with `cols` columns and `rows` rows
+    ///
+    /// Unlike [`PagerState::new`], this does not ask the terminal for its size, so the text is
+    /// formatted the same way whether or not stdout is a terminal.
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn with_size(cols: usize, rows: usize) -> Self {
         let prompt = std::env::current_exe()
             .unwrap_or_else(|_| std::path::PathBuf::from("minus"))
             .file_name()
@@ -127,20 +670,169 @@ impl PagerState {
             .into_string()
             .unwrap_or_else(|_| String::from("minus"));
 
+        let DisplayOptions {
+            smooth_scroll,
+            line_numbers,
+            line_number_style,
+            continuation_number_style,
+            line_wrapping,
+            wrap_mode,
+            wide_char_wrap,
+            ambiguous_width,
+            wrap_indicator,
+            show_whitespace,
+            carriage_return_mode,
+            color_enabled,
+            scrollbar,
+            max_content_width,
+            scroll_past_end,
+            frozen_header_lines,
+            page_overlap,
+            end_of_buffer_char,
+            truncation_marker,
+            text_direction,
+            reflow,
+            prompt_style,
+            mouse_scroll_amount,
+            horizontal_mouse_scroll_amount,
+            scroll_wrap,
+            prompt_position,
+            highlight_row,
+            highlight_color,
+            cursor_color,
+            scroll_off,
+            selection_color,
+            gutter_mark_glyph,
+            edge_feedback,
+            #[cfg(feature = "search")]
+            highlight_visible,
+            #[cfg(feature = "search")]
+            search_focus,
+            #[cfg(feature = "search")]
+            search_wrap,
+            #[cfg(feature = "search")]
+            center_on_match,
+            #[cfg(feature = "search")]
+            max_search_matches,
+            #[cfg(feature = "search")]
+            current_match_style,
+            #[cfg(feature = "search")]
+            other_match_style,
+            #[cfg(feature = "search")]
+            match_escapes,
+        } = DisplayOptions::default();
+
         let mut state = Self {
             lines: String::with_capacity(u16::MAX.into()),
+            line_ends: Vec::new(),
+            line_source: None,
+            source_lines_read: 0,
             formatted_lines: Vec::with_capacity(u16::MAX.into()),
-            line_numbers: LineNumbers::Disabled,
+            line_rows: Vec::new(),
+            lazy_formatting: false,
+            format_cache: FormatCache::default(),
+            rendering_paused: false,
+            redraw_interval: None,
+            last_draw: None,
+            redraw_pending: false,
+            resize_debounce: Duration::ZERO,
+            resize_deadline: None,
+            append_coalesce: None,
+            #[cfg(feature = "dynamic_output")]
+            pending_append: String::new(),
+            #[cfg(feature = "dynamic_output")]
+            append_deadline: None,
+            tick_interval: None,
+            next_tick: None,
+            smooth_scroll,
+            scroll_animation: None,
+            deterministic_mode: false,
+            incomplete_bytes: Vec::new(),
+            control_char_placeholder: None,
+            max_buffer_bytes: None,
+            line_numbers,
+            line_number_style,
+            continuation_number_style,
+            line_wrapping,
+            wrap_mode,
+            wide_char_wrap,
+            ambiguous_width,
+            wrap_indicator,
+            line_styler: None,
+            highlighter: None,
+            show_whitespace,
+            carriage_return_mode,
+            color_enabled,
+            scrollbar,
+            max_content_width,
+            scroll_past_end,
+            frozen_header_lines,
+            page_overlap,
+            end_of_buffer_char,
+            truncation_marker,
+            text_direction,
+            reflow,
+            reflow_source: String::new(),
             upper_mark: 0,
+            start_line: None,
+            left_mark: 0,
             unterminated: 0,
             prompt,
+            prompt_right: String::new(),
+            dynamic_prompt: None,
+            prompt_style,
+            status_bar: None,
             exit_strategy: ExitStrategy::ProcessQuit,
+            exit_dump_remaining: false,
+            exit_dump_scope: DumpScope::Remaining,
+            mouse_capture: true,
+            mouse_scroll_amount,
+            horizontal_mouse_scroll_amount,
+            drag_scroll: false,
+            scroll_wrap,
+            enter_action: EnterAction::ScrollDown,
+            prompt_position,
+            drag_row: None,
+            alternate_screen: true,
+            panic_cleanup: true,
+            external_input: false,
+            output: None,
+            custom_output: false,
+            highlight_row,
+            highlight_color,
+            cursor_line: None,
+            cursor_color,
+            scroll_off,
+            selection_anchor: None,
+            selection_color,
+            exit_keys: vec![parse_key_event("q"), parse_key_event("c-c")],
+            dump_exit_keys: vec![parse_key_event("Q")],
             input_classifier: Box::new(input::DefaultInputClassifier {}),
             exit_callbacks: Vec::with_capacity(5),
+            exit_reason: None,
+            append_feedback: None,
+            on_reach_bottom: None,
+            on_reach_top: None,
+            on_before_reflow: None,
+            on_after_reflow: None,
+            on_gutter_mark: None,
+            on_select: None,
+            event_observer: None,
+            observed_upper_mark: 0,
+            on_yank: None,
+            on_link_activate: None,
+            gutter_marks: BTreeSet::new(),
+            buffers: vec![Buffer::default()],
+            active_buffer: 0,
+            gutter_mark_glyph,
+            at_bottom: false,
+            at_top: true,
+            input_finished: false,
+            exit_on_finish: false,
             message: None,
             displayed_prompt: String::new(),
             #[cfg(feature = "static_output")]
-            run_no_overflow: false,
+            overflow_behavior: OverflowBehavior::default(),
             #[cfg(feature = "search")]
             search_term: None,
             #[cfg(feature = "search")]
@@ -149,14 +841,59 @@ impl PagerState {
             search_idx: BTreeSet::new(),
             #[cfg(feature = "search")]
             search_mark: 0,
+            #[cfg(feature = "search")]
+            highlight_visible,
+            #[cfg(feature = "search")]
+            search_focus,
+            #[cfg(feature = "search")]
+            search_wrap,
+            #[cfg(feature = "search")]
+            center_on_match,
+            #[cfg(feature = "search")]
+            search_multiline: false,
+            #[cfg(feature = "search")]
+            max_search_matches,
+            #[cfg(feature = "search")]
+            search_literal: false,
+            #[cfg(feature = "search")]
+            keep_unmatched_search: true,
+            edge_feedback,
+            flash_deadline: None,
+            #[cfg(feature = "search")]
+            beep_on_no_match: false,
+            #[cfg(feature = "search")]
+            search_keys: ('/', '?'),
+            #[cfg(feature = "search")]
+            search_range: None,
+            #[cfg(feature = "search")]
+            highlight_groups: Vec::new(),
+            #[cfg(feature = "search")]
+            section_pattern: None,
+            #[cfg(feature = "search")]
+            current_match_style,
+            #[cfg(feature = "search")]
+            other_match_style,
+            #[cfg(feature = "search")]
+            match_escapes,
+            #[cfg(feature = "clipboard")]
+            line_ref_template: "%L:%C".to_string(),
+            #[cfg(feature = "clipboard")]
+            clipboard: clipboard::default_backend(),
+            #[cfg(feature = "follow_file")]
+            followed_file: None,
             // Just to be safe in tests, keep at 1x1 size
             cols,
             rows,
             prefix_num: String::new(),
+            pending_key: None,
+            last_repeatable: None,
+            count_prefix_enabled: true,
+            count_display: true,
+            count_timeout: Duration::from_secs(3),
         };
 
         state.format_prompt();
-        Ok(state)
+        state
     }
 
     /// Generate the initial [`PagerState`]
@@ -175,6 +912,8 @@ impl PagerState {
         mut out: &mut Stdout,
     ) -> Result<Self, MinusError> {
         let mut ps = Self::new()?;
+        // Before the events, so that the application can still turn colors back on
+        ps.color_enabled = color_by_default();
         rx.try_iter().try_for_each(|ev| -> Result<(), MinusError> {
             handle_event(
                 ev,
@@ -192,16 +931,28 @@ impl PagerState {
         self.formatted_lines.len()
     }
 
+    /// Returns the escapes that search matches are styled with when the text is formatted
+    ///
+    /// Matches are styled even while the highlights are hidden, see
+    /// [`PagerState::highlight_visible`].
+    #[cfg(feature = "search")]
+    pub(crate) fn match_style(&self) -> (&str, &str) {
+        (&self.match_escapes.0, &self.match_escapes.1)
+    }
+
     /// Formats the given `line`
     ///
     /// - `line_numbers` tells whether to format the line with line numbers.
     /// - `len_line_number` is the length of the number of lines in [`PagerState::lines`] as in a string.
-    ///     For example, this will be 2 if number of lines in [`PagerState::lines`] is 50 and 3 if
-    ///     number of lines in [`PagerState::lines`] is 500. This is used for calculating the padding
-    ///     of each displayed line.
+    ///   For example, this will be 2 if number of lines in [`PagerState::lines`] is 50 and 3 if
+    ///   number of lines in [`PagerState::lines`] is 500. This is used for calculating the padding
+    ///   of each displayed line.
     /// - `idx` is the position index where the line is placed in [`PagerState::lines`].
     /// - `formatted_idx` is the position index where the line will be placed in the resulting
-    ///    [`PagerState::formatted_lines`]
+    ///   [`PagerState::formatted_lines`]
+    /// - `match_ranges` are the parts of the line matched by a multiline search. If it is `None`,
+    ///   each row is searched on its own. See [`search::multiline_match_ranges`].
+    #[allow(clippy::too_many_lines)]
     pub(crate) fn formatted_line(
         &self,
         line: &str,
@@ -209,57 +960,113 @@ impl PagerState {
         idx: usize,
         #[cfg(feature = "search")] formatted_idx: usize,
         #[cfg(feature = "search")] search_idx: &mut BTreeSet<usize>,
+        #[cfg(feature = "search")] match_ranges: Option<&[Range<usize>]>,
     ) -> Vec<String> {
         let line_numbers = matches!(
             self.line_numbers,
             LineNumbers::Enabled | LineNumbers::AlwaysOn
         );
+        let rtl = self.text_direction == TextDirection::Rtl;
+        let style = self.line_styler.as_ref().and_then(|func| func(idx, line));
+        #[cfg(feature = "search")]
+        let search_term = self.search_term.as_ref().filter(|_| {
+            self.in_search_range(idx)
+                && !self.reached_max_matches(self.search_idx.len() + search_idx.len())
+        });
+        let rendered = self.render_line(line);
+        let line = rendered.as_ref();
 
-        if line_numbers {
-            // Padding is the space that the actual line text will be shifted to accomodate for
+        let rows = if line_numbers {
+            // The gutter is the space that the actual line text will be shifted to accomodate for
             // in line numbers. This is equal to:-
-            // 1 for initial space + len_line_number + 1 for `.` sign and + 1 for the followup space
+            // LineNumbers::EXTRA_PADDING for the initial space + width of the number + width of
+            // the separator
             //
             // We reduce this from the number of available columns as this space cannot be used for
             // actual line display when wrapping the lines
-            let padding = len_line_number + LineNumbers::EXTRA_PADDING;
-            let wrapped_lines = wrap_str(line, self.cols.saturating_sub(padding + 2));
+            let number_width = self.line_number_style.min_width.max(len_line_number);
+            let gutter = self.gutter_width(len_line_number);
+            let text_cols = self.content_cols().saturating_sub(gutter);
+            let wrapped_lines = style_rows(self.highlight_line(line, idx, text_cols), style);
+            #[cfg(feature = "search")]
+            let row_offsets = match_ranges.map(|_| self.row_offsets(line, &wrapped_lines));
             let mut formatted_rows = Vec::with_capacity(256);
 
+            // Marked lines show the glyph in the gutter, right next to the line number
+            let glyph = self
+                .gutter_mark_glyph
+                .filter(|_| self.gutter_marks.contains(&idx));
+            let pad_number = |label: String| match self.line_number_style.alignment {
+                LineNumberAlignment::Left => format!("{label: <number_width$}"),
+                LineNumberAlignment::Right => format!("{label: >number_width$}"),
+            };
+            // Puts `row` and `number` together with the separator, on the side of the text
+            // direction
+            let with_gutter = |row: &str, number: &str, glyph: Option<char>| {
+                let number = if rtl {
+                    format!("{}{number}", glyph.unwrap_or(' '))
+                } else {
+                    format!(
+                        "{}{}{number}",
+                        glyph.unwrap_or(' '),
+                        " ".repeat(LineNumbers::EXTRA_PADDING - 1)
+                    )
+                };
+                let separator = &self.line_number_style.separator;
+
+                if rtl {
+                    // Mirror the layout, the text ends right before the line number and the
+                    // separator is placed at the edge of the screen
+                    let row = align_right(row, text_cols, self.ambiguous_width);
+                    let separator = separator.trim_end();
+                    if cfg!(not(test)) {
+                        format!(
+                            "{row}{bold}{number}{separator}{reset}",
+                            bold = crossterm::style::Attribute::Bold,
+                            reset = crossterm::style::Attribute::Reset,
+                        )
+                    } else {
+                        format!("{row}{number}{separator}")
+                    }
+                } else if cfg!(not(test)) {
+                    format!(
+                        "{bold}{number}{separator}{reset}{row}",
+                        bold = crossterm::style::Attribute::Bold,
+                        reset = crossterm::style::Attribute::Reset,
+                        row = row
+                    )
+                } else {
+                    // In tests, we don't care about ANSI sequences for cool looking line numbers
+                    // hence we don't include them in tests. It just makes testing more difficult
+                    format!("{number}{separator}{row}")
+                }
+            };
+
             let first_line = {
                 #[cfg_attr(not(feature = "search"), allow(unused_mut))]
-                let mut row = wrapped_lines.first().unwrap().to_string();
+                let mut row = wrapped_lines.first().unwrap().clone();
 
                 #[cfg(feature = "search")]
-                if let Some(st) = self.search_term.as_ref() {
+                if self.search_term.is_some() || !self.highlight_groups.is_empty() {
                     // highlight the lines with matching search terms
                     // If a match is found, add this line's index to PagerState::search_idx
-                    let (highlighted_row, is_match) = search::highlight_line_matches(&row, st);
+                    let multiline = match_ranges
+                        .zip(row_offsets.as_ref())
+                        .map(|(r, o)| (r, o[0]));
+                    let (highlighted_row, is_match) = search::highlight_row(
+                        &row,
+                        search_term,
+                        multiline,
+                        &self.highlight_groups,
+                        self.match_style(),
+                    );
                     if is_match {
                         search_idx.insert(formatted_idx);
                     }
                     row = highlighted_row;
                 }
 
-                if cfg!(not(test)) {
-                    format!(
-                        "{bold}{number: >len$}.{reset} {row}",
-                        bold = crossterm::style::Attribute::Bold,
-                        number = idx + 1,
-                        len = padding,
-                        reset = crossterm::style::Attribute::Reset,
-                        row = row
-                    )
-                } else {
-                    // In tests, we don't care about ANSI sequences for cool looking line numbers
-                    // hence we don't include them in tests. It just makes testing more difficult
-                    format!(
-                        "{number: >len$}. {row}",
-                        number = idx + 1,
-                        len = padding,
-                        row = row
-                    )
-                }
+                with_gutter(&row, &pad_number((idx + 1).to_string()), glyph)
             };
 
             formatted_rows.push(first_line);
@@ -272,141 +1079,869 @@ impl PagerState {
                 .skip(1)
                 .map(|(wrap_idx, mut row)| {
                     #[cfg(feature = "search")]
-                    if let Some(st) = self.search_term.as_ref() {
+                    if self.search_term.is_some() || !self.highlight_groups.is_empty() {
                         // highlight the lines with matching search terms
                         // If a match is found, add this line's index to PagerState::search_idx
-                        let (highlighted_row, is_match) = search::highlight_line_matches(&row, st);
+                        let multiline = match_ranges
+                            .zip(row_offsets.as_ref())
+                            .map(|(r, o)| (r, o[wrap_idx]));
+                        let (highlighted_row, is_match) = search::highlight_row(
+                            &row,
+                            search_term,
+                            multiline,
+                            &self.highlight_groups,
+                            self.match_style(),
+                        );
                         if is_match {
                             search_idx.insert(formatted_idx + wrap_idx);
                         }
                         row = highlighted_row;
                     }
-                    " ".repeat(padding + 2) + &row
+                    let row = format!("{}{row}", self.wrap_indicator.as_deref().unwrap_or(""));
+                    match self.continuation_number_style {
+                        ContinuationNumberStyle::Blank if rtl => {
+                            align_right(&row, text_cols, self.ambiguous_width)
+                        }
+                        ContinuationNumberStyle::Blank => " ".repeat(gutter) + &row,
+                        ContinuationNumberStyle::Repeat => {
+                            with_gutter(&row, &pad_number((idx + 1).to_string()), None)
+                        }
+                        ContinuationNumberStyle::Marker(marker) => {
+                            with_gutter(&row, &pad_number(marker.to_string()), None)
+                        }
+                    }
                 })
                 .collect::<Vec<String>>();
             formatted_rows.append(&mut lines_left);
             formatted_rows
         } else {
+            let wrapped_lines =
+                style_rows(self.highlight_line(line, idx, self.content_cols()), style);
+            #[cfg(feature = "search")]
+            let row_offsets = match_ranges.map(|_| self.row_offsets(line, &wrapped_lines));
+
             #[cfg_attr(not(feature = "search"), allow(unused_variables))]
-            wrap_str(line, self.cols)
+            wrapped_lines
                 .iter()
                 .enumerate()
                 .map(|(wrap_idx, row)| {
                     #[cfg(feature = "search")]
-                    {
-                        self.search_term.as_ref().map_or_else(
-                            || row.to_string(),
-                            |st| {
-                                // highlight the lines with matching search terms
-                                // If a match is found, add this line's index to PagerState::search_idx
-                                let (hrow, is_match) = search::highlight_line_matches(row, st);
-                                if is_match {
-                                    search_idx.insert(formatted_idx + wrap_idx);
-                                }
-                                hrow
-                            },
-                        )
-                    }
+                    let row = if self.search_term.is_some() || !self.highlight_groups.is_empty() {
+                        // highlight the lines with matching search terms
+                        // If a match is found, add this line's index to PagerState::search_idx
+                        let multiline = match_ranges
+                            .zip(row_offsets.as_ref())
+                            .map(|(r, o)| (r, o[wrap_idx]));
+                        let (hrow, is_match) = search::highlight_row(
+                            row,
+                            search_term,
+                            multiline,
+                            &self.highlight_groups,
+                            self.match_style(),
+                        );
+                        if is_match {
+                            search_idx.insert(formatted_idx + wrap_idx);
+                        }
+                        hrow
+                    } else {
+                        row.clone()
+                    };
                     #[cfg(not(feature = "search"))]
-                    row.to_string()
+                    let row = row.clone();
+                    let row = match self.wrap_indicator.as_deref() {
+                        Some(indicator) if wrap_idx > 0 => format!("{indicator}{row}"),
+                        _ => row,
+                    };
+                    if rtl {
+                        align_right(&row, self.content_cols(), self.ambiguous_width)
+                    } else {
+                        row
+                    }
                 })
                 .collect::<Vec<String>>()
+        };
+        if self.color_enabled {
+            rows
+        } else {
+            rows.iter().map(|row| strip_sgr(row).into_owned()).collect()
         }
     }
 
-    pub(crate) fn format_lines(&mut self) {
-        // Keep it for the record and don't call it unless it is really necessory as this is kinda
-        // expensive
-        let line_count = self.lines.lines().count();
+    /// Returns the number of columns taken by the [wrap indicator](PagerState::wrap_indicator)
+    /// on rows that continue a line
+    fn wrap_indent(&self) -> usize {
+        self.wrap_indicator.as_deref().map_or(0, display_width)
+    }
 
-        // Calculate len_line_number. This will be 2 if line_count is 50 and 3 if line_count is 100 (etc)
-        let len_line_number = line_count.to_string().len();
+    /// Returns `true` if the line at `idx` in [`PagerState::lines`] is within
+    /// [`PagerState::search_range`]
+    #[cfg(feature = "search")]
+    fn in_search_range(&self, idx: usize) -> bool {
+        self.search_range
+            .map_or(true, |(start, end)| (start..=end).contains(&idx))
+    }
 
-        // Search idx, this will get filled by the self.formatted_line function
-        // we will later set this to self.search_idx
-        #[cfg(feature = "search")]
-        let mut search_idx = BTreeSet::new();
-        let mut formatted_idx = 0;
+    /// Returns `true` if `found` search matches are as many as
+    /// [`PagerState::max_search_matches`] allows
+    #[cfg(feature = "search")]
+    fn reached_max_matches(&self, found: usize) -> bool {
+        self.max_search_matches.map_or(false, |max| found >= max)
+    }
 
-        self.formatted_lines = self
-            .lines
-            .lines()
-            .enumerate()
-            .flat_map(|(idx, line)| {
-                let new_line = self.formatted_line(
-                    line,
-                    len_line_number,
-                    idx,
-                    #[cfg(feature = "search")]
-                    formatted_idx,
-                    #[cfg(feature = "search")]
-                    &mut search_idx,
-                );
-                formatted_idx += new_line.len();
-                new_line
-            })
-            .collect::<Vec<String>>();
+    /// Returns `true` if the search stopped collecting matches at
+    /// [`PagerState::max_search_matches`]
+    #[cfg(feature = "search")]
+    pub(crate) fn search_matches_capped(&self) -> bool {
+        self.reached_max_matches(self.search_idx.len())
+    }
 
-        #[cfg(feature = "search")]
+    /// Drops the search matches past [`PagerState::max_search_matches`]
+    ///
+    /// Matches are only checked against the limit line by line, so the rows of a wrapped line can
+    /// take the matches past it.
+    #[cfg(feature = "search")]
+    fn cap_search_idx(&mut self) {
+        if let Some(&first_dropped) = self
+            .max_search_matches
+            .and_then(|max| self.search_idx.iter().nth(max))
         {
-            self.search_idx = search_idx;
+            self.search_idx.split_off(&first_dropped);
         }
-
-        self.format_prompt();
     }
 
-    /// Reformat the inputted prompt to how it should be displayed
-    pub(crate) fn format_prompt(&mut self) {
-        const SEARCH_BG: &str = "\x1b[34m";
-        const INPUT_BG: &str = "\x1b[33m";
+    /// Restores all options that affect how the text is displayed to their defaults
+    ///
+    /// The text and the line at the top of the screen are kept. The defaults are those of
+    /// [`DisplayOptions`].
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn reset_display_options(&mut self) {
+        let DisplayOptions {
+            smooth_scroll,
+            line_numbers,
+            line_number_style,
+            continuation_number_style,
+            line_wrapping,
+            wrap_mode,
+            wide_char_wrap,
+            ambiguous_width,
+            wrap_indicator,
+            show_whitespace,
+            carriage_return_mode,
+            color_enabled,
+            scrollbar,
+            max_content_width,
+            scroll_past_end,
+            frozen_header_lines,
+            page_overlap,
+            end_of_buffer_char,
+            truncation_marker,
+            text_direction,
+            reflow,
+            prompt_style,
+            mouse_scroll_amount,
+            horizontal_mouse_scroll_amount,
+            scroll_wrap,
+            prompt_position,
+            highlight_row,
+            highlight_color,
+            cursor_color,
+            scroll_off,
+            selection_color,
+            gutter_mark_glyph,
+            edge_feedback,
+            #[cfg(feature = "search")]
+            highlight_visible,
+            #[cfg(feature = "search")]
+            search_focus,
+            #[cfg(feature = "search")]
+            search_wrap,
+            #[cfg(feature = "search")]
+            center_on_match,
+            #[cfg(feature = "search")]
+            max_search_matches,
+            #[cfg(feature = "search")]
+            current_match_style,
+            #[cfg(feature = "search")]
+            other_match_style,
+            #[cfg(feature = "search")]
+            match_escapes,
+        } = DisplayOptions::default();
 
-        // Allocate the string. Add extra space in case for the
-        // ANSI escape things if we do have characters typed and search showing
-        let mut format_string = String::with_capacity(self.cols + (SEARCH_BG.len() * 2) + 4);
+        // Finish a running smooth scroll right away, as it may be turned off
+        if let Some(animation) = self.scroll_animation.take() {
+            self.upper_mark = animation.to;
+        }
+        // Turning reflowing off brings back the original lines, which moves the line at the top
+        self.set_reflow(reflow);
+        let top_line = self.line_at_row(self.upper_mark);
 
-        // Get the string that will contain the search index/match indicator
+        self.smooth_scroll = smooth_scroll;
+        self.line_numbers = line_numbers;
+        self.line_number_style = line_number_style;
+        self.continuation_number_style = continuation_number_style;
+        self.line_wrapping = line_wrapping;
+        self.wrap_mode = wrap_mode;
+        self.wide_char_wrap = wide_char_wrap;
+        self.ambiguous_width = ambiguous_width;
+        self.wrap_indicator = wrap_indicator;
+        self.show_whitespace = show_whitespace;
+        self.carriage_return_mode = carriage_return_mode;
+        // Like when the pager starts
+        self.color_enabled = color_enabled && color_by_default();
+        self.scrollbar = scrollbar;
+        self.max_content_width = max_content_width;
+        self.scroll_past_end = scroll_past_end;
+        self.frozen_header_lines = frozen_header_lines;
+        self.page_overlap = page_overlap;
+        self.end_of_buffer_char = end_of_buffer_char;
+        self.truncation_marker = truncation_marker;
+        self.text_direction = text_direction;
+        self.prompt_style = prompt_style;
+        self.mouse_scroll_amount = mouse_scroll_amount;
+        self.horizontal_mouse_scroll_amount = horizontal_mouse_scroll_amount;
+        self.scroll_wrap = scroll_wrap;
+        self.prompt_position = prompt_position;
+        self.highlight_row = highlight_row;
+        self.highlight_color = highlight_color;
+        self.cursor_color = cursor_color;
+        self.scroll_off = scroll_off;
+        self.selection_color = selection_color;
+        self.gutter_mark_glyph = gutter_mark_glyph;
+        self.edge_feedback = edge_feedback;
         #[cfg(feature = "search")]
-        let mut search_str = String::new();
+        {
+            self.highlight_visible = highlight_visible;
+            self.search_focus = search_focus;
+            self.search_wrap = search_wrap;
+            self.center_on_match = center_on_match;
+            self.max_search_matches = max_search_matches;
+            self.current_match_style = current_match_style;
+            self.other_match_style = other_match_style;
+            self.match_escapes = match_escapes;
+        }
+
+        self.format_lines();
+        self.upper_mark = self.row_of_line(top_line);
         #[cfg(feature = "search")]
-        if !self.search_idx.is_empty() {
-            search_str.push(' ');
-            search_str.push_str(&(self.search_mark + 1).to_string());
-            search_str.push('/');
-            search_str.push_str(&self.search_idx.len().to_string());
-            search_str.push(' ');
+        {
+            self.search_mark = self
+                .search_mark
+                .min(self.search_idx.len().saturating_sub(1));
         }
+    }
 
-        // And get the string that will contain the prefix_num
-        let mut prefix_str = String::new();
-        if !self.prefix_num.is_empty() {
-            prefix_str.push(' ');
-            prefix_str.push_str(&self.prefix_num);
-            prefix_str.push(' ');
+    /// Wraps or truncates `line` to `cols` columns depending on [`PagerState::line_wrapping`]
+    fn wrap_line(&self, line: &str, cols: usize) -> Vec<String> {
+        if self.line_wrapping {
+            let mut rows = wrap_str(
+                line,
+                cols,
+                self.wrap_mode,
+                self.wrap_indent(),
+                self.ambiguous_width,
+            );
+            if self.wide_char_wrap == WideCharWrap::PadAndBreak {
+                pad_wide_breaks(&mut rows, cols, self.wrap_indent(), self.ambiguous_width);
+            }
+            rows
+        } else {
+            vec![truncate_str(
+                &skip_columns(line, self.left_mark, self.ambiguous_width),
+                cols,
+                self.truncation_marker,
+                self.ambiguous_width,
+            )]
         }
+    }
 
-        // And lastly, the string that contains the prompt or msg
-        let prompt_str = self.message.as_ref().unwrap_or(&self.prompt);
+    /// Returns the position of each of the `rows` that the rendered `line` is formatted into
+    ///
+    /// See [`search::row_offsets`]. A line that is truncated instead of wrapped has a single row,
+    /// which starts after the columns that are scrolled past.
+    #[cfg(feature = "search")]
+    fn row_offsets(&self, line: &str, rows: &[String]) -> Vec<usize> {
+        if self.line_wrapping {
+            return search::row_offsets(line, rows);
+        }
+        let shown = strip_ansi(&skip_columns(line, self.left_mark, self.ambiguous_width)).len();
+        vec![strip_ansi(line).len().saturating_sub(shown)]
+    }
 
-        #[cfg(feature = "search")]
-        let search_len = search_str.len();
-        #[cfg(not(feature = "search"))]
-        let search_len = 0;
+    /// Returns the text with each line rendered like it is displayed
+    ///
+    /// Multiline searches run on this, so that their matches line up with the formatted rows.
+    #[cfg(feature = "search")]
+    fn rendered_text(&self) -> Cow<'_, str> {
+        if !self.show_whitespace && self.carriage_return_mode == CrMode::Literal {
+            return Cow::Borrowed(&self.lines);
+        }
+        Cow::Owned(
+            self.lines
+                .lines()
+                .map(|line| self.render_line(line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    /// Same as [`PagerState::wrap_line`], but runs the [highlighter](PagerState::highlighter) on
+    /// `line` first if there is one
+    ///
+    /// `idx` is the index of the line in [`PagerState::lines`].
+    fn highlight_line(&self, line: &str, idx: usize, cols: usize) -> Vec<String> {
+        let Some(highlighter) = self.highlighter.as_ref() else {
+            return self.wrap_line(line, cols);
+        };
+        let spans = highlighter.highlight(line, idx);
+        if self.line_wrapping {
+            let mut rows = highlight::wrap_spans(
+                &spans,
+                cols,
+                self.wrap_mode,
+                self.wrap_indent(),
+                self.ambiguous_width,
+            );
+            if self.wide_char_wrap == WideCharWrap::PadAndBreak {
+                pad_wide_breaks(&mut rows, cols, self.wrap_indent(), self.ambiguous_width);
+            }
+            rows
+        } else {
+            // Truncating keeps escapes intact, so the runs can be styled beforehand
+            self.wrap_line(&highlight::render_spans(&spans), cols)
+        }
+    }
+
+    /// Returns the largest useful value for [`PagerState::left_mark`]
+    ///
+    /// Scrolling further than this would only show empty space, as the longest line already
+    /// fits on the screen. Widths are measured in displayed columns, so ANSI escapes don't count.
+    pub(crate) fn max_left_mark(&self) -> usize {
+        let text_cols = self.content_cols().saturating_sub(self.line_number_width());
+        self.lines
+            .lines()
+            .map(display_width)
+            .max()
+            .unwrap_or(0)
+            .saturating_sub(text_cols)
+    }
+
+    /// Returns the number of columns taken up by the line numbers, including the space between
+    /// them and the text
+    ///
+    /// This is 0 if line numbers are not displayed.
+    pub(crate) fn line_number_width(&self) -> usize {
+        if matches!(
+            self.line_numbers,
+            LineNumbers::Enabled | LineNumbers::AlwaysOn
+        ) {
+            let len_line_number = self.lines.lines().count().to_string().len();
+            self.gutter_width(len_line_number)
+        } else {
+            0
+        }
+    }
+
+    /// Returns the number of columns taken up by the line numbers when the last line number has
+    /// `len_line_number` digits
+    pub(crate) fn gutter_width(&self, len_line_number: usize) -> usize {
+        LineNumbers::EXTRA_PADDING
+            + self.line_number_style.min_width.max(len_line_number)
+            + textwrap::core::display_width(&self.line_number_style.separator)
+    }
+
+    /// Returns the index of the line in [`PagerState::lines`] which is displayed at `row` on the
+    /// screen
+    ///
+    /// Returns `None` if `row` is the prompt or status bar or there is no text displayed there.
+    pub(crate) fn locate(&self, row: u16) -> Option<usize> {
+        let formatted_row = self.formatted_row_at(row)?;
+        (formatted_row < self.num_lines()).then(|| self.line_at_row(formatted_row))
+    }
+
+    /// Returns the formatted row displayed at `row` on the screen if there is a hyperlink at
+    /// `column` in it
+    ///
+    /// Returns `None` if `row` is the prompt or status bar or there is no hyperlink there.
+    pub(crate) fn hyperlink_row_at(&self, row: u16, column: usize) -> Option<usize> {
+        let formatted_row = self.formatted_row_at(row)?;
+        self.hyperlink_at(formatted_row, column)
+            .map(|_| formatted_row)
+    }
+
+    /// Returns the URI of the hyperlink at `column` in the formatted row at `formatted_row`
+    ///
+    /// Columns are counted from the start of the row as it is displayed, so lines that are
+    /// scrolled horizontally are counted from [`PagerState::left_mark`].
+    pub(crate) fn hyperlink_at(&self, formatted_row: usize, column: usize) -> Option<String> {
+        hyperlink_at(self.formatted_lines.get(formatted_row)?, column)
+    }
+
+    /// Returns the formatted row displayed at `row` on the screen
+    ///
+    /// Returns `None` if `row` is the prompt or status bar.
+    fn formatted_row_at(&self, row: u16) -> Option<usize> {
+        let row = usize::from(row).checked_sub(self.first_header_row())?;
+        let frozen = self.frozen_rows();
+        if row < frozen {
+            return Some(row);
+        }
+        let row = row - frozen;
+        (row < self.content_rows()).then(|| self.upper_mark + row)
+    }
+
+    /// Returns `line` with its carriage returns handled and whitespace made visible, as configured
+    fn render_line<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let line = match self.carriage_return_mode {
+            CrMode::Overwrite => overwrite_carriage_returns(line),
+            CrMode::Strip if line.contains('\r') => Cow::Owned(line.replace('\r', "")),
+            CrMode::Literal | CrMode::Strip => Cow::Borrowed(line),
+        };
+        if !self.show_whitespace {
+            return line;
+        }
+        match render_whitespace(&line) {
+            Cow::Borrowed(_) => line,
+            Cow::Owned(rendered) => Cow::Owned(rendered),
+        }
+    }
+
+    /// Returns the number of rows each line in [`PagerState::lines`] takes up when formatted
+    pub(crate) fn rows_per_line(&self) -> impl Iterator<Item = usize> + '_ {
+        let cols = self.content_cols().saturating_sub(self.line_number_width());
+        let indent = self.wrap_indent();
+        self.lines.lines().map(move |line| {
+            if self.line_wrapping {
+                wrap_str(
+                    &self.render_line(line),
+                    cols,
+                    self.wrap_mode,
+                    indent,
+                    self.ambiguous_width,
+                )
+                .len()
+            } else {
+                1
+            }
+        })
+    }
+
+    /// Returns the index of the line in [`PagerState::lines`] which is displayed at the formatted
+    /// row `row`
+    ///
+    /// Rows past the end belong to the last line.
+    pub(crate) fn line_at_row(&self, row: usize) -> usize {
+        self.line_rows
+            .partition_point(|&start| start <= row)
+            .saturating_sub(1)
+    }
+
+    /// Returns the index of the line of the text that is displayed at the formatted row `row`
+    ///
+    /// Rows are counted from the start of the text, like [`upper_mark`](PagerState::upper_mark),
+    /// and a line can span several rows if it is wrapped. Returns `None` if `row` is past the
+    /// end of the text.
+    #[must_use]
+    pub fn source_line_at_row(&self, row: usize) -> Option<usize> {
+        (row < self.num_lines()).then(|| self.line_at_row(row))
+    }
+
+    /// Returns the first formatted row of the line of the text at index `line`
+    ///
+    /// This is the row that [`upper_mark`](PagerState::upper_mark) has to be set to for the line
+    /// to be at the top of the screen. Returns `None` if there are fewer lines.
+    #[must_use]
+    pub fn first_row_of_line(&self, line: usize) -> Option<usize> {
+        self.line_rows.get(line).copied()
+    }
+
+    /// Returns the columns covered by the current search in the line of the text at index `line`
+    ///
+    /// Each span is `(start, end)`, where `start` is the display column of the first character of
+    /// a match and `end` the column right after its last one. Columns are counted in the original
+    /// line with all ANSI escapes removed, before wrapping or line numbers are applied, so a wide
+    /// character takes two columns. With [multiline search](PagerState::search_multiline), only
+    /// the part of a match that lies in this line is returned. Empty matches are left out.
+    ///
+    /// Returns an empty list if there is no search, if there are fewer lines or if the line is
+    /// outside [`PagerState::search_range`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    #[must_use]
+    pub fn match_spans(&self, line: usize) -> Vec<(usize, usize)> {
+        let (Some(query), Some(text)) = (self.search_term.as_ref(), self.lines.lines().nth(line))
+        else {
+            return Vec::new();
+        };
+        if !self.in_search_range(line) {
+            Vec::new()
+        } else if self.search_multiline {
+            let ranges = search::multiline_match_ranges(&self.lines, query);
+            ranges
+                .get(line)
+                .map_or_else(Vec::new, |r| search::match_spans(text, query, Some(r)))
+        } else {
+            search::match_spans(text, query, None)
+        }
+    }
+
+    /// Returns the index of the line in [`PagerState::lines`] that is `n` paragraph boundaries
+    /// after the line at the top of the screen, or before it if `forward` is `false`
+    ///
+    /// A boundary is a blank line next to a paragraph, so several blank lines in a row count as
+    /// one. If there are fewer boundaries, this returns the last or first line.
+    pub(crate) fn paragraph_boundary(&self, forward: bool, n: usize) -> usize {
+        let blank: Vec<bool> = self.lines.lines().map(|l| l.trim().is_empty()).collect();
+        let last = blank.len().saturating_sub(1);
+        let mut idx = self.line_at_row(self.upper_mark);
+        for _ in 0..n {
+            let mut in_paragraph = false;
+            loop {
+                let next = if forward {
+                    Some(idx + 1).filter(|i| *i <= last)
+                } else {
+                    idx.checked_sub(1)
+                };
+                let Some(next) = next else {
+                    return idx;
+                };
+                idx = next;
+                if !blank[idx] {
+                    in_paragraph = true;
+                } else if in_paragraph {
+                    break;
+                }
+            }
+        }
+        idx
+    }
+
+    /// Replaces the text with `text`, reflowing it if [`PagerState::reflow`] is set
+    ///
+    /// The text is not formatted, call [`PagerState::format_lines`] afterwards.
+    pub(crate) fn set_text(&mut self, text: String) {
+        if self.reflow {
+            self.lines = reflow(&text);
+            self.reflow_source = text;
+        } else {
+            self.lines = text;
+        }
+    }
+
+    /// Turns reflowing on or off and formats the text again
+    ///
+    /// The paragraph at the top of the screen, or the first line of it, stays in place.
+    pub(crate) fn set_reflow(&mut self, val: bool) {
+        if val == self.reflow {
+            return;
+        }
+        // Each line of the reflowed text is a unit of the original text, see `paragraph_units`
+        let top_line = self.line_at_row(self.upper_mark);
+        let top_line = if val {
+            // Index of the unit that contains the top line
+            paragraph_units(&self.lines)
+                .scan(0, |lines, unit| {
+                    *lines += unit.matches('\n').count().max(1);
+                    Some(*lines)
+                })
+                .take_while(|lines| *lines <= top_line)
+                .count()
+        } else {
+            // Index of the first line of the top unit
+            paragraph_units(&self.reflow_source)
+                .take(top_line)
+                .map(|unit| unit.matches('\n').count())
+                .sum()
+        };
+
+        self.reflow = val;
+        if val {
+            let text = std::mem::take(&mut self.lines);
+            self.set_text(text);
+        } else {
+            self.lines = std::mem::take(&mut self.reflow_source);
+        }
+        self.format_lines();
+        self.upper_mark = self.row_of_line(top_line);
+    }
+
+    /// Returns the byte offset in the text of the line at the top of the screen
+    ///
+    /// Like the byte position shown by `less`, this counts bytes rather than characters. This is
+    /// meant for [dynamic prompts](crate::Pager::set_dynamic_prompt). When lines are wrapped, it
+    /// takes time proportional to the number of lines above the screen.
+    ///
+    /// # Example
+    /// ```
+    /// use minus::Pager;
+    ///
+    /// let pager = Pager::new();
+    /// pager.set_dynamic_prompt(Box::new(|ps| {
+    ///     format!("byte {}/{}", ps.byte_offset(), ps.total_bytes())
+    /// }))
+    /// .expect("Failed to send data to the pager");
+    /// ```
+    #[must_use]
+    pub fn byte_offset(&self) -> usize {
+        if self.line_ends.is_empty() {
+            return 0;
+        }
+        let line_idx = if self.formats_lazily() {
+            self.upper_mark
+        } else {
+            self.line_at_row(self.upper_mark)
+        };
+        line_idx
+            .checked_sub(1)
+            .and_then(|idx| self.line_ends.get(idx))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the size of the text in bytes
+    ///
+    /// See [`PagerState::byte_offset`].
+    #[must_use]
+    pub fn total_bytes(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Brings [`PagerState::line_ends`] up to date after text has been appended to
+    /// [`PagerState::lines`]
+    ///
+    /// Clear [`PagerState::line_ends`] first if the text has been changed in any other way.
+    fn index_line_ends(&mut self) {
+        // A last line without a newline may have been continued by the appended text
+        if let Some(&end) = self.line_ends.last() {
+            if self.lines.as_bytes()[end - 1] != b'\n' {
+                self.line_ends.pop();
+            }
+        }
+        let mut end = self.line_ends.last().copied().unwrap_or(0);
+        for line in self.lines[end..].split_inclusive('\n') {
+            end += line.len();
+            self.line_ends.push(end);
+        }
+    }
+
+    /// Returns the index of the first formatted row of the line at `line_idx` in
+    /// [`PagerState::lines`]
+    ///
+    /// Lines past the end start at the end of the formatted rows.
+    pub(crate) fn row_of_line(&self, line_idx: usize) -> usize {
+        self.first_row_of_line(line_idx)
+            .unwrap_or_else(|| self.num_lines())
+    }
+
+    pub(crate) fn format_lines(&mut self) {
+        self.format_lines_with_progress(|_| {});
+    }
+
+    /// Same as [`PagerState::format_lines`], but calls `progress` with the percentage of lines
+    /// that have been formatted so far
+    ///
+    /// `progress` is called about a hundred times, starting with 0 before the first line is
+    /// formatted.
+    pub(crate) fn format_lines_with_progress(&mut self, mut progress: impl FnMut(usize)) {
+        // The text is formatted for the current terminal size, so a deferred resize is done
+        self.resize_deadline = None;
+        self.line_ends.clear();
+        self.index_line_ends();
+
+        if let Some(func) = self.on_before_reflow.as_mut() {
+            func();
+        }
+
+        // Keep it for the record and don't call it unless it is really necessory as this is kinda
+        // expensive
+        let line_count = self.lines.lines().count();
+
+        // Calculate len_line_number. This will be 2 if line_count is 50 and 3 if line_count is 100 (etc)
+        let len_line_number = line_count.to_string().len();
+
+        if self.formats_lazily() {
+            // Every line takes up exactly one row, so placeholders are enough to know where each
+            // line is. They are filled in by PagerState::ensure_formatted
+            self.formatted_lines = vec![String::new(); line_count];
+            self.line_rows = (0..line_count).collect();
+            self.format_cache.reset(line_count, len_line_number);
+            self.unterminated = usize::from(!self.lines.is_empty() && !self.lines.ends_with('\n'));
+            #[cfg(feature = "search")]
+            self.search_idx.clear();
+            self.format_prompt();
+            if let Some(func) = self.on_after_reflow.as_mut() {
+                func();
+            }
+            return;
+        }
+        self.format_cache.clear();
+        let progress_step = (line_count / 100).max(1);
+
+        // Search idx, this will get filled by the self.formatted_line function
+        // we will later set this to self.search_idx. The old matches are dropped right away, as
+        // they would count towards PagerState::max_search_matches otherwise
+        #[cfg(feature = "search")]
+        let mut search_idx = BTreeSet::new();
+        #[cfg(feature = "search")]
+        self.search_idx.clear();
+        let mut formatted_idx = 0;
+        let mut last_line_rows = 0;
+        let mut line_rows = Vec::with_capacity(line_count);
+
+        // Multiline patterns have to be run against the entire text at once
+        #[cfg(feature = "search")]
+        let match_ranges = self
+            .search_term
+            .as_ref()
+            .filter(|_| self.search_multiline)
+            .map(|st| search::multiline_match_ranges(&self.rendered_text(), st));
+
+        self.formatted_lines = self
+            .lines
+            .lines()
+            .enumerate()
+            .flat_map(|(idx, line)| {
+                if idx % progress_step == 0 {
+                    progress(idx * 100 / line_count);
+                }
+                let new_line = self.formatted_line(
+                    line,
+                    len_line_number,
+                    idx,
+                    #[cfg(feature = "search")]
+                    formatted_idx,
+                    #[cfg(feature = "search")]
+                    &mut search_idx,
+                    #[cfg(feature = "search")]
+                    match_ranges
+                        .as_ref()
+                        .and_then(|r| r.get(idx))
+                        .map(Vec::as_slice),
+                );
+                line_rows.push(formatted_idx);
+                formatted_idx += new_line.len();
+                last_line_rows = new_line.len();
+                new_line
+            })
+            .collect::<Vec<String>>();
+        self.line_rows = line_rows;
+        // The rows of a last line without a newline are replaced when more text is appended to it
+        self.unterminated = if self.lines.ends_with('\n') {
+            0
+        } else {
+            last_line_rows
+        };
+
+        #[cfg(feature = "search")]
+        {
+            self.search_idx = search_idx;
+            self.cap_search_idx();
+        }
+
+        self.format_prompt();
+
+        if let Some(func) = self.on_after_reflow.as_mut() {
+            func();
+        }
+    }
+
+    /// Reformat the inputted prompt to how it should be displayed
+    pub(crate) fn format_prompt(&mut self) {
+        const SEARCH_BG: &str = "\x1b[34m";
+        const INPUT_BG: &str = "\x1b[33m";
+
+        // Allocate the string. Add extra space in case for the
+        // ANSI escape things if we do have characters typed and search showing
+        let mut format_string = String::with_capacity(self.cols + (SEARCH_BG.len() * 2) + 4);
+
+        // Get the string that will contain the search index/match indicator
+        #[cfg(feature = "search")]
+        let mut search_str = String::new();
+        #[cfg(feature = "search")]
+        if self.search_term.is_some() {
+            search_str.push(' ');
+            if self.search_idx.is_empty() {
+                search_str.push_str("Pattern not found");
+            } else {
+                search_str.push_str(&(self.search_mark + 1).to_string());
+                search_str.push('/');
+                search_str.push_str(&self.search_idx.len().to_string());
+            }
+            search_str.push(' ');
+        }
+
+        // The position of the displayed buffer, if there is more than one
+        let buffer_str = if self.buffers.len() > 1 {
+            format!(" [{}/{}] ", self.active_buffer + 1, self.buffers.len())
+        } else {
+            String::new()
+        };
+
+        // And get the string that will contain the prefix_num
+        let mut prefix_str = String::new();
+        if self.count_display && !self.prefix_num.is_empty() {
+            prefix_str.push(' ');
+            prefix_str.push_str(&self.prefix_num);
+            prefix_str.push(' ');
+        }
+
+        // And lastly, the string that contains the prompt or msg
+        let dynamic_prompt = self
+            .dynamic_prompt
+            .as_ref()
+            .filter(|_| self.message.is_none())
+            .map(|func| func(self));
+        let prompt_str = self
+            .message
+            .as_ref()
+            .or(dynamic_prompt.as_ref())
+            .unwrap_or(&self.prompt);
+        // The right segment only goes along with the prompt it was set with
+        let right_str = if self.message.is_none() && dynamic_prompt.is_none() {
+            self.prompt_right.as_str()
+        } else {
+            ""
+        };
+
+        #[cfg(feature = "search")]
+        let search_len = search_str.len();
+        #[cfg(not(feature = "search"))]
+        let search_len = 0;
 
         // Calculate how much extra padding in the middle we need between
         // the prompt/message and the indicators on the right
         let prefix_len = prefix_str.len();
-        let extra_space = self
-            .cols
-            .saturating_sub(search_len + prefix_len + prompt_str.len());
-        let dsp_prompt: &str = if extra_space == 0 {
-            &prompt_str[..self.cols - search_len - prefix_len]
-        } else {
-            prompt_str
+        let indicators_len = search_len + prefix_len + buffer_str.len();
+        // Cuts off the end of a segment that doesn't fit into `cols` columns
+        let fit = |text: &str, cols: usize| {
+            if cols == 0 {
+                String::new()
+            } else {
+                truncate_str(text, cols, self.truncation_marker, self.ambiguous_width)
+            }
         };
+        if right_str.is_empty() {
+            let dsp_prompt = fit(prompt_str, self.cols.saturating_sub(indicators_len));
+            let extra_space = self.cols.saturating_sub(
+                indicators_len + display_width_as(&dsp_prompt, self.ambiguous_width),
+            );
+
+            // push the prompt/msg
+            format_string.push_str(&dsp_prompt);
+            format_string.push_str(&" ".repeat(extra_space));
+        } else {
+            // Push the right segment against the indicators at the end of the row. If the row
+            // is too narrow for both segments, the end of the left one is cut off first
+            let room = self.cols.saturating_sub(indicators_len);
+            let right = fit(right_str, room);
+            let room = room.saturating_sub(display_width_as(&right, self.ambiguous_width));
+            let left = fit(prompt_str, room);
+            let padding = room.saturating_sub(display_width_as(&left, self.ambiguous_width));
 
-        // push the prompt/msg
-        format_string.push_str(dsp_prompt);
-        format_string.push_str(&" ".repeat(extra_space));
+            format_string.push_str(&left);
+            format_string.push_str(&" ".repeat(padding));
+            format_string.push_str(&right);
+        }
+        format_string.push_str(&buffer_str);
 
         // add the prefix_num if it exists
         if prefix_len > 0 {
@@ -421,7 +1956,297 @@ impl PagerState {
             format_string.push_str(&search_str);
         }
 
-        self.displayed_prompt = format_string;
+        self.displayed_prompt = if self.color_enabled {
+            format_string
+        } else {
+            strip_sgr(&format_string).into_owned()
+        };
+    }
+
+    /// Returns the style to display the prompt in, which is plain if colors are turned off
+    pub(crate) fn displayed_prompt_style(&self) -> ContentStyle {
+        if self.color_enabled {
+            self.prompt_style
+        } else {
+            ContentStyle::new()
+        }
+    }
+
+    /// Whether a count has been typed and no further digit came within the count timeout
+    pub(crate) fn pending_count_expired(&self, since_last_digit: Duration) -> bool {
+        !self.prefix_num.is_empty() && since_last_digit >= self.count_timeout
+    }
+
+    /// Returns the status bar padded to the width of the screen, if one is set
+    pub(crate) fn format_status_bar(&self) -> Option<String> {
+        let text = self.status_bar.as_ref()?(self);
+        if self.cols == 0 {
+            return Some(String::new());
+        }
+        let text = truncate_str(
+            &text,
+            self.cols,
+            self.truncation_marker,
+            self.ambiguous_width,
+        );
+        let padding = self
+            .cols
+            .saturating_sub(display_width_as(&text, self.ambiguous_width));
+        Some(format!("{text}{}", " ".repeat(padding)))
+    }
+
+    /// Returns the number of rows available for the text
+    ///
+    /// One row is taken by the prompt and one by the status bar, if there is one. Messages are
+    /// shown in place of the prompt and don't take up a row of their own. The rows of the
+    /// [frozen header](crate::Pager::set_frozen_header_lines) are not counted either.
+    ///
+    /// All scrolling and drawing is based on this rather than on [`rows`](PagerState::rows), so
+    /// it is also what a [custom input classifier](crate::input::InputClassifier) should use to
+    /// scroll by a part of the screen.
+    #[must_use]
+    pub fn content_rows(&self) -> usize {
+        self.text_area_rows().saturating_sub(self.frozen_rows())
+    }
+
+    /// Returns the number of rows available for the text, including the frozen header
+    fn text_area_rows(&self) -> usize {
+        let reserved = if self.status_bar.is_some() { 2 } else { 1 };
+        self.rows.saturating_sub(reserved)
+    }
+
+    /// Returns the number of formatted rows that stay at the top of the screen
+    ///
+    /// These are the rows of the first [`frozen_header_lines`](PagerState::frozen_header_lines)
+    /// lines of the text. The [`upper_mark`](PagerState::upper_mark) never goes above them.
+    pub(crate) fn frozen_rows(&self) -> usize {
+        if self.frozen_header_lines == 0 {
+            return 0;
+        }
+        self.row_of_line(self.frozen_header_lines)
+            .min(self.text_area_rows().saturating_sub(1))
+    }
+
+    /// Returns the input event that the terminal event `ev` triggers, if any
+    ///
+    /// The event goes through the [input classifier](PagerState::input_classifier). Digits are
+    /// added to the count shown at the prompt and any other input ends the count, except for the
+    /// first key of a command made of two keys, which is kept in [`PagerState::pending_key`].
+    /// Key presses that `.` can repeat are remembered in [`PagerState::last_repeatable`].
+    pub(crate) fn classify_input(&mut self, ev: crossterm::event::Event) -> Option<InputEvent> {
+        let is_key = matches!(ev, crossterm::event::Event::Key(_));
+        let input = self.input_classifier.classify_input(ev, self);
+        // Only commands typed by the user are repeated with `.`, not scrolls with the mouse
+        if let Some(repeatable) = input
+            .as_ref()
+            .filter(|_| is_key)
+            .and_then(|input| input.repeatable(self.upper_mark))
+        {
+            self.last_repeatable = Some(repeatable);
+        }
+        self.pending_key = match input {
+            Some(InputEvent::PendingKey(key)) => Some(key),
+            _ => None,
+        };
+        if let Some(InputEvent::Number(n)) = input {
+            self.prefix_num.push(n);
+            self.format_prompt();
+        } else if self.pending_key.is_none() && !self.prefix_num.is_empty() {
+            self.prefix_num.clear();
+            self.format_prompt();
+        }
+        input
+    }
+
+    /// Returns the number of columns available for the text
+    ///
+    /// This is all of the [`cols`](PagerState::cols), except for the last one if the scrollbar
+    /// is shown, and less if the width of the text is limited. Line numbers are displayed within
+    /// these columns.
+    #[must_use]
+    pub fn content_cols(&self) -> usize {
+        let cols = if self.scrollbar {
+            self.cols.saturating_sub(1)
+        } else {
+            self.cols
+        };
+        self.max_content_width
+            .map_or(cols, |max| cols.min(max + self.line_number_width()))
+    }
+
+    /// Returns the number of blank columns left of the text, which centers text of a limited
+    /// width
+    pub(crate) fn left_margin(&self) -> usize {
+        let cols = if self.scrollbar {
+            self.cols.saturating_sub(1)
+        } else {
+            self.cols
+        };
+        cols.saturating_sub(self.content_cols()) / 2
+    }
+
+    /// Returns the row of the screen where the text starts
+    ///
+    /// This is the row below the prompt if the prompt is at the top, and the first row otherwise.
+    pub(crate) fn first_content_row(&self) -> usize {
+        self.first_header_row() + self.frozen_rows()
+    }
+
+    /// Returns the row of the screen where the frozen header starts
+    ///
+    /// This is the first row below the prompt if it is at the top.
+    pub(crate) const fn first_header_row(&self) -> usize {
+        match self.prompt_position {
+            PromptPosition::Top => 1,
+            PromptPosition::Bottom => 0,
+        }
+    }
+
+    /// Returns the row of the screen where the prompt is displayed
+    ///
+    /// At the bottom, this is one row past the end of the screen, which terminals treat as the
+    /// last row.
+    pub(crate) const fn prompt_row(&self) -> usize {
+        match self.prompt_position {
+            PromptPosition::Top => 0,
+            PromptPosition::Bottom => self.rows,
+        }
+    }
+
+    /// Moves the cursor to the row `row`, scrolling as little as needed to keep
+    /// [`scroll_off`](PagerState::scroll_off) rows around it on the screen
+    pub(crate) fn move_cursor(&mut self, row: usize) {
+        let row = row
+            .max(self.frozen_rows())
+            .min(self.num_lines().saturating_sub(1));
+        let content_rows = self.content_rows();
+        // Both margins can't be kept on a screen that is too small for them
+        let scroll_off = self.scroll_off.min(content_rows.saturating_sub(1) / 2);
+        let lowest = (row + scroll_off + 1).saturating_sub(content_rows);
+        let highest = row.saturating_sub(scroll_off);
+        self.upper_mark = self
+            .upper_mark
+            .max(lowest)
+            .min(highest)
+            .min(self.max_upper_mark())
+            .max(self.frozen_rows());
+        self.cursor_line = Some(row);
+    }
+
+    /// Returns the first and last formatted row of the selection, if lines are selected
+    pub(crate) fn selected_rows(&self) -> Option<(usize, usize)> {
+        let anchor = self.selection_anchor?;
+        let cursor = self.cursor_line?;
+        Some((anchor.min(cursor), anchor.max(cursor)))
+    }
+
+    /// Returns the text of the selected lines without any styling, if lines are selected
+    ///
+    /// Whole lines of the text are returned, even if only some of their rows are selected.
+    pub(crate) fn selected_text(&self) -> Option<String> {
+        let (first, last) = self.selected_rows()?;
+        let (first, last) = (self.line_at_row(first), self.line_at_row(last));
+        Some(
+            self.lines
+                .lines()
+                .skip(first)
+                .take(last + 1 - first)
+                .map(strip_ansi)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    /// Moves the cursor onto the screen, if it is in cursor mode and scrolling left it behind
+    pub(crate) fn keep_cursor_in_view(&mut self) {
+        let Some(cursor) = self.cursor_line else {
+            return;
+        };
+        let top = self
+            .upper_mark
+            .min(self.max_upper_mark())
+            .max(self.frozen_rows());
+        let bottom = top + self.content_rows().saturating_sub(1);
+        self.cursor_line = Some(
+            cursor
+                .clamp(top, bottom)
+                .min(self.num_lines().saturating_sub(1)),
+        );
+    }
+
+    /// Returns the number of lines a page movement scrolls by
+    ///
+    /// This is always at least 1, so that paging makes progress even if
+    /// [`page_overlap`](PagerState::page_overlap) is as large as the screen.
+    pub(crate) fn page_step(&self) -> usize {
+        // The status bar is not part of the height that the overlap is taken from
+        (self.content_rows() + 1)
+            .saturating_sub(self.page_overlap)
+            .max(1)
+    }
+
+    /// Returns the largest value the [`upper_mark`](PagerState::upper_mark) may take
+    pub(crate) fn max_upper_mark(&self) -> usize {
+        if self.scroll_past_end {
+            self.num_lines().saturating_sub(1)
+        } else {
+            self.num_lines().saturating_sub(self.content_rows())
+        }
+    }
+
+    /// Returns `true` if [`PagerState::format_lines`] leaves the formatting of each line until it
+    /// is displayed
+    ///
+    /// Lazy formatting only works while every line takes up exactly one row, i.e. while long
+    /// lines are truncated. Searches and highlight groups need all rows to find their matches, so
+    /// the text is formatted up front while either is active.
+    #[cfg_attr(not(feature = "search"), allow(clippy::missing_const_for_fn))]
+    pub(crate) fn formats_lazily(&self) -> bool {
+        #[cfg(feature = "search")]
+        if self.search_term.is_some() || !self.highlight_groups.is_empty() {
+            return false;
+        }
+        self.lazy_formatting && !self.line_wrapping
+    }
+
+    /// Formats the placeholder rows between `start` and `end`, along with a margin around them
+    ///
+    /// This does nothing unless the text was formatted lazily. Only the lines in the range are
+    /// looked at, so this takes as long wherever the range is in the text.
+    pub(crate) fn ensure_formatted(&mut self, start: usize, end: usize) {
+        const MARGIN: usize = 100;
+
+        let start = start.saturating_sub(MARGIN);
+        let end = end.saturating_add(MARGIN).min(self.format_cache.rows());
+        if !self.format_cache.any_pending(start..end) {
+            return;
+        }
+        // Every row is a line while formatting lazily, so the text can be cut at the first one
+        let Some(&offset) = start
+            .checked_sub(1)
+            .map_or(Some(&0), |idx| self.line_ends.get(idx))
+        else {
+            return;
+        };
+        let len_line_number = self.format_cache.len_line_number;
+        let lines = self.lines[offset..].lines().take(end - start);
+        for (idx, line) in (start..).zip(lines) {
+            if !self.format_cache.take(idx) {
+                continue;
+            }
+            let mut rows = self.formatted_line(
+                line,
+                len_line_number,
+                idx,
+                #[cfg(feature = "search")]
+                idx,
+                #[cfg(feature = "search")]
+                &mut BTreeSet::new(),
+                #[cfg(feature = "search")]
+                None,
+            );
+            self.formatted_lines[idx] = rows.swap_remove(0);
+        }
     }
 
     /// Returns all the text within the bounds, after flattening
@@ -436,12 +2261,71 @@ impl PagerState {
     }
 
     /// Runs the exit callbacks
-    pub(crate) fn exit(&mut self) {
+    ///
+    /// The callbacks only run the first time this is called, later calls are ignored.
+    pub(crate) fn exit(&mut self, reason: ExitReason) {
+        if self.exit_reason.is_some() {
+            return;
+        }
+        self.exit_reason = Some(reason);
         for func in &mut self.exit_callbacks {
-            func();
+            func(reason);
+        }
+        self.observe(|| PagerObservation::Exited(reason));
+    }
+
+    /// Tells [`PagerState::event_observer`] about the observation made by `f`
+    ///
+    /// `f` is only called if there is an observer.
+    pub(crate) fn observe(&self, f: impl FnOnce() -> PagerObservation) {
+        if let Some(func) = self.event_observer.as_ref() {
+            func(f());
         }
     }
 
+    /// Tells [`PagerState::event_observer`] if the screen has scrolled since the last time
+    pub(crate) fn observe_scroll(&mut self) {
+        if self.event_observer.is_some() && self.upper_mark != self.observed_upper_mark {
+            self.observed_upper_mark = self.upper_mark;
+            self.observe(|| PagerObservation::Scrolled {
+                row: self.upper_mark,
+            });
+        }
+    }
+
+    /// Returns `true` if the first row of the text is displayed on the screen
+    ///
+    /// Rows of the frozen header are always displayed, so this is the case once the
+    /// [`upper_mark`](PagerState::upper_mark) reaches the first row below them.
+    pub(crate) fn is_at_top(&self) -> bool {
+        self.upper_mark <= self.frozen_rows()
+    }
+
+    /// Returns `true` if the last row of the text is displayed on the screen
+    pub(crate) fn is_at_bottom(&self) -> bool {
+        self.upper_mark.saturating_add(self.content_rows()) >= self.num_lines()
+    }
+
+    /// Runs the callbacks for reaching the top or bottom of the text if the user has just scrolled
+    /// to either of them
+    pub(crate) fn run_reach_callbacks(&mut self) {
+        let at_top = self.is_at_top();
+        let at_bottom = self.is_at_bottom();
+
+        if at_top && !self.at_top {
+            if let Some(func) = self.on_reach_top.as_mut() {
+                func();
+            }
+        }
+        if at_bottom && !self.at_bottom {
+            if let Some(func) = self.on_reach_bottom.as_mut() {
+                func();
+            }
+        }
+        self.at_top = at_top;
+        self.at_bottom = at_bottom;
+    }
+
     pub(crate) fn append_str(&mut self, text: &str) -> AppendStyle {
         let append = self.lines.ends_with('\n') || self.lines.is_empty();
         let attachment = if append {
@@ -457,7 +2341,24 @@ impl PagerState {
             old_line_count.ilog10() + 1
         };
 
+        // With a multiline search, the new text can complete a match that started in the old text,
+        // hence everything has to be searched again
+        #[cfg(feature = "search")]
+        let full_search = self.search_multiline && self.search_term.is_some();
+        #[cfg(not(feature = "search"))]
+        let full_search = false;
+
+        // Wider line numbers leave less room for the text, so the old lines can take up more rows
+        // once everything is formatted again. Remember which line is at the top of the screen
+        // beforehand, so that appending never moves what the user is reading. The new text
+        // adds at most one line more than it has newlines
+        let max_line_count = old_line_count + text.matches('\n').count() + 1;
+        let may_widen = old_line_count != 0 && max_line_count.ilog10() + 1 != old_len_line_number;
+        let top = (self.upper_mark > 0 && (full_search || may_widen))
+            .then(|| self.row_anchor(self.upper_mark));
+
         self.lines.push_str(text);
+        self.index_line_ends();
 
         let new_line_count = self.lines.lines().count();
         let new_len_line_number = if new_line_count == 0 {
@@ -466,11 +2367,22 @@ impl PagerState {
             new_line_count.ilog10() + 1
         };
 
-        if new_len_line_number != old_len_line_number && old_len_line_number != 0 {
+        if full_search || (new_len_line_number != old_len_line_number && old_len_line_number != 0) {
             self.format_lines();
+            if let Some(top) = top {
+                self.upper_mark = self.anchored_row(top);
+            }
             return AppendStyle::FullRedraw;
         }
 
+        // Matches on the unterminated rows are found again in their reformatted version. They are
+        // dropped first, so that they don't count towards PagerState::max_search_matches
+        #[cfg(feature = "search")]
+        {
+            let first_changed = self.formatted_lines.len() - self.unterminated;
+            self.search_idx.retain(|&idx| idx < first_changed);
+        }
+
         let append_props = crate::minus_core::utils::text::make_append_str(
             self,
             text,
@@ -479,16 +2391,624 @@ impl PagerState {
             new_len_line_number.try_into().unwrap(),
         );
         let (fmt_line, num_unterminated) = (append_props.lines, append_props.num_unterminated);
+        // The last line is formatted again if the text continues it
+        if !append {
+            self.line_rows.pop();
+        }
+        self.line_rows.extend(append_props.line_rows);
 
         #[cfg(feature = "search")]
         {
             let mut append_search_idx = append_props.append_search_idx;
             self.search_idx.append(&mut append_search_idx);
+            self.cap_search_idx();
         }
 
         AppendStyle::PartialUpdate((fmt_line, num_unterminated))
     }
 
+    /// Checks whether a redraw should be deferred because of [`PagerState::redraw_interval`]
+    ///
+    /// If this returns `true`, the caller must not draw anything and a single redraw is scheduled
+    /// for the end of the interval instead. Redraws are never deferred in
+    /// [`PagerState::deterministic_mode`].
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) fn throttle_redraw(&mut self) -> bool {
+        if self.deterministic_mode {
+            return false;
+        }
+        // The text is redrawn anyway once it has been formatted for the new terminal size
+        if self.resize_deadline.is_some() {
+            return true;
+        }
+        if self.redraw_pending {
+            return true;
+        }
+        let (Some(interval), Some(last_draw)) = (self.redraw_interval, self.last_draw) else {
+            return false;
+        };
+        self.redraw_pending = last_draw.elapsed() < interval;
+        self.redraw_pending
+    }
+
+    /// Returns the time at which the deferred redraw should happen, if there is one
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) fn redraw_deadline(&self) -> Option<Instant> {
+        if !self.redraw_pending || self.rendering_paused {
+            return None;
+        }
+        Some(self.last_draw? + self.redraw_interval?)
+    }
+
+    /// Gathers appended text for [`PagerState::append_coalesce`]
+    ///
+    /// Returns the text that should be appended now, if any. Text is held back until it
+    /// terminates a line or the window runs out, so that a stream of tiny appends is formatted
+    /// and drawn once instead of once per append. Nothing is held back in
+    /// [`PagerState::deterministic_mode`].
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) fn coalesce_append(&mut self, text: String) -> Option<String> {
+        let window = self.append_coalesce.filter(|_| !self.deterministic_mode);
+        if window.is_none() && self.pending_append.is_empty() {
+            return Some(text);
+        }
+        self.pending_append.push_str(&text);
+        let due = self.append_deadline.map_or(false, |d| d <= Instant::now());
+        match window {
+            Some(window) if !due && !text.contains('\n') => {
+                self.append_deadline
+                    .get_or_insert_with(|| Instant::now() + window);
+                None
+            }
+            _ => self.take_pending_append(),
+        }
+    }
+
+    /// Takes the text held back by [`PagerState::coalesce_append`], if there is any
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) fn take_pending_append(&mut self) -> Option<String> {
+        self.append_deadline = None;
+        if self.pending_append.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.pending_append))
+        }
+    }
+
+    /// Returns the time at which the next tick is due, if ticks are enabled
+    #[cfg(feature = "dynamic_output")]
+    pub(crate) const fn tick_deadline(&self) -> Option<Instant> {
+        if self.deterministic_mode {
+            return None;
+        }
+        self.next_tick
+    }
+
+    /// Returns a smooth scroll to the given row, or `None` if it should be done right away
+    ///
+    /// Only scrolls by at least a page are animated, so that moving by a few lines stays
+    /// responsive. Nothing is animated in [`PagerState::deterministic_mode`] or while nothing can be
+    /// drawn.
+    pub(crate) fn start_scroll_animation(&self, um: usize) -> Option<ScrollAnimation> {
+        let duration = self.smooth_scroll?;
+        let to = um.min(self.max_upper_mark());
+        if self.deterministic_mode
+            || self.rendering_paused
+            || self.resize_deadline.is_some()
+            || to.abs_diff(self.upper_mark) < self.content_rows()
+        {
+            return None;
+        }
+        let start = Instant::now();
+        Some(ScrollAnimation {
+            from: self.upper_mark,
+            to,
+            start,
+            duration,
+            next_frame: start,
+        })
+    }
+
+    /// Returns the time at which the next frame of a smooth scroll is due, if one is running
+    pub(crate) fn scroll_frame_deadline(&self) -> Option<Instant> {
+        self.scroll_animation.map(|a| a.next_frame)
+    }
+
+    /// Updates the terminal size and reformats the text for it
+    ///
+    /// With a [resize debounce](PagerState::resize_debounce), the reformat is deferred until no
+    /// other resize has happened for that long. See [`PagerState::finish_resize`].
+    pub(crate) fn resize(&mut self, cols: usize, rows: usize) {
+        self.rows = rows;
+        self.cols = cols;
+        if self.resize_debounce.is_zero() || self.deterministic_mode {
+            self.format_lines();
+        } else {
+            self.resize_deadline = Some(Instant::now() + self.resize_debounce);
+        }
+    }
+
+    /// Reformats the text for the terminal size if a deferred resize is due
+    ///
+    /// Returns `true` if the text was reformatted.
+    pub(crate) fn finish_resize(&mut self) -> bool {
+        if self.resize_deadline.map_or(false, |d| d <= Instant::now()) {
+            self.format_lines();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Drops the oldest lines if [`PagerState::lines`] is larger than
+    /// [`PagerState::max_buffer_bytes`]
+    ///
+    /// Lines are dropped until the text is a quarter of the limit below it, so that a stream into a
+    /// full buffer only reformats the text once in a while instead of on every append. Only whole
+    /// lines are dropped and the last line is always kept, even if it alone exceeds the limit. If
+    /// anything is dropped, the text is reformatted, the screen is moved up by the number of
+    /// dropped rows and a message is shown at the prompt. Returns whether anything was dropped.
+    pub(crate) fn trim_buffer(&mut self) -> bool {
+        let Some(max_bytes) = self.max_buffer_bytes else {
+            return false;
+        };
+        if self.lines.len() <= max_bytes {
+            return false;
+        }
+        let excess = self.lines.len() - (max_bytes - max_bytes / 4);
+
+        // Index of the first byte after the line that contains the last excess byte
+        let cut = self.lines.as_bytes()[excess - 1..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(self.lines.len(), |pos| excess + pos);
+        let last_line_start = self
+            .lines
+            .strip_suffix('\n')
+            .unwrap_or(&self.lines)
+            .rfind('\n')
+            .map_or(0, |pos| pos + 1);
+        let cut = cut.min(last_line_start);
+        if cut == 0 {
+            return false;
+        }
+
+        let old_rows = self.num_lines();
+        // Marks refer to line indices, which all move up by the number of dropped lines
+        let dropped_lines = self.lines[..cut].matches('\n').count();
+        self.gutter_marks = self
+            .gutter_marks
+            .iter()
+            .filter_map(|idx| idx.checked_sub(dropped_lines))
+            .collect();
+        self.lines.drain(..cut);
+        if self.reflow {
+            // Each dropped line is a paragraph or blank line of the original text
+            let source_cut = paragraph_units(&self.reflow_source)
+                .take(dropped_lines)
+                .map(str::len)
+                .sum::<usize>();
+            self.reflow_source.drain(..source_cut);
+        }
+        self.format_lines();
+        self.upper_mark = self
+            .upper_mark
+            .saturating_sub(old_rows.saturating_sub(self.num_lines()));
+        #[cfg(feature = "search")]
+        {
+            self.search_mark = self
+                .search_mark
+                .min(self.search_idx.len().saturating_sub(1));
+        }
+        self.message = Some("Buffer trimmed, the oldest lines were dropped".to_string());
+        self.format_prompt();
+        true
+    }
+
+    /// Decodes `bytes` given to the pager into text, replacing invalid sequences with
+    /// `U+FFFD REPLACEMENT CHARACTER`
+    ///
+    /// If `append` is `true`, `bytes` follow the bytes decoded last and complete a multibyte
+    /// character left incomplete by them. Otherwise such a leftover character is discarded. An
+    /// incomplete character at the end of `bytes` is kept back until the next bytes are appended.
+    ///
+    /// Non-printable control characters are made visible like in a
+    /// [`WriteSink`](crate::WriteSink), with [`PagerState::control_char_placeholder`] if it is
+    /// set.
+    pub(crate) fn decode_bytes(&mut self, bytes: &[u8], append: bool) -> String {
+        if !append {
+            self.incomplete_bytes.clear();
+        }
+        self.incomplete_bytes.extend_from_slice(bytes);
+        render_control_chars(
+            decode_utf8_lossy(&mut self.incomplete_bytes),
+            self.control_char_placeholder,
+        )
+    }
+
+    /// Appends `text` to [`PagerState::lines`] and formats it
+    ///
+    /// Returns the first row of [`PagerState::formatted_lines`] that changed, from which on
+    /// everything has to be redrawn. Returns `None` if any row may have changed, for example
+    /// because the width of the line numbers grew or the oldest lines were dropped. The upper mark
+    /// is left alone either way, except for dropped lines which move it up.
+    pub(crate) fn append(&mut self, text: &str) -> Option<usize> {
+        let first_changed = self.num_lines() - self.unterminated;
+        let first_changed = if self.reflow {
+            // The last paragraph may continue in the new text, so it is reflowed again along with
+            // it. It is the last line of the reflowed text, the lines before it stay as they are
+            let source_start = last_paragraph_start(&self.reflow_source);
+            let line_start = self
+                .lines
+                .strip_suffix('\n')
+                .unwrap_or(&self.lines)
+                .rfind('\n')
+                .map_or(0, |idx| idx + 1);
+            self.reflow_source.push_str(text);
+            self.lines.truncate(line_start);
+            self.lines
+                .push_str(&reflow(&self.reflow_source[source_start..]));
+            self.format_lines();
+            None
+        } else {
+            match self.append_str(text) {
+                AppendStyle::FullRedraw => None,
+                AppendStyle::PartialUpdate((fmt_lines, num_unterminated)) => {
+                    self.append_str_on_unterminated(fmt_lines, num_unterminated);
+                    Some(first_changed)
+                }
+            }
+        };
+        let trimmed = self.trim_buffer();
+        let moved = self.apply_start_line();
+        // If the new data pushed the end of the text off the screen, reaching it again should
+        // run the callback again
+        self.at_bottom &= self.is_at_bottom();
+        let first_changed = first_changed.filter(|_| !trimmed && !moved);
+        if let Some(func) = self.append_feedback.as_ref() {
+            func(if first_changed.is_some() {
+                AppendFeedback::PartialUpdate
+            } else {
+                AppendFeedback::FullRedraw
+            });
+        }
+        first_changed
+    }
+
+    /// Inserts `lines` before the line at index `idx` of the text
+    ///
+    /// If there are fewer lines, they are appended instead. Everything that refers to lines after
+    /// the inserted ones, like the line at the top of the screen, the cursor and the gutter marks,
+    /// moves along with them, so the user keeps seeing the same text. With reflowing, `idx` is
+    /// the index of a line in the text before it is reflowed.
+    pub(crate) fn insert_lines(&mut self, idx: usize, lines: &[String]) {
+        if lines.is_empty() {
+            return;
+        }
+        let offset = self.source_offset(idx);
+        let source = if self.reflow {
+            &self.reflow_source
+        } else {
+            &self.lines
+        };
+        let mut text = lines.join("\n");
+        text.push('\n');
+        if offset == source.len() {
+            // An unterminated last line is ended, so that it isn't continued by the first new line
+            if !source.is_empty() && !source.ends_with('\n') {
+                text.insert(0, '\n');
+            }
+            self.append(&text);
+            return;
+        }
+
+        // Index of the line in PagerState::lines that the new lines are inserted before
+        let display_idx = if self.reflow {
+            reflow(&self.reflow_source[..offset]).lines().count()
+        } else {
+            idx
+        };
+        let old_line_count = self.lines.lines().count();
+        let top = self.row_anchor(self.upper_mark);
+        let cursor = self.cursor_line.map(|row| self.row_anchor(row));
+        let selection = self.selection_anchor.map(|row| self.row_anchor(row));
+
+        if self.reflow {
+            self.reflow_source.insert_str(offset, &text);
+            self.lines = reflow(&self.reflow_source);
+        } else {
+            self.lines.insert_str(offset, &text);
+        }
+        let added = self.lines.lines().count().saturating_sub(old_line_count);
+        let shift = |(line, offset): (usize, usize)| {
+            if line >= display_idx {
+                (line + added, offset)
+            } else {
+                (line, offset)
+            }
+        };
+        self.gutter_marks = self
+            .gutter_marks
+            .iter()
+            .map(|&line| shift((line, 0)).0)
+            .collect();
+        #[cfg(feature = "search")]
+        {
+            self.search_range = self
+                .search_range
+                .map(|(start, end)| (shift((start, 0)).0, shift((end, 0)).0));
+        }
+
+        self.format_lines();
+        self.upper_mark = self.anchored_row(shift(top));
+        self.cursor_line = cursor.map(|anchor| self.anchored_row(shift(anchor)));
+        self.selection_anchor = selection.map(|anchor| self.anchored_row(shift(anchor)));
+        self.trim_buffer();
+        #[cfg(feature = "search")]
+        self.mark_first_visible_match();
+    }
+
+    /// Removes the lines of the text whose indices are in `range`
+    ///
+    /// The range is clamped to the lines there are, so nothing happens if it is empty or starts
+    /// past the last line. Like with [`PagerState::insert_lines`], everything that refers to the
+    /// lines after the removed ones moves along with them. If the line at the top of the screen
+    /// is removed, the next remaining line takes its place. With reflowing, `range` refers to the
+    /// lines of the text before it is reflowed.
+    pub(crate) fn delete_lines(&mut self, range: Range<usize>) {
+        let (start, end) = (
+            self.source_offset(range.start),
+            self.source_offset(range.end),
+        );
+        if start >= end {
+            return;
+        }
+
+        // Index of the first removed line in PagerState::lines
+        let display_start = if self.reflow {
+            reflow(&self.reflow_source[..start]).lines().count()
+        } else {
+            range.start
+        };
+        let old_line_count = self.lines.lines().count();
+        let top = self.row_anchor(self.upper_mark);
+        let cursor = self.cursor_line.map(|row| self.row_anchor(row));
+        let selection = self.selection_anchor.map(|row| self.row_anchor(row));
+
+        if self.reflow {
+            self.reflow_source.replace_range(start..end, "");
+            self.lines = reflow(&self.reflow_source);
+        } else {
+            self.lines.replace_range(start..end, "");
+        }
+        let new_line_count = self.lines.lines().count();
+        let removed = display_start..display_start + old_line_count.saturating_sub(new_line_count);
+        let last_line = new_line_count.saturating_sub(1);
+        // Removed lines are replaced by the first line after them, or the last line if there is
+        // none
+        let shift = |(line, offset): (usize, usize)| {
+            if line < removed.start {
+                (line, offset)
+            } else if line >= removed.end {
+                (line - removed.len(), offset)
+            } else {
+                (removed.start.min(last_line), 0)
+            }
+        };
+        self.gutter_marks = self
+            .gutter_marks
+            .iter()
+            .filter(|line| !removed.contains(line))
+            .map(|&line| shift((line, 0)).0)
+            .collect();
+        #[cfg(feature = "search")]
+        {
+            self.search_range = self
+                .search_range
+                .map(|(start, end)| (shift((start, 0)).0, shift((end, 0)).0));
+        }
+
+        self.format_lines();
+        self.upper_mark = self.anchored_row(shift(top));
+        self.cursor_line = cursor.map(|anchor| self.anchored_row(shift(anchor)));
+        self.selection_anchor = selection.map(|anchor| self.anchored_row(shift(anchor)));
+        #[cfg(feature = "search")]
+        self.mark_first_visible_match();
+    }
+
+    /// Returns the byte offset at which the line at index `idx` of the text starts, or the length
+    /// of the text if there are fewer lines
+    ///
+    /// With reflowing, this is the offset in [`PagerState::reflow_source`].
+    fn source_offset(&self, idx: usize) -> usize {
+        let source = if self.reflow {
+            &self.reflow_source
+        } else {
+            &self.lines
+        };
+        source.split_inclusive('\n').take(idx).map(str::len).sum()
+    }
+
+    /// Returns the line of the text at the formatted row `row`, along with how many rows into
+    /// the line `row` is
+    fn row_anchor(&self, row: usize) -> (usize, usize) {
+        let line = self.line_at_row(row);
+        (line, row - self.row_of_line(line))
+    }
+
+    /// Returns the formatted row of an anchor returned by [`PagerState::row_anchor`]
+    ///
+    /// If the line takes up fewer rows now, this is its last row.
+    fn anchored_row(&self, (line, offset): (usize, usize)) -> usize {
+        let rows = self.rows_per_line().nth(line).unwrap_or(1);
+        self.row_of_line(line) + offset.min(rows - 1)
+    }
+
+    /// Points [`PagerState::search_mark`] at the first match on the screen, so that moving
+    /// between matches continues from there after the text has changed
+    #[cfg(feature = "search")]
+    pub(crate) fn mark_first_visible_match(&mut self) {
+        if self.search_term.is_some() {
+            self.search_mark = self
+                .search_idx
+                .range(..self.upper_mark)
+                .count()
+                .min(self.search_idx.len().saturating_sub(1));
+            self.format_prompt();
+        }
+    }
+
+    /// Scrolls to [`PagerState::start_line`] if the text has reached it
+    ///
+    /// Returns `true` if the view has moved.
+    pub(crate) fn apply_start_line(&mut self) -> bool {
+        let Some(line) = self.start_line else {
+            return false;
+        };
+        let idx = line.saturating_sub(1);
+        // Each line takes at least one row, so this rules out most texts that are too short
+        // without going through the lines
+        if self.num_lines() <= idx || self.rows_per_line().nth(idx).is_none() {
+            return false;
+        }
+        self.start_line = None;
+        self.upper_mark = self.row_of_line(idx).min(self.max_upper_mark());
+        self.at_bottom &= self.is_at_bottom();
+        true
+    }
+
+    /// Displays the text of `source` in place of the current text
+    ///
+    /// No lines are read yet, that happens once they are displayed.
+    pub(crate) fn set_line_source(&mut self, source: Box<dyn LineSource + Send + Sync>) {
+        self.set_text(String::new());
+        self.format_lines();
+        self.line_source = Some(source);
+        self.source_lines_read = 0;
+    }
+
+    /// Reads lines from the [`line_source`](PagerState::line_source) until the text has at least
+    /// `rows` formatted rows, or the source has no more lines
+    ///
+    /// The lines are appended to the text like any other text. A source that doesn't know its
+    /// length may never run out of lines, so at most [`SOURCE_CHUNK_LINES`] lines are read from
+    /// it at once and a message at the prompt tells that there may be more. This does nothing
+    /// without a line source.
+    pub(crate) fn read_source(&mut self, rows: usize) {
+        let Some(source) = self.line_source.as_ref() else {
+            return;
+        };
+        // Every line takes up at least one row
+        let wanted = rows.saturating_sub(self.num_lines());
+        let (wanted, bounded) = match source.len() {
+            Some(len) => (
+                wanted.min(len.saturating_sub(self.source_lines_read)),
+                false,
+            ),
+            None => (wanted.min(SOURCE_CHUNK_LINES), wanted > SOURCE_CHUNK_LINES),
+        };
+        let end = self.source_lines_read + wanted;
+        let mut text = String::new();
+        let mut read = self.source_lines_read;
+        while read < end {
+            let Some(line) = source.line(read) else {
+                break;
+            };
+            text.push_str(&line);
+            text.push('\n');
+            read += 1;
+        }
+        self.source_lines_read = read;
+        if !text.is_empty() {
+            self.append(&text);
+        }
+        if bounded && read == end {
+            self.message = Some(format!(
+                "Read {SOURCE_CHUNK_LINES} more lines, there may be more"
+            ));
+            self.format_prompt();
+        }
+    }
+
+    /// Adds a buffer with `text` after the last one, without displaying it
+    pub(crate) fn add_buffer(&mut self, text: String) {
+        self.buffers.push(Buffer {
+            lines: text,
+            ..Buffer::default()
+        });
+        self.format_prompt();
+    }
+
+    /// Appends `text` to the buffer that was added last
+    ///
+    /// This is where the output that is still coming in belongs, even while the user looks at
+    /// another buffer.
+    pub(crate) fn append_to_last_buffer(&mut self, text: &str) {
+        let last = self.buffers.len() - 1;
+        if self.active_buffer == last {
+            self.append(text);
+        } else {
+            self.buffers[last].lines.push_str(text);
+        }
+    }
+
+    /// Displays the buffer at `idx` in [`PagerState::buffers`]
+    ///
+    /// The displayed buffer is stored away along with its position and search, and the ones of
+    /// the buffer at `idx` are restored. Nothing happens if `idx` is out of bounds.
+    pub(crate) fn switch_buffer(&mut self, idx: usize) {
+        if idx == self.active_buffer || idx >= self.buffers.len() {
+            return;
+        }
+        self.swap_buffer(self.active_buffer);
+        self.active_buffer = idx;
+        self.swap_buffer(idx);
+
+        // Display options may have changed while the buffer was hidden, so it is formatted again.
+        // This also runs its search again
+        self.format_lines();
+        #[cfg(feature = "search")]
+        {
+            self.search_mark = self
+                .search_mark
+                .min(self.search_idx.len().saturating_sub(1));
+            self.format_prompt();
+        }
+        self.at_top = self.is_at_top();
+        self.at_bottom = self.is_at_bottom();
+    }
+
+    /// Switches to the next buffer, or the previous one if `forward` is `false`
+    ///
+    /// This wraps around at either end of [`PagerState::buffers`].
+    pub(crate) fn cycle_buffer(&mut self, forward: bool) {
+        let len = self.buffers.len();
+        let offset = if forward { 1 } else { len - 1 };
+        self.switch_buffer((self.active_buffer + offset) % len);
+    }
+
+    /// Exchanges the contents of the buffer at `idx` with the displayed ones
+    fn swap_buffer(&mut self, idx: usize) {
+        let buf = &mut self.buffers[idx];
+        // Buffers hold the text as it was given
+        if self.reflow {
+            std::mem::swap(&mut self.reflow_source, &mut buf.lines);
+            self.lines = reflow(&self.reflow_source);
+        } else {
+            std::mem::swap(&mut self.lines, &mut buf.lines);
+        }
+        std::mem::swap(&mut self.line_source, &mut buf.line_source);
+        std::mem::swap(&mut self.source_lines_read, &mut buf.source_lines_read);
+        std::mem::swap(&mut self.upper_mark, &mut buf.upper_mark);
+        std::mem::swap(&mut self.left_mark, &mut buf.left_mark);
+        std::mem::swap(&mut self.gutter_marks, &mut buf.gutter_marks);
+        #[cfg(feature = "search")]
+        {
+            std::mem::swap(&mut self.search_term, &mut buf.search_term);
+            std::mem::swap(&mut self.search_mark, &mut buf.search_mark);
+        }
+    }
+
     /// Conditionally appends to [`self.formatted_lines`] or changes the last unterminated rows of
     /// [`self.formatted_lines`]
     ///
@@ -502,8 +3022,102 @@ impl PagerState {
         if num_unterminated != 0 || self.unterminated != 0 {
             self.formatted_lines
                 .truncate(self.formatted_lines.len() - self.unterminated);
+            self.format_cache.truncate(self.formatted_lines.len());
         }
         self.formatted_lines.append(&mut fmt_line);
         self.unterminated = num_unterminated;
     }
 }
+
+/// Applies `style` to each of `rows`, if there is one
+fn style_rows(rows: Vec<String>, style: Option<ContentStyle>) -> Vec<String> {
+    match style {
+        Some(style) => rows
+            .into_iter()
+            .map(|row| style.apply(row).to_string())
+            .collect(),
+        None => rows,
+    }
+}
+
+/// Returns whether colors are turned on when the pager starts
+///
+/// They are, unless the `NO_COLOR` environment variable is set to a non-empty value, see
+/// <https://no-color.org>.
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn color_by_default() -> bool {
+    std::env::var_os("NO_COLOR").map_or(true, |val| val.is_empty())
+}
+
+/// Most lines that are read at once from a line source that doesn't know its length, see
+/// [`PagerState::read_source`]
+const SOURCE_CHUNK_LINES: usize = 10_000;
+
+/// Time between two frames of a smooth scroll
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) const SCROLL_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// A scroll that is drawn over several frames, see [`PagerState::smooth_scroll`]
+#[derive(Clone, Copy, Debug)]
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) struct ScrollAnimation {
+    /// The [`upper_mark`](PagerState::upper_mark) at the start of the scroll
+    pub(crate) from: usize,
+    /// The [`upper_mark`](PagerState::upper_mark) at the end of the scroll
+    pub(crate) to: usize,
+    /// When the scroll started
+    pub(crate) start: Instant,
+    /// How long the scroll takes
+    pub(crate) duration: Duration,
+    /// When the next frame is due
+    pub(crate) next_frame: Instant,
+}
+
+impl ScrollAnimation {
+    /// Returns the [`upper_mark`](PagerState::upper_mark) at the given time and whether the scroll
+    /// is done
+    pub(crate) fn position(&self, now: Instant) -> (usize, bool) {
+        let elapsed = now.saturating_duration_since(self.start).as_nanos();
+        let duration = self.duration.as_nanos();
+        if elapsed >= duration {
+            return (self.to, true);
+        }
+        // Ease out, so that the scroll slows down as it reaches its end
+        let distance = self.to.abs_diff(self.from) as u128;
+        let square = |n: u128| n * n;
+        let covered = distance * (square(duration) - square(duration - elapsed)) / square(duration);
+        let covered: usize = covered.try_into().unwrap_or(usize::MAX);
+        if self.to > self.from {
+            (self.from + covered, false)
+        } else {
+            (self.from - covered, false)
+        }
+    }
+}
+
+/// The text of a buffer along with where the user was in it
+///
+/// Only the text and position are kept. The formatted rows and search matches of a buffer are
+/// created again when it is displayed, since display options may change while it is hidden.
+#[derive(Default)]
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) struct Buffer {
+    /// The text of the buffer, see [`PagerState::lines`]
+    lines: String,
+    /// See [`PagerState::line_source`]
+    line_source: Option<Box<dyn LineSource + Send + Sync>>,
+    /// See [`PagerState::source_lines_read`]
+    source_lines_read: usize,
+    /// See [`PagerState::upper_mark`]
+    upper_mark: usize,
+    /// See [`PagerState::left_mark`]
+    left_mark: usize,
+    /// See [`PagerState::gutter_marks`]
+    gutter_marks: BTreeSet<usize>,
+    /// See [`PagerState::search_term`]
+    #[cfg(feature = "search")]
+    search_term: Option<regex::Regex>,
+    /// See [`PagerState::search_mark`]
+    #[cfg(feature = "search")]
+    search_mark: usize,
+}
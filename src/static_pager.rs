@@ -9,12 +9,10 @@ use crate::{error::MinusError, Pager};
 /// Since it is sure that fed data will never change, minus can do some checks like:-
 /// * If stdout is not a tty, minus not start a pager. It will simply print all the data and quit
 /// * If there are more rows in the terminal than the number of lines of data to display
-/// minus will not start a pager and simply display all data on the main stdout screen.
-/// This behaviour can be turned off if
-/// [`Pager::set_run_no_overflow(true)`](Pager::set_run_no_overflow) has been
-/// called before starting
+///   minus can simply display all data on the main stdout screen instead of starting a pager.
+///   This can be turned on with [`Pager::set_overflow_behavior`] before starting
 /// * Since any other event except user inputs will not occur, we can do some optimizations on
-/// matching events.
+///   matching events.
 ///
 /// See [example](../index.html#static-output) on how to use this functon.
 ///
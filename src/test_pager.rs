@@ -0,0 +1,511 @@
+//! Contains [`TestPager`] for running a pager without a terminal
+//!
+//! This makes it possible to test what an application displays through minus, for example with
+//! snapshot tests.
+use crate::minus_core::utils::text::{display_width, strip_ansi};
+use crate::minus_core::{ev_handler::handle_event, events::Event, utils::display::draw_full};
+use crate::{error::MinusError, input::InputEvent, ExitStrategy, Pager, PagerState};
+use crossbeam_channel::Receiver;
+#[cfg(feature = "search")]
+use parking_lot::{Condvar, Mutex};
+use std::sync::{atomic::AtomicBool, Arc};
+
+/// A pager that renders its screen to a [`String`] instead of a terminal
+///
+/// It handles everything sent through the [`Pager`] it was created from, just like a running
+/// pager would, and has a fixed size. Call [`TestPager::render`] to see what would be on the
+/// screen. Key presses and mouse events can be simulated with [`TestPager::input`].
+///
+/// Searches can't be started as they read the query from the terminal. Quitting never ends
+/// the process, regardless of the [`ExitStrategy`].
+///
+/// # Example
+/// ```
+/// use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+/// use minus::{Pager, TestPager};
+///
+/// let pager = Pager::new();
+/// pager.set_text("one\ntwo\nthree\nfour\n").unwrap();
+/// pager.set_prompt("test").unwrap();
+///
+/// // Room for two lines and the prompt
+/// let mut test_pager = TestPager::new(&pager, 20, 3).unwrap();
+/// assert_eq!(test_pager.render().unwrap(), "one\ntwo\ntest");
+///
+/// test_pager
+///     .input(Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)))
+///     .unwrap();
+/// assert_eq!(test_pager.render().unwrap(), "two\nthree\ntest");
+/// ```
+pub struct TestPager {
+    ps: PagerState,
+    rx: Receiver<Event>,
+    is_exitted: Arc<AtomicBool>,
+    #[cfg(feature = "search")]
+    user_input_active: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl TestPager {
+    /// Create a test pager with `cols` columns and `rows` rows for the events sent through
+    /// `pager`
+    ///
+    /// The events that have already been sent are handled right away.
+    ///
+    /// # Errors
+    /// This function returns an error if handling any of the events fails.
+    pub fn new(pager: &Pager, cols: usize, rows: usize) -> Result<Self, MinusError> {
+        let mut ps = PagerState::with_size(cols, rows);
+        // Everything is drawn as soon as it is rendered, so nothing should be deferred
+        ps.deterministic_mode = true;
+        let mut test_pager = Self {
+            ps,
+            rx: pager.rx.clone(),
+            is_exitted: Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "search")]
+            user_input_active: Arc::new((Mutex::new(true), Condvar::new())),
+        };
+        test_pager.handle_pending()?;
+        Ok(test_pager)
+    }
+
+    /// Returns the text on the screen, with one line for each row
+    ///
+    /// Styles and hyperlinks are left out and trailing spaces are removed from each row. The
+    /// events sent through the [`Pager`] since the last call are handled first.
+    ///
+    /// # Errors
+    /// This function returns an error if handling an event or drawing the screen fails.
+    pub fn render(&mut self) -> Result<String, MinusError> {
+        self.draw(false)
+    }
+
+    /// Returns the text on the screen along with the ANSI escape sequences that style it
+    ///
+    /// Rows are separated by newlines and kept as they are. The events sent through the
+    /// [`Pager`] since the last call are handled first.
+    ///
+    /// # Errors
+    /// This function returns an error if handling an event or drawing the screen fails.
+    pub fn render_styled(&mut self) -> Result<String, MinusError> {
+        self.draw(true)
+    }
+
+    /// Handle a terminal event like a key press, as if the user caused it
+    ///
+    /// The event goes through the [input classifier](Pager::set_input_classifier) like it would
+    /// in a running pager. Counts typed before a command apply to it.
+    ///
+    /// # Errors
+    /// This function returns an error if handling the resulting action fails.
+    pub fn input(&mut self, ev: crossterm::event::Event) -> Result<(), MinusError> {
+        self.handle_pending()?;
+        match self.ps.classify_input(ev) {
+            Some(InputEvent::Number(_)) | None => return Ok(()),
+            #[cfg(feature = "search")]
+            Some(InputEvent::Search(_)) => {}
+            Some(iev) => self.handle(Event::UserInput(iev))?,
+        }
+        self.ps.format_prompt();
+        Ok(())
+    }
+
+    /// Returns the state of the pager
+    #[must_use]
+    pub const fn state(&self) -> &PagerState {
+        &self.ps
+    }
+
+    /// Handles all events that have been sent through the [`Pager`]
+    fn handle_pending(&mut self) -> Result<(), MinusError> {
+        while let Ok(ev) = self.rx.try_recv() {
+            self.handle(ev)?;
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, ev: Event) -> Result<(), MinusError> {
+        // Quitting must not end the process that runs the tests
+        self.ps.exit_strategy = ExitStrategy::PagerQuit;
+        handle_event(
+            ev,
+            &mut Vec::new(),
+            &mut self.ps,
+            &self.is_exitted,
+            #[cfg(feature = "search")]
+            &self.user_input_active,
+        )?;
+        self.ps.exit_strategy = ExitStrategy::PagerQuit;
+        Ok(())
+    }
+
+    fn draw(&mut self, styled: bool) -> Result<String, MinusError> {
+        self.handle_pending()?;
+        let mut out = Vec::new();
+        draw_full(&mut out, &mut self.ps)?;
+        let screen = to_screen(&String::from_utf8_lossy(&out), self.ps.rows, styled);
+        Ok(if styled {
+            screen.join("\n")
+        } else {
+            screen
+                .iter()
+                .map(|row| row.trim_end())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+}
+
+/// Plays back the terminal output `out` onto a screen of `rows` rows and returns the rows
+///
+/// Only what minus writes when drawing is understood: text, line breaks, moving the cursor and
+/// clearing the screen or a row. Rows are written from their start, or from the column the
+/// cursor was moved to if that is past their end. Styles and hyperlinks are kept if `styled` is
+/// set and dropped otherwise.
+fn to_screen(out: &str, rows: usize, styled: bool) -> Vec<String> {
+    let mut screen = vec![String::new(); rows];
+    let mut row = 0;
+    let mut chars = out.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // Control sequence, ending with a character in the range '@'..='~'
+                Some('[') => {
+                    let mut params = String::new();
+                    let Some(end) = chars.by_ref().find(|c| {
+                        let end = ('@'..='~').contains(c);
+                        if !end {
+                            params.push(*c);
+                        }
+                        end
+                    }) else {
+                        break;
+                    };
+                    match end {
+                        // Like terminals do, moving past the last row stops at it. The prompt
+                        // relies on this.
+                        'H' => {
+                            let mut pos = params
+                                .split(';')
+                                .map(|n| n.parse::<usize>().unwrap_or(1).saturating_sub(1));
+                            row = pos.next().unwrap_or(0).min(rows.saturating_sub(1));
+                            let col = pos.next().unwrap_or(0);
+                            if let Some(text) = screen.get_mut(row) {
+                                let width = display_width(&strip_ansi(text));
+                                text.extend(std::iter::repeat(' ').take(col.saturating_sub(width)));
+                            }
+                        }
+                        'J' => screen.iter_mut().for_each(String::clear),
+                        'K' => {
+                            if let Some(text) = screen.get_mut(row) {
+                                text.clear();
+                            }
+                        }
+                        'm' if styled => {
+                            if let Some(text) = screen.get_mut(row) {
+                                text.push_str("\x1b[");
+                                text.push_str(&params);
+                                text.push('m');
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                // Operating system command like a hyperlink, ending with BEL or ESC \
+                Some(']') => {
+                    let mut command = String::from("\x1b]");
+                    while let Some(c) = chars.next() {
+                        command.push(c);
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            command.push(chars.next().unwrap());
+                            break;
+                        }
+                    }
+                    if let Some(text) = screen.get_mut(row).filter(|_| styled) {
+                        text.push_str(&command);
+                    }
+                }
+                _ => {}
+            },
+            // Each row is written once, so returning to its start is the same as clearing it
+            '\r' => {
+                if let Some(text) = screen.get_mut(row) {
+                    text.clear();
+                }
+            }
+            '\n' => row += 1,
+            c => {
+                if let Some(text) = screen.get_mut(row) {
+                    text.push(c);
+                }
+            }
+        }
+    }
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_screen, TestPager};
+    use crate::Pager;
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn plays_back_output() {
+        let out = "\x1b[1;1H\x1b[2J\r\x1b[1mbold\x1b[0m\n\rplain\n\x1b[4;1H\rprompt";
+        assert_eq!(
+            to_screen(out, 4, false),
+            vec!["bold", "plain", "", "prompt"]
+        );
+        assert_eq!(
+            to_screen(out, 4, true),
+            vec!["\x1b[1mbold\x1b[0m", "plain", "", "prompt"]
+        );
+        // Moving the cursor past the end of a row pads the row up to that column
+        assert_eq!(to_screen("ab\x1b[1;5Hc", 1, false), vec!["ab  c"]);
+    }
+
+    #[test]
+    fn renders_and_scrolls() {
+        let pager = Pager::new();
+        pager.set_text("1\n2\n3\n4\n5\n6\n").unwrap();
+        pager.set_prompt("end").unwrap();
+        let mut test_pager = TestPager::new(&pager, 10, 4).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "1\n2\n3\nend");
+
+        // A count applies to the command after it
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        test_pager.input(key('2')).unwrap();
+        test_pager.input(key('j')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "3\n4\n5\nend");
+
+        // Events sent through the pager are handled before rendering
+        pager.set_line_numbers(crate::LineNumbers::Enabled).unwrap();
+        assert_eq!(
+            test_pager.render().unwrap(),
+            "     3. 3\n     4. 4\n     5. 5\nend"
+        );
+    }
+
+    #[test]
+    fn color_off() {
+        use crossterm::style::{ContentStyle, Stylize};
+
+        let pager = Pager::new();
+        pager.set_text("\x1b[31mred\x1b[0m\nplain\n").unwrap();
+        pager.set_status_bar(Some("status".to_string())).unwrap();
+        pager
+            .set_line_styler(Box::new(|_, _| Some(ContentStyle::new().bold())))
+            .unwrap();
+        pager.set_highlight_row(Some(1)).unwrap();
+        pager.set_end_of_buffer_char(Some('~')).unwrap();
+        pager.set_prompt("end").unwrap();
+        let mut test_pager = TestPager::new(&pager, 10, 5).unwrap();
+        assert!(test_pager.render_styled().unwrap().contains("\x1b["));
+
+        pager.set_color(false).unwrap();
+        let screen = test_pager.render_styled().unwrap();
+        assert!(!screen.contains('\x1b'), "{:?}", screen);
+        // The highlighted row is marked instead
+        assert_eq!(screen, "red\n>plain\n~\nstatus    \nend       ");
+
+        // Rows that fill the screen make room for the marker at their end
+        pager.set_text("red\n0123456789\n").unwrap();
+        let screen = test_pager.render_styled().unwrap();
+        assert_eq!(screen, "red\n>01234567>\n~\nstatus    \nend       ");
+    }
+
+    #[test]
+    fn prompt_position() {
+        let pager = Pager::new();
+        pager.set_text("1\n2\n3\n4\n5\n").unwrap();
+        pager.set_prompt("end").unwrap();
+        let mut test_pager = TestPager::new(&pager, 10, 4).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "1\n2\n3\nend");
+
+        pager
+            .set_prompt_position(crate::PromptPosition::Top)
+            .unwrap();
+        assert_eq!(test_pager.render().unwrap(), "end\n1\n2\n3");
+        // Scrolling redraws below the prompt
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        test_pager.input(key('j')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "end\n2\n3\n4");
+
+        // The status bar stays below the text
+        pager.set_status_bar(Some("status".to_string())).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "end\n2\n3\nstatus");
+    }
+
+    #[test]
+    fn scrollbar() {
+        let pager = Pager::new();
+        pager.set_text("1\n2\n3\n4\n5\n6\n7\n8\n9\n").unwrap();
+        pager.set_prompt("end").unwrap();
+        pager.set_scrollbar(true).unwrap();
+        let mut test_pager = TestPager::new(&pager, 6, 4).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "1    █\n2    │\n3    │\nend");
+
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        test_pager.input(key('G')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "7    │\n8    │\n9    █\nend");
+
+        // The text is one column narrower
+        pager.set_text("123456\n").unwrap();
+        assert_eq!(test_pager.render().unwrap(), "12345█\n6    █\n~    █\nend");
+    }
+
+    #[test]
+    fn frozen_header() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let pager = Pager::new();
+        pager.set_text("head\n1\n2\n3\n4\n5\n6\n7\n8\n").unwrap();
+        pager.set_prompt("end").unwrap();
+        pager.set_frozen_header_lines(1).unwrap();
+        let reached_top = Arc::new(AtomicUsize::new(0));
+        let reached_top2 = reached_top.clone();
+        pager
+            .set_on_reach_top(Box::new(move || {
+                reached_top2.fetch_add(1, Ordering::SeqCst);
+            }))
+            .unwrap();
+        let mut test_pager = TestPager::new(&pager, 10, 4).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "head\n1\n2\nend");
+
+        // Only the rows below the header scroll
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        test_pager.input(key('j')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "head\n2\n3\nend");
+        assert!(!test_pager.state().is_at_top());
+        test_pager.input(key(' ')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "head\n4\n5\nend");
+        test_pager.input(key('G')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "head\n7\n8\nend");
+        // Going back to the top doesn't show the header twice
+        test_pager.input(key('g')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "head\n1\n2\nend");
+        // The first row below the header is the top of the text
+        assert!(test_pager.state().is_at_top());
+        assert_eq!(reached_top.load(Ordering::SeqCst), 1);
+        test_pager.input(key('k')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "head\n1\n2\nend");
+
+        // The header stays below a prompt at the top
+        pager
+            .set_prompt_position(crate::PromptPosition::Top)
+            .unwrap();
+        test_pager.input(key('j')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "end\nhead\n2\n3");
+    }
+
+    #[test]
+    fn line_source() {
+        use crate::source::LineSource;
+        use std::borrow::Cow;
+
+        /// Numbers up to 1000, without saying how many there are
+        struct Numbers;
+        impl LineSource for Numbers {
+            fn line(&self, idx: usize) -> Option<Cow<'_, str>> {
+                (idx < 1000).then(|| Cow::Owned(idx.to_string()))
+            }
+        }
+
+        /// Numbers that never end
+        struct Endless;
+        impl LineSource for Endless {
+            fn line(&self, idx: usize) -> Option<Cow<'_, str>> {
+                Some(Cow::Owned(idx.to_string()))
+            }
+        }
+
+        let pager = Pager::new();
+        pager.set_text("replaced\n").unwrap();
+        pager.set_line_source(Box::new(Numbers)).unwrap();
+        pager.set_prompt("end").unwrap();
+        let mut test_pager = TestPager::new(&pager, 10, 4).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "0\n1\n2\nend");
+        // Only the lines on the screen have been read
+        assert_eq!(test_pager.state().lines, "0\n1\n2\n");
+
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        test_pager.input(key('j')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "1\n2\n3\nend");
+        assert_eq!(test_pager.state().lines.lines().count(), 4);
+
+        // Going to the end reads the rest
+        test_pager.input(key('G')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "997\n998\n999\nend");
+        assert_eq!(test_pager.state().lines.lines().count(), 1000);
+
+        // Setting the text stops reading from the source
+        pager.set_text("new\n").unwrap();
+        test_pager.input(key('j')).unwrap();
+        assert_eq!(test_pager.render().unwrap(), "new\n~\n~\nend");
+
+        // Going to the end of a source that may not have one reads a chunk of it at a time
+        pager.set_line_source(Box::new(Endless)).unwrap();
+        test_pager.input(key('G')).unwrap();
+        assert_eq!(test_pager.state().lines.lines().count(), 10_000);
+        assert_eq!(test_pager.render().unwrap(), "9997\n9998\n9999\nRead 1000>");
+        assert_eq!(
+            test_pager.state().message.as_deref(),
+            Some("Read 10000 more lines, there may be more")
+        );
+        test_pager.input(key('G')).unwrap();
+        assert_eq!(test_pager.state().lines.lines().count(), 20_000);
+    }
+
+    #[test]
+    fn repeat_last_jump() {
+        let pager = Pager::new();
+        pager.set_text("line\nline\n\n".repeat(30)).unwrap();
+        let mut test_pager = TestPager::new(&pager, 10, 5).unwrap();
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+
+        // Nothing to repeat yet
+        test_pager.input(key('.')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 0);
+
+        // A page jump moves by another page, moving by a line is not repeated
+        test_pager.input(key(' ')).unwrap();
+        test_pager.input(key('j')).unwrap();
+        test_pager.input(key('.')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 9);
+
+        // A count replaces the one the command was given
+        test_pager.input(key('}')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 11);
+        test_pager.input(key('3')).unwrap();
+        test_pager.input(key('.')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 20);
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    #[allow(clippy::trivial_regex)]
+    fn repeat_next_match() {
+        let pager = Pager::new();
+        pager.set_text("text\nmatch\ntext\n".repeat(20)).unwrap();
+        let mut test_pager = TestPager::new(&pager, 10, 5).unwrap();
+        test_pager.ps.search_term = Some(regex::Regex::new("match").unwrap());
+        test_pager.ps.format_lines();
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+
+        test_pager.input(key('n')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 1);
+        // `.` after `n` goes to the match after that
+        test_pager.input(key('.')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 4);
+        test_pager.input(key('2')).unwrap();
+        test_pager.input(key('.')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 10);
+        // The count sticks for the next repeat
+        test_pager.input(key('.')).unwrap();
+        assert_eq!(test_pager.state().upper_mark, 16);
+    }
+}
@@ -24,6 +24,122 @@ mod fmt_write {
             assert_eq!(text, TEST.to_string());
         }
     }
+
+    #[test]
+    fn split_writes_form_single_line() {
+        use crate::{minus_core::utils::text::AppendStyle, PagerState};
+
+        let mut pager = Pager::new();
+        let mut ps = PagerState::new().unwrap();
+        for i in 1..=2 {
+            writeln!(pager, "line {i}").unwrap();
+        }
+        // The formatting machinery may break a single write!() into multiple appends
+        while let Ok(Event::AppendData(text)) = pager.rx.try_recv() {
+            let AppendStyle::PartialUpdate((fmt_line, num_unterminated)) = ps.append_str(&text)
+            else {
+                unreachable!()
+            };
+            ps.append_str_on_unterminated(fmt_line, num_unterminated);
+        }
+        assert_eq!(ps.formatted_lines, vec!["line 1", "line 2"]);
+    }
+}
+
+// Test the implementation of std::io::Write on WriteSink
+mod io_write {
+    use crate::{
+        minus_core::{events::Event, utils::text::AppendStyle},
+        Pager, PagerState,
+    };
+    use std::io::Write;
+
+    #[test]
+    fn write_bytes() {
+        let pager = Pager::new();
+        let mut sink = pager.write_sink();
+        sink.write_all(b"This is a line\n").unwrap();
+        assert_eq!(
+            Event::AppendData("This is a line\n".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn split_multibyte_char() {
+        let bytes = "caf\u{e9}".as_bytes();
+        let pager = Pager::new();
+        let mut sink = pager.write_sink();
+
+        // Split in the middle of the two byte long é
+        sink.write_all(&bytes[..4]).unwrap();
+        assert_eq!(
+            Event::AppendData("caf".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+        sink.write_all(&bytes[4..]).unwrap();
+        assert_eq!(
+            Event::AppendData("\u{e9}".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+        assert!(pager.rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn invalid_bytes_are_replaced() {
+        let pager = Pager::new();
+        let mut sink = pager.write_sink();
+        sink.write_all(b"a\xffb").unwrap();
+        assert_eq!(
+            Event::AppendData("a\u{fffd}b".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn control_chars_are_rendered() {
+        let pager = Pager::new();
+        let mut sink = pager.write_sink();
+        sink.write_all(b"a\0b\x01\tc\x7f\n").unwrap();
+        assert_eq!(
+            Event::AppendData("a^@b^A\tc^?\n".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+
+        sink.set_control_char_placeholder(Some('.'));
+        sink.write_all(b"\x1b[1mbold\x1b[0m\0\n").unwrap();
+        assert_eq!(
+            Event::AppendData("\x1b[1mbold\x1b[0m.\n".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn nul_does_not_split_lines() {
+        let pager = Pager::new();
+        let mut sink = pager.write_sink();
+        sink.write_all(b"first\0line\nsecond line\n").unwrap();
+
+        let mut ps = PagerState::new().unwrap();
+        while let Ok(Event::AppendData(text)) = pager.rx.try_recv() {
+            if let AppendStyle::PartialUpdate((lines, unterminated)) = ps.append_str(&text) {
+                ps.append_str_on_unterminated(lines, unterminated);
+            }
+        }
+        assert_eq!(ps.formatted_lines, vec!["first^@line", "second line"]);
+    }
+
+    #[test]
+    fn incomplete_char_on_drop() {
+        let pager = Pager::new();
+        let mut sink = pager.write_sink();
+        sink.write_all(&"\u{e9}".as_bytes()[..1]).unwrap();
+        drop(sink);
+        assert_eq!(
+            Event::AppendData("\u{fffd}".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
 }
 
 mod pager_append_str {
@@ -32,7 +148,9 @@ mod pager_append_str {
     /// Helper function for calling [append_str][PagerState::append_str] and then
     /// [append_str_on_unterminated](PagerState::append_str_on_unterminated)
     fn append_str(ps: &mut PagerState, text: &str) {
-        let AppendStyle::PartialUpdate((fmt_line, num_unterminated)) = ps.append_str(text) else { unreachable!() };
+        let AppendStyle::PartialUpdate((fmt_line, num_unterminated)) = ps.append_str(text) else {
+            unreachable!()
+        };
         ps.append_str_on_unterminated(fmt_line, num_unterminated);
     }
 
@@ -241,15 +359,199 @@ fn exit_callback() {
     let mut ps = PagerState::new().unwrap();
     let exited = Arc::new(AtomicBool::new(false));
     let exited_within_callback = exited.clone();
-    ps.exit_callbacks.push(Box::new(move || {
+    ps.exit_callbacks.push(Box::new(move |_| {
         exited_within_callback.store(true, Ordering::Relaxed);
     }));
-    ps.exit();
+    ps.exit(crate::ExitReason::UserQuit);
 
     assert!(exited.load(Ordering::Relaxed));
 }
 
+#[test]
+fn read_back_from_running_pager() {
+    use crate::{Pager, PagerState};
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    let pager = Pager::new();
+    let mut ps = PagerState::new().unwrap();
+    ps.lines = "first\nsecond\n".to_string();
+    // Stand in for the pager starting up
+    let ps = Arc::new(Mutex::new(ps));
+    *pager.state.lock() = Arc::downgrade(&ps);
+
+    assert_eq!(pager.buffer_text(), "first\nsecond\n");
+    assert_eq!(pager.buffer_lines(), vec!["first", "second"]);
+
+    // Nothing can be read once the pager has quit
+    drop(ps);
+    assert!(pager.buffer_text().is_empty());
+    assert!(pager.buffer_lines().is_empty());
+}
+
+#[test]
+fn wait_for_exit() {
+    use crate::Pager;
+    use std::{thread, time::Duration};
+
+    let pager = Pager::new();
+    assert!(!pager.has_exited());
+
+    let pager2 = pager.clone();
+    let waiter = thread::spawn(move || pager2.wait_for_exit());
+    thread::sleep(Duration::from_millis(20));
+    assert!(!waiter.is_finished());
+
+    pager.exit_signal.set(true);
+    waiter.join().unwrap();
+    assert!(pager.has_exited());
+    // Waiting after the pager has quit returns right away
+    pager.wait_for_exit();
+}
+
+// Tests that run a pager which draws into a buffer instead of the terminal
+#[cfg(feature = "dynamic_output")]
+mod running_pager {
+    use crate::{dynamic_paging, error::MinusError, ExitStrategy, Pager};
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use parking_lot::Mutex;
+    use std::io::Write;
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::{Duration, Instant};
+
+    /// Held while a pager runs, as only one can run at a time
+    static RUNNING: Mutex<()> = parking_lot::const_mutex(());
+
+    /// Collects what the pager writes
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Output {
+        fn text(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock()).into_owned()
+        }
+    }
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Starts `pager` drawing into `output` and waits until it is running
+    fn start(pager: &Pager, output: &Output) -> JoinHandle<Result<(), MinusError>> {
+        pager.set_output(Box::new(output.clone())).unwrap();
+        pager.set_external_input(true).unwrap();
+        pager.set_exit_strategy(ExitStrategy::PagerQuit).unwrap();
+        let pager2 = pager.clone();
+        let paging = std::thread::spawn(move || dynamic_paging(pager2));
+        while !pager.is_at_top() {
+            std::thread::yield_now();
+        }
+        paging
+    }
+
+    fn quit(pager: &Pager, paging: JoinHandle<Result<(), MinusError>>) {
+        pager
+            .feed_event(Event::Key(KeyEvent::new(
+                KeyCode::Char('q'),
+                KeyModifiers::NONE,
+            )))
+            .unwrap();
+        paging.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn custom_output() {
+        let _running = RUNNING.lock();
+        let output = Output::default();
+        let pager = Pager::new();
+        pager.set_text("drawn into the output\n").unwrap();
+        let paging = start(&pager, &output);
+
+        // Another output can't be switched to while the pager runs
+        let ignored = Output::default();
+        pager.set_output(Box::new(ignored.clone())).unwrap();
+        quit(&pager, paging);
+
+        let written = output.text();
+        assert!(written.contains("drawn into the output"));
+        // The screen is restored in the output
+        assert!(written.ends_with("\x1b[?1049l"));
+        assert!(ignored.0.lock().is_empty());
+    }
+
+    #[test]
+    fn deterministic_mode() {
+        let _running = RUNNING.lock();
+        let output = Output::default();
+        let pager = Pager::new();
+        let text = (0..100)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<String>>()
+            .join("\n");
+        pager.set_text(text).unwrap();
+        // Each of these would hold back the scroll below for a minute
+        pager
+            .set_redraw_interval(Some(Duration::from_secs(60)))
+            .unwrap();
+        pager
+            .set_smooth_scroll(Some(Duration::from_secs(60)))
+            .unwrap();
+        pager.set_deterministic_mode(true).unwrap();
+        let paging = start(&pager, &output);
+
+        pager.scroll_to(50).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !output.text().contains("line 50") && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        quit(&pager, paging);
+
+        let written = output.text();
+        let scrolled = written.find("line 50").expect("the scroll was not drawn");
+        // The screen is drawn at the new position at once, without any frames in between
+        assert!(!written[..scrolled].contains("line 10"));
+    }
+}
+
+#[test]
+fn scroll_position_of_running_pager() {
+    use crate::{Pager, PagerState};
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    let pager = Pager::new();
+    let mut ps = PagerState::new().unwrap();
+    ps.rows = 5;
+    ps.lines = "line\n".repeat(10);
+    ps.format_lines();
+    let ps = Arc::new(Mutex::new(ps));
+    *pager.state.lock() = Arc::downgrade(&ps);
+
+    assert!(pager.is_at_top());
+    assert!(!pager.is_at_bottom());
+
+    // The last page starts at the 7th line with 4 rows of text on the screen
+    ps.lock().upper_mark = 5;
+    assert!(!pager.is_at_top());
+    assert!(!pager.is_at_bottom());
+    ps.lock().upper_mark = 6;
+    assert!(pager.is_at_bottom());
+
+    drop(ps);
+    assert!(!pager.is_at_top());
+    assert!(!pager.is_at_bottom());
+}
+
 mod wrapping {
+    use crate::AmbiguousWidth;
+
     // Test wrapping functions
     #[test]
     fn wrap_str() {
@@ -260,18 +562,182 @@ mod wrapping {
             }
             line
         };
-        let result = crate::wrap_str(&test, 80);
+        let result = crate::wrap_str(&test, 80, crate::WrapMode::Word, 0, AmbiguousWidth::Narrow);
         assert_eq!(result.len(), 3);
         assert_eq!(
             (80, 80, 40),
             (result[0].len(), result[1].len(), result[2].len()),
         );
     }
+
+    #[test]
+    fn wrap_modes() {
+        use crate::WrapMode;
+
+        let line = "The quick brown fox jumps";
+        // Words are kept together
+        assert_eq!(
+            crate::wrap_str(line, 12, WrapMode::Word, 0, AmbiguousWidth::Narrow),
+            vec!["The quick", "brown fox", "jumps"]
+        );
+        // Every row is filled up to the last column
+        assert_eq!(
+            crate::wrap_str(line, 12, WrapMode::Character, 0, AmbiguousWidth::Narrow),
+            vec!["The quick br", "own fox jump", "s"]
+        );
+        // Escape sequences don't take up any column
+        assert_eq!(
+            crate::wrap_str(
+                "\x1b[31mabcdef\x1b[0m",
+                4,
+                WrapMode::Character,
+                0,
+                AmbiguousWidth::Narrow
+            ),
+            vec!["\x1b[31mabcd", "ef\x1b[0m"]
+        );
+        assert_eq!(
+            crate::wrap_str("", 4, WrapMode::Character, 0, AmbiguousWidth::Narrow),
+            vec![""]
+        );
+    }
+
+    #[test]
+    fn wide_char_wrap() {
+        use crate::WrapMode;
+
+        let wrap = |line, cols, indent| {
+            let pushed = crate::wrap_str(
+                line,
+                cols,
+                WrapMode::Character,
+                indent,
+                AmbiguousWidth::Narrow,
+            );
+            let mut padded = pushed.clone();
+            crate::pad_wide_breaks(&mut padded, cols, indent, AmbiguousWidth::Narrow);
+            (pushed, padded)
+        };
+
+        // Rows that the wide characters fill exactly are left alone
+        let (pushed, padded) = wrap("ab漢字", 4, 0);
+        assert_eq!(pushed, vec!["ab漢", "字"]);
+        assert_eq!(padded, pushed);
+
+        // The column that a wide character doesn't fit into is either left empty or padded
+        let (pushed, padded) = wrap("ab漢字", 5, 0);
+        assert_eq!(pushed, vec!["ab漢", "字"]);
+        assert_eq!(padded, vec!["ab漢 ", "字"]);
+
+        let (pushed, padded) = wrap("a漢字x", 4, 0);
+        assert_eq!(pushed, vec!["a漢", "字x"]);
+        assert_eq!(padded, vec!["a漢 ", "字x"]);
+
+        let (pushed, padded) = wrap("abc漢字仮名", 4, 0);
+        assert_eq!(pushed, vec!["abc", "漢字", "仮名"]);
+        assert_eq!(padded, vec!["abc ", "漢字", "仮名"]);
+
+        // Rows after the first one are shorter by the indent
+        let (pushed, padded) = wrap("ab漢字仮名", 5, 2);
+        assert_eq!(pushed, vec!["ab漢", "字", "仮", "名"]);
+        assert_eq!(padded, vec!["ab漢 ", "字 ", "仮 ", "名"]);
+
+        // Escape sequences don't take up any column
+        let (_, padded) = wrap("\x1b[31mab漢字\x1b[0m", 5, 0);
+        assert_eq!(padded, vec!["\x1b[31mab漢 ", "字\x1b[0m"]);
+    }
+
+    #[test]
+    fn ambiguous_width() {
+        use crate::WrapMode;
+
+        // Circled numbers have an ambiguous width
+        let wrap = |line, cols, mode, ambiguous| crate::wrap_str(line, cols, mode, 0, ambiguous);
+        assert_eq!(
+            wrap("①②③④", 4, WrapMode::Character, AmbiguousWidth::Narrow),
+            vec!["①②③④"]
+        );
+        assert_eq!(
+            wrap("①②③④", 4, WrapMode::Character, AmbiguousWidth::Wide),
+            vec!["①②", "③④"]
+        );
+        assert_eq!(
+            wrap("①② ③④", 5, WrapMode::Word, AmbiguousWidth::Narrow),
+            vec!["①② ③④"]
+        );
+        assert_eq!(
+            wrap("①② ③④", 5, WrapMode::Word, AmbiguousWidth::Wide),
+            vec!["①②", "③④"]
+        );
+        // Words that are too long for a row are broken
+        assert_eq!(
+            wrap("a ①②③④⑤ b", 4, WrapMode::Word, AmbiguousWidth::Wide),
+            vec!["a", "①②", "③④", "⑤ b"]
+        );
+
+        assert_eq!(
+            crate::truncate_str("①②③④", 4, '>', AmbiguousWidth::Narrow),
+            "①②③④"
+        );
+        assert_eq!(
+            crate::truncate_str("①②③④", 4, '>', AmbiguousWidth::Wide),
+            "①>"
+        );
+    }
+
+    #[test]
+    fn truncate_str() {
+        let test = "#".repeat(200);
+        let result = crate::truncate_str(&test, 80, '>', AmbiguousWidth::Narrow);
+        assert_eq!(result, "#".repeat(79) + ">");
+
+        // Lines that already fit are left alone
+        assert_eq!(
+            crate::truncate_str("short line", 80, '>', AmbiguousWidth::Narrow),
+            "short line"
+        );
+    }
+
+    #[test]
+    fn truncate_str_ansi() {
+        // Escape sequences take up no columns and styles are reset before the marker
+        let test = "\x1b[31m".to_string() + &"#".repeat(20) + "\x1b[0m";
+        let result = crate::truncate_str(&test, 10, '>', AmbiguousWidth::Narrow);
+        assert_eq!(result, "\x1b[31m".to_string() + &"#".repeat(9) + "\x1b[0m>");
+    }
+
+    #[test]
+    fn skip_columns() {
+        let skip = |line, cols| crate::skip_columns(line, cols, AmbiguousWidth::Narrow);
+        assert_eq!(skip("0123456789", 0), "0123456789");
+        assert_eq!(skip("0123456789", 4), "456789");
+        assert_eq!(skip("0123", 10), "");
+
+        // Escapes in the skipped part are kept
+        assert_eq!(skip("\x1b[31m0123\x1b[0m4567", 2), "\x1b[31m23\x1b[0m4567");
+        // Wide characters take up two columns and are padded when cut in half
+        assert_eq!(skip("日本語", 2), "本語");
+        assert_eq!(skip("日本語", 3), " 語");
+
+        // So do characters of ambiguous width if they are measured as wide
+        assert_eq!(skip("①②③", 2), "③");
+        assert_eq!(crate::skip_columns("①②③", 2, AmbiguousWidth::Wide), "②③");
+        assert_eq!(crate::skip_columns("①②③", 3, AmbiguousWidth::Wide), " ③");
+    }
+
+    #[test]
+    fn align_right() {
+        assert_eq!(crate::align_right("①②", 4, AmbiguousWidth::Narrow), "  ①②");
+        assert_eq!(crate::align_right("①②", 4, AmbiguousWidth::Wide), "①②");
+    }
 }
 
 mod emit_events {
     // Check functions emit correct events on functin calls
-    use crate::{minus_core::events::Event, ExitStrategy, LineNumbers, Pager};
+    use crate::{
+        minus_core::events::Event, ExitStrategy, LineNumberAlignment, LineNumberStyle, LineNumbers,
+        Pager, TextDirection,
+    };
 
     const TEST_STR: &str = "This is sample text";
     #[test]
@@ -284,6 +750,33 @@ mod emit_events {
         );
     }
 
+    #[test]
+    fn set_text_keep_position() {
+        let pager = Pager::new();
+        pager.set_text_keep_position(TEST_STR).unwrap();
+        assert_eq!(
+            Event::SetDataKeepPosition(TEST_STR.to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn insert_lines() {
+        let pager = Pager::new();
+        pager.insert_lines(3, ["a", "b"]).unwrap();
+        assert_eq!(
+            Event::InsertLines(3, vec!["a".to_string(), "b".to_string()]),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn delete_lines() {
+        let pager = Pager::new();
+        pager.delete_lines(2..5).unwrap();
+        assert_eq!(Event::DeleteLines(2..5), pager.rx.try_recv().unwrap());
+    }
+
     #[test]
     fn push_str() {
         let pager = Pager::new();
@@ -304,6 +797,16 @@ mod emit_events {
         );
     }
 
+    #[test]
+    fn set_prompt_segments() {
+        let pager = Pager::new();
+        pager.set_prompt_segments("left", "right").unwrap();
+        assert_eq!(
+            Event::SetPromptSegments("left".to_string(), "right".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
     #[test]
     fn send_message() {
         let pager = Pager::new();
@@ -316,10 +819,19 @@ mod emit_events {
 
     #[test]
     #[cfg(feature = "static_output")]
+    #[allow(deprecated)]
     fn set_run_no_overflow() {
         let pager = Pager::new();
         pager.set_run_no_overflow(false).unwrap();
         assert_eq!(Event::SetRunNoOverflow(false), pager.rx.try_recv().unwrap());
+
+        pager
+            .set_overflow_behavior(crate::OverflowBehavior::PrintIfFits)
+            .unwrap();
+        assert_eq!(
+            Event::SetOverflowBehavior(crate::OverflowBehavior::PrintIfFits),
+            pager.rx.try_recv().unwrap()
+        );
     }
 
     #[test]
@@ -333,21 +845,1013 @@ mod emit_events {
     }
 
     #[test]
-    fn set_exit_strategy() {
+    fn pause_resume() {
         let pager = Pager::new();
-        pager.set_exit_strategy(ExitStrategy::PagerQuit).unwrap();
+        pager.pause().unwrap();
+        assert_eq!(Event::PauseRendering, pager.rx.try_recv().unwrap());
+        pager.resume().unwrap();
+        assert_eq!(Event::ResumeRendering, pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_max_buffer_bytes() {
+        let pager = Pager::new();
+        pager.set_max_buffer_bytes(Some(1024)).unwrap();
         assert_eq!(
-            Event::SetExitStrategy(ExitStrategy::PagerQuit),
+            Event::SetMaxBufferBytes(Some(1024)),
             pager.rx.try_recv().unwrap()
         );
     }
 
     #[test]
-    fn add_exit_callback() {
-        let func = Box::new(|| println!("Hello"));
+    fn reset_display_options() {
         let pager = Pager::new();
-        pager.add_exit_callback(func.clone()).unwrap();
+        pager.reset_display_options().unwrap();
+        assert_eq!(Event::ResetDisplayOptions, pager.rx.try_recv().unwrap());
+    }
 
-        assert_eq!(Event::AddExitCallback(func), pager.rx.try_recv().unwrap());
+    #[test]
+    fn set_highlight_row() {
+        use crossterm::style::Color;
+
+        let pager = Pager::new();
+        pager.set_highlight_row(Some(0)).unwrap();
+        assert_eq!(
+            Event::SetHighlightRow(Some(0)),
+            pager.rx.try_recv().unwrap()
+        );
+
+        pager.set_highlight_color(Color::DarkBlue).unwrap();
+        assert_eq!(
+            Event::SetHighlightColor(Color::DarkBlue),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_prompt_style() {
+        use crossterm::style::ContentStyle;
+
+        let pager = Pager::new();
+        pager.set_prompt_style(ContentStyle::new()).unwrap();
+        assert_eq!(
+            Event::SetPromptStyle(ContentStyle::new()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_alternate_screen() {
+        let pager = Pager::new();
+        pager.set_alternate_screen(false).unwrap();
+        assert_eq!(
+            Event::SetAlternateScreen(false),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_mouse_capture() {
+        let pager = Pager::new();
+        pager.set_mouse_capture(false).unwrap();
+        assert_eq!(Event::SetMouseCapture(false), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn exit_on_finish() {
+        let pager = Pager::new();
+        pager.set_exit_on_finish(true).unwrap();
+        assert_eq!(Event::SetExitOnFinish(true), pager.rx.try_recv().unwrap());
+        pager.finish_input().unwrap();
+        assert_eq!(Event::InputFinished, pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "follow_file")]
+    fn follow_file() {
+        let pager = Pager::new();
+        let path = std::env::temp_dir().join(format!("minus-follow-event-{}", std::process::id()));
+        std::fs::write(&path, "text\n").unwrap();
+        pager.follow_file(path.clone()).unwrap();
+        assert_eq!(
+            Event::FollowFile(path.clone()),
+            pager.rx.try_recv().unwrap()
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        // A missing file is reported right away
+        assert!(matches!(
+            pager.follow_file(path),
+            Err(crate::error::MinusError::FollowFile(_))
+        ));
+        assert!(pager.rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn set_mouse_scroll_amounts() {
+        let pager = Pager::new();
+        pager.set_mouse_scroll_amount(3).unwrap();
+        assert_eq!(Event::SetMouseScrollAmount(3), pager.rx.try_recv().unwrap());
+        pager.set_horizontal_mouse_scroll_amount(8).unwrap();
+        assert_eq!(
+            Event::SetHorizontalMouseScrollAmount(8),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_scroll_wrap() {
+        let pager = Pager::new();
+        pager.set_scroll_wrap(true).unwrap();
+        assert_eq!(Event::SetScrollWrap(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_drag_scroll() {
+        let pager = Pager::new();
+        pager.set_drag_scroll(true).unwrap();
+        assert_eq!(Event::SetDragScroll(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_exit_keys() {
+        use crate::input::definitions::keydefs::parse_key_event;
+
+        let pager = Pager::new();
+        pager.set_exit_keys(&["esc", "c-c"]).unwrap();
+        assert_eq!(
+            Event::SetExitKeys(vec![parse_key_event("esc"), parse_key_event("c-c")]),
+            pager.rx.try_recv().unwrap()
+        );
+
+        pager.set_exit_keys(&[]).unwrap();
+        assert_eq!(Event::SetExitKeys(vec![]), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_dump_exit_keys() {
+        use crate::input::definitions::keydefs::parse_key_event;
+
+        let pager = Pager::new();
+        pager.set_dump_exit_keys(&["c-q"]).unwrap();
+        assert_eq!(
+            Event::SetDumpExitKeys(vec![parse_key_event("c-q")]),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_count_prefix_enabled() {
+        let pager = Pager::new();
+        pager.set_count_prefix_enabled(false).unwrap();
+        assert_eq!(
+            Event::SetCountPrefixEnabled(false),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn count_display_and_timeout() {
+        let pager = Pager::new();
+        pager.set_count_display(false).unwrap();
+        assert_eq!(Event::SetCountDisplay(false), pager.rx.try_recv().unwrap());
+        let timeout = std::time::Duration::from_secs(1);
+        pager.set_count_timeout(timeout).unwrap();
+        assert_eq!(
+            Event::SetCountTimeout(timeout),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn buffers() {
+        let pager = Pager::new();
+        pager.add_buffer("text").unwrap();
+        pager.next_buffer().unwrap();
+        pager.prev_buffer().unwrap();
+        assert_eq!(
+            Event::AddBuffer("text".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+        assert_eq!(Event::NextBuffer, pager.rx.try_recv().unwrap());
+        assert_eq!(Event::PrevBuffer, pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_dynamic_prompt() {
+        let pager = Pager::new();
+        pager
+            .set_dynamic_prompt(Box::new(|_| "prompt".to_string()))
+            .unwrap();
+        assert_eq!(
+            Event::SetDynamicPrompt(Box::new(|_| String::new())),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_append_feedback() {
+        let pager = Pager::new();
+        pager.set_append_feedback(Box::new(|_| {})).unwrap();
+        assert_eq!(
+            Event::SetAppendFeedback(Box::new(|_| {})),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_status_bar() {
+        let pager = Pager::new();
+        pager.set_status_bar(Some("status".to_string())).unwrap();
+        assert_eq!(
+            Event::SetStatusBar(Some("status".to_string())),
+            pager.rx.try_recv().unwrap()
+        );
+        pager
+            .set_dynamic_status_bar(Box::new(|_| "status".to_string()))
+            .unwrap();
+        assert_eq!(
+            Event::SetDynamicStatusBar(Box::new(|_| String::new())),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn data_bytes() {
+        let pager = Pager::new();
+        pager.set_text_bytes(b"text\xff".as_slice()).unwrap();
+        pager.push_bytes(vec![b'a']).unwrap();
+        assert_eq!(
+            Event::SetDataBytes(b"text\xff".to_vec()),
+            pager.rx.try_recv().unwrap()
+        );
+        assert_eq!(
+            Event::AppendDataBytes(vec![b'a']),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_highlighter() {
+        use crate::highlight::Highlighter;
+        use crossterm::style::ContentStyle;
+
+        struct Plain;
+        impl Highlighter for Plain {
+            fn highlight(&self, line: &str, _: usize) -> Vec<(ContentStyle, String)> {
+                vec![(ContentStyle::new(), line.to_string())]
+            }
+        }
+
+        let pager = Pager::new();
+        pager.set_highlighter(Box::new(Plain)).unwrap();
+        assert_eq!(
+            Event::SetHighlighter(Box::new(Plain)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_source() {
+        use crate::source::LineSource;
+        use std::borrow::Cow;
+
+        struct Empty;
+        impl LineSource for Empty {
+            fn line(&self, _: usize) -> Option<Cow<'_, str>> {
+                None
+            }
+        }
+
+        let pager = Pager::new();
+        pager.set_line_source(Box::new(Empty)).unwrap();
+        assert_eq!(
+            Event::SetLineSource(Box::new(Empty)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_styler() {
+        let pager = Pager::new();
+        pager.set_line_styler(Box::new(|_, _| None)).unwrap();
+        assert_eq!(
+            Event::SetLineStyler(Box::new(|_, _| None)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_redraw_interval() {
+        let pager = Pager::new();
+        let interval = Some(std::time::Duration::from_millis(50));
+        pager.set_redraw_interval(interval).unwrap();
+        assert_eq!(
+            Event::SetRedrawInterval(interval),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_append_coalesce() {
+        let pager = Pager::new();
+        let window = Some(std::time::Duration::from_millis(20));
+        pager.set_append_coalesce(window).unwrap();
+        assert_eq!(
+            Event::SetAppendCoalesce(window),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_resize_debounce() {
+        let pager = Pager::new();
+        let debounce = std::time::Duration::from_millis(100);
+        pager.set_resize_debounce(debounce).unwrap();
+        assert_eq!(
+            Event::SetResizeDebounce(debounce),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_tick_interval() {
+        let pager = Pager::new();
+        let interval = Some(std::time::Duration::from_millis(100));
+        pager.set_tick_interval(interval).unwrap();
+        assert_eq!(
+            Event::SetTickInterval(interval),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_smooth_scroll() {
+        let pager = Pager::new();
+        let duration = Some(std::time::Duration::from_millis(150));
+        pager.set_smooth_scroll(duration).unwrap();
+        assert_eq!(
+            Event::SetSmoothScroll(duration),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_deterministic_mode() {
+        let pager = Pager::new();
+        pager.set_deterministic_mode(true).unwrap();
+        assert_eq!(
+            Event::SetDeterministicMode(true),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_terminal_size() {
+        let pager = Pager::new();
+        pager.set_terminal_size(100, 30).unwrap();
+        assert_eq!(
+            Event::SetTerminalSize(100, 30),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_text_direction() {
+        let pager = Pager::new();
+        pager.set_text_direction(TextDirection::Rtl).unwrap();
+        assert_eq!(
+            Event::SetTextDirection(TextDirection::Rtl),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_number_style() {
+        let pager = Pager::new();
+        let style = LineNumberStyle {
+            separator: "| ".to_string(),
+            alignment: LineNumberAlignment::Left,
+            min_width: 3,
+        };
+        pager.set_line_number_style(style.clone()).unwrap();
+        assert_eq!(
+            Event::SetLineNumberStyle(style),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_line_wrapping() {
+        let pager = Pager::new();
+        pager.set_line_wrapping(false).unwrap();
+        assert_eq!(Event::SetLineWrapping(false), pager.rx.try_recv().unwrap());
+
+        pager.set_truncation_marker('~').unwrap();
+        assert_eq!(
+            Event::SetTruncationMarker('~'),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn scroll() {
+        let pager = Pager::new();
+        pager.scroll_to(10).unwrap();
+        pager.scroll_by(-3).unwrap();
+        pager.scroll_to_top().unwrap();
+        pager.scroll_to_bottom().unwrap();
+        assert_eq!(
+            pager.rx.try_iter().collect::<Vec<Event>>(),
+            vec![
+                Event::ScrollTo(10),
+                Event::ScrollBy(-3),
+                Event::ScrollTo(0),
+                Event::ScrollTo(usize::MAX),
+            ]
+        );
+    }
+
+    #[test]
+    fn set_show_whitespace() {
+        let pager = Pager::new();
+        pager.set_show_whitespace(true).unwrap();
+        assert_eq!(Event::SetShowWhitespace(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_reflow() {
+        let pager = Pager::new();
+        pager.set_reflow(true).unwrap();
+        assert_eq!(Event::SetReflow(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_scroll_past_end() {
+        let pager = Pager::new();
+        pager.set_scroll_past_end(true).unwrap();
+        assert_eq!(Event::SetScrollPastEnd(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_frozen_header_lines() {
+        let pager = Pager::new();
+        pager.set_frozen_header_lines(2).unwrap();
+        assert_eq!(Event::SetFrozenHeaderLines(2), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn redraw() {
+        let pager = Pager::new();
+        pager.redraw().unwrap();
+        assert_eq!(Event::Redraw, pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_page_overlap() {
+        let pager = Pager::new();
+        pager.set_page_overlap(3).unwrap();
+        assert_eq!(Event::SetPageOverlap(3), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_wrap_indicator() {
+        let pager = Pager::new();
+        pager.set_wrap_indicator(Some("> ".to_string())).unwrap();
+        assert_eq!(
+            Event::SetWrapIndicator(Some("> ".to_string())),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_wrap_mode() {
+        let pager = Pager::new();
+        pager.set_wrap_mode(crate::WrapMode::Character).unwrap();
+        assert_eq!(
+            Event::SetWrapMode(crate::WrapMode::Character),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_control_char_placeholder() {
+        let pager = Pager::new();
+        pager.set_control_char_placeholder(Some('.')).unwrap();
+        assert_eq!(
+            Event::SetControlCharPlaceholder(Some('.')),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_carriage_return_mode() {
+        let pager = Pager::new();
+        pager
+            .set_carriage_return_mode(crate::CrMode::Overwrite)
+            .unwrap();
+        assert_eq!(
+            Event::SetCarriageReturnMode(crate::CrMode::Overwrite),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_wide_char_wrap() {
+        let pager = Pager::new();
+        pager
+            .set_wide_char_wrap(crate::WideCharWrap::PadAndBreak)
+            .unwrap();
+        assert_eq!(
+            Event::SetWideCharWrap(crate::WideCharWrap::PadAndBreak),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_ambiguous_width() {
+        let pager = Pager::new();
+        pager
+            .set_ambiguous_width(crate::AmbiguousWidth::Wide)
+            .unwrap();
+        assert_eq!(
+            Event::SetAmbiguousWidth(crate::AmbiguousWidth::Wide),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_end_of_buffer_char() {
+        let pager = Pager::new();
+        pager.set_end_of_buffer_char(None).unwrap();
+        assert_eq!(
+            Event::SetEndOfBufferChar(None),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_lazy_formatting() {
+        let pager = Pager::new();
+        pager.set_lazy_formatting(true).unwrap();
+        assert_eq!(Event::SetLazyFormatting(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_search_wrap() {
+        let pager = Pager::new();
+        pager.set_search_wrap(false).unwrap();
+        assert_eq!(Event::SetSearchWrap(false), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_center_on_match() {
+        let pager = Pager::new();
+        pager.set_center_on_match(true).unwrap();
+        assert_eq!(Event::SetCenterOnMatch(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_highlight_visible() {
+        let pager = Pager::new();
+        pager.set_highlight_visible(false).unwrap();
+        assert_eq!(
+            Event::SetHighlightVisible(false),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_search_focus() {
+        let pager = Pager::new();
+        pager.set_search_focus(true).unwrap();
+        assert_eq!(Event::SetSearchFocus(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_search_range() {
+        let pager = Pager::new();
+        pager.set_search_range(Some((5, 10))).unwrap();
+        assert_eq!(
+            Event::SetSearchRange(Some((5, 10))),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_search_keys() {
+        let pager = Pager::new();
+        pager.set_search_keys('s', 'S').unwrap();
+        assert_eq!(Event::SetSearchKeys('s', 'S'), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_search_multiline() {
+        let pager = Pager::new();
+        pager.set_search_multiline(true).unwrap();
+        assert_eq!(
+            Event::SetSearchMultiline(true),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_max_search_matches() {
+        let pager = Pager::new();
+        pager.set_max_search_matches(Some(100)).unwrap();
+        assert_eq!(
+            Event::SetMaxSearchMatches(Some(100)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_search_literal() {
+        let pager = Pager::new();
+        pager.set_search_literal(true).unwrap();
+        assert_eq!(Event::SetSearchLiteral(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_match_styles() {
+        use crossterm::style::{ContentStyle, Stylize};
+
+        let pager = Pager::new();
+        pager
+            .set_current_match_style(ContentStyle::new().bold())
+            .unwrap();
+        assert_eq!(
+            Event::SetCurrentMatchStyle(ContentStyle::new().bold()),
+            pager.rx.try_recv().unwrap()
+        );
+        pager
+            .set_other_match_style(ContentStyle::new().underlined())
+            .unwrap();
+        assert_eq!(
+            Event::SetOtherMatchStyle(ContentStyle::new().underlined()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_highlight_groups() {
+        let pager = Pager::new();
+        pager
+            .set_highlight_groups(&["ERROR|FATAL", "WARN(ING)?"])
+            .unwrap();
+        assert_eq!(
+            Event::SetHighlightGroups(vec![
+                regex::Regex::new("ERROR|FATAL").unwrap(),
+                regex::Regex::new("WARN(ING)?").unwrap()
+            ]),
+            pager.rx.try_recv().unwrap()
+        );
+
+        // Invalid patterns are rejected without sending anything
+        assert!(pager.set_highlight_groups(&["(unclosed"]).is_err());
+        assert!(pager.rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn set_exit_strategy() {
+        let pager = Pager::new();
+        pager.set_exit_strategy(ExitStrategy::PagerQuit).unwrap();
+        assert_eq!(
+            Event::SetExitStrategy(ExitStrategy::PagerQuit),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_exit_dump_remaining() {
+        let pager = Pager::new();
+        pager.set_exit_dump_remaining(true).unwrap();
+        assert_eq!(
+            Event::SetExitDumpRemaining(true),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_exit_dump_scope() {
+        let pager = Pager::new();
+        pager.set_exit_dump_scope(crate::DumpScope::Entire).unwrap();
+        assert_eq!(
+            Event::SetExitDumpScope(crate::DumpScope::Entire),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn add_exit_callback() {
+        let func = Box::new(|| println!("Hello"));
+        let pager = Pager::new();
+        pager.add_exit_callback(func.clone()).unwrap();
+
+        assert_eq!(Event::AddExitCallback(func), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn with_state() {
+        let pager = Pager::new();
+        pager.with_state(|ps| println!("{}", ps.rows)).unwrap();
+        assert_eq!(
+            Event::WithState(Box::new(|_| {})),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn add_exit_callback_with_reason() {
+        let pager = Pager::new();
+        pager
+            .add_exit_callback_with_reason(Box::new(|reason| println!("{reason:?}")))
+            .unwrap();
+        assert_eq!(
+            Event::AddExitCallbackWithReason(Box::new(|_| {})),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_reach_callbacks() {
+        let func = Box::new(|| println!("Hello"));
+        let pager = Pager::new();
+        pager.set_on_reach_bottom(func.clone()).unwrap();
+        assert_eq!(
+            Event::SetOnReachBottom(func.clone()),
+            pager.rx.try_recv().unwrap()
+        );
+
+        pager.set_on_reach_top(func.clone()).unwrap();
+        assert_eq!(Event::SetOnReachTop(func), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_on_link_activate() {
+        let func = Box::new(|_| println!("Hello"));
+        let pager = Pager::new();
+        pager.set_on_link_activate(func.clone()).unwrap();
+        assert_eq!(Event::SetOnLinkActivate(func), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_gutter_marks() {
+        let func = Box::new(|_, _| println!("Hello"));
+        let pager = Pager::new();
+        pager.set_on_gutter_mark(func.clone()).unwrap();
+        assert_eq!(Event::SetOnGutterMark(func), pager.rx.try_recv().unwrap());
+
+        pager.set_gutter_mark_glyph(Some('*')).unwrap();
+        assert_eq!(
+            Event::SetGutterMarkGlyph(Some('*')),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_reflow_callbacks() {
+        let func = Box::new(|| println!("Hello"));
+        let pager = Pager::new();
+        pager.set_on_before_reflow(func.clone()).unwrap();
+        assert_eq!(
+            Event::SetOnBeforeReflow(func.clone()),
+            pager.rx.try_recv().unwrap()
+        );
+
+        pager.set_on_after_reflow(func.clone()).unwrap();
+        assert_eq!(Event::SetOnAfterReflow(func), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "clipboard")]
+    fn set_line_ref_template() {
+        let pager = Pager::new();
+        pager.set_line_ref_template("main.rs:%L").unwrap();
+        assert_eq!(
+            Event::SetLineRefTemplate("main.rs:%L".to_string()),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_cursor_mode() {
+        use crossterm::style::Color;
+
+        let pager = Pager::new();
+        pager.set_cursor_mode(true).unwrap();
+        assert_eq!(Event::SetCursorMode(true), pager.rx.try_recv().unwrap());
+
+        pager.set_cursor_color(Color::Red).unwrap();
+        assert_eq!(
+            Event::SetCursorColor(Color::Red),
+            pager.rx.try_recv().unwrap()
+        );
+
+        pager.set_scroll_off(3).unwrap();
+        assert_eq!(Event::SetScrollOff(3), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_selection() {
+        use crossterm::style::Color;
+
+        let pager = Pager::new();
+        pager.set_selection_color(Color::Red).unwrap();
+        assert_eq!(
+            Event::SetSelectionColor(Color::Red),
+            pager.rx.try_recv().unwrap()
+        );
+
+        let func = Box::new(|text| println!("{text}"));
+        pager.set_on_yank(func.clone()).unwrap();
+        assert_eq!(Event::SetOnYank(func), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_enter_action() {
+        let pager = Pager::new();
+        pager
+            .set_enter_action(crate::EnterAction::EmitSelect)
+            .unwrap();
+        assert_eq!(
+            Event::SetEnterAction(crate::EnterAction::EmitSelect),
+            pager.rx.try_recv().unwrap()
+        );
+
+        let func = Box::new(|line| println!("{line}"));
+        pager.set_on_select(func.clone()).unwrap();
+        assert_eq!(Event::SetOnSelect(func), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_continuation_number_style() {
+        use crate::ContinuationNumberStyle;
+
+        let pager = Pager::new();
+        pager
+            .set_continuation_number_style(ContinuationNumberStyle::Repeat)
+            .unwrap();
+        assert_eq!(
+            Event::SetContinuationNumberStyle(ContinuationNumberStyle::Repeat),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn set_section_pattern() {
+        let pager = Pager::new();
+        pager.set_section_pattern(Some(r"^@@ -\d+")).unwrap();
+        assert_eq!(
+            Event::SetSectionPattern(Some(regex::Regex::new(r"^@@ -\d+").unwrap())),
+            pager.rx.try_recv().unwrap()
+        );
+        assert!(pager.set_section_pattern(Some("(")).is_err());
+    }
+
+    #[test]
+    fn set_panic_cleanup() {
+        let pager = Pager::new();
+        pager.set_panic_cleanup(false).unwrap();
+        assert_eq!(Event::SetPanicCleanup(false), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn push_styled_line() {
+        use crossterm::style::{ContentStyle, Stylize};
+
+        let pager = Pager::new();
+        let spans = vec![(ContentStyle::new().red(), "red".to_string())];
+        pager.push_styled_line(spans.clone()).unwrap();
+        assert_eq!(Event::AppendStyledLine(spans), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "search")]
+    fn search_without_matches() {
+        let pager = Pager::new();
+        pager.set_keep_unmatched_search(false).unwrap();
+        pager.set_beep_on_no_match(true).unwrap();
+        assert_eq!(
+            Event::SetKeepUnmatchedSearch(false),
+            pager.rx.try_recv().unwrap()
+        );
+        assert_eq!(Event::SetBeepOnNoMatch(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_start_line() {
+        let pager = Pager::new();
+        pager.set_start_line(42).unwrap();
+        assert_eq!(Event::SetStartLine(42), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_color() {
+        let pager = Pager::new();
+        pager.set_color(false).unwrap();
+        assert_eq!(Event::SetColor(false), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_prompt_position() {
+        let pager = Pager::new();
+        pager
+            .set_prompt_position(crate::PromptPosition::Top)
+            .unwrap();
+        assert_eq!(
+            Event::SetPromptPosition(crate::PromptPosition::Top),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn batch() {
+        let pager = Pager::new();
+        pager
+            .batch(|batch| {
+                batch.set_text("Hello")?;
+                batch.batch(|inner| inner.set_prompt("prompt"))?;
+                batch.push_str("\n")
+            })
+            .unwrap();
+        assert_eq!(
+            Event::Batch(vec![
+                Event::SetData("Hello".to_string()),
+                Event::SetPrompt("prompt".to_string()),
+                Event::AppendData("\n".to_string()),
+            ]),
+            pager.rx.try_recv().unwrap()
+        );
+        assert!(pager.rx.try_recv().is_err());
+
+        // Nothing is sent if the batch fails
+        let res = pager.batch(|batch| {
+            batch.set_text("Hello")?;
+            Err(crate::MinusError::Communication(
+                crossbeam_channel::SendError(Event::Redraw),
+            ))
+        });
+        assert!(res.is_err());
+        assert!(pager.rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn set_scrollbar() {
+        let pager = Pager::new();
+        pager.set_scrollbar(true).unwrap();
+        assert_eq!(Event::SetScrollbar(true), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn feed_event() {
+        use crossterm::event::{Event as TermEvent, KeyCode, KeyEvent, KeyModifiers};
+
+        let pager = Pager::new();
+        pager.set_external_input(true).unwrap();
+        assert_eq!(Event::SetExternalInput(true), pager.rx.try_recv().unwrap());
+
+        let ev = TermEvent::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        pager.feed_event(ev).unwrap();
+        assert_eq!(Event::FeedInput(ev), pager.rx.try_recv().unwrap());
+    }
+
+    #[test]
+    fn set_output() {
+        let pager = Pager::new();
+        pager.set_output(Box::new(std::io::sink())).unwrap();
+        assert!(matches!(pager.rx.try_recv().unwrap(), Event::SetOutput(_)));
+    }
+
+    #[test]
+    fn set_max_content_width() {
+        let pager = Pager::new();
+        pager.set_max_content_width(Some(80)).unwrap();
+        assert_eq!(
+            Event::SetMaxContentWidth(Some(80)),
+            pager.rx.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn set_edge_feedback() {
+        let pager = Pager::new();
+        pager.set_edge_feedback(crate::EdgeFeedback::Flash).unwrap();
+        assert_eq!(
+            Event::SetEdgeFeedback(crate::EdgeFeedback::Flash),
+            pager.rx.try_recv().unwrap()
+        );
     }
 }